@@ -0,0 +1,129 @@
+//! The [`mcfunction!`] macro, re-exported by `minecraft-datapack-generator` with its
+//! `macros` feature.
+
+use proc_macro::{Delimiter, Group, Literal, Span, TokenStream, TokenTree};
+
+/// The commands of Java Edition, for catching misspelled command names.
+const COMMANDS: &[&str] = &[
+    "advancement", "attribute", "ban", "ban-ip", "banlist", "bossbar", "clear", "clone", "damage", "data", "datapack",
+    "debug", "defaultgamemode", "deop", "dialog", "difficulty", "effect", "enchant", "execute", "experience", "fill",
+    "fillbiome", "forceload", "function", "gamemode", "gamerule", "give", "help", "item", "jfr", "kick", "kill", "list",
+    "locate", "loot", "me", "msg", "op", "pardon", "pardon-ip", "particle", "perf", "place", "playsound", "publish",
+    "random", "recipe", "reload", "return", "ride", "rotate", "save-all", "save-off", "save-on", "say", "schedule",
+    "scoreboard", "seed", "setblock", "setidletimeout", "setworldspawn", "spawnpoint", "spectate", "spreadplayers",
+    "stop", "stopsound", "summon", "tag", "team", "teammsg", "teleport", "tell", "tellraw", "test", "tick", "time",
+    "title", "tm", "tp", "transfer", "trigger", "version", "w", "waypoint", "weather", "whitelist", "worldborder", "xp",
+];
+
+/// A piece of a command's text and where it was written.
+struct Piece {
+    text: String,
+    span: Span,
+}
+
+fn pieces(tokens: TokenStream, into: &mut Vec<Piece>) {
+    for token in tokens {
+        match token {
+            TokenTree::Group(group) => {
+                let (open, close) = match group.delimiter() {
+                    Delimiter::Parenthesis => ("(", ")"),
+                    Delimiter::Brace => ("{", "}"),
+                    Delimiter::Bracket => ("[", "]"),
+                    Delimiter::None => ("", ""),
+                };
+                into.push(Piece { text: open.to_string(), span: group.span_open() });
+                pieces(group.stream(), into);
+                into.push(Piece { text: close.to_string(), span: group.span_close() });
+            }
+            token => into.push(Piece { text: token.to_string(), span: token.span() }),
+        }
+    }
+}
+
+/// Joins the pieces of a command with a space wherever there was whitespace between them.
+fn command_text(pieces: &[Piece]) -> String {
+    let mut text = String::new();
+    let mut end: Option<(usize, usize)> = None;
+    for piece in pieces.iter().filter(|piece| !piece.text.is_empty()) {
+        let start = (piece.span.line(), piece.span.column());
+        if end.is_some_and(|end| end != start) {
+            text.push(' ');
+        }
+        text.push_str(&piece.text);
+        end = Some((piece.span.end().line(), piece.span.end().column()));
+    }
+    text
+}
+
+/// `compile_error!(message)`, pointing at `span`.
+fn compile_error(message: &str, span: Span) -> TokenStream {
+    let mut message = TokenTree::Literal(Literal::string(message));
+    message.set_span(span);
+    let path: TokenStream = "::core::compile_error!".parse().expect("the path should be valid tokens");
+    path.into_iter()
+        .chain([Group::new(Delimiter::Parenthesis, message.into()).into()])
+        .map(|mut token: TokenTree| {
+            token.set_span(span);
+            token
+        })
+        .collect()
+}
+
+/// Writes a function's commands as tokens, one per statement ending in `;`, and expands to
+/// the `McFunction` with them.
+///
+/// ```ignore
+/// let function = mcfunction! {
+///     # Lifts everyone up
+///     execute as @a at @s run tp ~ ~1 ~;
+///     say Hello;
+/// };
+/// ```
+///
+/// The first word of each command is checked against the game's commands, so a misspelled
+/// command fails to compile. Comments start with `#` and end with their line, and macro
+/// lines start with `$`. Whitespace between tokens is kept as a single space, and left out
+/// where there was none, so `~-1` and `@s[tag=a]` are written as they are.
+///
+/// Since the commands must be Rust tokens, text in single quotes, such as `'{"text":"a"}'`,
+/// can't be written; use double quotes instead.
+#[proc_macro]
+pub fn mcfunction(input: TokenStream) -> TokenStream {
+    let mut statements: Vec<Vec<TokenTree>> = vec![Vec::new()];
+    for token in input {
+        let statement = statements.last().expect("there is always a statement");
+        let comment_line = match statement.first() {
+            Some(TokenTree::Punct(punct)) if punct.as_char() == '#' => Some(punct.span().line()),
+            _ => None,
+        };
+        if comment_line.is_some_and(|line| token.span().line() > line) {
+            statements.push(Vec::new());
+        }
+        match &token {
+            TokenTree::Punct(punct) if punct.as_char() == ';' => statements.push(Vec::new()),
+            _ => statements.last_mut().expect("there is always a statement").push(token),
+        }
+    }
+
+    let mut commands = Vec::new();
+    for statement in statements.into_iter().filter(|statement| !statement.is_empty()) {
+        let mut statement_pieces = Vec::new();
+        pieces(statement.into_iter().collect(), &mut statement_pieces);
+        let command = command_text(&statement_pieces);
+        let span = statement_pieces[0].span;
+        if !command.starts_with('#') {
+            let name = command.trim_start_matches('$').split(' ').next().unwrap_or_default();
+            if !COMMANDS.contains(&name) {
+                return compile_error(&format!("'{name}' isn't a command"), span);
+            }
+        }
+        commands.push(Literal::string(&command).to_string());
+    }
+
+    format!(
+        "::minecraft_datapack_generator::data::function::McFunction::from(::std::vec::Vec::<&'static str>::from([{}]))",
+        commands.join(", "),
+    )
+    .parse()
+    .expect("the expansion should be valid tokens")
+}
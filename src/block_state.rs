@@ -0,0 +1,90 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+use serde::Serialize;
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+use serde_json::Value;
+use crate::nbt::{NbtCompound, Snbt};
+use crate::resource_location::ResourceLocation;
+
+/// A block and its properties, serialized as `{"Name": ..., "Properties": {...}}` in JSON
+/// files, and displayed as `name[key=value,...]` for commands.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct BlockState {
+    #[serde(rename = "Name")]
+    pub name: ResourceLocation,
+    #[serde(rename = "Properties", default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub properties: BTreeMap<String, String>,
+}
+
+impl BlockState {
+    pub fn new(name: ResourceLocation) -> Self {
+        Self { name, properties: BTreeMap::new() }
+    }
+
+    pub fn property(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.properties.insert(key.into(), value.into());
+        self
+    }
+
+    /// The JSON form, as in worldgen files.
+    pub fn to_json(&self) -> Value {
+        serde_json::to_value(self).expect("block states always serialize")
+    }
+}
+
+/// The command form, such as `minecraft:oak_log[axis=y]`, without brackets when there are
+/// no properties.
+impl Display for BlockState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if !self.properties.is_empty() {
+            let properties: Vec<String> = self.properties.iter().map(|(key, value)| format!("{key}={value}")).collect();
+            write!(f, "[{}]", properties.join(","))?;
+        }
+        Ok(())
+    }
+}
+
+impl From<ResourceLocation> for BlockState {
+    fn from(name: ResourceLocation) -> Self {
+        Self::new(name)
+    }
+}
+
+/// The NBT form, as in block display entities and particle options.
+impl From<&BlockState> for Snbt {
+    fn from(state: &BlockState) -> Self {
+        let mut nbt = NbtCompound::new().with("Name", state.name.to_string());
+        if !state.properties.is_empty() {
+            let properties: NbtCompound = state.properties.iter().map(|(key, value)| (key.as_str(), value.as_str())).collect();
+            nbt.insert("Properties", properties);
+        }
+        nbt.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn oak_log() -> BlockState {
+        BlockState::new("minecraft:oak_log".parse().expect("should have parsed the resource location"))
+    }
+
+    #[test]
+    fn command_form() {
+        assert_eq!(oak_log().to_string(), "minecraft:oak_log");
+        assert_eq!(oak_log().property("axis", "y").to_string(), "minecraft:oak_log[axis=y]");
+        let stairs = oak_log().property("waterlogged", "true").property("facing", "east");
+        assert_eq!(stairs.to_string(), "minecraft:oak_log[facing=east,waterlogged=true]", "properties should be sorted");
+    }
+
+    #[test]
+    fn json_form() {
+        assert_eq!(oak_log().to_json(), json!({"Name": "minecraft:oak_log"}));
+        assert_eq!(oak_log().property("axis", "y").to_json(), json!({"Name": "minecraft:oak_log", "Properties": {"axis": "y"}}));
+    }
+}
@@ -0,0 +1,189 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+use serde::{Serialize, Serializer};
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer};
+use crate::error::DatapackError;
+
+/// One of the 16 named chat colors, or any `0xRRGGBB` color.
+///
+/// Text components write colors as names or `#RRGGBB` strings, which is how this
+/// serializes; files that store colors as packed integers, such as biomes, use
+/// [`Color::as_packed_int`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Black,
+    DarkBlue,
+    DarkGreen,
+    DarkAqua,
+    DarkRed,
+    DarkPurple,
+    Gold,
+    Gray,
+    DarkGray,
+    Blue,
+    Green,
+    Aqua,
+    Red,
+    LightPurple,
+    Yellow,
+    White,
+    /// A `0xRRGGBB` color, displayed as `#RRGGBB`.
+    Hex(u32),
+}
+
+impl Color {
+    pub const NAMED: [Color; 16] = [
+        Self::Black,
+        Self::DarkBlue,
+        Self::DarkGreen,
+        Self::DarkAqua,
+        Self::DarkRed,
+        Self::DarkPurple,
+        Self::Gold,
+        Self::Gray,
+        Self::DarkGray,
+        Self::Blue,
+        Self::Green,
+        Self::Aqua,
+        Self::Red,
+        Self::LightPurple,
+        Self::Yellow,
+        Self::White,
+    ];
+
+    /// The name of a named color, such as `dark_aqua`.
+    pub fn as_named(&self) -> Option<&'static str> {
+        Some(match self {
+            Self::Black => "black",
+            Self::DarkBlue => "dark_blue",
+            Self::DarkGreen => "dark_green",
+            Self::DarkAqua => "dark_aqua",
+            Self::DarkRed => "dark_red",
+            Self::DarkPurple => "dark_purple",
+            Self::Gold => "gold",
+            Self::Gray => "gray",
+            Self::DarkGray => "dark_gray",
+            Self::Blue => "blue",
+            Self::Green => "green",
+            Self::Aqua => "aqua",
+            Self::Red => "red",
+            Self::LightPurple => "light_purple",
+            Self::Yellow => "yellow",
+            Self::White => "white",
+            Self::Hex(_) => return None,
+        })
+    }
+
+    /// The color as `#RRGGBB`, using the game's RGB values for named colors.
+    pub fn as_hex_string(&self) -> String {
+        format!("#{:06X}", self.as_packed_int())
+    }
+
+    /// The color as a `0xRRGGBB` integer, using the game's RGB values for named colors.
+    pub fn as_packed_int(&self) -> u32 {
+        match self {
+            Self::Black => 0x000000,
+            Self::DarkBlue => 0x0000AA,
+            Self::DarkGreen => 0x00AA00,
+            Self::DarkAqua => 0x00AAAA,
+            Self::DarkRed => 0xAA0000,
+            Self::DarkPurple => 0xAA00AA,
+            Self::Gold => 0xFFAA00,
+            Self::Gray => 0xAAAAAA,
+            Self::DarkGray => 0x555555,
+            Self::Blue => 0x5555FF,
+            Self::Green => 0x55FF55,
+            Self::Aqua => 0x55FFFF,
+            Self::Red => 0xFF5555,
+            Self::LightPurple => 0xFF55FF,
+            Self::Yellow => 0xFFFF55,
+            Self::White => 0xFFFFFF,
+            Self::Hex(rgb) => rgb & 0xFFFFFF,
+        }
+    }
+}
+
+impl From<u32> for Color {
+    fn from(rgb: u32) -> Self {
+        Self::Hex(rgb)
+    }
+}
+
+impl Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.as_named() {
+            Some(name) => f.write_str(name),
+            None => f.write_str(&self.as_hex_string()),
+        }
+    }
+}
+
+impl FromStr for Color {
+    type Err = DatapackError;
+
+    /// Parses a color name or `#RRGGBB`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || DatapackError::Validation(format!("invalid color '{s}'"));
+        if let Some(hex) = s.strip_prefix('#') {
+            if hex.len() != 6 {
+                return Err(invalid());
+            }
+            return u32::from_str_radix(hex, 16).map(Self::Hex).map_err(|_| invalid());
+        }
+        Self::NAMED.into_iter().find(|color| color.as_named() == Some(s)).ok_or_else(invalid)
+    }
+}
+
+impl Serialize for Color {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(de::Error::custom)
+    }
+}
+
+/// Serializes a color as a packed integer, for `#[serde(serialize_with)]`.
+pub(crate) fn serialize_packed<S: Serializer>(color: &Color, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u32(color.as_packed_int())
+}
+
+/// [`serialize_packed`] for optional colors.
+pub(crate) fn serialize_packed_option<S: Serializer>(color: &Option<Color>, serializer: S) -> Result<S::Ok, S::Error> {
+    color.map(|color| color.as_packed_int()).serialize(serializer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn named_round_trip() {
+        for color in Color::NAMED {
+            let name = color.as_named().expect("named colors should have a name");
+            assert_eq!(name.parse::<Color>().expect("should have parsed the name"), color);
+            assert_eq!(color.to_string(), name);
+        }
+        assert_eq!(Color::Hex(0x123456).as_named(), None);
+    }
+
+    #[test]
+    fn hex_conversions() {
+        let color: Color = "#9a5cc6".parse().expect("should have parsed the hex color");
+        assert_eq!(color, Color::Hex(0x9A5CC6));
+        assert_eq!(color.as_packed_int(), 10116294);
+        assert_eq!(color.as_hex_string(), "#9A5CC6");
+        assert_eq!(color.to_string(), "#9A5CC6");
+        assert_eq!(Color::Gold.as_packed_int(), 0xFFAA00);
+        assert_eq!(Color::Gold.as_hex_string(), "#FFAA00");
+        assert_eq!(Color::Hex(0xFF123456).as_packed_int(), 0x123456, "alpha should have been dropped");
+        for invalid in ["#12345", "#GGGGGG", "purple", ""] {
+            assert!(invalid.parse::<Color>().is_err(), "'{invalid}' should have been rejected");
+        }
+    }
+}
@@ -0,0 +1,17 @@
+pub mod selector;
+pub mod position;
+pub mod data;
+pub mod execute;
+pub mod item;
+pub mod scoreboard;
+pub mod text;
+pub mod damage;
+pub mod function;
+pub mod summon;
+pub mod particle;
+pub mod bossbar;
+pub mod team;
+pub mod schedule;
+pub mod return_;
+pub mod random;
+pub mod raycast;
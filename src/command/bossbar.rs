@@ -0,0 +1,393 @@
+use std::fmt::{self, Display};
+use crate::command::selector::Selector;
+use crate::data::function::McFunction;
+use crate::error::DatapackError;
+use crate::resource_location::ResourceLocation;
+use crate::text::TextComponent;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BossbarColor {
+    Blue,
+    Green,
+    Pink,
+    Purple,
+    Red,
+    #[default]
+    White,
+    Yellow,
+}
+
+impl Display for BossbarColor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Blue => "blue",
+            Self::Green => "green",
+            Self::Pink => "pink",
+            Self::Purple => "purple",
+            Self::Red => "red",
+            Self::White => "white",
+            Self::Yellow => "yellow",
+        })
+    }
+}
+
+/// How a bossbar is divided.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BossbarStyle {
+    /// A single, continuous bar.
+    #[default]
+    Progress,
+    Notched6,
+    Notched10,
+    Notched12,
+    Notched20,
+}
+
+impl Display for BossbarStyle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Progress => "progress",
+            Self::Notched6 => "notched_6",
+            Self::Notched10 => "notched_10",
+            Self::Notched12 => "notched_12",
+            Self::Notched20 => "notched_20",
+        })
+    }
+}
+
+/// The number of a bossbar that `execute store` writes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BossbarValue {
+    Value,
+    Max,
+}
+
+impl Display for BossbarValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Value => "value",
+            Self::Max => "max",
+        })
+    }
+}
+
+/// What a `bossbar set` command changes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BossbarSetting {
+    Color(BossbarColor),
+    Style(BossbarStyle),
+    Max(u32),
+    Value(u32),
+    /// The players who see the bossbar, or nobody.
+    Players(Option<Selector>),
+    Visible(bool),
+    Name(Box<TextComponent>),
+}
+
+impl Display for BossbarSetting {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Color(color) => write!(f, "color {color}"),
+            Self::Style(style) => write!(f, "style {style}"),
+            Self::Max(max) => write!(f, "max {max}"),
+            Self::Value(value) => write!(f, "value {value}"),
+            Self::Players(Some(players)) => write!(f, "players {players}"),
+            Self::Players(None) => f.write_str("players"),
+            Self::Visible(visible) => write!(f, "visible {visible}"),
+            Self::Name(name) => write!(f, "name {name}"),
+        }
+    }
+}
+
+/// What a `bossbar get` command returns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BossbarQuery {
+    Value,
+    Max,
+    /// The number of players who see the bossbar.
+    Players,
+    /// `1` if the bossbar is visible.
+    Visible,
+}
+
+impl Display for BossbarQuery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Value => "value",
+            Self::Max => "max",
+            Self::Players => "players",
+            Self::Visible => "visible",
+        })
+    }
+}
+
+/// A `bossbar` command, made with the methods of [`Bossbar`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum BossbarCommand {
+    Add { id: ResourceLocation, name: Box<TextComponent> },
+    Remove(ResourceLocation),
+    Set { id: ResourceLocation, setting: BossbarSetting },
+    Get { id: ResourceLocation, query: BossbarQuery },
+}
+
+impl Display for BossbarCommand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Add { id, name } => write!(f, "bossbar add {id} {name}"),
+            Self::Remove(id) => write!(f, "bossbar remove {id}"),
+            Self::Set { id, setting } => write!(f, "bossbar set {id} {setting}"),
+            Self::Get { id, query } => write!(f, "bossbar get {id} {query}"),
+        }
+    }
+}
+
+/// A bossbar, by its ID, for making the commands that change it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Bossbar {
+    id: ResourceLocation,
+}
+
+impl Bossbar {
+    pub fn new(id: ResourceLocation) -> Self {
+        Self { id }
+    }
+
+    pub fn id(&self) -> &ResourceLocation {
+        &self.id
+    }
+
+    /// Fails in the game if a bossbar with this ID already exists.
+    pub fn add(&self, name: impl Into<TextComponent>) -> BossbarCommand {
+        BossbarCommand::Add { id: self.id.clone(), name: Box::new(name.into()) }
+    }
+
+    pub fn remove(&self) -> BossbarCommand {
+        BossbarCommand::Remove(self.id.clone())
+    }
+
+    pub fn set(&self, setting: BossbarSetting) -> BossbarCommand {
+        BossbarCommand::Set { id: self.id.clone(), setting }
+    }
+
+    pub fn set_color(&self, color: BossbarColor) -> BossbarCommand {
+        self.set(BossbarSetting::Color(color))
+    }
+
+    pub fn set_style(&self, style: BossbarStyle) -> BossbarCommand {
+        self.set(BossbarSetting::Style(style))
+    }
+
+    /// Fails if `max` is 0 or more than `i32::MAX`, which the game doesn't accept.
+    pub fn set_max(&self, max: u32) -> Result<BossbarCommand, DatapackError> {
+        check_max(max)?;
+        Ok(self.set(BossbarSetting::Max(max)))
+    }
+
+    pub fn set_value(&self, value: u32) -> BossbarCommand {
+        self.set(BossbarSetting::Value(value))
+    }
+
+    /// Shows the bossbar to `players` only, or to nobody with `None`.
+    pub fn set_players(&self, players: impl Into<Option<Selector>>) -> BossbarCommand {
+        self.set(BossbarSetting::Players(players.into()))
+    }
+
+    pub fn set_visible(&self, visible: bool) -> BossbarCommand {
+        self.set(BossbarSetting::Visible(visible))
+    }
+
+    pub fn set_name(&self, name: impl Into<TextComponent>) -> BossbarCommand {
+        self.set(BossbarSetting::Name(Box::new(name.into())))
+    }
+
+    pub fn get(&self, query: BossbarQuery) -> BossbarCommand {
+        BossbarCommand::Get { id: self.id.clone(), query }
+    }
+
+    pub fn get_value(&self) -> BossbarCommand {
+        self.get(BossbarQuery::Value)
+    }
+
+    pub fn get_max(&self) -> BossbarCommand {
+        self.get(BossbarQuery::Max)
+    }
+
+    pub fn get_players(&self) -> BossbarCommand {
+        self.get(BossbarQuery::Players)
+    }
+
+    pub fn get_visible(&self) -> BossbarCommand {
+        self.get(BossbarQuery::Visible)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Declared {
+    bossbar: Bossbar,
+    name: TextComponent,
+    settings: Vec<BossbarSetting>,
+}
+
+/// The bossbars of a datapack, for making the functions that add and remove them all.
+///
+/// Bossbars are saved with the world, so the setup function is meant for the load
+/// function, and the teardown function for an uninstall function.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BossbarRegistry {
+    namespace: String,
+    bossbars: Vec<Declared>,
+}
+
+impl BossbarRegistry {
+    /// A registry whose bossbars are in `namespace`.
+    pub fn new(namespace: impl Into<String>) -> Self {
+        Self { namespace: namespace.into(), bossbars: Vec::new() }
+    }
+
+    /// Declares the bossbar `<namespace>:<path>`, set up with `settings` after it is added.
+    /// Fails if `path` isn't a valid path, if the bossbar was already declared, or if a
+    /// maximum in `settings` is out of range, like [`Bossbar::set_max`].
+    pub fn declare(
+        &mut self,
+        path: &str,
+        name: impl Into<TextComponent>,
+        settings: impl IntoIterator<Item = BossbarSetting>,
+    ) -> Result<Bossbar, DatapackError> {
+        let bossbar = Bossbar::new(ResourceLocation::new(self.namespace.as_str(), path)?);
+        if self.bossbars.iter().any(|declared| declared.bossbar == bossbar) {
+            return Err(DatapackError::Validation(format!("the bossbar '{}' was already declared", bossbar.id)));
+        }
+        let settings: Vec<_> = settings.into_iter().collect();
+        for setting in &settings {
+            if let BossbarSetting::Max(max) = setting {
+                check_max(*max)?;
+            }
+        }
+        self.bossbars.push(Declared { bossbar: bossbar.clone(), name: name.into(), settings });
+        Ok(bossbar)
+    }
+
+    /// Like [`BossbarRegistry::declare`], with a generated ID such as
+    /// `<namespace>:bossbar_0`, for bossbars that are only referenced through the returned
+    /// handle.
+    pub fn declare_generated(
+        &mut self,
+        name: impl Into<TextComponent>,
+        settings: impl IntoIterator<Item = BossbarSetting>,
+    ) -> Result<Bossbar, DatapackError> {
+        let mut index = self.bossbars.len();
+        let mut path = format!("bossbar_{index}");
+        while self.bossbars.iter().any(|declared| declared.bossbar.id.path() == path) {
+            index += 1;
+            path = format!("bossbar_{index}");
+        }
+        self.declare(&path, name, settings)
+    }
+
+    pub fn bossbars(&self) -> impl Iterator<Item = &Bossbar> {
+        self.bossbars.iter().map(|declared| &declared.bossbar)
+    }
+
+    /// A function that adds each bossbar, in the order they were declared, and applies its
+    /// settings.
+    pub fn setup(&self) -> McFunction {
+        let mut function = McFunction::new();
+        for declared in &self.bossbars {
+            function.push(declared.bossbar.add(declared.name.clone()));
+            for setting in &declared.settings {
+                function.push(declared.bossbar.set(setting.clone()));
+            }
+        }
+        function
+    }
+
+    /// A function that removes each bossbar.
+    pub fn teardown(&self) -> McFunction {
+        self.bossbars().map(Bossbar::remove).collect()
+    }
+}
+
+/// Checks that `max` is a maximum `bossbar set` accepts, from 1 to `i32::MAX`.
+fn check_max(max: u32) -> Result<(), DatapackError> {
+    if max == 0 || max > i32::MAX as u32 {
+        return Err(DatapackError::Validation(format!("invalid bossbar maximum: {max} must be between 1 and {}", i32::MAX)));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+    use crate::command::execute::ExecuteCommand;
+
+    fn bossbar() -> Bossbar {
+        Bossbar::new("test:boss".parse().expect("should have parsed the resource location"))
+    }
+
+    #[test]
+    fn commands() {
+        let boss = bossbar();
+        assert_eq!(boss.add(TextComponent::text("Wither").color(Color::DarkPurple)).to_string(), r#"bossbar add test:boss {"text":"Wither","color":"dark_purple"}"#);
+        assert_eq!(boss.remove().to_string(), "bossbar remove test:boss");
+        assert_eq!(boss.set_color(BossbarColor::Purple).to_string(), "bossbar set test:boss color purple");
+        assert_eq!(boss.set_style(BossbarStyle::Notched10).to_string(), "bossbar set test:boss style notched_10");
+        assert_eq!(boss.set_max(300).expect("should have accepted the maximum").to_string(), "bossbar set test:boss max 300");
+        assert_eq!(boss.set_value(150).to_string(), "bossbar set test:boss value 150");
+        assert_eq!(boss.set_players(Selector::all_players()).to_string(), "bossbar set test:boss players @a");
+        assert_eq!(boss.set_players(None).to_string(), "bossbar set test:boss players");
+        assert_eq!(boss.set_visible(false).to_string(), "bossbar set test:boss visible false");
+        assert_eq!(boss.set_name(TextComponent::text("Phase 2")).to_string(), r#"bossbar set test:boss name "Phase 2""#);
+        assert_eq!(boss.get_value().to_string(), "bossbar get test:boss value");
+        assert_eq!(boss.get_max().to_string(), "bossbar get test:boss max");
+        assert_eq!(boss.get_players().to_string(), "bossbar get test:boss players");
+        assert_eq!(boss.get_visible().to_string(), "bossbar get test:boss visible");
+    }
+
+    #[test]
+    fn max_range() {
+        let boss = bossbar();
+        assert!(boss.set_max(0).is_err(), "a maximum of 0 should have been rejected");
+        assert!(boss.set_max(i32::MAX as u32 + 1).is_err(), "a maximum above i32::MAX should have been rejected");
+        assert_eq!(boss.set_max(i32::MAX as u32).expect("should have accepted the maximum").to_string(), "bossbar set test:boss max 2147483647");
+        let mut registry = BossbarRegistry::new("test");
+        assert!(registry.declare("timer", TextComponent::text("Timer"), [BossbarSetting::Max(0)]).is_err(), "a maximum of 0 should have been rejected");
+    }
+
+    #[test]
+    fn execute_store() {
+        let command = ExecuteCommand::new()
+            .store_result_bossbar(&bossbar(), BossbarValue::Value)
+            .run("scoreboard players get #boss health")
+            .build()
+            .expect("should have built the command");
+        assert_eq!(command, "execute store result bossbar test:boss value run scoreboard players get #boss health");
+
+        let command = ExecuteCommand::new()
+            .store_result_score(Selector::executing_entity(), "max")
+            .run(bossbar().get_max())
+            .build()
+            .expect("should have built the command");
+        assert_eq!(command, "execute store result score @s max run bossbar get test:boss max");
+    }
+
+    #[test]
+    fn registry() {
+        let mut registry = BossbarRegistry::new("test");
+        let timer = registry
+            .declare("timer", TextComponent::text("Time left"), [BossbarSetting::Color(BossbarColor::Green), BossbarSetting::Max(600)])
+            .expect("should have declared the bossbar");
+        assert!(registry.declare("timer", TextComponent::text("Again"), []).is_err(), "the ID is taken");
+        let generated = registry.declare_generated(TextComponent::text("Boss"), []).expect("should have declared the bossbar");
+        assert_eq!(timer.id().to_string(), "test:timer");
+        assert_eq!(generated.id().to_string(), "test:bossbar_1");
+
+        assert_eq!(registry.setup().commands(), [
+            r#"bossbar add test:timer "Time left""#,
+            "bossbar set test:timer color green",
+            "bossbar set test:timer max 600",
+            r#"bossbar add test:bossbar_1 "Boss""#,
+        ]);
+        assert_eq!(registry.teardown().commands(), ["bossbar remove test:timer", "bossbar remove test:bossbar_1"]);
+    }
+}
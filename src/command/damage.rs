@@ -0,0 +1,101 @@
+use std::fmt::{self, Display};
+use crate::command::position::Position;
+use crate::command::selector::Selector;
+use crate::resource_location::ResourceLocation;
+
+/// Where the damage of a [`DamageCommand`] comes from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DamageSource {
+    At(Position),
+    /// Dealt by `entity`, such as an arrow, which `cause`, such as the player who shot it,
+    /// is blamed for.
+    By { entity: Selector, cause: Option<Selector> },
+}
+
+impl Display for DamageSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::At(position) => write!(f, "at {position}"),
+            Self::By { entity, cause: None } => write!(f, "by {entity}"),
+            Self::By { entity, cause: Some(cause) } => write!(f, "by {entity} from {cause}"),
+        }
+    }
+}
+
+/// A `damage` command, available since 1.19.4.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DamageCommand {
+    target: Selector,
+    amount: f32,
+    damage_type: Option<ResourceLocation>,
+    source: Option<DamageSource>,
+}
+
+impl DamageCommand {
+    pub fn new(target: Selector, amount: f32) -> Self {
+        Self { target, amount, damage_type: None, source: None }
+    }
+
+    /// A damage type, such as one added with
+    /// [`Namespace::add_damage_type`](crate::datapack::namespace::Namespace::add_damage_type).
+    pub fn damage_type(mut self, damage_type: ResourceLocation) -> Self {
+        self.damage_type = Some(damage_type);
+        self
+    }
+
+    pub fn at(mut self, position: Position) -> Self {
+        self.source = Some(DamageSource::At(position));
+        self
+    }
+
+    pub fn by(mut self, entity: Selector) -> Self {
+        self.source = Some(DamageSource::By { entity, cause: None });
+        self
+    }
+
+    pub fn by_from(mut self, entity: Selector, cause: Selector) -> Self {
+        self.source = Some(DamageSource::By { entity, cause: Some(cause) });
+        self
+    }
+}
+
+impl Display for DamageCommand {
+    /// A source needs a damage type before it, so `minecraft:generic` is written if a
+    /// source is set without one.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "damage {} {}", self.target, self.amount)?;
+        match (&self.damage_type, &self.source) {
+            (Some(damage_type), None) => write!(f, " {damage_type}"),
+            (Some(damage_type), Some(source)) => write!(f, " {damage_type} {source}"),
+            (None, Some(source)) => write!(f, " minecraft:generic {source}"),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::selector::SelectorBase;
+
+    fn location(location: &str) -> ResourceLocation {
+        location.parse().expect("should have parsed the resource location")
+    }
+
+    #[test]
+    fn damage_command_display() {
+        let this = || Selector::new(SelectorBase::ExecutingEntity);
+        let players = || Selector::new(SelectorBase::AllPlayers);
+        assert_eq!(DamageCommand::new(this(), 4.0).to_string(), "damage @s 4");
+        assert_eq!(DamageCommand::new(this(), 2.5).damage_type(location("test:spikes")).to_string(), "damage @s 2.5 test:spikes");
+        assert_eq!(
+            DamageCommand::new(this(), 1.0).damage_type(location("test:spikes")).at(Position::absolute(0.0, 64.0, 0.0)).to_string(),
+            "damage @s 1 test:spikes at 0 64 0",
+        );
+        assert_eq!(
+            DamageCommand::new(players(), 6.0).damage_type(location("minecraft:arrow")).by_from(this(), players()).to_string(),
+            "damage @a 6 minecraft:arrow by @s from @a",
+        );
+        assert_eq!(DamageCommand::new(this(), 1.0).by(players()).to_string(), "damage @s 1 minecraft:generic by @a");
+    }
+}
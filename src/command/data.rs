@@ -0,0 +1,369 @@
+use std::fmt::{self, Display};
+use crate::command::position::Position;
+use crate::command::selector::Selector;
+use crate::nbt::{NbtCompound, Snbt};
+use crate::resource_location::ResourceLocation;
+
+/// Where a `data` command reads or writes NBT.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DataTarget {
+    Block(Position),
+    Entity(Selector),
+    Storage(ResourceLocation),
+}
+
+impl Display for DataTarget {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Block(position) => write!(f, "block {position}"),
+            Self::Entity(selector) => write!(f, "entity {selector}"),
+            Self::Storage(location) => write!(f, "storage {location}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NbtPathNode {
+    /// `{...}` at the start of a path, matching the root compound.
+    Root(NbtCompound),
+    Key(String),
+    /// `key{...}`
+    KeyMatching(String, NbtCompound),
+    Index(i32),
+    /// `[]`
+    AllElements,
+    /// `[{...}]`
+    ElementsMatching(NbtCompound),
+}
+
+/// A path into NBT such as `Items[{id:"minecraft:diamond"}].tag.display`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NbtPath {
+    nodes: Vec<NbtPathNode>,
+}
+
+impl NbtPath {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn root_matching(compound: NbtCompound) -> Self {
+        Self { nodes: vec![NbtPathNode::Root(compound)] }
+    }
+
+    pub fn node(mut self, node: NbtPathNode) -> Self {
+        self.nodes.push(node);
+        self
+    }
+
+    pub fn key(self, key: impl Into<String>) -> Self {
+        self.node(NbtPathNode::Key(key.into()))
+    }
+
+    pub fn key_matching(self, key: impl Into<String>, compound: NbtCompound) -> Self {
+        self.node(NbtPathNode::KeyMatching(key.into(), compound))
+    }
+
+    pub fn index(self, index: i32) -> Self {
+        self.node(NbtPathNode::Index(index))
+    }
+
+    pub fn all_elements(self) -> Self {
+        self.node(NbtPathNode::AllElements)
+    }
+
+    pub fn elements_matching(self, compound: NbtCompound) -> Self {
+        self.node(NbtPathNode::ElementsMatching(compound))
+    }
+
+    pub fn nodes(&self) -> &[NbtPathNode] {
+        &self.nodes
+    }
+}
+
+impl Display for NbtPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (index, node) in self.nodes.iter().enumerate() {
+            let is_key = matches!(node, NbtPathNode::Key(_) | NbtPathNode::KeyMatching(..));
+            if is_key && index > 0 {
+                write!(f, ".")?;
+            }
+            match node {
+                NbtPathNode::Root(compound) => write!(f, "{compound}")?,
+                NbtPathNode::Key(key) => write!(f, "{}", quote_key(key))?,
+                NbtPathNode::KeyMatching(key, compound) => write!(f, "{}{compound}", quote_key(key))?,
+                NbtPathNode::Index(index) => write!(f, "[{index}]")?,
+                NbtPathNode::AllElements => write!(f, "[]")?,
+                NbtPathNode::ElementsMatching(compound) => write!(f, "[{compound}]")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+fn quote_key(key: &str) -> String {
+    let unquoted = |c: char| !matches!(c, '.' | '[' | ']' | '{' | '}' | '"' | '\'' | ' ' | '\\');
+    if !key.is_empty() && key.chars().all(unquoted) {
+        key.to_string()
+    } else {
+        format!("\"{}\"", key.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModifyOperation {
+    Set,
+    Append,
+    Prepend,
+    Insert(i32),
+    Merge,
+}
+
+impl Display for ModifyOperation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Set => write!(f, "set"),
+            Self::Append => write!(f, "append"),
+            Self::Prepend => write!(f, "prepend"),
+            Self::Insert(index) => write!(f, "insert {index}"),
+            Self::Merge => write!(f, "merge"),
+        }
+    }
+}
+
+/// Where `data modify` takes its new value from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ModifySource {
+    From { source: DataTarget, path: Option<NbtPath> },
+    /// `string <source> [<path>] [<start>] [<end>]`, where `end` is only written after `start`.
+    String { source: DataTarget, path: Option<NbtPath>, start: Option<i32>, end: Option<i32> },
+    Value(Snbt),
+}
+
+impl ModifySource {
+    pub fn from_target(source: DataTarget, path: impl Into<Option<NbtPath>>) -> Self {
+        Self::From { source, path: path.into() }
+    }
+
+    pub fn string(source: DataTarget, path: impl Into<Option<NbtPath>>) -> Self {
+        Self::String { source, path: path.into(), start: None, end: None }
+    }
+
+    pub fn string_slice(source: DataTarget, path: NbtPath, start: i32, end: impl Into<Option<i32>>) -> Self {
+        Self::String { source, path: Some(path), start: Some(start), end: end.into() }
+    }
+
+    pub fn value(value: impl Into<Snbt>) -> Self {
+        Self::Value(value.into())
+    }
+}
+
+impl Display for ModifySource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::From { source, path } => {
+                write!(f, "from {source}")?;
+                write_optional(f, path)
+            }
+            Self::String { source, path, start, end } => {
+                write!(f, "string {source}")?;
+                write_optional(f, path)?;
+                match (start, end) {
+                    (Some(start), _) => write!(f, " {start}")?,
+                    (None, Some(_)) => write!(f, " 0")?,
+                    (None, None) => {}
+                }
+                write_optional(f, end)
+            }
+            Self::Value(value) => write!(f, "value {value}"),
+        }
+    }
+}
+
+/// A `data get|merge|modify|remove` command.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DataCommand {
+    Get { target: DataTarget, path: Option<NbtPath>, scale: Option<f64> },
+    Merge { target: DataTarget, nbt: NbtCompound },
+    Modify { target: DataTarget, path: NbtPath, operation: ModifyOperation, source: ModifySource },
+    Remove { target: DataTarget, path: NbtPath },
+}
+
+impl DataCommand {
+    /// A scale is only written when `path` is given, since the game requires one.
+    pub fn get(target: DataTarget, path: impl Into<Option<NbtPath>>, scale: impl Into<Option<f64>>) -> Self {
+        Self::Get { target, path: path.into(), scale: scale.into() }
+    }
+
+    pub fn merge(target: DataTarget, nbt: NbtCompound) -> Self {
+        Self::Merge { target, nbt }
+    }
+
+    pub fn modify(target: DataTarget, path: NbtPath, operation: ModifyOperation, source: ModifySource) -> Self {
+        Self::Modify { target, path, operation, source }
+    }
+
+    /// `data modify <target> <path> set value <value>`, the most common modification.
+    pub fn modify_set(target: DataTarget, path: NbtPath, value: impl Into<Snbt>) -> Self {
+        Self::modify(target, path, ModifyOperation::Set, ModifySource::value(value))
+    }
+
+    pub fn remove(target: DataTarget, path: NbtPath) -> Self {
+        Self::Remove { target, path }
+    }
+}
+
+impl Display for DataCommand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Get { target, path, scale } => {
+                write!(f, "data get {target}")?;
+                if let Some(path) = path {
+                    write!(f, " {path}")?;
+                    write_optional(f, scale)?;
+                }
+                Ok(())
+            }
+            Self::Merge { target, nbt } => write!(f, "data merge {target} {nbt}"),
+            Self::Modify { target, path, operation, source } => write!(f, "data modify {target} {path} {operation} {source}"),
+            Self::Remove { target, path } => write!(f, "data remove {target} {path}"),
+        }
+    }
+}
+
+fn write_optional(f: &mut fmt::Formatter, value: &Option<impl Display>) -> fmt::Result {
+    match value {
+        Some(value) => write!(f, " {value}"),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nbt_compound;
+
+    fn storage(location: &str) -> DataTarget {
+        DataTarget::Storage(location.parse().expect("should have parsed the resource location"))
+    }
+
+    #[test]
+    fn nbt_path_display() {
+        let path = NbtPath::new().key("Items").index(0).key("tag").key("custom");
+        assert_eq!(path.to_string(), "Items[0].tag.custom");
+
+        let path = NbtPath::new()
+            .key("Items")
+            .elements_matching(nbt_compound! { "id" => "minecraft:diamond" })
+            .key("count");
+        assert_eq!(path.to_string(), r#"Items[{id:"minecraft:diamond"}].count"#);
+
+        let path = NbtPath::root_matching(nbt_compound! { "OnGround" => true }).key("Motion").all_elements();
+        assert_eq!(path.to_string(), "{OnGround:1b}.Motion[]");
+
+        let path = NbtPath::new().key_matching("Item", nbt_compound! { "Count" => 1i8 }).key("id");
+        assert_eq!(path.to_string(), "Item{Count:1b}.id");
+    }
+
+    #[test]
+    fn nbt_path_quotes_keys() {
+        let path = NbtPath::new().key("components").key("minecraft:custom_data").key("my key").key("a.b");
+        assert_eq!(path.to_string(), r#"components.minecraft:custom_data."my key"."a.b""#);
+    }
+
+    #[test]
+    fn get_merge_remove() {
+        let entity = DataTarget::Entity(Selector::executing_entity());
+        assert_eq!(DataCommand::get(entity.clone(), None, None).to_string(), "data get entity @s");
+        assert_eq!(
+            DataCommand::get(entity.clone(), NbtPath::new().key("Pos").index(1), 100.0).to_string(),
+            "data get entity @s Pos[1] 100",
+        );
+        assert_eq!(
+            DataCommand::merge(DataTarget::Block(Position::absolute(0.0, 64.0, 0.0)), nbt_compound! { "Lock" => "key" }).to_string(),
+            r#"data merge block 0 64 0 {Lock:"key"}"#,
+        );
+        assert_eq!(
+            DataCommand::remove(entity, NbtPath::new().key("Tags").index(-1)).to_string(),
+            "data remove entity @s Tags[-1]",
+        );
+    }
+
+    #[test]
+    fn storage_merge_and_set() {
+        let config = nbt_compound! { "enabled" => true, "radius" => 8 };
+        assert_eq!(DataCommand::merge(storage("test:config"), config).to_string(), "data merge storage test:config {enabled:1b,radius:8}");
+        assert_eq!(
+            DataCommand::modify_set(storage("test:config"), NbtPath::new().key("radius"), 16).to_string(),
+            "data modify storage test:config radius set value 16",
+        );
+    }
+
+    #[test]
+    fn modify_from_storage() {
+        let command = DataCommand::modify(
+            DataTarget::Entity(Selector::executing_entity()),
+            NbtPath::new().key("Items").index(0).key("tag").key("custom"),
+            ModifyOperation::Set,
+            ModifySource::from_target(storage("test:items"), NbtPath::new().key("template")),
+        );
+        assert_eq!(command.to_string(), "data modify entity @s Items[0].tag.custom set from storage test:items template");
+
+        let command = DataCommand::modify(
+            storage("test:queue"),
+            NbtPath::new().key("pending"),
+            ModifyOperation::Insert(0),
+            ModifySource::from_target(storage("test:queue"), NbtPath::new().key("incoming").index(-1)),
+        );
+        assert_eq!(command.to_string(), "data modify storage test:queue pending insert 0 from storage test:queue incoming[-1]");
+
+        let command = DataCommand::modify(
+            storage("test:copy"),
+            NbtPath::new().key("all"),
+            ModifyOperation::Merge,
+            ModifySource::from_target(storage("test:source"), None),
+        );
+        assert_eq!(command.to_string(), "data modify storage test:copy all merge from storage test:source");
+    }
+
+    #[test]
+    fn modify_string_slices() {
+        let source = DataTarget::Entity(Selector::executing_entity());
+        let name = NbtPath::new().key("CustomName");
+
+        let command = DataCommand::modify(
+            storage("test:text"),
+            NbtPath::new().key("name"),
+            ModifyOperation::Set,
+            ModifySource::string_slice(source.clone(), name.clone(), 1, -1),
+        );
+        assert_eq!(command.to_string(), "data modify storage test:text name set string entity @s CustomName 1 -1");
+
+        let command = DataCommand::modify(
+            storage("test:text"),
+            NbtPath::new().key("rest"),
+            ModifyOperation::Append,
+            ModifySource::string_slice(source.clone(), name, 3, None),
+        );
+        assert_eq!(command.to_string(), "data modify storage test:text rest append string entity @s CustomName 3");
+
+        let command = DataCommand::modify(
+            storage("test:text"),
+            NbtPath::new().key("uuid"),
+            ModifyOperation::Prepend,
+            ModifySource::string(source, NbtPath::new().key("UUID").index(0)),
+        );
+        assert_eq!(command.to_string(), "data modify storage test:text uuid prepend string entity @s UUID[0]");
+    }
+
+    #[test]
+    fn modify_value() {
+        let command = DataCommand::modify(
+            storage("test:state"),
+            NbtPath::new().key("flags"),
+            ModifyOperation::Append,
+            ModifySource::value(nbt_compound! { "name" => "it's \"done\"", "set" => true }),
+        );
+        assert_eq!(command.to_string(), r#"data modify storage test:state flags append value {name:"it's \"done\"",set:1b}"#);
+    }
+}
@@ -0,0 +1,497 @@
+use std::fmt::{self, Display};
+use crate::command::bossbar::{Bossbar, BossbarValue};
+use crate::command::data::{DataTarget, NbtPath};
+use crate::command::function::call_function;
+use crate::command::position::{Coordinate, Position};
+use crate::command::scoreboard::ScoreHolder;
+use crate::command::selector::Selector;
+use crate::error::DatapackError;
+use crate::range::MinecraftRange;
+use crate::resource_location::ResourceLocation;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Anchor {
+    Feet,
+    Eyes,
+}
+
+impl Display for Anchor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Feet => "feet",
+            Self::Eyes => "eyes",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Equal,
+    GreaterOrEqual,
+    Greater,
+}
+
+impl Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Less => "<",
+            Self::LessOrEqual => "<=",
+            Self::Equal => "=",
+            Self::GreaterOrEqual => ">=",
+            Self::Greater => ">",
+        })
+    }
+}
+
+/// What a score is checked against in `if score`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScoreComparison {
+    /// Another holder's score, such as `< @s other`.
+    Compare { comparison: Comparison, source: Selector, objective: String },
+    Matches(MinecraftRange<i32>),
+}
+
+impl Display for ScoreComparison {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Compare { comparison, source, objective } => write!(f, "{comparison} {source} {objective}"),
+            Self::Matches(range) => write!(f, "matches {range}"),
+        }
+    }
+}
+
+/// The condition of an `if` or `unless` subcommand.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExecuteCondition {
+    Entity(Selector),
+    /// A block predicate such as `minecraft:stone` or `#minecraft:logs[axis=y]`.
+    Block(Position, String),
+    Blocks { start: Position, end: Position, destination: Position, masked: bool },
+    Predicate(ResourceLocation),
+    Score { target: ScoreHolder, objective: String, comparison: ScoreComparison },
+    Loaded(Position),
+    Biome(Position, ResourceLocation),
+    Dimension(ResourceLocation),
+    Data(DataTarget, NbtPath),
+}
+
+impl Display for ExecuteCondition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Entity(selector) => write!(f, "entity {selector}"),
+            Self::Block(position, block) => write!(f, "block {position} {block}"),
+            Self::Blocks { start, end, destination, masked } => {
+                write!(f, "blocks {start} {end} {destination} {}", if *masked { "masked" } else { "all" })
+            }
+            Self::Predicate(predicate) => write!(f, "predicate {predicate}"),
+            Self::Score { target, objective, comparison } => write!(f, "score {target} {objective} {comparison}"),
+            Self::Loaded(position) => write!(f, "loaded {position}"),
+            Self::Biome(position, biome) => write!(f, "biome {position} {biome}"),
+            Self::Dimension(dimension) => write!(f, "dimension {dimension}"),
+            Self::Data(target, path) => write!(f, "data {target} {path}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StoreKind {
+    Result,
+    Success,
+}
+
+impl Display for StoreKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Result => "result",
+            Self::Success => "success",
+        })
+    }
+}
+
+/// The numeric NBT type `store` writes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StoreType {
+    Byte,
+    Short,
+    Int,
+    Long,
+    Float,
+    Double,
+}
+
+impl Display for StoreType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Byte => "byte",
+            Self::Short => "short",
+            Self::Int => "int",
+            Self::Long => "long",
+            Self::Float => "float",
+            Self::Double => "double",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StoreTarget {
+    Score { holder: ScoreHolder, objective: String },
+    Nbt { target: DataTarget, path: NbtPath, store_type: StoreType, scale: f64 },
+    Bossbar { id: ResourceLocation, value: BossbarValue },
+}
+
+impl Display for StoreTarget {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Score { holder, objective } => write!(f, "score {holder} {objective}"),
+            Self::Nbt { target, path, store_type, scale } => write!(f, "{target} {path} {store_type} {scale}"),
+            Self::Bossbar { id, value } => write!(f, "bossbar {id} {value}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExecuteSubcommand {
+    As(Selector),
+    At(Selector),
+    In(ResourceLocation),
+    Positioned(Position),
+    PositionedAs(Selector),
+    /// `rotated <yaw> <pitch>`
+    Rotated(Coordinate, Coordinate),
+    RotatedAs(Selector),
+    Facing(Position),
+    FacingEntity(Selector, Anchor),
+    /// The axes to floor, such as `xz`.
+    Align(String),
+    Anchored(Anchor),
+    If(ExecuteCondition),
+    Unless(ExecuteCondition),
+    Store(StoreKind, StoreTarget),
+    Run(String),
+}
+
+impl Display for ExecuteSubcommand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::As(selector) => write!(f, "as {selector}"),
+            Self::At(selector) => write!(f, "at {selector}"),
+            Self::In(dimension) => write!(f, "in {dimension}"),
+            Self::Positioned(position) => write!(f, "positioned {position}"),
+            Self::PositionedAs(selector) => write!(f, "positioned as {selector}"),
+            Self::Rotated(yaw, pitch) => write!(f, "rotated {yaw} {pitch}"),
+            Self::RotatedAs(selector) => write!(f, "rotated as {selector}"),
+            Self::Facing(position) => write!(f, "facing {position}"),
+            Self::FacingEntity(selector, anchor) => write!(f, "facing entity {selector} {anchor}"),
+            Self::Align(axes) => write!(f, "align {axes}"),
+            Self::Anchored(anchor) => write!(f, "anchored {anchor}"),
+            Self::If(condition) => write!(f, "if {condition}"),
+            Self::Unless(condition) => write!(f, "unless {condition}"),
+            Self::Store(kind, target) => write!(f, "store {kind} {target}"),
+            Self::Run(command) => write!(f, "run {command}"),
+        }
+    }
+}
+
+/// A builder for `execute` commands.
+///
+/// ```
+/// use minecraft_datapack_generator::command::execute::ExecuteCommand;
+/// use minecraft_datapack_generator::command::selector::Selector;
+///
+/// let command = ExecuteCommand::new()
+///     .as_(Selector::all_players())
+///     .at(Selector::executing_entity())
+///     .run("particle minecraft:heart ~ ~2 ~")
+///     .build()
+///     .unwrap();
+/// assert_eq!(command, "execute as @a at @s run particle minecraft:heart ~ ~2 ~");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExecuteCommand {
+    subcommands: Vec<ExecuteSubcommand>,
+}
+
+impl ExecuteCommand {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn subcommand(mut self, subcommand: ExecuteSubcommand) -> Self {
+        self.subcommands.push(subcommand);
+        self
+    }
+
+    pub fn subcommands(&self) -> &[ExecuteSubcommand] {
+        &self.subcommands
+    }
+
+    pub fn as_(self, selector: Selector) -> Self {
+        self.subcommand(ExecuteSubcommand::As(selector))
+    }
+
+    pub fn at(self, selector: Selector) -> Self {
+        self.subcommand(ExecuteSubcommand::At(selector))
+    }
+
+    pub fn in_(self, dimension: ResourceLocation) -> Self {
+        self.subcommand(ExecuteSubcommand::In(dimension))
+    }
+
+    pub fn positioned(self, position: Position) -> Self {
+        self.subcommand(ExecuteSubcommand::Positioned(position))
+    }
+
+    pub fn positioned_as(self, selector: Selector) -> Self {
+        self.subcommand(ExecuteSubcommand::PositionedAs(selector))
+    }
+
+    pub fn rotated(self, yaw: Coordinate, pitch: Coordinate) -> Self {
+        self.subcommand(ExecuteSubcommand::Rotated(yaw, pitch))
+    }
+
+    pub fn rotated_as(self, selector: Selector) -> Self {
+        self.subcommand(ExecuteSubcommand::RotatedAs(selector))
+    }
+
+    pub fn facing(self, position: Position) -> Self {
+        self.subcommand(ExecuteSubcommand::Facing(position))
+    }
+
+    pub fn facing_entity(self, selector: Selector, anchor: Anchor) -> Self {
+        self.subcommand(ExecuteSubcommand::FacingEntity(selector, anchor))
+    }
+
+    pub fn align(self, axes: impl Into<String>) -> Self {
+        self.subcommand(ExecuteSubcommand::Align(axes.into()))
+    }
+
+    pub fn anchored(self, anchor: Anchor) -> Self {
+        self.subcommand(ExecuteSubcommand::Anchored(anchor))
+    }
+
+    pub fn if_(self, condition: ExecuteCondition) -> Self {
+        self.subcommand(ExecuteSubcommand::If(condition))
+    }
+
+    pub fn unless(self, condition: ExecuteCondition) -> Self {
+        self.subcommand(ExecuteSubcommand::Unless(condition))
+    }
+
+    pub fn if_entity(self, selector: Selector) -> Self {
+        self.if_(ExecuteCondition::Entity(selector))
+    }
+
+    pub fn unless_entity(self, selector: Selector) -> Self {
+        self.unless(ExecuteCondition::Entity(selector))
+    }
+
+    pub fn if_block(self, position: Position, block: impl Display) -> Self {
+        self.if_(ExecuteCondition::Block(position, block.to_string()))
+    }
+
+    pub fn unless_block(self, position: Position, block: impl Display) -> Self {
+        self.unless(ExecuteCondition::Block(position, block.to_string()))
+    }
+
+    pub fn if_predicate(self, predicate: ResourceLocation) -> Self {
+        self.if_(ExecuteCondition::Predicate(predicate))
+    }
+
+    pub fn unless_predicate(self, predicate: ResourceLocation) -> Self {
+        self.unless(ExecuteCondition::Predicate(predicate))
+    }
+
+    /// Checks the score of `target`, such as an entity or a fake player like `#global`.
+    pub fn if_score(self, target: impl Into<ScoreHolder>, objective: impl Into<String>, comparison: ScoreComparison) -> Self {
+        self.if_(ExecuteCondition::Score { target: target.into(), objective: objective.into(), comparison })
+    }
+
+    pub fn unless_score(self, target: impl Into<ScoreHolder>, objective: impl Into<String>, comparison: ScoreComparison) -> Self {
+        self.unless(ExecuteCondition::Score { target: target.into(), objective: objective.into(), comparison })
+    }
+
+    /// Stores the result in the score of `holder`, such as an entity or a fake player like
+    /// `#global`.
+    pub fn store_result_score(self, holder: impl Into<ScoreHolder>, objective: impl Into<String>) -> Self {
+        self.store(StoreKind::Result, StoreTarget::Score { holder: holder.into(), objective: objective.into() })
+    }
+
+    pub fn store_success_score(self, holder: impl Into<ScoreHolder>, objective: impl Into<String>) -> Self {
+        self.store(StoreKind::Success, StoreTarget::Score { holder: holder.into(), objective: objective.into() })
+    }
+
+    pub fn store_result_bossbar(self, bossbar: &Bossbar, value: BossbarValue) -> Self {
+        self.store(StoreKind::Result, StoreTarget::Bossbar { id: bossbar.id().clone(), value })
+    }
+
+    pub fn store(self, kind: StoreKind, target: StoreTarget) -> Self {
+        self.subcommand(ExecuteSubcommand::Store(kind, target))
+    }
+
+    /// Runs `command`, which is written without a leading slash.
+    pub fn run(self, command: impl Display) -> Self {
+        self.subcommand(ExecuteSubcommand::Run(command.to_string()))
+    }
+
+    /// Runs `function`, such as one that returns a value to `store`.
+    pub fn run_function(self, function: &ResourceLocation) -> Self {
+        self.run(call_function(function))
+    }
+
+    /// Renders the command after checking that the chain ends in `run` (or in a condition,
+    /// which the game accepts as a test) and that nothing follows `run`.
+    pub fn build(&self) -> Result<String, DatapackError> {
+        let invalid = |message: &str| Err(DatapackError::Validation(format!("invalid execute command: {message}")));
+        match self.subcommands.last() {
+            None => return invalid("it has no subcommands"),
+            Some(ExecuteSubcommand::Run(_) | ExecuteSubcommand::If(_) | ExecuteSubcommand::Unless(_)) => {}
+            Some(_) => return invalid("it doesn't end with a 'run' subcommand"),
+        }
+        let last = self.subcommands.len() - 1;
+        for (index, subcommand) in self.subcommands.iter().enumerate() {
+            match subcommand {
+                ExecuteSubcommand::Run(_) if index != last => return invalid("'run' must be the last subcommand"),
+                ExecuteSubcommand::Run(command) if command.is_empty() || command.starts_with('/') => {
+                    return invalid("'run' needs a command without a leading slash");
+                }
+                ExecuteSubcommand::Rotated(yaw, pitch) if yaw.is_local() || pitch.is_local() => {
+                    return invalid("'rotated' can't use local coordinates");
+                }
+                ExecuteSubcommand::Align(axes) if !is_swizzle(axes) => {
+                    return invalid("'align' needs a combination of the axes 'x', 'y' and 'z'");
+                }
+                _ => {}
+            }
+        }
+        let subcommands = self.subcommands.iter().map(ToString::to_string).collect::<Vec<_>>();
+        Ok(format!("execute {}", subcommands.join(" ")))
+    }
+}
+
+fn is_swizzle(axes: &str) -> bool {
+    let mut seen = Vec::new();
+    !axes.is_empty() && axes.chars().all(|axis| {
+        let new = matches!(axis, 'x' | 'y' | 'z') && !seen.contains(&axis);
+        seen.push(axis);
+        new
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block_state::BlockState;
+    use crate::command::data::{DataCommand, DataTarget};
+
+    fn location(location: &str) -> ResourceLocation {
+        location.parse().expect("should have parsed the resource location")
+    }
+
+    #[test]
+    fn store_function_result() {
+        let command = ExecuteCommand::new()
+            .store_result_score(Selector::executing_entity(), "count")
+            .run_function(&location("test:count_items"))
+            .build()
+            .expect("should have built the command");
+        assert_eq!(command, "execute store result score @s count run function test:count_items");
+    }
+
+    #[test]
+    fn store_in_fake_player() {
+        let command = ExecuteCommand::new()
+            .store_result_score("#total", "count")
+            .store_success_score("#found", "count")
+            .run("clear @a minecraft:stone 0")
+            .build()
+            .expect("should have built the command");
+        assert_eq!(command, "execute store result score #total count store success score #found count run clear @a minecraft:stone 0");
+    }
+
+    #[test]
+    fn if_block_state() {
+        let lit = BlockState::new(location("minecraft:furnace")).property("lit", "true");
+        let command = ExecuteCommand::new().if_block(Position::absolute(0.0, 64.0, 0.0), lit).build().expect("should have built the command");
+        assert_eq!(command, "execute if block 0 64 0 minecraft:furnace[lit=true]");
+    }
+
+    #[test]
+    fn long_chain() {
+        let command = ExecuteCommand::new()
+            .as_(Selector::all_entities().type_("minecraft:zombie"))
+            .at(Selector::executing_entity())
+            .in_(location("minecraft:the_nether"))
+            .positioned(Position::relative(0.0, 1.0, 0.0))
+            .rotated(Coordinate::Relative(90.0), Coordinate::Absolute(0.0))
+            .facing_entity(Selector::nearest_player(), Anchor::Eyes)
+            .align("xz")
+            .anchored(Anchor::Eyes)
+            .if_block(Position::relative(0.0, -1.0, 0.0), "#minecraft:logs")
+            .unless_entity(Selector::executing_entity().tag("done"))
+            .if_score(Selector::executing_entity(), "timer", ScoreComparison::Matches((5..).into()))
+            .store_result_score(Selector::executing_entity(), "result")
+            .run("say hi")
+            .build()
+            .expect("should have built the command");
+        assert_eq!(
+            command,
+            "execute as @e[type=minecraft:zombie] at @s in minecraft:the_nether positioned ~ ~1 ~ rotated ~90 0 \
+             facing entity @p eyes align xz anchored eyes if block ~ ~-1 ~ #minecraft:logs unless entity @s[tag=done] \
+             if score @s timer matches 5.. store result score @s result run say hi",
+        );
+    }
+
+    #[test]
+    fn store_nbt_and_run_command() {
+        let storage = DataTarget::Storage(location("test:state"));
+        let command = ExecuteCommand::new()
+            .store(StoreKind::Success, StoreTarget::Nbt {
+                target: storage.clone(),
+                path: NbtPath::new().key("found"),
+                store_type: StoreType::Byte,
+                scale: 1.0,
+            })
+            .if_score(Selector::executing_entity(), "a", ScoreComparison::Compare {
+                comparison: Comparison::LessOrEqual,
+                source: Selector::executing_entity(),
+                objective: "b".to_string(),
+            })
+            .run(DataCommand::get(storage, None, None))
+            .build()
+            .expect("should have built the command");
+        assert_eq!(
+            command,
+            "execute store success storage test:state found byte 1 if score @s a <= @s b run data get storage test:state",
+        );
+    }
+
+    #[test]
+    fn condition_without_run() {
+        let command = ExecuteCommand::new()
+            .if_predicate(location("test:is_night"))
+            .build()
+            .expect("a trailing condition should be accepted");
+        assert_eq!(command, "execute if predicate test:is_night");
+    }
+
+    #[test]
+    fn invalid_chains() {
+        let invalid = [
+            ExecuteCommand::new(),
+            ExecuteCommand::new().as_(Selector::all_players()),
+            ExecuteCommand::new().run("say hi").at(Selector::executing_entity()),
+            ExecuteCommand::new().run("/say hi"),
+            ExecuteCommand::new().align("xx").run("say hi"),
+            ExecuteCommand::new().align("w").run("say hi"),
+            ExecuteCommand::new().rotated(Coordinate::Local(0.0), Coordinate::Local(0.0)).run("say hi"),
+        ];
+        for command in invalid {
+            match command.build() {
+                Err(DatapackError::Validation(_)) => {}
+                result => panic!("{:?} should have been rejected, got {result:?}", command.subcommands()),
+            }
+        }
+    }
+}
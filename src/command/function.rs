@@ -0,0 +1,129 @@
+use std::fmt::{self, Display};
+use crate::command::data::{DataTarget, NbtPath};
+use crate::data::function::McFunction;
+use crate::datapack::pack_format::MACRO_PACK_FORMAT;
+use crate::error::DatapackError;
+use crate::nbt::NbtCompound;
+use crate::resource_location::ResourceLocation;
+
+/// `function <function>`, which runs `function` right away, before the rest of the
+/// calling function.
+pub fn call_function(function: &ResourceLocation) -> String {
+    format!("function {function}")
+}
+
+/// `function #<tag>`, which runs every function of the function tag `tag`.
+pub fn call_function_tag(tag: &ResourceLocation) -> String {
+    format!("function #{tag}")
+}
+
+/// The arguments a macro function is called with.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FunctionArguments {
+    /// `{name:value,...}`
+    Compound(NbtCompound),
+    /// `with <source> [<path>]`, the compound at `path` of `source`, or all of it.
+    Data { source: DataTarget, path: Option<NbtPath> },
+}
+
+impl Display for FunctionArguments {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Compound(compound) => write!(f, "{compound}"),
+            Self::Data { source, path: Some(path) } => write!(f, "with {source} {path}"),
+            Self::Data { source, path: None } => write!(f, "with {source}"),
+        }
+    }
+}
+
+/// A `function` command that can pass arguments to a macro function.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionCall {
+    pub function: ResourceLocation,
+    pub arguments: Option<FunctionArguments>,
+}
+
+impl FunctionCall {
+    pub fn new(function: ResourceLocation) -> Self {
+        Self { function, arguments: None }
+    }
+
+    pub fn arguments(mut self, arguments: NbtCompound) -> Self {
+        self.arguments = Some(FunctionArguments::Compound(arguments));
+        self
+    }
+
+    pub fn with_data(mut self, source: DataTarget, path: impl Into<Option<NbtPath>>) -> Self {
+        self.arguments = Some(FunctionArguments::Data { source, path: path.into() });
+        self
+    }
+
+    /// Checks that `callee`, the called function, gets the arguments it needs: any at all if
+    /// it has macro lines, and each it uses or declared if they are given as a compound.
+    pub fn check_callee(&self, callee: &McFunction) -> Result<(), DatapackError> {
+        let invalid = |message: String| Err(DatapackError::Validation(format!("invalid call of '{}': {message}", self.function)));
+        match &self.arguments {
+            None if callee.is_macro() => invalid("it uses macros, so it needs arguments".to_string()),
+            Some(FunctionArguments::Compound(arguments)) => {
+                let needed = callee.params().map_or_else(|| callee.macro_params(), |params| params.iter().map(String::as_str).collect());
+                match needed.into_iter().find(|param| !arguments.contains_key(param)) {
+                    Some(missing) => invalid(format!("the argument '{missing}' is missing")),
+                    None => Ok(()),
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// The command for a datapack of `pack_format`. Fails if it has arguments and
+    /// `pack_format` is older than 18, which added macros.
+    pub fn render(&self, pack_format: u32) -> Result<String, DatapackError> {
+        match &self.arguments {
+            Some(_) if pack_format < MACRO_PACK_FORMAT => Err(DatapackError::Validation(format!(
+                "invalid call of '{}': arguments need pack format {MACRO_PACK_FORMAT} or newer, but the pack format is {pack_format}",
+                self.function,
+            ))),
+            Some(arguments) => Ok(format!("function {} {arguments}", self.function)),
+            None => Ok(call_function(&self.function)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::selector::Selector;
+
+    fn location(location: &str) -> ResourceLocation {
+        location.parse().expect("should have parsed the resource location")
+    }
+
+    #[test]
+    fn function_calls() {
+        assert_eq!(call_function(&location("test:tick/players")), "function test:tick/players");
+        assert_eq!(call_function_tag(&location("minecraft:load")), "function #minecraft:load");
+    }
+
+    #[test]
+    fn call_with_arguments() {
+        let teleport = McFunction::new().macro_line("tp @s $(x) $(y) $(z)").with_params(["x", "y", "z"]);
+
+        let call = FunctionCall::new(location("test:teleport")).with_data(DataTarget::Storage(location("test:args")), NbtPath::new().key("spawn"));
+        assert_eq!(call.render(48).expect("should have rendered the call"), "function test:teleport with storage test:args spawn");
+        assert!(call.check_callee(&teleport).is_ok());
+        assert!(call.render(15).is_err(), "arguments need pack format 18");
+
+        let call = FunctionCall::new(location("test:teleport")).with_data(DataTarget::Entity(Selector::executing_entity()), None);
+        assert_eq!(call.render(48).expect("should have rendered the call"), "function test:teleport with entity @s");
+
+        let arguments = NbtCompound::new().with("x", 1).with("y", 64);
+        let call = FunctionCall::new(location("test:teleport")).arguments(arguments.clone());
+        assert_eq!(call.render(48).expect("should have rendered the call"), "function test:teleport {x:1,y:64}");
+        assert!(call.check_callee(&teleport).is_err(), "'z' is missing");
+        assert!(FunctionCall::new(location("test:teleport")).arguments(arguments.with("z", 0)).check_callee(&teleport).is_ok());
+
+        let plain = FunctionCall::new(location("test:teleport"));
+        assert_eq!(plain.render(15).expect("should have rendered the call"), "function test:teleport");
+        assert!(plain.check_callee(&teleport).is_err(), "a macro function needs arguments");
+    }
+}
@@ -0,0 +1,249 @@
+use std::fmt::{self, Display};
+use crate::command::position::Position;
+use crate::command::selector::Selector;
+use crate::datapack::pack_format::ITEM_COMPONENTS_PACK_FORMAT;
+use crate::error::DatapackError;
+use crate::item_stack::{ItemComponent, ItemFilter, ItemStack};
+use crate::resource_location::ResourceLocation;
+
+/// The block or entities whose slots an `item` command changes or copies from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ItemTarget {
+    Block(Position),
+    Entity(Selector),
+}
+
+impl Display for ItemTarget {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Block(position) => write!(f, "block {position}"),
+            Self::Entity(selector) => write!(f, "entity {selector}"),
+        }
+    }
+}
+
+/// An `item modify|replace` command. Slots are written as is, such as `weapon.mainhand`
+/// or `container.0`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ItemCommand {
+    /// Applies the item modifier file `modifier` to the item in `slot`.
+    Modify { target: ItemTarget, slot: String, modifier: ResourceLocation },
+    /// Puts `count` of `item`, such as `minecraft:stone` or an item with components, in `slot`.
+    ReplaceWith { target: ItemTarget, slot: String, item: String, count: Option<u32> },
+    /// Copies the item in `source_slot` of `source` to `slot`, through `modifier` if set.
+    ReplaceFrom { target: ItemTarget, slot: String, source: ItemTarget, source_slot: String, modifier: Option<ResourceLocation> },
+}
+
+impl ItemCommand {
+    pub fn modify(target: ItemTarget, slot: impl Into<String>, modifier: ResourceLocation) -> Self {
+        Self::Modify { target, slot: slot.into(), modifier }
+    }
+
+    pub fn replace_with(target: ItemTarget, slot: impl Into<String>, item: impl Display, count: impl Into<Option<u32>>) -> Self {
+        Self::ReplaceWith { target, slot: slot.into(), item: item.to_string(), count: count.into() }
+    }
+
+    /// Puts `stack` in `slot`, with its components written for a datapack of `pack_format`.
+    /// Fails for an empty stack.
+    pub fn replace_with_stack(target: ItemTarget, slot: impl Into<String>, stack: &ItemStack, pack_format: u32) -> Result<Self, DatapackError> {
+        let count = stack.checked_count()?;
+        Ok(Self::replace_with(target, slot, stack.render(pack_format)?, (count != 1).then_some(count)))
+    }
+
+    pub fn replace_from(
+        target: ItemTarget,
+        slot: impl Into<String>,
+        source: ItemTarget,
+        source_slot: impl Into<String>,
+        modifier: impl Into<Option<ResourceLocation>>,
+    ) -> Self {
+        Self::ReplaceFrom { target, slot: slot.into(), source, source_slot: source_slot.into(), modifier: modifier.into() }
+    }
+}
+
+impl Display for ItemCommand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Modify { target, slot, modifier } => write!(f, "item modify {target} {slot} {modifier}"),
+            Self::ReplaceWith { target, slot, item, count } => {
+                write!(f, "item replace {target} {slot} with {item}")?;
+                match count {
+                    Some(count) => write!(f, " {count}"),
+                    None => Ok(()),
+                }
+            }
+            Self::ReplaceFrom { target, slot, source, source_slot, modifier } => {
+                write!(f, "item replace {target} {slot} from {source} {source_slot}")?;
+                match modifier {
+                    Some(modifier) => write!(f, " {modifier}"),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+/// A `give` command. Unlike `item replace`, the items go to the first free slots, and
+/// are dropped if the inventory is full.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GiveCommand {
+    pub targets: Selector,
+    pub item: ItemStack,
+}
+
+impl GiveCommand {
+    pub fn new(targets: Selector, item: ItemStack) -> Self {
+        Self { targets, item }
+    }
+
+    /// The command for a datapack of `pack_format`, which decides how the item's components
+    /// are written. Fails for an empty stack.
+    pub fn render(&self, pack_format: u32) -> Result<String, DatapackError> {
+        let count = self.item.checked_count()?;
+        let mut command = format!("give {} {}", self.targets, self.item.render(pack_format)?);
+        if count != 1 {
+            command += &format!(" {count}");
+        }
+        Ok(command)
+    }
+}
+
+/// A `give` command with `components` in brackets, such as
+/// `give @s minecraft:stick[minecraft:unbreakable={}] 2`, as read since 1.20.5.
+///
+/// Text in components is written as JSON strings, as before 1.21.5. For other pack
+/// formats, use [`GiveCommand::render`].
+pub fn give(
+    targets: &Selector,
+    item: ResourceLocation,
+    count: u32,
+    components: impl IntoIterator<Item = ItemComponent>,
+) -> Result<String, DatapackError> {
+    let mut stack = ItemStack::new(item).count(count);
+    stack.components.extend(components);
+    GiveCommand::new(targets.clone(), stack).render(ITEM_COMPONENTS_PACK_FORMAT)
+}
+
+/// A `clear` command, which removes the items matching `item`, or every item, from the
+/// inventories of `targets`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClearCommand {
+    pub targets: Selector,
+    pub item: Option<ItemFilter>,
+    /// At most how many items are removed. With `0`, nothing is removed, but the matching
+    /// items are still counted.
+    pub max_count: Option<u32>,
+}
+
+impl ClearCommand {
+    pub fn new(targets: Selector) -> Self {
+        Self { targets, item: None, max_count: None }
+    }
+
+    pub fn item(mut self, item: impl Into<ItemFilter>) -> Self {
+        self.item = Some(item.into());
+        self
+    }
+
+    /// Only written with an item, since the command takes it first.
+    pub fn max_count(mut self, max_count: u32) -> Self {
+        self.max_count = Some(max_count);
+        self
+    }
+
+    pub fn render(&self, pack_format: u32) -> Result<String, DatapackError> {
+        let mut command = format!("clear {}", self.targets);
+        if let Some(item) = &self.item {
+            command += &format!(" {}", item.render(pack_format)?);
+            if let Some(max_count) = self.max_count {
+                command += &format!(" {max_count}");
+            }
+        }
+        Ok(command)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+    use crate::command::selector::SelectorBase;
+    use crate::data::tag::TagValue;
+    use crate::text::TextComponent;
+
+    fn location(location: &str) -> ResourceLocation {
+        location.parse().expect("should have parsed the resource location")
+    }
+
+    #[test]
+    fn item_command_display() {
+        let player = ItemTarget::Entity(Selector::new(SelectorBase::ExecutingEntity));
+        let chest = ItemTarget::Block(Position::absolute(0.0, 64.0, 0.0));
+
+        let command = ItemCommand::modify(player.clone(), "weapon.mainhand", location("test:rename"));
+        assert_eq!(command.to_string(), "item modify entity @s weapon.mainhand test:rename");
+
+        let command = ItemCommand::replace_with(chest.clone(), "container.0", location("minecraft:diamond"), 3);
+        assert_eq!(command.to_string(), "item replace block 0 64 0 container.0 with minecraft:diamond 3");
+
+        let command = ItemCommand::replace_from(player, "armor.head", chest, "container.0", location("test:rename"));
+        assert_eq!(command.to_string(), "item replace entity @s armor.head from block 0 64 0 container.0 test:rename");
+    }
+
+    fn excalibur() -> ItemStack {
+        ItemStack::new(location("minecraft:diamond_sword"))
+            .custom_name(TextComponent::text("Excalibur").color(Color::Gold))
+            .enchantment(location("minecraft:sharpness"), 5)
+    }
+
+    /// The same command for 1.20.4 and 1.21.
+    #[test]
+    fn give_by_pack_format() {
+        let give = GiveCommand::new(Selector::new(SelectorBase::AllPlayers), excalibur());
+        assert_eq!(
+            give.render(26).expect("should have rendered the command"),
+            r#"give @a minecraft:diamond_sword{display:{Name:'{"text":"Excalibur","color":"gold"}'},Enchantments:[{id:"minecraft:sharpness",lvl:5s}]}"#,
+        );
+        assert_eq!(
+            give.render(48).expect("should have rendered the command"),
+            r#"give @a minecraft:diamond_sword[minecraft:custom_name='{"text":"Excalibur","color":"gold"}',minecraft:enchantments={levels:{"minecraft:sharpness":5}}]"#,
+        );
+
+        let give = GiveCommand::new(Selector::new(SelectorBase::ExecutingEntity), ItemStack::new(location("minecraft:stone")).count(64));
+        assert_eq!(give.render(48).expect("should have rendered the command"), "give @s minecraft:stone 64");
+
+        let command = ItemCommand::replace_with_stack(ItemTarget::Entity(Selector::new(SelectorBase::ExecutingEntity)), "weapon.mainhand", &excalibur(), 26)
+            .expect("should have rendered the item");
+        assert_eq!(
+            command.to_string(),
+            r#"item replace entity @s weapon.mainhand with minecraft:diamond_sword{display:{Name:'{"text":"Excalibur","color":"gold"}'},Enchantments:[{id:"minecraft:sharpness",lvl:5s}]}"#,
+        );
+    }
+
+    #[test]
+    fn give_helper() {
+        let player = Selector::new(SelectorBase::ExecutingEntity);
+        assert_eq!(give(&player, location("minecraft:bread"), 1, []).expect("should have rendered the command"), "give @s minecraft:bread");
+        assert_eq!(give(&player, location("minecraft:bread"), 16, []).expect("should have rendered the command"), "give @s minecraft:bread 16");
+
+        let components = [
+            ItemComponent::CustomName(Box::new(TextComponent::text("Excalibur").color(Color::Gold))),
+            ItemComponent::Enchantments(vec![(location("minecraft:sharpness"), 5), (location("minecraft:looting"), 3)]),
+        ];
+        assert_eq!(
+            give(&Selector::new(SelectorBase::AllPlayers), location("minecraft:diamond_sword"), 1, components).expect("should have rendered the command"),
+            r#"give @a minecraft:diamond_sword[minecraft:custom_name='{"text":"Excalibur","color":"gold"}',minecraft:enchantments={levels:{"minecraft:sharpness":5,"minecraft:looting":3}}]"#,
+        );
+    }
+
+    #[test]
+    fn clear_by_pack_format() {
+        let player = Selector::new(SelectorBase::ExecutingEntity);
+        assert_eq!(ClearCommand::new(player.clone()).max_count(3).render(48).expect("should have rendered the command"), "clear @s", "a count needs an item");
+
+        let wands = ItemFilter::new(TagValue::Tag(location("test:wands"))).component(ItemComponent::Unbreakable);
+        let clear = ClearCommand::new(player).item(wands).max_count(0);
+        assert_eq!(clear.render(26).expect("should have rendered the command"), "clear @s #test:wands{Unbreakable:1b} 0");
+        assert_eq!(clear.render(48).expect("should have rendered the command"), "clear @s #test:wands[minecraft:unbreakable={}] 0");
+    }
+}
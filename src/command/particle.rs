@@ -0,0 +1,261 @@
+use std::fmt::Write;
+use crate::block_state::BlockState;
+use crate::command::position::Position;
+use crate::command::selector::Selector;
+use crate::error::DatapackError;
+use crate::nbt::{NbtCompound, Snbt};
+use crate::resource_location::ResourceLocation;
+
+/// The first pack format whose particle options are SNBT after the particle ID, 1.20.5's.
+const SNBT_OPTIONS_PACK_FORMAT: u32 = 41;
+
+fn check_color(color: [f32; 3]) -> Result<(), DatapackError> {
+    match color.iter().all(|component| (0.0..=1.0).contains(component)) {
+        true => Ok(()),
+        false => Err(DatapackError::Validation(format!("invalid particle color {color:?}: components must be from 0 to 1"))),
+    }
+}
+
+fn check_scale(scale: f32) -> Result<(), DatapackError> {
+    match (0.01..=4.0).contains(&scale) {
+        true => Ok(()),
+        false => Err(DatapackError::Validation(format!("invalid particle scale {scale}: it must be from 0.01 to 4"))),
+    }
+}
+
+fn floats(values: [f32; 3]) -> Snbt {
+    Snbt::List(values.into_iter().map(Snbt::Float).collect())
+}
+
+/// A particle and its options. Colors are RGB, with each component from 0 to 1, which the
+/// constructors check, and rendering checks again for particles built from the variants.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Particle {
+    Dust { color: [f32; 3], scale: f32 },
+    DustColorTransition { from: [f32; 3], to: [f32; 3], scale: f32 },
+    /// The particles of a block being broken.
+    Block(BlockState),
+    /// The particle of barriers and light blocks.
+    BlockMarker(BlockState),
+    FallingDust(BlockState),
+    Item(ResourceLocation),
+    /// Travels to the block at `destination`, which can't be relative.
+    Vibration { destination: [i32; 3], arrival_in_ticks: u32 },
+    Shriek { delay: u32 },
+    /// Any particle without options, such as `minecraft:flame`.
+    Simple(ResourceLocation),
+}
+
+impl Particle {
+    /// `scale` is from 0.01 to 4.
+    pub fn dust(color: [f32; 3], scale: f32) -> Result<Self, DatapackError> {
+        let dust = Self::Dust { color, scale };
+        dust.validate()?;
+        Ok(dust)
+    }
+
+    pub fn dust_color_transition(from: [f32; 3], to: [f32; 3], scale: f32) -> Result<Self, DatapackError> {
+        let dust = Self::DustColorTransition { from, to, scale };
+        dust.validate()?;
+        Ok(dust)
+    }
+
+    fn validate(&self) -> Result<(), DatapackError> {
+        match *self {
+            Self::Dust { color, scale } => {
+                check_color(color)?;
+                check_scale(scale)
+            }
+            Self::DustColorTransition { from, to, scale } => {
+                check_color(from)?;
+                check_color(to)?;
+                check_scale(scale)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn id(&self) -> ResourceLocation {
+        let name = match self {
+            Self::Dust { .. } => "dust",
+            Self::DustColorTransition { .. } => "dust_color_transition",
+            Self::Block(_) => "block",
+            Self::BlockMarker(_) => "block_marker",
+            Self::FallingDust(_) => "falling_dust",
+            Self::Item(_) => "item",
+            Self::Vibration { .. } => "vibration",
+            Self::Shriek { .. } => "shriek",
+            Self::Simple(id) => return id.clone(),
+        };
+        ResourceLocation::minecraft(name).expect("the path is valid")
+    }
+
+    /// The options as SNBT, as they are read since 1.20.5.
+    fn options(&self) -> Option<NbtCompound> {
+        let options = match self {
+            Self::Dust { color, scale } => NbtCompound::new().with("color", floats(*color)).with("scale", *scale),
+            Self::DustColorTransition { from, to, scale } => {
+                NbtCompound::new().with("from_color", floats(*from)).with("to_color", floats(*to)).with("scale", *scale)
+            }
+            Self::Block(state) | Self::BlockMarker(state) | Self::FallingDust(state) => NbtCompound::new().with("block_state", state),
+            Self::Item(item) => NbtCompound::new().with("item", NbtCompound::new().with("id", item.to_string())),
+            Self::Vibration { destination: [x, y, z], arrival_in_ticks } => {
+                let destination = NbtCompound::new().with("type", "block").with("pos", Snbt::IntArray(vec![*x, *y, *z]));
+                NbtCompound::new().with("destination", destination).with("arrival_in_ticks", *arrival_in_ticks as i32)
+            }
+            Self::Shriek { delay } => NbtCompound::new().with("delay", *delay as i32),
+            Self::Simple(_) => return None,
+        };
+        Some(options)
+    }
+
+    /// The options as arguments after the particle ID, as they were read before 1.20.5.
+    fn arguments(&self) -> Option<String> {
+        let arguments = match self {
+            Self::Dust { color: [r, g, b], scale } => format!("{r} {g} {b} {scale}"),
+            Self::DustColorTransition { from: [r, g, b], to: [to_r, to_g, to_b], scale } => format!("{r} {g} {b} {scale} {to_r} {to_g} {to_b}"),
+            Self::Block(state) | Self::BlockMarker(state) | Self::FallingDust(state) => state.to_string(),
+            Self::Item(item) => item.to_string(),
+            Self::Vibration { destination: [x, y, z], arrival_in_ticks } => format!("{x} {y} {z} {arrival_in_ticks}"),
+            Self::Shriek { delay } => delay.to_string(),
+            Self::Simple(_) => return None,
+        };
+        Some(arguments)
+    }
+
+    /// The particle and its options as the `particle` command reads them in a datapack of
+    /// `pack_format`. Fails for colors and scales out of range.
+    pub fn render(&self, pack_format: u32) -> Result<String, DatapackError> {
+        self.validate()?;
+        let id = self.id();
+        Ok(match pack_format >= SNBT_OPTIONS_PACK_FORMAT {
+            true => self.options().map_or_else(|| id.to_string(), |options| format!("{id}{options}")),
+            false => self.arguments().map_or_else(|| id.to_string(), |arguments| format!("{id} {arguments}")),
+        })
+    }
+}
+
+impl From<ResourceLocation> for Particle {
+    fn from(id: ResourceLocation) -> Self {
+        Self::Simple(id)
+    }
+}
+
+/// Whether particles are shown to players far away or with reduced particle settings.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ParticleMode {
+    #[default]
+    Normal,
+    /// Shown up to 512 blocks away, whatever the player's particle settings.
+    Force,
+}
+
+/// How many particles are shown, and how far they spread from the position.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParticleSpread {
+    pub delta: [f64; 3],
+    pub speed: f64,
+    pub count: u32,
+}
+
+/// A `particle` command. Arguments are written up to the last one that is set, with the
+/// defaults of the game before it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParticleCommand {
+    pub particle: Particle,
+    pub position: Option<Position>,
+    pub spread: Option<ParticleSpread>,
+    pub mode: Option<ParticleMode>,
+    pub viewers: Option<Selector>,
+}
+
+impl ParticleCommand {
+    pub fn new(particle: impl Into<Particle>) -> Self {
+        Self { particle: particle.into(), position: None, spread: None, mode: None, viewers: None }
+    }
+
+    pub fn at(mut self, position: Position) -> Self {
+        self.position = Some(position);
+        self
+    }
+
+    /// With a `count` of 0, one particle is shown, moving in the direction of `delta` at
+    /// `speed`.
+    pub fn spread(mut self, delta: [f64; 3], speed: f64, count: u32) -> Self {
+        self.spread = Some(ParticleSpread { delta, speed, count });
+        self
+    }
+
+    pub fn force(mut self) -> Self {
+        self.mode = Some(ParticleMode::Force);
+        self
+    }
+
+    /// Only shows the particles to `viewers`.
+    pub fn viewers(mut self, viewers: Selector) -> Self {
+        self.viewers = Some(viewers);
+        self
+    }
+
+    pub fn render(&self, pack_format: u32) -> Result<String, DatapackError> {
+        let mut command = format!("particle {}", self.particle.render(pack_format)?);
+        let mode = self.mode.or(self.viewers.as_ref().map(|_| ParticleMode::default()));
+        let spread = self.spread.or(mode.map(|_| ParticleSpread { delta: [0.0; 3], speed: 0.0, count: 1 }));
+        let position = self.position.or(spread.map(|_| Position::here()));
+        let mut write = |argument: &dyn std::fmt::Display| write!(command, " {argument}").expect("writing to a string doesn't fail");
+        if let Some(position) = position {
+            write(&position);
+        }
+        if let Some(ParticleSpread { delta: [dx, dy, dz], speed, count }) = spread {
+            write(&format_args!("{dx} {dy} {dz} {speed} {count}"));
+        }
+        if let Some(mode) = mode {
+            write(&if mode == ParticleMode::Force { "force" } else { "normal" });
+        }
+        if let Some(viewers) = &self.viewers {
+            write(viewers);
+        }
+        Ok(command)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::selector::SelectorBase;
+
+    fn location(location: &str) -> ResourceLocation {
+        location.parse().expect("should have parsed the resource location")
+    }
+
+    #[test]
+    fn dust() {
+        let dust = Particle::dust([1.0, 0.5, 0.0], 1.5).expect("the color and scale are valid");
+        let command = ParticleCommand::new(dust).at(Position::relative(0.0, 1.0, 0.0)).spread([0.2, 0.2, 0.2], 0.0, 10).force();
+        assert_eq!(command.render(26).expect("should have rendered the command"), "particle minecraft:dust 1 0.5 0 1.5 ~ ~1 ~ 0.2 0.2 0.2 0 10 force");
+        assert_eq!(command.render(41).expect("should have rendered the command"), "particle minecraft:dust{color:[1f,0.5f,0f],scale:1.5f} ~ ~1 ~ 0.2 0.2 0.2 0 10 force");
+
+        assert!(Particle::dust([1.5, 0.0, 0.0], 1.0).is_err(), "color components are at most 1");
+        assert!(Particle::dust_color_transition([0.0; 3], [-0.1, 0.0, 0.0], 1.0).is_err(), "color components are at least 0");
+        assert!(Particle::dust([0.0; 3], 0.0).is_err(), "the scale is at least 0.01");
+        let unchecked = ParticleCommand::new(Particle::Dust { color: [2.0, 0.0, 0.0], scale: 1.0 });
+        assert!(matches!(unchecked.render(48), Err(DatapackError::Validation(_))), "the color is checked when it is written too");
+    }
+
+    #[test]
+    fn block() {
+        let stairs = BlockState::new(location("minecraft:oak_stairs")).property("facing", "east").property("half", "top");
+        let command = ParticleCommand::new(Particle::Block(stairs));
+        assert_eq!(command.render(26).expect("should have rendered the command"), "particle minecraft:block minecraft:oak_stairs[facing=east,half=top]");
+        assert_eq!(command.render(48).expect("should have rendered the command"), r#"particle minecraft:block{block_state:{Name:"minecraft:oak_stairs",Properties:{facing:"east",half:"top"}}}"#);
+    }
+
+    #[test]
+    fn simple() {
+        let command = ParticleCommand::new(location("minecraft:flame")).viewers(Selector::new(SelectorBase::ExecutingEntity));
+        let expected = "particle minecraft:flame ~ ~ ~ 0 0 0 0 1 normal @s";
+        assert_eq!(command.render(26).expect("should have rendered the command"), expected, "earlier arguments should be filled in with their defaults");
+        assert_eq!(command.render(48).expect("should have rendered the command"), expected, "particles without options are the same in both syntaxes");
+        assert_eq!(ParticleCommand::new(location("minecraft:heart")).render(48).expect("should have rendered the command"), "particle minecraft:heart");
+    }
+}
@@ -0,0 +1,173 @@
+use std::fmt::{self, Display};
+use crate::error::DatapackError;
+
+/// A single coordinate in world (`5`, `~5`) or local (`^5`) notation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Coordinate {
+    Absolute(f64),
+    Relative(f64),
+    Local(f64),
+}
+
+impl Coordinate {
+    pub fn is_local(&self) -> bool {
+        matches!(self, Self::Local(_))
+    }
+}
+
+impl Display for Coordinate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // `0.0 == -0.0`, so this also keeps negative zero from rendering as `-0`.
+        match *self {
+            Self::Absolute(value) => write!(f, "{}", if value == 0.0 { 0.0 } else { value }),
+            Self::Relative(value) | Self::Local(value) => {
+                write!(f, "{}", if self.is_local() { "^" } else { "~" })?;
+                if value != 0.0 {
+                    write!(f, "{value}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Three coordinates, as taken by `tp`, `summon`, `execute positioned` and the like.
+///
+/// Local coordinates can't be mixed with world coordinates, which [`Position::new`] checks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Position {
+    x: Coordinate,
+    y: Coordinate,
+    z: Coordinate,
+}
+
+impl Position {
+    pub fn new(x: Coordinate, y: Coordinate, z: Coordinate) -> Result<Self, DatapackError> {
+        let position = Self { x, y, z };
+        let local_count = position.coordinates().iter().filter(|coordinate| coordinate.is_local()).count();
+        if local_count != 0 && local_count != 3 {
+            return Err(DatapackError::Validation(format!(
+                "position '{position}' mixes local and world coordinates",
+            )));
+        }
+        Ok(position)
+    }
+
+    pub fn absolute(x: f64, y: f64, z: f64) -> Self {
+        Self { x: Coordinate::Absolute(x), y: Coordinate::Absolute(y), z: Coordinate::Absolute(z) }
+    }
+
+    pub fn relative(x: f64, y: f64, z: f64) -> Self {
+        Self { x: Coordinate::Relative(x), y: Coordinate::Relative(y), z: Coordinate::Relative(z) }
+    }
+
+    pub fn local(left: f64, up: f64, forwards: f64) -> Self {
+        Self { x: Coordinate::Local(left), y: Coordinate::Local(up), z: Coordinate::Local(forwards) }
+    }
+
+    /// `~ ~ ~`
+    pub fn here() -> Self {
+        Self::relative(0.0, 0.0, 0.0)
+    }
+
+    pub fn coordinates(&self) -> [Coordinate; 3] {
+        [self.x, self.y, self.z]
+    }
+
+    pub fn is_local(&self) -> bool {
+        self.x.is_local()
+    }
+}
+
+impl Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} {}", self.x, self.y, self.z)
+    }
+}
+
+/// Two world coordinates, as taken by `setworldspawn`, `worldborder center` and `forceload`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColumnPosition {
+    x: Coordinate,
+    z: Coordinate,
+}
+
+impl ColumnPosition {
+    pub fn new(x: Coordinate, z: Coordinate) -> Result<Self, DatapackError> {
+        let position = Self { x, z };
+        if x.is_local() || z.is_local() {
+            return Err(DatapackError::Validation(format!(
+                "column position '{position}' can't use local coordinates",
+            )));
+        }
+        Ok(position)
+    }
+
+    pub fn absolute(x: f64, z: f64) -> Self {
+        Self { x: Coordinate::Absolute(x), z: Coordinate::Absolute(z) }
+    }
+
+    pub fn relative(x: f64, z: f64) -> Self {
+        Self { x: Coordinate::Relative(x), z: Coordinate::Relative(z) }
+    }
+
+    pub fn coordinates(&self) -> [Coordinate; 2] {
+        [self.x, self.z]
+    }
+}
+
+impl Display for ColumnPosition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.x, self.z)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coordinate_display() {
+        assert_eq!(Coordinate::Absolute(5.0).to_string(), "5");
+        assert_eq!(Coordinate::Absolute(-12.25).to_string(), "-12.25");
+        assert_eq!(Coordinate::Absolute(-0.0).to_string(), "0");
+        assert_eq!(Coordinate::Relative(0.0).to_string(), "~");
+        assert_eq!(Coordinate::Relative(-0.0).to_string(), "~");
+        assert_eq!(Coordinate::Relative(1.0).to_string(), "~1");
+        assert_eq!(Coordinate::Relative(-0.5).to_string(), "~-0.5");
+        assert_eq!(Coordinate::Local(0.5).to_string(), "^0.5");
+        assert_eq!(Coordinate::Local(0.0).to_string(), "^");
+    }
+
+    #[test]
+    fn coordinate_display_small_floats() {
+        assert_eq!(Coordinate::Absolute(0.0000001).to_string(), "0.0000001");
+        assert_eq!(Coordinate::Local(1e-10).to_string(), "^0.0000000001");
+        assert_eq!(Coordinate::Absolute(30000000.0).to_string(), "30000000");
+    }
+
+    #[test]
+    fn position_display() {
+        assert_eq!(Position::here().to_string(), "~ ~ ~");
+        assert_eq!(Position::relative(0.0, 1.0, 0.0).to_string(), "~ ~1 ~");
+        assert_eq!(Position::absolute(10.0, 64.0, -3.5).to_string(), "10 64 -3.5");
+        assert_eq!(Position::local(0.0, 0.0, 0.1).to_string(), "^ ^ ^0.1");
+    }
+
+    #[test]
+    fn position_mixed_coordinates() {
+        let mixed = Position::new(Coordinate::Local(0.0), Coordinate::Relative(1.0), Coordinate::Local(0.0));
+        assert!(mixed.is_err(), "local and relative coordinates should not be mixed");
+
+        let position = Position::new(Coordinate::Absolute(1.0), Coordinate::Relative(1.0), Coordinate::Absolute(1.0))
+            .expect("absolute and relative coordinates can be mixed");
+        assert_eq!(position.to_string(), "1 ~1 1");
+    }
+
+    #[test]
+    fn column_position() {
+        assert_eq!(ColumnPosition::absolute(0.0, -100.0).to_string(), "0 -100");
+        assert_eq!(ColumnPosition::relative(0.0, 16.0).to_string(), "~ ~16");
+        assert!(ColumnPosition::new(Coordinate::Local(0.0), Coordinate::Local(0.0)).is_err(), "local coordinates should be rejected");
+    }
+}
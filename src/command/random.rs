@@ -0,0 +1,313 @@
+use std::fmt::{self, Display};
+use crate::command::execute::ExecuteCommand;
+use crate::command::function::call_function;
+use crate::data::function::McFunction;
+use crate::datapack::namespace::Namespace;
+use crate::error::DatapackError;
+use crate::resource_location::ResourceLocation;
+
+/// The pack format of 1.20.2, which added `random`.
+const RANDOM_PACK_FORMAT: u32 = 18;
+
+/// The most values a `random` range may have.
+const MAX_RANGE_SIZE: u64 = 2147483646;
+
+/// The inclusive range of a `random value` or `random roll`, which has at least 2 and at
+/// most 2147483646 values, as the game requires.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RandomRange {
+    min: i32,
+    max: i32,
+}
+
+impl RandomRange {
+    pub fn new(min: i32, max: i32) -> Result<Self, DatapackError> {
+        let size = i64::from(max) - i64::from(min) + 1;
+        if size < 2 || size as u64 > MAX_RANGE_SIZE {
+            return Err(DatapackError::Validation(format!(
+                "invalid random range: {min}..{max} must have between 2 and {MAX_RANGE_SIZE} values",
+            )));
+        }
+        Ok(Self { min, max })
+    }
+
+    pub fn min(&self) -> i32 {
+        self.min
+    }
+
+    pub fn max(&self) -> i32 {
+        self.max
+    }
+}
+
+impl Display for RandomRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}..{}", self.min, self.max)
+    }
+}
+
+/// A `random` command. Values are drawn from the random sequence given, or from one only
+/// this command uses without it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RandomCommand {
+    /// Draws a value as the result of the command, such as for `execute store`.
+    Value { range: RandomRange, sequence: Option<ResourceLocation> },
+    /// Draws a value and announces it to every player.
+    Roll { range: RandomRange, sequence: Option<ResourceLocation> },
+    /// Resets `sequence`, or every sequence without one.
+    Reset(Option<ResourceLocation>),
+}
+
+impl RandomCommand {
+    pub fn value(range: RandomRange) -> Self {
+        Self::Value { range, sequence: None }
+    }
+
+    pub fn roll(range: RandomRange) -> Self {
+        Self::Roll { range, sequence: None }
+    }
+
+    pub fn reset(sequence: impl Into<Option<ResourceLocation>>) -> Self {
+        Self::Reset(sequence.into())
+    }
+
+    /// Sets the sequence of a `random value` or `random roll` command.
+    pub fn sequence(mut self, location: ResourceLocation) -> Self {
+        if let Self::Value { sequence, .. } | Self::Roll { sequence, .. } = &mut self {
+            *sequence = Some(location);
+        }
+        self
+    }
+
+    /// The command for a datapack of `pack_format`. Fails before 1.20.2.
+    pub fn render(&self, pack_format: u32) -> Result<String, DatapackError> {
+        if pack_format < RANDOM_PACK_FORMAT {
+            return Err(DatapackError::Validation(format!(
+                "invalid random command: it needs pack format {RANDOM_PACK_FORMAT} or newer, but the pack format is {pack_format}",
+            )));
+        }
+        let (action, range, sequence) = match self {
+            Self::Value { range, sequence } => ("value", range, sequence),
+            Self::Roll { range, sequence } => ("roll", range, sequence),
+            Self::Reset(Some(sequence)) => return Ok(format!("random reset {sequence}")),
+            Self::Reset(None) => return Ok("random reset *".to_string()),
+        };
+        Ok(match sequence {
+            Some(sequence) => format!("random {action} {range} {sequence}"),
+            None => format!("random {action} {range}"),
+        })
+    }
+}
+
+/// Runs one of several functions at random, each chosen with a chance in proportion to its
+/// weight.
+///
+/// [`WeightedChoice::generate`] adds a function that stores `random value` in a score, and
+/// a binary search over the options with `execute if score ... matches`. Since this needs
+/// `random`, it fails for pack formats before 1.20.2 instead of falling back to older
+/// sources of randomness.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeightedChoice {
+    holder: String,
+    objective: String,
+    sequence: Option<ResourceLocation>,
+}
+
+impl WeightedChoice {
+    /// Draws into the score of the fake player `#random` for `objective`, which the
+    /// datapack has to add, as a dummy objective for example.
+    pub fn new(objective: impl Into<String>) -> Self {
+        Self { holder: "#random".to_string(), objective: objective.into(), sequence: None }
+    }
+
+    /// Draws into the score of `holder` instead.
+    pub fn holder(mut self, holder: impl Into<String>) -> Self {
+        self.holder = holder.into();
+        self
+    }
+
+    /// Draws from the random sequence `sequence`, so the choices can be reproduced.
+    pub fn sequence(mut self, sequence: ResourceLocation) -> Self {
+        self.sequence = Some(sequence);
+        self
+    }
+
+    /// Adds the function at `path` that runs one of the `options` at random, with the
+    /// functions it needs, and returns the command that runs it.
+    ///
+    /// Each option is added as `<path>/option_<n>`, and each step of the search as
+    /// `<path>/branch_<first>_<last>` for the options it picks between, so the names only
+    /// depend on the number of options.
+    pub fn generate(&self, namespace: &mut Namespace, path: &str, options: Vec<(u32, McFunction)>) -> Result<String, DatapackError> {
+        let invalid = |message: &str| Err(DatapackError::Validation(format!("invalid weighted choice: {message}")));
+        if options.is_empty() {
+            return invalid("it has no options");
+        }
+        if options.iter().any(|(weight, _)| *weight == 0) {
+            return invalid("every weight must be positive");
+        }
+        let weights: Vec<u32> = options.iter().map(|(weight, _)| *weight).collect();
+        let total: u64 = weights.iter().map(|weight| u64::from(*weight)).sum();
+        if total > MAX_RANGE_SIZE {
+            return invalid(&format!("the weights must add up to at most {MAX_RANGE_SIZE}"));
+        }
+
+        let draw = match options.len() {
+            1 => None,
+            _ => {
+                let mut draw = RandomCommand::value(RandomRange::new(0, (total - 1) as i32)?);
+                if let Some(sequence) = &self.sequence {
+                    draw = draw.sequence(sequence.clone());
+                }
+                let draw = draw.render(namespace.pack_format())?;
+                Some(ExecuteCommand::new().store_result_score(self.holder.as_str(), self.objective.as_str()).run(draw).build()?)
+            }
+        };
+
+        namespace.transaction(|namespace| {
+            let mut locations = Vec::new();
+            for (index, (_, function)) in options.into_iter().enumerate() {
+                locations.push(namespace.add_function(&format!("{path}/option_{index}"), function)?);
+            }
+            let root = match draw {
+                None => McFunction::from(vec![call_function(&locations[0])]),
+                Some(draw) => McFunction::from(vec![draw]) + self.branch(namespace, path, &partition(&weights), &locations, 0, locations.len() - 1)?,
+            };
+            let location = namespace.add_function(path, root)?;
+            Ok(call_function(&location))
+        })
+    }
+
+    /// The commands picking between the options `first..=last`, adding a function for
+    /// each half with more than one option.
+    fn branch(
+        &self,
+        namespace: &mut Namespace,
+        path: &str,
+        ranges: &[(i32, i32)],
+        options: &[ResourceLocation],
+        first: usize,
+        last: usize,
+    ) -> Result<McFunction, DatapackError> {
+        let middle = first + (last - first) / 2;
+        let mut function = McFunction::new();
+        for (start, end, run) in [(first, middle, "return run "), (middle + 1, last, "")] {
+            let target = match start == end {
+                true => options[start].clone(),
+                false => {
+                    let half = self.branch(namespace, path, ranges, options, start, end)?;
+                    namespace.add_function(&format!("{path}/branch_{start}_{end}"), half)?
+                }
+            };
+            let (min, max) = (ranges[start].0, ranges[end].1);
+            function.push(format!(
+                "execute if score {} {} matches {min}..{max} run {run}{}",
+                self.holder,
+                self.objective,
+                call_function(&target),
+            ));
+        }
+        Ok(function)
+    }
+}
+
+/// The inclusive range of draws from `0` that picks each option, in order.
+fn partition(weights: &[u32]) -> Vec<(i32, i32)> {
+    let mut start = 0i64;
+    weights
+        .iter()
+        .map(|weight| {
+            let end = start + i64::from(*weight);
+            let range = (start as i32, (end - 1) as i32);
+            start = end;
+            range
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datapack::pack_meta::PackMeta;
+    use crate::datapack::Datapack;
+    use crate::utils::file_system::Entry;
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+
+    fn location(location: &str) -> ResourceLocation {
+        location.parse().expect("should have parsed the resource location")
+    }
+
+    #[test]
+    fn random_commands() {
+        let range = RandomRange::new(1, 6).expect("should have accepted the range");
+        assert_eq!(RandomCommand::value(range).render(18).expect("should have rendered the command"), "random value 1..6");
+        assert_eq!(
+            RandomCommand::roll(range).sequence(location("test:dice")).render(48).expect("should have rendered the command"),
+            "random roll 1..6 test:dice",
+        );
+        assert_eq!(RandomCommand::reset(None).render(18).expect("should have rendered the command"), "random reset *");
+        assert_eq!(RandomCommand::reset(location("test:dice")).render(18).expect("should have rendered the command"), "random reset test:dice");
+        assert!(RandomCommand::value(range).render(15).is_err(), "'random' needs 1.20.2");
+        assert!(RandomRange::new(3, 3).is_err(), "a range needs at least 2 values");
+        assert!(RandomRange::new(i32::MIN, i32::MAX).is_err());
+    }
+
+    #[test]
+    fn partition_covers_weights() {
+        for weights in [vec![1], vec![1, 1], vec![3, 1, 4, 1, 5, 9, 2, 6], vec![1000, 1, 250]] {
+            let ranges = partition(&weights);
+            let mut next = 0;
+            for ((min, max), weight) in ranges.iter().zip(&weights) {
+                assert_eq!(*min, next, "the ranges should have no gaps or overlaps");
+                assert_eq!((max - min + 1) as u32, *weight, "each range should have as many values as its weight");
+                next = max + 1;
+            }
+            assert_eq!(next as u32, weights.iter().sum::<u32>(), "the ranges should end at the total weight");
+        }
+    }
+
+    #[test]
+    fn weighted_choice() {
+        let mut datapack = Datapack::new(PackMeta::new(48, "test"));
+        let mut namespace = datapack.namespace("test").expect("should have accepted the namespace");
+        let options = vec![
+            (3, McFunction::from(vec!["say common"])),
+            (1, McFunction::from(vec!["say rare"])),
+            (6, McFunction::from(vec!["say very common"])),
+        ];
+        let command = WeightedChoice::new("rng").generate(&mut namespace, "loot", options).expect("should have generated the choice");
+        assert_eq!(command, "function test:loot");
+
+        let mut functions = BTreeMap::new();
+        datapack.visit(&mut |path, entry| {
+            if let Some(contents) = entry.contents() {
+                functions.insert(path.to_path_buf(), String::from_utf8(contents).expect("functions are UTF-8"));
+            }
+        });
+        let function = |path: &str| functions[&PathBuf::from(format!("data/test/function/{path}.mcfunction"))].as_str();
+        assert_eq!(
+            function("loot"),
+            "execute store result score #random rng run random value 0..9\n\
+             execute if score #random rng matches 0..3 run return run function test:loot/branch_0_1\n\
+             execute if score #random rng matches 4..9 run function test:loot/option_2\n",
+        );
+        assert_eq!(
+            function("loot/branch_0_1"),
+            "execute if score #random rng matches 0..2 run return run function test:loot/option_0\n\
+             execute if score #random rng matches 3..3 run function test:loot/option_1\n",
+        );
+        assert_eq!(function("loot/option_1"), "say rare\n");
+    }
+
+    #[test]
+    fn weighted_choice_errors() {
+        let mut datapack = Datapack::new(PackMeta::new(15, "test"));
+        let mut namespace = datapack.namespace("test").expect("should have accepted the namespace");
+        let choice = WeightedChoice::new("rng");
+        assert!(choice.generate(&mut namespace, "empty", Vec::new()).is_err());
+        assert!(choice.generate(&mut namespace, "zero", vec![(0, McFunction::new()), (1, McFunction::new())]).is_err());
+        assert!(choice.generate(&mut namespace, "old", vec![(1, McFunction::new()), (1, McFunction::new())]).is_err(), "'random' needs 1.20.2");
+        assert_eq!(choice.generate(&mut namespace, "single", vec![(5, McFunction::new())]).expect("one option needs no draw"), "function test:single");
+    }
+}
@@ -0,0 +1,289 @@
+use std::fmt::Display;
+use crate::command::execute::{Anchor, ExecuteCommand, ExecuteCondition, ScoreComparison};
+use crate::command::function::call_function;
+use crate::command::position::Position;
+use crate::command::return_::ReturnCommand;
+use crate::command::scoreboard::{ScoreboardCommand, ScoreboardObjective};
+use crate::command::selector::Selector;
+use crate::data::function::McFunction;
+use crate::datapack::namespace::Namespace;
+use crate::error::DatapackError;
+use crate::range::MinecraftRange;
+use crate::resource_location::ResourceLocation;
+
+/// Generates the functions of a raycast: starting at the executing entity's eyes, it steps
+/// forwards until a hit condition holds, running the hit commands there, or until it has
+/// gone its full distance, running the miss commands there. The steps it has left are
+/// counted by the fake player `#<namespace>:<path>`, so raycasts at different paths can
+/// run inside each other's hit commands.
+///
+/// It ends the step function with `return run`, so it fails for pack formats before 1.20.2.
+///
+/// ```
+/// use minecraft_datapack_generator::command::raycast::Raycast;
+/// use minecraft_datapack_generator::command::selector::Selector;
+/// use minecraft_datapack_generator::datapack::{pack_meta::PackMeta, Datapack};
+///
+/// let mut datapack = Datapack::new(PackMeta::new(48, "Wands"));
+/// let mut namespace = datapack.namespace("wands").unwrap();
+/// let zap = Raycast::builder("zap")
+///     .max_distance(16.0)
+///     .hit_entity(Selector::all_entities().type_("minecraft:zombie").distance(..=1.0))
+///     .on_hit(["particle minecraft:flash ~ ~ ~", "kill @e[type=minecraft:zombie,distance=..1]"])
+///     .generate(&mut namespace)
+///     .unwrap();
+/// assert_eq!(zap.to_string(), "wands:zap");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Raycast {
+    path: String,
+    step: f64,
+    max_distance: f64,
+    hit: Vec<ExecuteCondition>,
+    pass_through: Option<ResourceLocation>,
+    on_hit: McFunction,
+    on_miss: McFunction,
+    objective: String,
+}
+
+impl Raycast {
+    /// A raycast whose functions are added at `path`: the entry point at `path`, and
+    /// `<path>/step`, `<path>/hit` and `<path>/miss` next to it, so raycasts at different
+    /// paths don't collide.
+    ///
+    /// It steps 0.1 blocks at a time for up to 64 blocks by default.
+    pub fn builder(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            step: 0.1,
+            max_distance: 64.0,
+            hit: Vec::new(),
+            pass_through: None,
+            on_hit: McFunction::new(),
+            on_miss: McFunction::new(),
+            objective: "raycast".to_string(),
+        }
+    }
+
+    pub fn step(mut self, step: f64) -> Self {
+        self.step = step;
+        self
+    }
+
+    pub fn max_distance(mut self, max_distance: f64) -> Self {
+        self.max_distance = max_distance;
+        self
+    }
+
+    /// Adds a condition that has to hold for the ray to hit, checked at each step.
+    pub fn hit(mut self, condition: ExecuteCondition) -> Self {
+        self.hit.push(condition);
+        self
+    }
+
+    /// Hits the entities of `selector`, which should be limited to those near the ray,
+    /// with `distance=..1` for example.
+    pub fn hit_entity(self, selector: Selector) -> Self {
+        self.hit(ExecuteCondition::Entity(selector))
+    }
+
+    /// Hits a block, such as `minecraft:target` or `#minecraft:logs`.
+    pub fn hit_block(self, block: impl Display) -> Self {
+        self.hit(ExecuteCondition::Block(Position::here(), block.to_string()))
+    }
+
+    pub fn hit_predicate(self, predicate: ResourceLocation) -> Self {
+        self.hit(ExecuteCondition::Predicate(predicate))
+    }
+
+    /// Stops the ray as a miss at any block outside the block tag `tag`, such as
+    /// `minecraft:air`, so it can't pass through walls.
+    pub fn pass_through(mut self, tag: ResourceLocation) -> Self {
+        self.pass_through = Some(tag);
+        self
+    }
+
+    /// The commands run where the ray hits.
+    pub fn on_hit<C: Display>(mut self, commands: impl IntoIterator<Item = C>) -> Self {
+        self.on_hit.extend(commands);
+        self
+    }
+
+    /// The commands run where the ray stops without a hit.
+    pub fn on_miss<C: Display>(mut self, commands: impl IntoIterator<Item = C>) -> Self {
+        self.on_miss.extend(commands);
+        self
+    }
+
+    /// Counts the steps in `objective` instead of `raycast`.
+    pub fn objective(mut self, objective: impl Into<String>) -> Self {
+        self.objective = objective.into();
+        self
+    }
+
+    /// How many positions the ray checks: the start, and each step that is at most the
+    /// maximum distance away.
+    pub fn iterations(&self) -> u32 {
+        // Rounds up distances a hair short of a whole step, such as 5 / 0.1.
+        ((self.max_distance / self.step + 1e-9).floor() as u32).saturating_add(1)
+    }
+
+    /// Adds the functions to `namespace`, declares the objective, which `<namespace>:load`
+    /// adds and `<namespace>:uninstall` removes, and returns the location of the entry
+    /// point. Raycasts can share an objective, since each counts with its own holder. Fails
+    /// without a hit condition, if the step or maximum distance isn't a positive number,
+    /// or if there are more iterations than a score can hold.
+    pub fn generate(self, namespace: &mut Namespace) -> Result<ResourceLocation, DatapackError> {
+        let invalid = |message: &str| Err(DatapackError::Validation(format!("invalid raycast '{}': {message}", self.path)));
+        if self.hit.is_empty() {
+            return invalid("it has no hit condition");
+        }
+        if !(self.step > 0.0 && self.max_distance > 0.0 && self.step.is_finite() && self.max_distance.is_finite()) {
+            return invalid("the step and maximum distance must be positive numbers");
+        }
+        let Ok(iterations) = i32::try_from(self.iterations()) else {
+            return invalid("it has more iterations than a score can hold");
+        };
+        let objective = ScoreboardObjective::dummy(self.objective.as_str())?;
+        let location = |name: &str| namespace.location(&format!("{}/{name}", self.path));
+        let (step, hit, miss) = (location("step")?, location("hit")?, location("miss")?);
+        let holder = format!("#{}", namespace.location(&self.path)?);
+        let pack_format = namespace.pack_format();
+        let return_function = |function: &ResourceLocation| ReturnCommand::run(call_function(function)).render(pack_format);
+
+        let mut start = McFunction::new();
+        start.push(ScoreboardCommand::set(holder.as_str(), objective.name(), iterations));
+        start.push(ExecuteCommand::new().anchored(Anchor::Eyes).positioned(Position::local(0.0, 0.0, 0.0)).run_function(&step).build()?);
+
+        let mut stepping = McFunction::new();
+        let hits = self.hit.iter().cloned().fold(ExecuteCommand::new(), ExecuteCommand::if_);
+        stepping.push(hits.run(return_function(&hit)?).build()?);
+        if let Some(tag) = &self.pass_through {
+            let blocked = ExecuteCommand::new().unless_block(Position::here(), format_args!("#{tag}"));
+            stepping.push(blocked.run(return_function(&miss)?).build()?);
+        }
+        stepping.push(ScoreboardCommand::add(holder.as_str(), objective.name(), -1));
+        let next = ExecuteCommand::new()
+            .if_score(holder.as_str(), objective.name(), ScoreComparison::Matches(MinecraftRange::AtLeast(1)))
+            .positioned(Position::local(0.0, 0.0, self.step))
+            .run(return_function(&step)?);
+        stepping.push(next.build()?);
+        stepping.push(call_function(&miss));
+
+        namespace.transaction(|namespace| {
+            namespace.use_objectives([objective])?;
+            namespace.add_function(step.path(), stepping)?;
+            namespace.add_function(hit.path(), self.on_hit)?;
+            namespace.add_function(miss.path(), self.on_miss)?;
+            namespace.add_function(&self.path, start)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datapack::pack_meta::PackMeta;
+    use crate::datapack::Datapack;
+    use crate::utils::file_system::Entry;
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+
+    fn location(location: &str) -> ResourceLocation {
+        location.parse().expect("should have parsed the resource location")
+    }
+
+    fn functions(datapack: &Datapack) -> BTreeMap<PathBuf, String> {
+        let mut functions = BTreeMap::new();
+        datapack.visit(&mut |path, entry| {
+            if path.extension().is_some_and(|extension| extension == "mcfunction") {
+                let contents = entry.contents().expect("functions should know their contents");
+                functions.insert(path.to_path_buf(), String::from_utf8(contents).expect("functions are UTF-8"));
+            }
+        });
+        functions
+    }
+
+    #[test]
+    fn raycast_functions() {
+        let mut datapack = Datapack::new(PackMeta::new(48, "test"));
+        let mut namespace = datapack.namespace("test").expect("should have accepted the namespace");
+        let entry = Raycast::builder("laser")
+            .step(0.5)
+            .max_distance(10.0)
+            .hit_block("minecraft:target")
+            .pass_through(location("minecraft:air"))
+            .on_hit(["setblock ~ ~ ~ minecraft:redstone_block"])
+            .on_miss(["particle minecraft:smoke ~ ~ ~"])
+            .generate(&mut namespace)
+            .expect("should have generated the raycast");
+        assert_eq!(entry, location("test:laser"));
+        Raycast::builder("other/laser")
+            .hit_predicate(location("test:is_marked"))
+            .generate(&mut namespace)
+            .expect("a raycast at another path shouldn't collide");
+
+        let functions = functions(&datapack);
+        let function = |path: &str| functions[&PathBuf::from(format!("data/test/function/{path}.mcfunction"))].as_str();
+        assert_eq!(
+            function("laser"),
+            "scoreboard players set #test:laser raycast 21\n\
+             execute anchored eyes positioned ^ ^ ^ run function test:laser/step\n",
+        );
+        assert_eq!(
+            function("laser/step"),
+            "execute if block ~ ~ ~ minecraft:target run return run function test:laser/hit\n\
+             execute unless block ~ ~ ~ #minecraft:air run return run function test:laser/miss\n\
+             scoreboard players remove #test:laser raycast 1\n\
+             execute if score #test:laser raycast matches 1.. positioned ^ ^ ^0.5 run return run function test:laser/step\n\
+             function test:laser/miss\n",
+        );
+        assert_eq!(function("laser/hit"), "setblock ~ ~ ~ minecraft:redstone_block\n");
+        assert_eq!(function("load"), "scoreboard objectives add raycast dummy\n", "the raycasts share the objective");
+        assert_eq!(function("uninstall"), "scoreboard objectives remove raycast\n");
+        assert_eq!(functions.len(), 10);
+    }
+
+    #[test]
+    fn iterations() {
+        let raycast = |step, max_distance| Raycast::builder("ray").step(step).max_distance(max_distance);
+        assert_eq!(raycast(0.1, 5.0).iterations(), 51, "5 / 0.1 shouldn't lose a step to rounding");
+        assert_eq!(raycast(0.5, 10.0).iterations(), 21);
+        assert_eq!(raycast(2.0, 3.0).iterations(), 2, "a step past the maximum distance isn't taken");
+
+        // The step function checks a position, then counts down and steps while the
+        // counter is at least 1, like this.
+        for ray in [raycast(0.1, 5.0), raycast(0.25, 1.0), raycast(3.0, 2.0)] {
+            let (mut counter, mut checked) = (ray.iterations() as i32, Vec::new());
+            let mut distance = 0.0;
+            loop {
+                checked.push(distance);
+                counter -= 1;
+                if counter < 1 {
+                    break;
+                }
+                distance += ray.step;
+            }
+            assert_eq!(checked.len() as u32, ray.iterations());
+            assert!(distance <= ray.max_distance + 1e-6, "the ray shouldn't go past its maximum distance");
+            assert!(distance + ray.step > ray.max_distance + 1e-6, "the ray should go as far as it can");
+        }
+    }
+
+    #[test]
+    fn invalid_raycasts() {
+        let mut datapack = Datapack::new(PackMeta::new(48, "test"));
+        let mut namespace = datapack.namespace("test").expect("should have accepted the namespace");
+        assert!(Raycast::builder("none").generate(&mut namespace).is_err(), "it needs a hit condition");
+        let targets = || Raycast::builder("ray").hit_block("minecraft:target");
+        assert!(targets().step(0.0).generate(&mut namespace).is_err());
+        assert!(targets().max_distance(f64::INFINITY).generate(&mut namespace).is_err(), "the ray has to end");
+        assert!(targets().step(f64::NAN).generate(&mut namespace).is_err());
+        assert!(targets().step(1e-9).generate(&mut namespace).is_err(), "a score can't count that many steps");
+        assert!(targets().objective("not valid").generate(&mut namespace).is_err());
+
+        let mut datapack = Datapack::new(PackMeta::new(15, "test"));
+        let mut namespace = datapack.namespace("test").expect("should have accepted the namespace");
+        assert!(targets().generate(&mut namespace).is_err(), "'return run' needs 1.20.2");
+    }
+}
@@ -0,0 +1,89 @@
+use std::fmt::Display;
+use crate::error::DatapackError;
+
+/// The pack format of 1.20, which added `return <value>`.
+pub(crate) const RETURN_PACK_FORMAT: u32 = 15;
+/// The pack format of 1.20.2, which added `return run`.
+const RETURN_RUN_PACK_FORMAT: u32 = 18;
+/// The pack format of 1.20.3, which added `return fail`.
+const RETURN_FAIL_PACK_FORMAT: u32 = 26;
+
+/// A `return` command, which ends the function and gives its result to whatever ran it, such
+/// as `execute store result ... run function ...`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReturnCommand {
+    Value(i32),
+    /// Returns the result of `command`, which is written without a leading slash.
+    Run(String),
+    /// Ends the function as failed, so it has no result.
+    Fail,
+}
+
+impl ReturnCommand {
+    pub fn value(value: i32) -> Self {
+        Self::Value(value)
+    }
+
+    pub fn run(command: impl Display) -> Self {
+        Self::Run(command.to_string())
+    }
+
+    /// The oldest pack format with this form of `return`.
+    pub fn min_pack_format(&self) -> u32 {
+        match self {
+            Self::Value(_) => RETURN_PACK_FORMAT,
+            Self::Run(_) => RETURN_RUN_PACK_FORMAT,
+            Self::Fail => RETURN_FAIL_PACK_FORMAT,
+        }
+    }
+
+    /// The command for a datapack of `pack_format`. Fails if `pack_format` is older than
+    /// [`ReturnCommand::min_pack_format`].
+    pub fn render(&self, pack_format: u32) -> Result<String, DatapackError> {
+        let required = self.min_pack_format();
+        if pack_format < required {
+            return Err(DatapackError::Validation(format!(
+                "invalid return command: it needs pack format {required} or newer, but the pack format is {pack_format}",
+            )));
+        }
+        Ok(match self {
+            Self::Value(value) => format!("return {value}"),
+            Self::Run(command) => format!("return run {command}"),
+            Self::Fail => "return fail".to_string(),
+        })
+    }
+}
+
+/// Whether `command` ends its function with `return`, directly or after `execute ... run`.
+pub(crate) fn is_return(command: &str) -> bool {
+    let words: Vec<&str> = command.trim_start_matches('$').split_whitespace().collect();
+    words.first() == Some(&"return") || words.windows(2).any(|pair| pair == ["run", "return"])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::execute::ExecuteCommand;
+    use crate::command::selector::Selector;
+
+    #[test]
+    fn return_forms() {
+        assert_eq!(ReturnCommand::value(3).render(15).expect("should have rendered the command"), "return 3");
+        assert_eq!(ReturnCommand::Fail.render(26).expect("should have rendered the command"), "return fail");
+        assert!(ReturnCommand::Fail.render(18).is_err(), "'return fail' needs 1.20.3");
+        assert!(ReturnCommand::value(1).render(12).is_err(), "'return' needs 1.20");
+
+        let condition = ExecuteCommand::new().if_entity(Selector::executing_entity()).build().expect("should have built the command");
+        let command = ReturnCommand::run(condition);
+        assert_eq!(command.render(48).expect("should have rendered the command"), "return run execute if entity @s");
+        assert!(command.render(15).is_err(), "'return run' needs 1.20.2");
+    }
+
+    #[test]
+    fn returns() {
+        assert!(is_return("return 1"));
+        assert!(is_return("execute if entity @s run return fail"));
+        assert!(is_return("$return $(value)"));
+        assert!(!is_return("say return"));
+    }
+}
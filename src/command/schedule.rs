@@ -0,0 +1,144 @@
+use std::fmt::{self, Display};
+use std::num::NonZeroU32;
+use crate::data::tag::TagValue;
+use crate::error::DatapackError;
+
+/// A non-zero amount of game time, as commands like `schedule` take it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameTime {
+    Ticks(NonZeroU32),
+    /// 20 ticks each.
+    Seconds(NonZeroU32),
+    /// 24000 ticks each.
+    Days(NonZeroU32),
+}
+
+fn non_zero(amount: u32) -> Result<NonZeroU32, DatapackError> {
+    NonZeroU32::new(amount).ok_or_else(|| DatapackError::Validation("invalid time: it must not be zero".to_string()))
+}
+
+impl GameTime {
+    pub fn ticks(ticks: u32) -> Result<Self, DatapackError> {
+        non_zero(ticks).map(Self::Ticks)
+    }
+
+    pub fn seconds(seconds: u32) -> Result<Self, DatapackError> {
+        non_zero(seconds).map(Self::Seconds)
+    }
+
+    pub fn days(days: u32) -> Result<Self, DatapackError> {
+        non_zero(days).map(Self::Days)
+    }
+
+    pub fn as_ticks(&self) -> u64 {
+        match *self {
+            Self::Ticks(ticks) => ticks.get().into(),
+            Self::Seconds(seconds) => u64::from(seconds.get()) * 20,
+            Self::Days(days) => u64::from(days.get()) * 24000,
+        }
+    }
+}
+
+impl Display for GameTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Ticks(ticks) => write!(f, "{ticks}t"),
+            Self::Seconds(seconds) => write!(f, "{seconds}s"),
+            Self::Days(days) => write!(f, "{days}d"),
+        }
+    }
+}
+
+/// What happens to earlier schedules of the same function.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ScheduleMode {
+    /// Replaces them, as the game does when no mode is given.
+    #[default]
+    Replace,
+    /// Keeps them, so the function runs once for each.
+    Append,
+}
+
+impl Display for ScheduleMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Replace => "replace",
+            Self::Append => "append",
+        })
+    }
+}
+
+/// A `schedule` command, for a function or a `#` function tag.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScheduleCommand {
+    /// Runs `function` after `time`. The mode is left out without one.
+    Function { function: TagValue, time: GameTime, mode: Option<ScheduleMode> },
+    /// Cancels every schedule of `function`.
+    Clear(TagValue),
+}
+
+impl ScheduleCommand {
+    pub fn function(function: impl Into<TagValue>, time: GameTime) -> Self {
+        Self::Function { function: function.into(), time, mode: None }
+    }
+
+    pub fn clear(function: impl Into<TagValue>) -> Self {
+        Self::Clear(function.into())
+    }
+
+    /// Sets the mode of a `schedule function` command.
+    pub fn mode(mut self, mode: ScheduleMode) -> Self {
+        if let Self::Function { mode: existing, .. } = &mut self {
+            *existing = Some(mode);
+        }
+        self
+    }
+}
+
+impl Display for ScheduleCommand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Function { function, time, mode } => {
+                write!(f, "schedule function {function} {time}")?;
+                if let Some(mode) = mode {
+                    write!(f, " {mode}")?;
+                }
+                Ok(())
+            }
+            Self::Clear(function) => write!(f, "schedule clear {function}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resource_location::ResourceLocation;
+
+    fn location(location: &str) -> ResourceLocation {
+        location.parse().expect("should have parsed the resource location")
+    }
+
+    #[test]
+    fn game_time() {
+        assert_eq!(GameTime::ticks(5).expect("should have accepted the time").to_string(), "5t");
+        assert_eq!(GameTime::seconds(3).expect("should have accepted the time").to_string(), "3s");
+        assert_eq!(GameTime::days(2).expect("should have accepted the time").to_string(), "2d");
+        assert_eq!(GameTime::days(1).expect("should have accepted the time").as_ticks(), 24000);
+        assert!(GameTime::ticks(0).is_err());
+        assert!(GameTime::seconds(0).is_err());
+    }
+
+    #[test]
+    fn schedule_commands() {
+        let time = GameTime::seconds(10).expect("should have accepted the time");
+        let schedule = ScheduleCommand::function(location("test:explode"), time);
+        assert_eq!(schedule.to_string(), "schedule function test:explode 10s");
+        assert_eq!(schedule.clone().mode(ScheduleMode::Append).to_string(), "schedule function test:explode 10s append");
+        assert_eq!(schedule.mode(ScheduleMode::Replace).to_string(), "schedule function test:explode 10s replace");
+
+        let tag = TagValue::Tag(location("test:timers"));
+        assert_eq!(ScheduleCommand::clear(tag).to_string(), "schedule clear #test:timers");
+        assert_eq!(ScheduleCommand::clear(location("test:explode")).mode(ScheduleMode::Append).to_string(), "schedule clear test:explode");
+    }
+}
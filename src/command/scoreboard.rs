@@ -0,0 +1,329 @@
+use std::fmt::{self, Display};
+use crate::color::Color;
+use crate::command::selector::Selector;
+use crate::error::DatapackError;
+use crate::text::TextComponent;
+
+/// An objective as created by `scoreboard objectives add`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoreboardObjective {
+    name: String,
+    criterion: String,
+    display_name: Option<TextComponent>,
+}
+
+impl ScoreboardObjective {
+    /// Fails if `name` is empty or has characters other than `A-Z`, `a-z`, `0-9` and
+    /// `_.+-`, which the game can't parse without quotes.
+    pub fn new(name: impl Into<String>, criterion: impl Into<String>) -> Result<Self, DatapackError> {
+        let name = name.into();
+        if !is_objective_name(&name) {
+            return Err(DatapackError::Validation(format!("invalid objective name '{name}'")));
+        }
+        Ok(Self { name, criterion: criterion.into(), display_name: None })
+    }
+
+    /// An objective that only changes through commands.
+    pub fn dummy(name: impl Into<String>) -> Result<Self, DatapackError> {
+        Self::new(name, "dummy")
+    }
+
+    pub fn display_name(mut self, display_name: impl Into<TextComponent>) -> Self {
+        self.display_name = Some(display_name.into());
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn criterion(&self) -> &str {
+        &self.criterion
+    }
+}
+
+pub(super) fn is_objective_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '+' | '-'))
+}
+
+/// Whose score a command reads or changes: the entities of a selector, or a name such
+/// as a player name or a fake player like `#global`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScoreHolder {
+    Selector(Selector),
+    Name(String),
+    /// `*`, every holder with a score.
+    All,
+}
+
+impl From<Selector> for ScoreHolder {
+    fn from(selector: Selector) -> Self {
+        Self::Selector(selector)
+    }
+}
+
+impl From<&str> for ScoreHolder {
+    fn from(name: &str) -> Self {
+        Self::Name(name.to_string())
+    }
+}
+
+impl From<String> for ScoreHolder {
+    fn from(name: String) -> Self {
+        Self::Name(name)
+    }
+}
+
+impl Display for ScoreHolder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Selector(selector) => write!(f, "{selector}"),
+            Self::Name(name) => write!(f, "{name}"),
+            Self::All => write!(f, "*"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScoreOperation {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Modulo,
+    Assign,
+    Min,
+    Max,
+    Swap,
+}
+
+impl Display for ScoreOperation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Add => "+=",
+            Self::Subtract => "-=",
+            Self::Multiply => "*=",
+            Self::Divide => "/=",
+            Self::Modulo => "%=",
+            Self::Assign => "=",
+            Self::Min => "<",
+            Self::Max => ">",
+            Self::Swap => "><",
+        })
+    }
+}
+
+/// A `scoreboard objectives` or `scoreboard players` command.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScoreboardCommand {
+    AddObjective(Box<ScoreboardObjective>),
+    RemoveObjective(String),
+    Set { holder: ScoreHolder, objective: String, score: i32 },
+    /// Written as `players remove` when `amount` is negative, since `players add` only
+    /// takes positive amounts.
+    Add { holder: ScoreHolder, objective: String, amount: i32 },
+    Reset { holder: ScoreHolder, objective: Option<String> },
+    Operation {
+        target: ScoreHolder,
+        target_objective: String,
+        operation: ScoreOperation,
+        source: ScoreHolder,
+        source_objective: String,
+    },
+}
+
+impl ScoreboardCommand {
+    pub fn add_objective(objective: ScoreboardObjective) -> Self {
+        Self::AddObjective(Box::new(objective))
+    }
+
+    pub fn remove_objective(objective: impl Into<String>) -> Self {
+        Self::RemoveObjective(objective.into())
+    }
+
+    pub fn set(holder: impl Into<ScoreHolder>, objective: impl Into<String>, score: i32) -> Self {
+        Self::Set { holder: holder.into(), objective: objective.into(), score }
+    }
+
+    pub fn add(holder: impl Into<ScoreHolder>, objective: impl Into<String>, amount: i32) -> Self {
+        Self::Add { holder: holder.into(), objective: objective.into(), amount }
+    }
+
+    pub fn reset(holder: impl Into<ScoreHolder>, objective: impl Into<Option<String>>) -> Self {
+        Self::Reset { holder: holder.into(), objective: objective.into() }
+    }
+
+    pub fn operation(
+        target: impl Into<ScoreHolder>,
+        target_objective: impl Into<String>,
+        operation: ScoreOperation,
+        source: impl Into<ScoreHolder>,
+        source_objective: impl Into<String>,
+    ) -> Self {
+        Self::Operation {
+            target: target.into(),
+            target_objective: target_objective.into(),
+            operation,
+            source: source.into(),
+            source_objective: source_objective.into(),
+        }
+    }
+}
+
+impl Display for ScoreboardCommand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::AddObjective(objective) => {
+                write!(f, "scoreboard objectives add {} {}", objective.name, objective.criterion)?;
+                if let Some(display_name) = &objective.display_name {
+                    write!(f, " {display_name}")?;
+                }
+                Ok(())
+            }
+            Self::RemoveObjective(objective) => write!(f, "scoreboard objectives remove {objective}"),
+            Self::Set { holder, objective, score } => write!(f, "scoreboard players set {holder} {objective} {score}"),
+            Self::Add { holder, objective, amount } if *amount < 0 => {
+                write!(f, "scoreboard players remove {holder} {objective} {}", amount.unsigned_abs())
+            }
+            Self::Add { holder, objective, amount } => write!(f, "scoreboard players add {holder} {objective} {amount}"),
+            Self::Reset { holder, objective } => {
+                write!(f, "scoreboard players reset {holder}")?;
+                if let Some(objective) = objective {
+                    write!(f, " {objective}")?;
+                }
+                Ok(())
+            }
+            Self::Operation { target, target_objective, operation, source, source_objective } => write!(
+                f,
+                "scoreboard players operation {target} {target_objective} {operation} {source} {source_objective}",
+            ),
+        }
+    }
+}
+
+/// The pack format of 1.20.2, where `belowName` became `below_name`.
+const SNAKE_CASE_DISPLAY_SLOTS_PACK_FORMAT: u32 = 18;
+
+/// Where `scoreboard objectives setdisplay` shows an objective.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisplaySlot {
+    /// The player list.
+    List,
+    Sidebar,
+    /// Under the name tags of players.
+    BelowName,
+    /// The sidebar of the players on teams of a named color, made with
+    /// [`DisplaySlot::sidebar_team`].
+    SidebarTeam(Color),
+}
+
+impl DisplaySlot {
+    /// Fails for hex colors, since only named colors have a sidebar.
+    pub fn sidebar_team(color: Color) -> Result<Self, DatapackError> {
+        match color.as_named() {
+            Some(_) => Ok(Self::SidebarTeam(color)),
+            None => Err(DatapackError::Validation(format!("invalid display slot: '{color}' isn't a named color"))),
+        }
+    }
+
+    /// The slot as written in a datapack of `pack_format`. Fails for the sidebar of a hex
+    /// color, like [`DisplaySlot::sidebar_team`].
+    pub fn render(&self, pack_format: u32) -> Result<String, DatapackError> {
+        Ok(match self {
+            Self::List => "list".to_string(),
+            Self::Sidebar => "sidebar".to_string(),
+            Self::BelowName if pack_format >= SNAKE_CASE_DISPLAY_SLOTS_PACK_FORMAT => "below_name".to_string(),
+            Self::BelowName => "belowName".to_string(),
+            Self::SidebarTeam(color) => {
+                Self::sidebar_team(*color)?;
+                format!("sidebar.team.{color}")
+            }
+        })
+    }
+}
+
+/// A `scoreboard objectives setdisplay` command, which shows `objective` in `slot`, or
+/// clears the slot without an objective.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetDisplayCommand {
+    pub slot: DisplaySlot,
+    pub objective: Option<String>,
+}
+
+impl SetDisplayCommand {
+    pub fn new(slot: DisplaySlot) -> Self {
+        Self { slot, objective: None }
+    }
+
+    pub fn objective(mut self, objective: impl Into<String>) -> Self {
+        self.objective = Some(objective.into());
+        self
+    }
+
+    pub fn render(&self, pack_format: u32) -> Result<String, DatapackError> {
+        let mut command = format!("scoreboard objectives setdisplay {}", self.slot.render(pack_format)?);
+        if let Some(objective) = &self.objective {
+            command += &format!(" {objective}");
+        }
+        Ok(command)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn objectives() {
+        let objective = ScoreboardObjective::new("kills", "minecraft.killed:minecraft.zombie")
+            .expect("should have accepted the objective name")
+            .display_name(TextComponent::text("Zombie Kills").bold());
+        assert_eq!(
+            ScoreboardCommand::add_objective(objective).to_string(),
+            r#"scoreboard objectives add kills minecraft.killed:minecraft.zombie {"text":"Zombie Kills","bold":true}"#,
+        );
+        let objective = ScoreboardObjective::dummy("test.timer").expect("should have accepted the objective name");
+        assert_eq!(ScoreboardCommand::add_objective(objective).to_string(), "scoreboard objectives add test.timer dummy");
+        assert_eq!(ScoreboardCommand::remove_objective("kills").to_string(), "scoreboard objectives remove kills");
+    }
+
+    #[test]
+    fn invalid_objective_names() {
+        for name in ["", "has space", "colon:name", "quote\""] {
+            assert!(ScoreboardObjective::dummy(name).is_err(), "'{name}' should have been rejected");
+        }
+    }
+
+    #[test]
+    fn set_display() {
+        let below_name = SetDisplayCommand::new(DisplaySlot::BelowName).objective("health");
+        let render = |command: SetDisplayCommand, pack_format: u32| command.render(pack_format).expect("should have rendered the command");
+        assert_eq!(render(below_name.clone(), 15), "scoreboard objectives setdisplay belowName health");
+        assert_eq!(render(below_name, 48), "scoreboard objectives setdisplay below_name health");
+
+        let slot = DisplaySlot::sidebar_team(Color::DarkRed).expect("named colors have a sidebar");
+        assert_eq!(render(SetDisplayCommand::new(slot).objective("kills"), 48), "scoreboard objectives setdisplay sidebar.team.dark_red kills");
+        assert_eq!(render(SetDisplayCommand::new(DisplaySlot::Sidebar), 48), "scoreboard objectives setdisplay sidebar");
+        assert!(DisplaySlot::sidebar_team(Color::Hex(0x123456)).is_err());
+        assert!(SetDisplayCommand::new(DisplaySlot::SidebarTeam(Color::Hex(0x123456))).render(48).is_err(), "the slot is checked when it is written too");
+    }
+
+    #[test]
+    fn players() {
+        assert_eq!(
+            ScoreboardCommand::set(Selector::all_players(), "timer", 0).to_string(),
+            "scoreboard players set @a timer 0",
+        );
+        assert_eq!(ScoreboardCommand::add("#global", "timer", 5).to_string(), "scoreboard players add #global timer 5");
+        assert_eq!(ScoreboardCommand::add("#global", "timer", -5).to_string(), "scoreboard players remove #global timer 5");
+        assert_eq!(ScoreboardCommand::reset(ScoreHolder::All, None).to_string(), "scoreboard players reset *");
+        assert_eq!(
+            ScoreboardCommand::reset(Selector::executing_entity(), "timer".to_string()).to_string(),
+            "scoreboard players reset @s timer",
+        );
+        assert_eq!(
+            ScoreboardCommand::operation(Selector::executing_entity(), "score", ScoreOperation::Swap, "#best", "score").to_string(),
+            "scoreboard players operation @s score >< #best score",
+        );
+    }
+}
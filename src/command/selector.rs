@@ -0,0 +1,611 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+use std::mem::discriminant;
+use serde::Serialize;
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+use crate::error::DatapackError;
+use crate::range::MinecraftRange;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectorBase {
+    NearestPlayer,
+    AllPlayers,
+    RandomPlayer,
+    ExecutingEntity,
+    AllEntities,
+    NearestEntity,
+}
+
+impl Display for SelectorBase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::NearestPlayer => "@p",
+            Self::AllPlayers => "@a",
+            Self::RandomPlayer => "@r",
+            Self::ExecutingEntity => "@s",
+            Self::AllEntities => "@e",
+            Self::NearestEntity => "@n",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sort {
+    Nearest,
+    Furthest,
+    Random,
+    Arbitrary,
+}
+
+impl Display for Sort {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Nearest => "nearest",
+            Self::Furthest => "furthest",
+            Self::Random => "random",
+            Self::Arbitrary => "arbitrary",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[serde(rename_all = "lowercase")]
+pub enum GameMode {
+    Survival,
+    Creative,
+    Adventure,
+    Spectator,
+}
+
+impl Display for GameMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Survival => "survival",
+            Self::Creative => "creative",
+            Self::Adventure => "adventure",
+            Self::Spectator => "spectator",
+        })
+    }
+}
+
+/// Either whether an advancement is done, or whether each of its criteria is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AdvancementCondition {
+    Done(bool),
+    Criteria(BTreeMap<String, bool>),
+}
+
+impl Display for AdvancementCondition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Done(done) => write!(f, "{done}"),
+            Self::Criteria(criteria) => {
+                write!(f, "{{")?;
+                write_separated(f, criteria.iter().map(|(criterion, done)| format!("{criterion}={done}")))?;
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+/// A single `key=value` argument of a [`Selector`].
+///
+/// The variants are declared in the order [`Selector`] displays them.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SelectorArgument {
+    Type { entity_type: String, negated: bool },
+    Tag { tag: String, negated: bool },
+    Name { name: String, negated: bool },
+    Distance(MinecraftRange<f64>),
+    X(f64),
+    Y(f64),
+    Z(f64),
+    Dx(f64),
+    Dy(f64),
+    Dz(f64),
+    Scores(BTreeMap<String, MinecraftRange<i32>>),
+    Limit(u32),
+    Sort(Sort),
+    GameMode { game_mode: GameMode, negated: bool },
+    Team { team: String, negated: bool },
+    Level(MinecraftRange<u32>),
+    XRotation(MinecraftRange<f64>),
+    YRotation(MinecraftRange<f64>),
+    Nbt { nbt: String, negated: bool },
+    Predicate { predicate: String, negated: bool },
+    Advancements(BTreeMap<String, AdvancementCondition>),
+}
+
+impl SelectorArgument {
+    /// Whether the game accepts this argument more than once in a selector.
+    fn is_repeatable(&self) -> bool {
+        match self {
+            Self::Type { negated, .. }
+            | Self::Name { negated, .. }
+            | Self::GameMode { negated, .. }
+            | Self::Team { negated, .. } => *negated,
+            Self::Tag { .. } | Self::Nbt { .. } | Self::Predicate { .. } => true,
+            _ => false,
+        }
+    }
+
+    fn key(&self) -> &'static str {
+        match self {
+            Self::Type { .. } => "type",
+            Self::Tag { .. } => "tag",
+            Self::Name { .. } => "name",
+            Self::Distance(_) => "distance",
+            Self::X(_) => "x",
+            Self::Y(_) => "y",
+            Self::Z(_) => "z",
+            Self::Dx(_) => "dx",
+            Self::Dy(_) => "dy",
+            Self::Dz(_) => "dz",
+            Self::Scores(_) => "scores",
+            Self::Limit(_) => "limit",
+            Self::Sort(_) => "sort",
+            Self::GameMode { .. } => "gamemode",
+            Self::Team { .. } => "team",
+            Self::Level(_) => "level",
+            Self::XRotation(_) => "x_rotation",
+            Self::YRotation(_) => "y_rotation",
+            Self::Nbt { .. } => "nbt",
+            Self::Predicate { .. } => "predicate",
+            Self::Advancements(_) => "advancements",
+        }
+    }
+
+    fn order(&self) -> u8 {
+        match self {
+            Self::Type { .. } => 0,
+            Self::Tag { .. } => 1,
+            Self::Name { .. } => 2,
+            Self::Distance(_) => 3,
+            Self::X(_) => 4,
+            Self::Y(_) => 5,
+            Self::Z(_) => 6,
+            Self::Dx(_) => 7,
+            Self::Dy(_) => 8,
+            Self::Dz(_) => 9,
+            Self::Scores(_) => 10,
+            Self::Limit(_) => 11,
+            Self::Sort(_) => 12,
+            Self::GameMode { .. } => 13,
+            Self::Team { .. } => 14,
+            Self::Level(_) => 15,
+            Self::XRotation(_) => 16,
+            Self::YRotation(_) => 17,
+            Self::Nbt { .. } => 18,
+            Self::Predicate { .. } => 19,
+            Self::Advancements(_) => 20,
+        }
+    }
+}
+
+impl Display for SelectorArgument {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fn not(negated: &bool) -> &'static str {
+            if *negated { "!" } else { "" }
+        }
+
+        match self {
+            Self::Type { entity_type, negated } => write!(f, "type={}{entity_type}", not(negated)),
+            Self::Tag { tag, negated } => write!(f, "tag={}{}", not(negated), quote(tag)),
+            Self::Name { name, negated } => write!(f, "name={}{}", not(negated), quote(name)),
+            Self::Distance(range) => write!(f, "distance={range}"),
+            Self::X(x) => write!(f, "x={x}"),
+            Self::Y(y) => write!(f, "y={y}"),
+            Self::Z(z) => write!(f, "z={z}"),
+            Self::Dx(dx) => write!(f, "dx={dx}"),
+            Self::Dy(dy) => write!(f, "dy={dy}"),
+            Self::Dz(dz) => write!(f, "dz={dz}"),
+            Self::Scores(scores) => {
+                write!(f, "scores={{")?;
+                write_separated(f, scores.iter().map(|(objective, range)| format!("{}={range}", quote(objective))))?;
+                write!(f, "}}")
+            }
+            Self::Limit(limit) => write!(f, "limit={limit}"),
+            Self::Sort(sort) => write!(f, "sort={sort}"),
+            Self::GameMode { game_mode, negated } => write!(f, "gamemode={}{game_mode}", not(negated)),
+            Self::Team { team, negated } => write!(f, "team={}{}", not(negated), quote(team)),
+            Self::Level(range) => write!(f, "level={range}"),
+            Self::XRotation(range) => write!(f, "x_rotation={range}"),
+            Self::YRotation(range) => write!(f, "y_rotation={range}"),
+            Self::Nbt { nbt, negated } => write!(f, "nbt={}{nbt}", not(negated)),
+            Self::Predicate { predicate, negated } => write!(f, "predicate={}{predicate}", not(negated)),
+            Self::Advancements(advancements) => {
+                write!(f, "advancements={{")?;
+                write_separated(f, advancements.iter().map(|(advancement, condition)| format!("{advancement}={condition}")))?;
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+/// A target selector such as `@e[type=minecraft:zombie,distance=..5]`.
+///
+/// Arguments are displayed in a fixed order regardless of the order they were added
+/// in, and the brackets are omitted when there are none.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Selector {
+    pub base: SelectorBase,
+    pub arguments: Vec<SelectorArgument>,
+}
+
+impl Selector {
+    pub fn new(base: SelectorBase) -> Self {
+        Self {
+            base,
+            arguments: Vec::new(),
+        }
+    }
+
+    pub fn nearest_player() -> Self {
+        Self::new(SelectorBase::NearestPlayer)
+    }
+
+    pub fn all_players() -> Self {
+        Self::new(SelectorBase::AllPlayers)
+    }
+
+    pub fn random_player() -> Self {
+        Self::new(SelectorBase::RandomPlayer)
+    }
+
+    pub fn executing_entity() -> Self {
+        Self::new(SelectorBase::ExecutingEntity)
+    }
+
+    pub fn all_entities() -> Self {
+        Self::new(SelectorBase::AllEntities)
+    }
+
+    pub fn nearest_entity() -> Self {
+        Self::new(SelectorBase::NearestEntity)
+    }
+
+    /// Adds `argument` as is, even if an argument of the same kind already exists.
+    pub fn argument(mut self, argument: SelectorArgument) -> Self {
+        self.arguments.push(argument);
+        self
+    }
+
+    /// Adds `argument`, replacing any argument of the same kind.
+    fn replace(mut self, argument: SelectorArgument) -> Self {
+        self.arguments.retain(|existing| discriminant(existing) != discriminant(&argument));
+        self.argument(argument)
+    }
+
+    pub fn type_(self, entity_type: impl Into<String>) -> Self {
+        self.argument(SelectorArgument::Type { entity_type: entity_type.into(), negated: false })
+    }
+
+    pub fn not_type(self, entity_type: impl Into<String>) -> Self {
+        self.argument(SelectorArgument::Type { entity_type: entity_type.into(), negated: true })
+    }
+
+    pub fn tag(self, tag: impl Into<String>) -> Self {
+        self.argument(SelectorArgument::Tag { tag: tag.into(), negated: false })
+    }
+
+    pub fn not_tag(self, tag: impl Into<String>) -> Self {
+        self.argument(SelectorArgument::Tag { tag: tag.into(), negated: true })
+    }
+
+    pub fn name(self, name: impl Into<String>) -> Self {
+        self.argument(SelectorArgument::Name { name: name.into(), negated: false })
+    }
+
+    pub fn not_name(self, name: impl Into<String>) -> Self {
+        self.argument(SelectorArgument::Name { name: name.into(), negated: true })
+    }
+
+    pub fn distance(self, distance: impl Into<MinecraftRange<f64>>) -> Self {
+        self.replace(SelectorArgument::Distance(distance.into()))
+    }
+
+    pub fn x(self, x: f64) -> Self {
+        self.replace(SelectorArgument::X(x))
+    }
+
+    pub fn y(self, y: f64) -> Self {
+        self.replace(SelectorArgument::Y(y))
+    }
+
+    pub fn z(self, z: f64) -> Self {
+        self.replace(SelectorArgument::Z(z))
+    }
+
+    pub fn dx(self, dx: f64) -> Self {
+        self.replace(SelectorArgument::Dx(dx))
+    }
+
+    pub fn dy(self, dy: f64) -> Self {
+        self.replace(SelectorArgument::Dy(dy))
+    }
+
+    pub fn dz(self, dz: f64) -> Self {
+        self.replace(SelectorArgument::Dz(dz))
+    }
+
+    pub fn scores(self, scores: BTreeMap<String, MinecraftRange<i32>>) -> Self {
+        self.replace(SelectorArgument::Scores(scores))
+    }
+
+    pub fn score(mut self, objective: impl Into<String>, range: impl Into<MinecraftRange<i32>>) -> Self {
+        let (objective, range) = (objective.into(), range.into());
+        for argument in &mut self.arguments {
+            if let SelectorArgument::Scores(scores) = argument {
+                scores.insert(objective, range);
+                return self;
+            }
+        }
+        self.argument(SelectorArgument::Scores(BTreeMap::from([(objective, range)])))
+    }
+
+    pub fn limit(self, limit: u32) -> Self {
+        self.replace(SelectorArgument::Limit(limit))
+    }
+
+    pub fn sort(self, sort: Sort) -> Self {
+        self.replace(SelectorArgument::Sort(sort))
+    }
+
+    pub fn gamemode(self, game_mode: GameMode) -> Self {
+        self.argument(SelectorArgument::GameMode { game_mode, negated: false })
+    }
+
+    pub fn not_gamemode(self, game_mode: GameMode) -> Self {
+        self.argument(SelectorArgument::GameMode { game_mode, negated: true })
+    }
+
+    pub fn team(self, team: impl Into<String>) -> Self {
+        self.argument(SelectorArgument::Team { team: team.into(), negated: false })
+    }
+
+    pub fn not_team(self, team: impl Into<String>) -> Self {
+        self.argument(SelectorArgument::Team { team: team.into(), negated: true })
+    }
+
+    pub fn level(self, level: impl Into<MinecraftRange<u32>>) -> Self {
+        self.replace(SelectorArgument::Level(level.into()))
+    }
+
+    pub fn x_rotation(self, x_rotation: impl Into<MinecraftRange<f64>>) -> Self {
+        self.replace(SelectorArgument::XRotation(x_rotation.into()))
+    }
+
+    pub fn y_rotation(self, y_rotation: impl Into<MinecraftRange<f64>>) -> Self {
+        self.replace(SelectorArgument::YRotation(y_rotation.into()))
+    }
+
+    pub fn nbt(self, nbt: impl Display) -> Self {
+        self.argument(SelectorArgument::Nbt { nbt: nbt.to_string(), negated: false })
+    }
+
+    pub fn not_nbt(self, nbt: impl Display) -> Self {
+        self.argument(SelectorArgument::Nbt { nbt: nbt.to_string(), negated: true })
+    }
+
+    pub fn predicate(self, predicate: impl Into<String>) -> Self {
+        self.argument(SelectorArgument::Predicate { predicate: predicate.into(), negated: false })
+    }
+
+    pub fn not_predicate(self, predicate: impl Into<String>) -> Self {
+        self.argument(SelectorArgument::Predicate { predicate: predicate.into(), negated: true })
+    }
+
+    pub fn advancement(self, advancement: impl Into<String>, done: bool) -> Self {
+        self.with_advancement(advancement.into(), |condition| *condition = AdvancementCondition::Done(done))
+    }
+
+    pub fn advancement_criterion(self, advancement: impl Into<String>, criterion: impl Into<String>, done: bool) -> Self {
+        let criterion = criterion.into();
+        self.with_advancement(advancement.into(), |condition| match condition {
+            AdvancementCondition::Criteria(criteria) => {
+                criteria.insert(criterion, done);
+            }
+            AdvancementCondition::Done(_) => *condition = AdvancementCondition::Criteria(BTreeMap::from([(criterion, done)])),
+        })
+    }
+
+    /// Checks for argument combinations the game rejects: repeated arguments that may
+    /// only appear once (a non-negated `type`, `name`, `gamemode` or `team` excludes any
+    /// other of its kind), a `type` on a player selector, `limit` or `sort` on
+    /// `@s`, and a negative `distance` or non-positive `limit`.
+    pub fn validate(&self) -> Result<(), DatapackError> {
+        let invalid = |message: String| Err(DatapackError::Validation(format!("invalid selector '{self}': {message}")));
+        for (index, argument) in self.arguments.iter().enumerate() {
+            let repeated = self.arguments[..index].iter().any(|previous| {
+                discriminant(previous) == discriminant(argument) && !(previous.is_repeatable() && argument.is_repeatable())
+            });
+            if repeated {
+                return invalid(format!("'{}' can't be repeated", argument.key()));
+            }
+            match argument {
+                SelectorArgument::Type { .. } if self.targets_only_players() => {
+                    return invalid(format!("'type' can't be used with {}", self.base));
+                }
+                SelectorArgument::Limit(_) | SelectorArgument::Sort(_) if self.base == SelectorBase::ExecutingEntity => {
+                    return invalid(format!("'{}' can't be used with @s", argument.key()));
+                }
+                SelectorArgument::Limit(0) => return invalid("'limit' must be at least 1".to_string()),
+                SelectorArgument::Distance(distance) if distance.min().or(distance.max()).is_some_and(|bound| bound < 0.0) => {
+                    return invalid("'distance' can't be negative".to_string());
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn targets_only_players(&self) -> bool {
+        matches!(self.base, SelectorBase::NearestPlayer | SelectorBase::AllPlayers | SelectorBase::RandomPlayer)
+    }
+
+    fn with_advancement(mut self, advancement: String, update: impl FnOnce(&mut AdvancementCondition)) -> Self {
+        let position = self.arguments.iter().position(|argument| matches!(argument, SelectorArgument::Advancements(_)));
+        let index = position.unwrap_or_else(|| {
+            self.arguments.push(SelectorArgument::Advancements(BTreeMap::new()));
+            self.arguments.len() - 1
+        });
+        if let SelectorArgument::Advancements(advancements) = &mut self.arguments[index] {
+            update(advancements.entry(advancement).or_insert(AdvancementCondition::Done(true)));
+        }
+        self
+    }
+}
+
+impl Display for Selector {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.base)?;
+        if self.arguments.is_empty() {
+            return Ok(());
+        }
+        let mut arguments: Vec<&SelectorArgument> = self.arguments.iter().collect();
+        arguments.sort_by_key(|argument| argument.order());
+        write!(f, "[")?;
+        write_separated(f, arguments)?;
+        write!(f, "]")
+    }
+}
+
+fn write_separated(f: &mut fmt::Formatter, items: impl IntoIterator<Item = impl Display>) -> fmt::Result {
+    for (index, item) in items.into_iter().enumerate() {
+        if index > 0 {
+            write!(f, ",")?;
+        }
+        write!(f, "{item}")?;
+    }
+    Ok(())
+}
+
+/// Quotes `value` unless it only contains characters allowed in an unquoted string.
+fn quote(value: &str) -> String {
+    let unquoted = |c: char| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '+');
+    if value.chars().all(unquoted) {
+        value.to_string()
+    } else {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selector_without_arguments() {
+        assert_eq!(Selector::nearest_player().to_string(), "@p");
+        assert_eq!(Selector::all_players().to_string(), "@a");
+        assert_eq!(Selector::random_player().to_string(), "@r");
+        assert_eq!(Selector::executing_entity().to_string(), "@s");
+        assert_eq!(Selector::all_entities().to_string(), "@e");
+        assert_eq!(Selector::nearest_entity().to_string(), "@n");
+    }
+
+    #[test]
+    fn selector_argument_order() {
+        let selector = Selector::all_entities()
+            .sort(Sort::Nearest)
+            .limit(1)
+            .distance(..=5.0)
+            .tag("my_marker")
+            .type_("minecraft:armor_stand");
+        assert_eq!(selector.to_string(), "@e[type=minecraft:armor_stand,tag=my_marker,distance=..5,limit=1,sort=nearest]");
+    }
+
+    #[test]
+    fn selector_negated_and_repeated_arguments() {
+        let selector = Selector::all_entities()
+            .tag("a")
+            .not_tag("b")
+            .tag("c")
+            .not_type("minecraft:player")
+            .not_gamemode(GameMode::Spectator)
+            .not_name("Steve");
+        assert_eq!(selector.to_string(), "@e[type=!minecraft:player,tag=a,tag=!b,tag=c,name=!Steve,gamemode=!spectator]");
+    }
+
+    #[test]
+    fn selector_replaces_single_arguments() {
+        let selector = Selector::all_players().limit(5).limit(1).distance(1.0..=2.0).distance(3.0..);
+        assert_eq!(selector.to_string(), "@a[distance=3..,limit=1]");
+    }
+
+    #[test]
+    fn selector_scores_and_advancements() {
+        let selector = Selector::all_players()
+            .score("kills", 10..)
+            .score("deaths", ..=2)
+            .advancement("minecraft:story/root", true)
+            .advancement_criterion("test:quest", "found_item", false);
+        assert_eq!(
+            selector.to_string(),
+            "@a[scores={deaths=..2,kills=10..},advancements={minecraft:story/root=true,test:quest={found_item=false}}]",
+        );
+    }
+
+    #[test]
+    fn selector_volume_and_rotation() {
+        let selector = Selector::all_entities()
+            .dz(4.0)
+            .x(-1.5)
+            .y(64.0)
+            .z(0.0)
+            .dx(2.0)
+            .dy(1.0)
+            .x_rotation(-90.0..=0.0)
+            .y_rotation(45.0)
+            .level(30..);
+        assert_eq!(selector.to_string(), "@e[x=-1.5,y=64,z=0,dx=2,dy=1,dz=4,level=30..,x_rotation=-90..0,y_rotation=45]");
+    }
+
+    #[test]
+    fn selector_validate() {
+        let valid = [
+            Selector::all_entities().not_type("minecraft:zombie").not_type("minecraft:husk").tag("a").tag("b"),
+            Selector::all_players().x(0.0).dx(5.0),
+            Selector::executing_entity().not_gamemode(GameMode::Creative).not_gamemode(GameMode::Spectator),
+            Selector::nearest_player().limit(2).sort(Sort::Furthest),
+        ];
+        for selector in valid {
+            assert!(selector.validate().is_ok(), "'{selector}' should be valid");
+        }
+
+        let invalid = [
+            Selector::all_entities().type_("minecraft:zombie").type_("minecraft:husk"),
+            Selector::all_entities().type_("minecraft:zombie").not_type("minecraft:husk"),
+            Selector::all_players().not_type("minecraft:player"),
+            Selector::executing_entity().limit(1),
+            Selector::executing_entity().sort(Sort::Random),
+            Selector::all_entities().limit(0),
+            Selector::all_entities().distance(-1.0..=5.0),
+            Selector::all_entities().team("red").team("blue"),
+            Selector::all_entities().argument(SelectorArgument::Dx(1.0)).argument(SelectorArgument::Dx(2.0)),
+        ];
+        for selector in invalid {
+            match selector.validate() {
+                Err(DatapackError::Validation(_)) => {}
+                _ => panic!("'{selector}' should be invalid"),
+            }
+        }
+    }
+
+    #[test]
+    fn selector_quotes_names() {
+        let selector = Selector::all_entities().name("Big \"Boss\"").team("red");
+        assert_eq!(selector.to_string(), "@e[name=\"Big \\\"Boss\\\"\",team=red]");
+    }
+
+    #[test]
+    fn selector_nbt_and_predicates() {
+        let selector = Selector::executing_entity()
+            .not_predicate("test:is_sneaking")
+            .nbt("{OnGround:1b}")
+            .predicate("test:in_water");
+        assert_eq!(selector.to_string(), "@s[nbt={OnGround:1b},predicate=!test:is_sneaking,predicate=test:in_water]");
+    }
+}
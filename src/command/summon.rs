@@ -0,0 +1,513 @@
+use std::fmt::{self, Display};
+use crate::block_state::BlockState;
+use crate::command::position::Position;
+use crate::datapack::pack_format::ITEM_COMPONENTS_PACK_FORMAT;
+use crate::nbt::{NbtCompound, Snbt};
+use crate::resource_location::ResourceLocation;
+use crate::text::TextComponent;
+
+/// An entity whose NBT is built from typed settings, to be summoned with
+/// [`Summon::preset`].
+pub trait EntityPreset {
+    fn entity_type(&self) -> ResourceLocation;
+
+    /// The NBT of the entity as read by a datapack of `pack_format`.
+    fn to_nbt(&self, pack_format: u32) -> NbtCompound;
+}
+
+fn entity_type(path: &str) -> ResourceLocation {
+    ResourceLocation::minecraft(path).expect("the path is valid")
+}
+
+fn floats<const N: usize>(values: [f32; N]) -> Snbt {
+    Snbt::List(values.into_iter().map(Snbt::Float).collect())
+}
+
+/// A `summon` command. The NBT is written on the same line however large it is, and needs
+/// a position, so `~ ~ ~` is written when it has none.
+///
+/// ```
+/// use minecraft_datapack_generator::command::summon::{Marker, Summon};
+/// use minecraft_datapack_generator::nbt::NbtCompound;
+///
+/// let marker = Marker::with_data(NbtCompound::new().with("owner", "Steve"));
+/// assert_eq!(Summon::preset(&marker, 48).tag("spawn").to_string(), r#"summon minecraft:marker ~ ~ ~ {data:{owner:"Steve"},Tags:["spawn"]}"#);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summon {
+    pub entity_type: ResourceLocation,
+    pub position: Option<Position>,
+    pub nbt: Option<NbtCompound>,
+}
+
+impl Summon {
+    pub fn new(entity_type: ResourceLocation) -> Self {
+        Self { entity_type, position: None, nbt: None }
+    }
+
+    /// Summons `preset` with its NBT written for a datapack of `pack_format`.
+    pub fn preset(preset: &impl EntityPreset, pack_format: u32) -> Self {
+        Self::new(preset.entity_type()).nbt(preset.to_nbt(pack_format))
+    }
+
+    pub fn at(mut self, position: Position) -> Self {
+        self.position = Some(position);
+        self
+    }
+
+    /// Merges `nbt` into the NBT of the entity, replacing the keys it has.
+    pub fn nbt(mut self, nbt: NbtCompound) -> Self {
+        match &mut self.nbt {
+            Some(existing) => existing.extend(nbt.iter().map(|(key, value)| (key, value.clone()))),
+            None if nbt.is_empty() => {}
+            None => self.nbt = Some(nbt),
+        }
+        self
+    }
+
+    /// Adds a scoreboard tag, so the entity can be selected with `@e[tag=...]`.
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        let nbt = self.nbt.get_or_insert_with(NbtCompound::new);
+        let mut tags = match nbt.remove("Tags") {
+            Some(Snbt::List(tags)) => tags,
+            _ => Vec::new(),
+        };
+        tags.push(Snbt::String(tag.into()));
+        nbt.insert("Tags", tags);
+        self
+    }
+}
+
+impl Display for Summon {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "summon {}", self.entity_type)?;
+        match (&self.position, &self.nbt) {
+            (Some(position), Some(nbt)) => write!(f, " {position} {nbt}"),
+            (None, Some(nbt)) => write!(f, " {} {nbt}", Position::here()),
+            (Some(position), None) => write!(f, " {position}"),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+/// A `minecraft:marker`, an invisible entity that is only ticked by commands, for keeping
+/// a position and custom data.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Marker {
+    pub data: NbtCompound,
+}
+
+impl Marker {
+    pub fn with_data(data: NbtCompound) -> Self {
+        Self { data }
+    }
+}
+
+impl EntityPreset for Marker {
+    fn entity_type(&self) -> ResourceLocation {
+        entity_type("marker")
+    }
+
+    fn to_nbt(&self, _pack_format: u32) -> NbtCompound {
+        match self.data.is_empty() {
+            true => NbtCompound::new(),
+            false => NbtCompound::new().with("data", self.data.clone()),
+        }
+    }
+}
+
+/// The rotation of each part of an armor stand, in degrees around the X, Y and Z axes.
+/// Unset parts keep their default pose.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Pose {
+    pub head: Option<[f32; 3]>,
+    pub body: Option<[f32; 3]>,
+    pub left_arm: Option<[f32; 3]>,
+    pub right_arm: Option<[f32; 3]>,
+    pub left_leg: Option<[f32; 3]>,
+    pub right_leg: Option<[f32; 3]>,
+}
+
+impl Pose {
+    fn to_nbt(self) -> NbtCompound {
+        [
+            ("Head", self.head),
+            ("Body", self.body),
+            ("LeftArm", self.left_arm),
+            ("RightArm", self.right_arm),
+            ("LeftLeg", self.left_leg),
+            ("RightLeg", self.right_leg),
+        ]
+        .into_iter()
+        .filter_map(|(key, angles)| Some((key, floats(angles?))))
+        .collect()
+    }
+}
+
+/// A `minecraft:armor_stand`. Only the flags that are set are written.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ArmorStand {
+    pub invisible: bool,
+    pub no_gravity: bool,
+    /// Shrinks the hitbox to nothing, so it can't be hit or collided with.
+    pub marker: bool,
+    pub small: bool,
+    pub show_arms: bool,
+    pub pose: Pose,
+}
+
+impl ArmorStand {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn invisible(mut self) -> Self {
+        self.invisible = true;
+        self
+    }
+
+    pub fn no_gravity(mut self) -> Self {
+        self.no_gravity = true;
+        self
+    }
+
+    pub fn marker(mut self) -> Self {
+        self.marker = true;
+        self
+    }
+
+    pub fn small(mut self) -> Self {
+        self.small = true;
+        self
+    }
+
+    pub fn show_arms(mut self) -> Self {
+        self.show_arms = true;
+        self
+    }
+
+    pub fn pose(mut self, pose: Pose) -> Self {
+        self.pose = pose;
+        self
+    }
+}
+
+impl EntityPreset for ArmorStand {
+    fn entity_type(&self) -> ResourceLocation {
+        entity_type("armor_stand")
+    }
+
+    fn to_nbt(&self, _pack_format: u32) -> NbtCompound {
+        let flags = [
+            ("Invisible", self.invisible),
+            ("NoGravity", self.no_gravity),
+            ("Marker", self.marker),
+            ("Small", self.small),
+            ("ShowArms", self.show_arms),
+        ];
+        let mut nbt: NbtCompound = flags.into_iter().filter(|(_, set)| *set).collect();
+        let pose = self.pose.to_nbt();
+        if !pose.is_empty() {
+            nbt.insert("Pose", pose);
+        }
+        nbt
+    }
+}
+
+/// How a display entity is moved, rotated and scaled from its position.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Transformation {
+    /// A 4×4 matrix, row by row.
+    Matrix([f32; 16]),
+    /// Rotations are quaternions, written as `[x, y, z, w]`. The left rotation is applied
+    /// after scaling, and the right one before.
+    Decomposed { translation: [f32; 3], left_rotation: [f32; 4], scale: [f32; 3], right_rotation: [f32; 4] },
+}
+
+impl Transformation {
+    pub const IDENTITY_ROTATION: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+
+    /// Moves the entity by `translation`, without rotating or scaling it.
+    pub fn translation(translation: [f32; 3]) -> Self {
+        Self::Decomposed {
+            translation,
+            left_rotation: Self::IDENTITY_ROTATION,
+            scale: [1.0; 3],
+            right_rotation: Self::IDENTITY_ROTATION,
+        }
+    }
+
+    pub fn scale(scale: [f32; 3]) -> Self {
+        Self::Decomposed {
+            translation: [0.0; 3],
+            left_rotation: Self::IDENTITY_ROTATION,
+            scale,
+            right_rotation: Self::IDENTITY_ROTATION,
+        }
+    }
+}
+
+impl From<Transformation> for Snbt {
+    fn from(transformation: Transformation) -> Self {
+        match transformation {
+            Transformation::Matrix(matrix) => floats(matrix),
+            Transformation::Decomposed { translation, left_rotation, scale, right_rotation } => NbtCompound::new()
+                .with("left_rotation", floats(left_rotation))
+                .with("right_rotation", floats(right_rotation))
+                .with("translation", floats(translation))
+                .with("scale", floats(scale))
+                .into(),
+        }
+    }
+}
+
+/// Whether a display entity turns to face the player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Billboard {
+    Fixed,
+    /// Turns around the vertical axis only.
+    Vertical,
+    Horizontal,
+    /// Always faces the player.
+    Center,
+}
+
+impl Billboard {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Fixed => "fixed",
+            Self::Vertical => "vertical",
+            Self::Horizontal => "horizontal",
+            Self::Center => "center",
+        }
+    }
+}
+
+/// Light levels from 0 to 15 a display entity is rendered with, instead of those around it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Brightness {
+    pub block: u8,
+    pub sky: u8,
+}
+
+/// What every display entity may be configured with. Unset settings are left out.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DisplaySettings {
+    pub transformation: Option<Transformation>,
+    pub billboard: Option<Billboard>,
+    pub brightness: Option<Brightness>,
+}
+
+impl DisplaySettings {
+    fn to_nbt(self) -> NbtCompound {
+        let mut nbt = NbtCompound::new();
+        if let Some(transformation) = self.transformation {
+            nbt.insert("transformation", transformation);
+        }
+        if let Some(billboard) = self.billboard {
+            nbt.insert("billboard", billboard.name());
+        }
+        if let Some(Brightness { block, sky }) = self.brightness {
+            nbt.insert("brightness", NbtCompound::new().with("block", i32::from(block)).with("sky", i32::from(sky)));
+        }
+        nbt
+    }
+}
+
+macro_rules! display_settings {
+    ($($display:ty),+ $(,)?) => {
+        $(
+            impl $display {
+                pub fn transformation(mut self, transformation: Transformation) -> Self {
+                    self.settings.transformation = Some(transformation);
+                    self
+                }
+
+                pub fn billboard(mut self, billboard: Billboard) -> Self {
+                    self.settings.billboard = Some(billboard);
+                    self
+                }
+
+                pub fn brightness(mut self, block: u8, sky: u8) -> Self {
+                    self.settings.brightness = Some(Brightness { block, sky });
+                    self
+                }
+            }
+        )+
+    };
+}
+
+/// A `minecraft:item_display`. The item is written with a lowercase `count` since 1.20.5,
+/// and with a `Count` byte before.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemDisplay {
+    pub item: ResourceLocation,
+    pub settings: DisplaySettings,
+}
+
+impl ItemDisplay {
+    pub fn new(item: ResourceLocation) -> Self {
+        Self { item, settings: DisplaySettings::default() }
+    }
+}
+
+impl EntityPreset for ItemDisplay {
+    fn entity_type(&self) -> ResourceLocation {
+        entity_type("item_display")
+    }
+
+    fn to_nbt(&self, pack_format: u32) -> NbtCompound {
+        let item = NbtCompound::new().with("id", self.item.to_string());
+        let item = match pack_format >= ITEM_COMPONENTS_PACK_FORMAT {
+            true => item.with("count", 1),
+            false => item.with("Count", 1i8),
+        };
+        self.settings.to_nbt().with("item", item)
+    }
+}
+
+/// A `minecraft:block_display`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockDisplay {
+    pub block_state: BlockState,
+    pub settings: DisplaySettings,
+}
+
+impl BlockDisplay {
+    pub fn new(block_state: impl Into<BlockState>) -> Self {
+        Self { block_state: block_state.into(), settings: DisplaySettings::default() }
+    }
+}
+
+impl EntityPreset for BlockDisplay {
+    fn entity_type(&self) -> ResourceLocation {
+        entity_type("block_display")
+    }
+
+    fn to_nbt(&self, _pack_format: u32) -> NbtCompound {
+        self.settings.to_nbt().with("block_state", &self.block_state)
+    }
+}
+
+/// A `minecraft:text_display`. The text is written as a JSON string before 1.21.5, and as
+/// SNBT since.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextDisplay {
+    pub text: TextComponent,
+    /// The width lines are wrapped at, in pixels.
+    pub line_width: Option<i32>,
+    /// The background color as ARGB, such as `0x40000000` for the default.
+    pub background: Option<u32>,
+    pub shadow: bool,
+    pub see_through: bool,
+    pub settings: DisplaySettings,
+}
+
+impl TextDisplay {
+    pub fn new(text: impl Into<TextComponent>) -> Self {
+        Self { text: text.into(), line_width: None, background: None, shadow: false, see_through: false, settings: DisplaySettings::default() }
+    }
+
+    pub fn line_width(mut self, line_width: i32) -> Self {
+        self.line_width = Some(line_width);
+        self
+    }
+
+    pub fn background(mut self, background: u32) -> Self {
+        self.background = Some(background);
+        self
+    }
+
+    pub fn shadow(mut self) -> Self {
+        self.shadow = true;
+        self
+    }
+
+    pub fn see_through(mut self) -> Self {
+        self.see_through = true;
+        self
+    }
+}
+
+impl EntityPreset for TextDisplay {
+    fn entity_type(&self) -> ResourceLocation {
+        entity_type("text_display")
+    }
+
+    fn to_nbt(&self, pack_format: u32) -> NbtCompound {
+        let mut nbt = self.settings.to_nbt().with("text", self.text.to_nbt(pack_format));
+        if let Some(line_width) = self.line_width {
+            nbt.insert("line_width", line_width);
+        }
+        if let Some(background) = self.background {
+            nbt.insert("background", background as i32);
+        }
+        if self.shadow {
+            nbt.insert("shadow", true);
+        }
+        if self.see_through {
+            nbt.insert("see_through", true);
+        }
+        nbt
+    }
+}
+
+display_settings!(ItemDisplay, BlockDisplay, TextDisplay);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+
+    fn location(location: &str) -> ResourceLocation {
+        location.parse().expect("should have parsed the resource location")
+    }
+
+    #[test]
+    fn text_display() {
+        let display = TextDisplay::new(TextComponent::text("Welcome").color(Color::Gold))
+            .transformation(Transformation::translation([0.0, 0.5, 0.0]))
+            .billboard(Billboard::Center);
+        let summon = Summon::preset(&display, 48).at(Position::relative(0.0, 1.0, 0.0));
+        assert_eq!(
+            summon.to_string(),
+            r#"summon minecraft:text_display ~ ~1 ~ {transformation:{left_rotation:[0f,0f,0f,1f],right_rotation:[0f,0f,0f,1f],translation:[0f,0.5f,0f],scale:[1f,1f,1f]},billboard:"center",text:'{"text":"Welcome","color":"gold"}'}"#,
+        );
+        assert!(!summon.to_string().contains('\n'), "a command is a single line");
+    }
+
+    #[test]
+    fn armor_stand_and_displays() {
+        let pose = Pose { head: Some([-15.0, 0.0, 0.0]), right_arm: Some([-90.0, 0.0, 0.0]), ..Pose::default() };
+        let stand = ArmorStand::new().invisible().no_gravity().small().pose(pose);
+        assert_eq!(
+            Summon::preset(&stand, 48).at(Position::absolute(0.5, 64.0, 0.5)).to_string(),
+            "summon minecraft:armor_stand 0.5 64 0.5 {Invisible:1b,NoGravity:1b,Small:1b,Pose:{Head:[-15f,0f,0f],RightArm:[-90f,0f,0f]}}",
+        );
+        assert_eq!(Summon::preset(&ArmorStand::new(), 48).to_string(), "summon minecraft:armor_stand", "empty NBT should be left out");
+
+        let block = BlockDisplay::new(BlockState::new(location("minecraft:oak_stairs")).property("facing", "east"))
+            .transformation(Transformation::scale([0.5; 3]))
+            .brightness(15, 15);
+        assert_eq!(
+            Summon::preset(&block, 48).to_string(),
+            r#"summon minecraft:block_display ~ ~ ~ {transformation:{left_rotation:[0f,0f,0f,1f],right_rotation:[0f,0f,0f,1f],translation:[0f,0f,0f],scale:[0.5f,0.5f,0.5f]},brightness:{block:15,sky:15},block_state:{Name:"minecraft:oak_stairs",Properties:{facing:"east"}}}"#,
+        );
+
+        let item = ItemDisplay::new(location("minecraft:diamond")).billboard(Billboard::Vertical);
+        let summon = Summon::preset(&item, 48).tag("shop").tag("spinning").nbt(NbtCompound::new().with("Glowing", true));
+        assert_eq!(
+            summon.to_string(),
+            r#"summon minecraft:item_display ~ ~ ~ {billboard:"vertical",item:{id:"minecraft:diamond",count:1},Tags:["shop","spinning"],Glowing:1b}"#,
+        );
+    }
+
+    #[test]
+    fn displays_by_pack_format() {
+        let text = TextDisplay::new(TextComponent::text("Welcome").color(Color::Gold));
+        assert_eq!(Summon::preset(&text, 71).to_string(), r#"summon minecraft:text_display ~ ~ ~ {text:{color:"gold",text:"Welcome"}}"#);
+
+        let item = ItemDisplay::new(location("minecraft:diamond"));
+        assert_eq!(Summon::preset(&item, 26).to_string(), r#"summon minecraft:item_display ~ ~ ~ {item:{id:"minecraft:diamond",Count:1b}}"#);
+    }
+}
@@ -0,0 +1,284 @@
+use std::fmt::{self, Display};
+use crate::color::Color;
+use crate::command::scoreboard::{is_objective_name, ScoreHolder};
+use crate::data::function::McFunction;
+use crate::error::DatapackError;
+use crate::text::TextComponent;
+
+/// The pack format of 1.18, which lifted the limit of 16 characters on team names.
+const LONG_TEAM_NAMES_PACK_FORMAT: u32 = 8;
+
+/// Whose name tags or death messages the members of a team see.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Visibility {
+    #[default]
+    Always,
+    Never,
+    HideForOtherTeams,
+    HideForOwnTeam,
+}
+
+impl Display for Visibility {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Always => "always",
+            Self::Never => "never",
+            Self::HideForOtherTeams => "hideForOtherTeams",
+            Self::HideForOwnTeam => "hideForOwnTeam",
+        })
+    }
+}
+
+/// Which entities the members of a team push.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CollisionRule {
+    #[default]
+    Always,
+    Never,
+    PushOtherTeams,
+    PushOwnTeam,
+}
+
+impl Display for CollisionRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Always => "always",
+            Self::Never => "never",
+            Self::PushOtherTeams => "pushOtherTeams",
+            Self::PushOwnTeam => "pushOwnTeam",
+        })
+    }
+}
+
+/// What a `team modify` command changes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TeamOption {
+    DisplayName(Box<TextComponent>),
+    /// The color of the members' names, made with [`TeamOption::color`], or `reset` with
+    /// `None`.
+    Color(Option<Color>),
+    FriendlyFire(bool),
+    SeeFriendlyInvisibles(bool),
+    NametagVisibility(Visibility),
+    DeathMessageVisibility(Visibility),
+    CollisionRule(CollisionRule),
+    /// Text before the members' names.
+    Prefix(Box<TextComponent>),
+    /// Text after the members' names.
+    Suffix(Box<TextComponent>),
+}
+
+impl TeamOption {
+    /// Fails for hex colors, since teams only take named colors.
+    pub fn color(color: Color) -> Result<Self, DatapackError> {
+        match color.as_named() {
+            Some(_) => Ok(Self::Color(Some(color))),
+            None => Err(DatapackError::Validation(format!("invalid team color: '{color}' isn't a named color"))),
+        }
+    }
+
+    pub fn display_name(name: impl Into<TextComponent>) -> Self {
+        Self::DisplayName(Box::new(name.into()))
+    }
+
+    pub fn prefix(prefix: impl Into<TextComponent>) -> Self {
+        Self::Prefix(Box::new(prefix.into()))
+    }
+
+    pub fn suffix(suffix: impl Into<TextComponent>) -> Self {
+        Self::Suffix(Box::new(suffix.into()))
+    }
+}
+
+impl Display for TeamOption {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::DisplayName(name) => write!(f, "displayName {name}"),
+            Self::Color(Some(color)) => write!(f, "color {color}"),
+            Self::Color(None) => f.write_str("color reset"),
+            Self::FriendlyFire(enabled) => write!(f, "friendlyFire {enabled}"),
+            Self::SeeFriendlyInvisibles(enabled) => write!(f, "seeFriendlyInvisibles {enabled}"),
+            Self::NametagVisibility(visibility) => write!(f, "nametagVisibility {visibility}"),
+            Self::DeathMessageVisibility(visibility) => write!(f, "deathMessageVisibility {visibility}"),
+            Self::CollisionRule(rule) => write!(f, "collisionRule {rule}"),
+            Self::Prefix(prefix) => write!(f, "prefix {prefix}"),
+            Self::Suffix(suffix) => write!(f, "suffix {suffix}"),
+        }
+    }
+}
+
+/// A `team` command, made with the methods of [`Team`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum TeamCommand {
+    Add { team: String, display_name: Option<Box<TextComponent>> },
+    Remove(String),
+    /// Removes every member of the team.
+    Empty(String),
+    Modify { team: String, option: TeamOption },
+    /// Adds `members` to the team, or the executing entity without members.
+    Join { team: String, members: Option<ScoreHolder> },
+    /// Removes `members` from their teams.
+    Leave(ScoreHolder),
+}
+
+impl Display for TeamCommand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Add { team, display_name } => {
+                write!(f, "team add {team}")?;
+                if let Some(display_name) = display_name {
+                    write!(f, " {display_name}")?;
+                }
+                Ok(())
+            }
+            Self::Remove(team) => write!(f, "team remove {team}"),
+            Self::Empty(team) => write!(f, "team empty {team}"),
+            Self::Modify { team, option } => write!(f, "team modify {team} {option}"),
+            Self::Join { team, members } => {
+                write!(f, "team join {team}")?;
+                if let Some(members) = members {
+                    write!(f, " {members}")?;
+                }
+                Ok(())
+            }
+            Self::Leave(members) => write!(f, "team leave {members}"),
+        }
+    }
+}
+
+/// A team with the options it is set up with, for making the commands that change it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Team {
+    name: String,
+    display_name: Option<TextComponent>,
+    options: Vec<TeamOption>,
+}
+
+impl Team {
+    /// Fails if `name` has characters other than `A-Z`, `a-z`, `0-9` and `_.+-`, or, for
+    /// pack formats before 1.18, more than 16 characters.
+    pub fn new(name: impl Into<String>, pack_format: u32) -> Result<Self, DatapackError> {
+        let name = name.into();
+        if !is_objective_name(&name) {
+            return Err(DatapackError::Validation(format!("invalid team name '{name}'")));
+        }
+        if pack_format < LONG_TEAM_NAMES_PACK_FORMAT && name.chars().count() > 16 {
+            return Err(DatapackError::Validation(format!(
+                "invalid team name '{name}': it must be at most 16 characters before pack format {LONG_TEAM_NAMES_PACK_FORMAT}",
+            )));
+        }
+        Ok(Self { name, display_name: None, options: Vec::new() })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn display_name(mut self, display_name: impl Into<TextComponent>) -> Self {
+        self.display_name = Some(display_name.into());
+        self
+    }
+
+    /// Adds `option` to the commands of [`Team::setup`].
+    pub fn option(mut self, option: TeamOption) -> Self {
+        self.options.push(option);
+        self
+    }
+
+    pub fn add(&self) -> TeamCommand {
+        TeamCommand::Add { team: self.name.clone(), display_name: self.display_name.clone().map(Box::new) }
+    }
+
+    pub fn remove(&self) -> TeamCommand {
+        TeamCommand::Remove(self.name.clone())
+    }
+
+    pub fn empty(&self) -> TeamCommand {
+        TeamCommand::Empty(self.name.clone())
+    }
+
+    pub fn modify(&self, option: TeamOption) -> TeamCommand {
+        TeamCommand::Modify { team: self.name.clone(), option }
+    }
+
+    pub fn join(&self, members: impl Into<ScoreHolder>) -> TeamCommand {
+        TeamCommand::Join { team: self.name.clone(), members: Some(members.into()) }
+    }
+
+    pub fn leave(&self, members: impl Into<ScoreHolder>) -> TeamCommand {
+        TeamCommand::Leave(members.into())
+    }
+
+    /// The commands that add the team and apply its options.
+    pub fn setup(&self) -> Vec<TeamCommand> {
+        let options = self.options.iter().map(|option| self.modify(option.clone()));
+        [self.add()].into_iter().chain(options).collect()
+    }
+}
+
+/// A function that sets up each of `teams`, in order, for the load function.
+pub fn teams_setup<'t>(teams: impl IntoIterator<Item = &'t Team>) -> McFunction {
+    teams.into_iter().flat_map(Team::setup).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::selector::Selector;
+
+    fn red() -> Team {
+        Team::new("red", 48).expect("should have accepted the team name")
+    }
+
+    #[test]
+    fn modify_commands() {
+        let red = red();
+        let option = TeamOption::color(Color::Red).expect("named colors are valid");
+        assert_eq!(red.modify(option).to_string(), "team modify red color red");
+        assert_eq!(red.modify(TeamOption::Color(None)).to_string(), "team modify red color reset");
+        assert_eq!(red.modify(TeamOption::FriendlyFire(false)).to_string(), "team modify red friendlyFire false");
+        assert_eq!(
+            red.modify(TeamOption::NametagVisibility(Visibility::HideForOtherTeams)).to_string(),
+            "team modify red nametagVisibility hideForOtherTeams",
+        );
+        assert_eq!(red.modify(TeamOption::CollisionRule(CollisionRule::PushOwnTeam)).to_string(), "team modify red collisionRule pushOwnTeam");
+        assert_eq!(
+            red.modify(TeamOption::prefix(TextComponent::text("[Red] ").color(Color::Red))).to_string(),
+            r#"team modify red prefix {"text":"[Red] ","color":"red"}"#,
+        );
+        assert!(TeamOption::color(Color::Hex(0xFF0000)).is_err(), "teams only take named colors");
+    }
+
+    #[test]
+    fn members() {
+        let red = red();
+        assert_eq!(red.join(Selector::all_players()).to_string(), "team join red @a");
+        assert_eq!(red.join("#boss").to_string(), "team join red #boss");
+        assert_eq!(red.leave(Selector::executing_entity()).to_string(), "team leave @s");
+        assert_eq!(red.empty().to_string(), "team empty red");
+        assert_eq!(red.remove().to_string(), "team remove red");
+    }
+
+    #[test]
+    fn team_names() {
+        assert!(Team::new("a_very_long_team_name", 7).is_err(), "names were at most 16 characters before 1.18");
+        assert!(Team::new("a_very_long_team_name", 8).is_ok());
+        assert!(Team::new("red team", 48).is_err());
+    }
+
+    #[test]
+    fn setup_function() {
+        let red = red()
+            .display_name(TextComponent::text("Red Team"))
+            .option(TeamOption::color(Color::Red).expect("named colors are valid"))
+            .option(TeamOption::FriendlyFire(false));
+        let blue = Team::new("blue", 48).expect("should have accepted the team name").option(TeamOption::SeeFriendlyInvisibles(true));
+        assert_eq!(teams_setup([&red, &blue]).commands(), [
+            r#"team add red "Red Team""#,
+            "team modify red color red",
+            "team modify red friendlyFire false",
+            "team add blue",
+            "team modify blue seeFriendlyInvisibles true",
+        ]);
+    }
+}
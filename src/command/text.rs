@@ -0,0 +1,69 @@
+use std::fmt::{self, Display};
+use crate::command::selector::Selector;
+use crate::text::TextComponent;
+
+/// Where `title` shows its text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TitlePosition {
+    Title,
+    Subtitle,
+    Actionbar,
+}
+
+impl Display for TitlePosition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Title => "title",
+            Self::Subtitle => "subtitle",
+            Self::Actionbar => "actionbar",
+        })
+    }
+}
+
+/// `say <message>`, with line breaks turned into spaces, since a function reads each line
+/// as its own command.
+pub fn say(message: &str) -> String {
+    let message: Vec<&str> = message.lines().collect();
+    format!("say {}", message.join(" "))
+}
+
+/// `tellraw <targets> <component>`
+pub fn tellraw(targets: &Selector, component: impl Into<TextComponent>) -> String {
+    format!("tellraw {targets} {}", component.into())
+}
+
+/// `title <targets> title|subtitle|actionbar <component>`
+pub fn title(targets: &Selector, position: TitlePosition, component: impl Into<TextComponent>) -> String {
+    format!("title {targets} {position} {}", component.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+
+    #[test]
+    fn colored_tellraw() {
+        let component = TextComponent::text("Welcome, ").color(Color::Gold).extra(TextComponent::selector("@s"));
+        assert_eq!(
+            tellraw(&Selector::all_players(), component),
+            r#"tellraw @a {"text":"Welcome, ","color":"gold","extra":[{"selector":"@s"}]}"#,
+        );
+        assert_eq!(tellraw(&Selector::executing_entity(), "hi"), r#"tellraw @s "hi""#);
+    }
+
+    #[test]
+    fn multiline_say() {
+        assert_eq!(say("Hello!"), "say Hello!");
+        assert_eq!(say("Restarting\r\nin a minute"), "say Restarting in a minute");
+    }
+
+    #[test]
+    fn actionbar_title() {
+        let component = TextComponent::text("Low health!").color(Color::Red).bold();
+        assert_eq!(
+            title(&Selector::nearest_player(), TitlePosition::Actionbar, component),
+            r#"title @p actionbar {"text":"Low health!","color":"red","bold":true}"#,
+        );
+    }
+}
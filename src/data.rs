@@ -0,0 +1,19 @@
+pub mod damage_type;
+pub mod trim;
+pub mod worldgen;
+pub mod predicates;
+pub mod advancement;
+pub mod enchantment;
+pub mod number_provider;
+pub mod conditions;
+pub mod loot_function;
+pub mod loot_table;
+pub mod ingredient;
+pub mod recipe;
+pub mod function;
+pub mod tag;
+pub mod dimension;
+pub mod chat_type;
+pub mod painting_variant;
+pub mod wolf_variant;
+pub mod jukebox_song;
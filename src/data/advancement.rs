@@ -0,0 +1,488 @@
+use std::collections::BTreeMap;
+use serde::Serialize;
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+use serde_json::Value;
+use crate::data::predicates::{EntityPredicate, ItemPredicate};
+use crate::data::function::McFunction;
+use crate::data::loot_table::LootTable;
+use crate::data::recipe::Recipe;
+use crate::datapack::resource::{Reference, Resource};
+use crate::error::DatapackError;
+use crate::resource_location::ResourceLocation;
+use crate::text::TextComponent;
+use crate::utils::json::json_file;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[serde(rename_all = "snake_case")]
+pub enum AdvancementFrame {
+    #[default]
+    Task,
+    Goal,
+    Challenge,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct AdvancementIcon {
+    pub item: ResourceLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nbt: Option<String>,
+}
+
+impl From<ResourceLocation> for AdvancementIcon {
+    fn from(item: ResourceLocation) -> Self {
+        Self { item, nbt: None }
+    }
+}
+
+/// How an advancement shows up in the advancement screen, toasts and chat.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct AdvancementDisplay {
+    pub icon: AdvancementIcon,
+    pub title: TextComponent,
+    pub description: TextComponent,
+    #[serde(default)]
+    pub frame: AdvancementFrame,
+    /// The tab background, only used by root advancements.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background: Option<String>,
+    #[serde(default = "default_true")]
+    pub show_toast: bool,
+    #[serde(default = "default_true")]
+    pub announce_to_chat: bool,
+    #[serde(default)]
+    pub hidden: bool,
+}
+
+#[cfg(feature = "serde")]
+fn default_true() -> bool {
+    true
+}
+
+impl AdvancementDisplay {
+    pub fn new(icon: impl Into<AdvancementIcon>, title: impl Into<TextComponent>, description: impl Into<TextComponent>) -> Self {
+        Self {
+            icon: icon.into(),
+            title: title.into(),
+            description: description.into(),
+            frame: AdvancementFrame::Task,
+            background: None,
+            show_toast: true,
+            announce_to_chat: true,
+            hidden: false,
+        }
+    }
+
+    pub fn frame(mut self, frame: AdvancementFrame) -> Self {
+        self.frame = frame;
+        self
+    }
+
+    pub fn background(mut self, background: impl Into<String>) -> Self {
+        self.background = Some(background.into());
+        self
+    }
+
+    pub fn show_toast(mut self, show_toast: bool) -> Self {
+        self.show_toast = show_toast;
+        self
+    }
+
+    pub fn announce_to_chat(mut self, announce_to_chat: bool) -> Self {
+        self.announce_to_chat = announce_to_chat;
+        self
+    }
+
+    pub fn hidden(mut self, hidden: bool) -> Self {
+        self.hidden = hidden;
+        self
+    }
+}
+
+/// A trigger without typed conditions, written as is.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct RawTrigger {
+    pub trigger: ResourceLocation,
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub conditions: Value,
+}
+
+/// What completes a criterion, with the conditions it's checked against. Unset
+/// conditions match anything.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[serde(tag = "trigger", content = "conditions")]
+pub enum Trigger {
+    #[serde(rename = "minecraft:impossible")]
+    Impossible,
+    #[serde(rename = "minecraft:tick")]
+    Tick {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        player: Option<EntityPredicate>,
+    },
+    #[serde(rename = "minecraft:location")]
+    Location {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        player: Option<EntityPredicate>,
+    },
+    #[serde(rename = "minecraft:consume_item")]
+    ConsumeItem {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        player: Option<EntityPredicate>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        item: Option<ItemPredicate>,
+    },
+    #[serde(rename = "minecraft:inventory_changed")]
+    InventoryChanged {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        player: Option<EntityPredicate>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        items: Vec<ItemPredicate>,
+    },
+    #[serde(rename = "minecraft:player_interacted_with_entity")]
+    PlayerInteractedWithEntity {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        player: Option<EntityPredicate>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        item: Option<ItemPredicate>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        entity: Option<EntityPredicate>,
+    },
+    #[serde(rename = "minecraft:placed_block")]
+    PlacedBlock {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        player: Option<EntityPredicate>,
+        /// Loot conditions checked against the placed block.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        location: Vec<Value>,
+    },
+    /// `minecraft:using_item`, checked every tick an item is being used.
+    #[serde(rename = "minecraft:using_item")]
+    UsingItem {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        player: Option<EntityPredicate>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        item: Option<ItemPredicate>,
+    },
+    #[serde(untagged)]
+    Raw(RawTrigger),
+}
+
+impl Trigger {
+    pub fn tick() -> Self {
+        Self::Tick { player: None }
+    }
+
+    pub fn consume_item(item: ItemPredicate) -> Self {
+        Self::ConsumeItem { player: None, item: Some(item) }
+    }
+
+    pub fn inventory_changed(items: impl IntoIterator<Item = ItemPredicate>) -> Self {
+        Self::InventoryChanged { player: None, items: items.into_iter().collect() }
+    }
+
+    pub fn raw(trigger: ResourceLocation, conditions: impl Into<Value>) -> Self {
+        Self::Raw(RawTrigger { trigger, conditions: conditions.into() })
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct AdvancementRewards {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub function: Option<ResourceLocation>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub recipes: Vec<ResourceLocation>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub loot: Vec<ResourceLocation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub experience: Option<i32>,
+}
+
+impl AdvancementRewards {
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// A `data/<namespace>/advancements` file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct Advancement {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent: Option<ResourceLocation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display: Option<AdvancementDisplay>,
+    pub criteria: BTreeMap<String, Trigger>,
+    /// Groups of criterion names: the advancement is done once each group has one done
+    /// criterion. Left out, every criterion is required.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub requirements: Vec<Vec<String>>,
+    #[serde(default, skip_serializing_if = "AdvancementRewards::is_empty")]
+    pub rewards: AdvancementRewards,
+}
+
+impl Advancement {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The first advancement of a tab, which has no parent. Its display gives the icon and
+    /// background of the tab.
+    pub fn root(display: AdvancementDisplay) -> Self {
+        Self::new().display(display)
+    }
+
+    /// An advancement shown after `parent` in the tree.
+    pub fn child<S: Into<String>>(
+        parent: ResourceLocation,
+        display: AdvancementDisplay,
+        criteria: impl IntoIterator<Item = (S, Trigger)>,
+    ) -> Self {
+        let advancement = Self::new().parent(parent).display(display);
+        criteria.into_iter().fold(advancement, |advancement, (name, trigger)| advancement.criterion(name, trigger))
+    }
+
+    pub fn parent(mut self, parent: ResourceLocation) -> Self {
+        self.parent = Some(parent);
+        self
+    }
+
+    pub fn display(mut self, display: AdvancementDisplay) -> Self {
+        self.display = Some(display);
+        self
+    }
+
+    pub fn criterion(mut self, name: impl Into<String>, trigger: Trigger) -> Self {
+        self.criteria.insert(name.into(), trigger);
+        self
+    }
+
+    /// Adds a group of criteria, one of which has to be done.
+    pub fn requirement<S: Into<String>>(mut self, criteria: impl IntoIterator<Item = S>) -> Self {
+        self.requirements.push(criteria.into_iter().map(Into::into).collect());
+        self
+    }
+
+    pub fn rewards(mut self, rewards: AdvancementRewards) -> Self {
+        self.rewards = rewards;
+        self
+    }
+}
+
+json_file!(Advancement);
+
+impl Resource for Advancement {
+    const DIRECTORY: &'static str = "advancements";
+
+    /// Renamed to `advancement` in pack format 45.
+    fn directory(pack_format: u32) -> &'static str {
+        if pack_format >= 45 { "advancement" } else { Self::DIRECTORY }
+    }
+
+    /// Checks that there is at least one criterion, that no criterion name is empty, and
+    /// that requirements only name declared criteria.
+    fn validate(&self) -> Result<(), DatapackError> {
+        let invalid = |message: String| Err(DatapackError::Validation(format!("invalid advancement: {message}")));
+        if self.criteria.is_empty() {
+            return invalid("it has no criteria".to_string());
+        }
+        if self.criteria.contains_key("") {
+            return invalid("a criterion name is empty".to_string());
+        }
+        for group in &self.requirements {
+            if group.is_empty() {
+                return invalid("a requirement group is empty".to_string());
+            }
+            if let Some(unknown) = group.iter().find(|criterion| !self.criteria.contains_key(*criterion)) {
+                return invalid(format!("requirement '{unknown}' is not a declared criterion"));
+            }
+        }
+        Ok(())
+    }
+
+    /// The parent, and the function, recipes and loot tables of the rewards.
+    fn references(&self) -> Vec<Reference> {
+        let rewards = &self.rewards;
+        let parent = self.parent.iter().map(|parent| Reference::to::<Advancement>(parent.clone()));
+        let function = rewards.function.iter().map(|function| Reference::to::<McFunction>(function.clone()));
+        let recipes = rewards.recipes.iter().map(|recipe| Reference::to::<Recipe>(recipe.clone()));
+        let loot = rewards.loot.iter().map(|table| Reference::to::<LootTable>(table.clone()));
+        parent.chain(function).chain(recipes).chain(loot).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use crate::data::predicates::LocationPredicate;
+    use crate::datapack::Datapack;
+    use crate::datapack::pack_meta::PackMeta;
+    use crate::color::Color;
+
+    fn location(location: &str) -> ResourceLocation {
+        location.parse().expect("should have parsed the resource location")
+    }
+
+    #[test]
+    fn root_and_child() {
+        let root = Advancement::new()
+            .display(
+                AdvancementDisplay::new(location("minecraft:diamond"), "Gems", "Find every gem")
+                    .background("minecraft:textures/gui/advancements/backgrounds/stone.png")
+                    .show_toast(false)
+                    .announce_to_chat(false),
+            )
+            .criterion("tick", Trigger::tick());
+        let child = Advancement::new()
+            .parent(location("test:gems/root"))
+            .display(
+                AdvancementDisplay::new(location("minecraft:emerald"), TextComponent::text("Green").color(Color::Green), "Hold five emeralds")
+                    .frame(AdvancementFrame::Goal),
+            )
+            .criterion("has_emeralds", Trigger::inventory_changed([ItemPredicate::new().item(location("minecraft:emerald")).count(5..)]))
+            .criterion("in_jungle", Trigger::Location {
+                player: Some(EntityPredicate::new().location(LocationPredicate::new().biome(location("minecraft:jungle")))),
+            })
+            .requirement(["has_emeralds", "in_jungle"])
+            .rewards(AdvancementRewards { experience: Some(50), function: Some(location("test:reward")), ..Default::default() });
+
+        let root_json: Value = serde_json::from_str(&root.to_string()).expect("root should be valid JSON");
+        assert_eq!(root_json, json!({
+            "display": {
+                "icon": {"item": "minecraft:diamond"},
+                "title": "Gems",
+                "description": "Find every gem",
+                "frame": "task",
+                "background": "minecraft:textures/gui/advancements/backgrounds/stone.png",
+                "show_toast": false,
+                "announce_to_chat": false,
+                "hidden": false,
+            },
+            "criteria": {"tick": {"trigger": "minecraft:tick", "conditions": {}}},
+        }), "root advancement was not rendered correctly");
+
+        let child_json: Value = serde_json::from_str(&child.to_string()).expect("child should be valid JSON");
+        assert_eq!(child_json, json!({
+            "parent": "test:gems/root",
+            "display": {
+                "icon": {"item": "minecraft:emerald"},
+                "title": {"text": "Green", "color": "green"},
+                "description": "Hold five emeralds",
+                "frame": "goal",
+                "show_toast": true,
+                "announce_to_chat": true,
+                "hidden": false,
+            },
+            "criteria": {
+                "has_emeralds": {
+                    "trigger": "minecraft:inventory_changed",
+                    "conditions": {"items": [{"items": ["minecraft:emerald"], "count": {"min": 5}}]},
+                },
+                "in_jungle": {
+                    "trigger": "minecraft:location",
+                    "conditions": {"player": {"location": {"biome": "minecraft:jungle"}}},
+                },
+            },
+            "requirements": [["has_emeralds", "in_jungle"]],
+            "rewards": {"function": "test:reward", "experience": 50},
+        }), "child advancement was not rendered correctly");
+    }
+
+    #[test]
+    fn impossible_and_raw_triggers() {
+        let json = serde_json::to_value(Trigger::Impossible).expect("should have serialized the trigger");
+        assert_eq!(json, json!({"trigger": "minecraft:impossible"}));
+
+        let trigger = Trigger::raw(location("minecraft:brewed_potion"), json!({"potion": "minecraft:swiftness"}));
+        let json = serde_json::to_value(trigger).expect("should have serialized the trigger");
+        assert_eq!(json, json!({"trigger": "minecraft:brewed_potion", "conditions": {"potion": "minecraft:swiftness"}}));
+    }
+
+    #[test]
+    fn validation() {
+        let valid = Advancement::new().criterion("a", Trigger::Impossible).criterion("b", Trigger::tick()).requirement(["a"]);
+        assert!(valid.validate().is_ok(), "advancement should be valid");
+
+        let invalid = [
+            Advancement::new(),
+            Advancement::new().criterion("", Trigger::Impossible),
+            Advancement::new().criterion("a", Trigger::Impossible).requirement(["b"]),
+            Advancement::new().criterion("a", Trigger::Impossible).requirement(Vec::<String>::new()),
+        ];
+        for advancement in invalid {
+            let mut datapack = Datapack::new(PackMeta::new(15, "test"));
+            match datapack.add(&location("test:invalid"), advancement) {
+                Err(DatapackError::Validation(_)) => {}
+                result => panic!("advancement should have been rejected, got {result:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn namespace_placement() {
+        for (pack_format, advancement_path, function_path) in [
+            (26, "data/test/advancements/gems/ruby.json", "data/test/functions/gems/reward.mcfunction"),
+            (48, "data/test/advancement/gems/ruby.json", "data/test/function/gems/reward.mcfunction"),
+        ] {
+            let mut datapack = Datapack::new(PackMeta::new(pack_format, "test"));
+            let mut namespace = datapack.namespace("test").expect("should have accepted the namespace");
+            let reward = namespace.add_function("gems/reward", ["say ruby!"].into_iter().collect()).expect("should have added the function");
+            let rewards = AdvancementRewards { function: Some(reward), ..AdvancementRewards::default() };
+            namespace
+                .add_advancement("gems/ruby", Advancement::new().criterion("tick", Trigger::tick()).rewards(rewards))
+                .expect("should have added the advancement");
+            assert!(datapack.contains(advancement_path), "the advancement should have been placed at '{advancement_path}'");
+            assert!(datapack.contains(function_path), "the function should have been placed at '{function_path}'");
+            assert!(datapack.validate().is_empty(), "the reward should have been found");
+        }
+    }
+
+    #[test]
+    fn root_and_child_constructors() {
+        let display = AdvancementDisplay::new(location("minecraft:diamond"), "Gems", "Find every gem")
+            .background("minecraft:textures/gui/advancements/backgrounds/stone.png");
+        let root = Advancement::root(display.clone()).criterion("tick", Trigger::tick());
+        assert_eq!(root.parent, None);
+        assert!(root.validate().is_ok());
+        assert_eq!(root, Advancement::new().display(display).criterion("tick", Trigger::tick()));
+
+        let emerald = AdvancementDisplay::new(location("minecraft:emerald"), "Green", "Hold an emerald");
+        let child = Advancement::child(
+            location("test:gems/root"),
+            emerald.clone(),
+            [("has_emerald", Trigger::inventory_changed([ItemPredicate::new().item(location("minecraft:emerald"))]))],
+        );
+        assert_eq!(child, Advancement::new().parent(location("test:gems/root")).display(emerald).criterion(
+            "has_emerald",
+            Trigger::inventory_changed([ItemPredicate::new().item(location("minecraft:emerald"))]),
+        ));
+        assert!(child.validate().is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn read_back() {
+        let advancement = Advancement::new()
+            .parent(location("test:gems/root"))
+            .display(AdvancementDisplay::new(location("minecraft:emerald"), TextComponent::text("Green").color(Color::Green), "Hold five emeralds"))
+            .criterion("has_emeralds", Trigger::inventory_changed([ItemPredicate::new().item(location("minecraft:emerald")).count(5..)]))
+            .criterion("tick", Trigger::tick())
+            .requirement(["has_emeralds", "tick"]);
+        let read: Advancement = serde_json::from_str(&advancement.to_string()).expect("should have read the advancement back");
+        assert_eq!(read, advancement, "advancement did not survive a round trip");
+
+        let read: Advancement = serde_json::from_str(r#"{
+            "display": {"icon": {"item": "minecraft:stone"}, "title": "Rock", "description": ["Mine ", {"text": "stone", "bold": true}]},
+            "criteria": {"mined": {"trigger": "minecraft:inventory_changed", "conditions": {"items": [{"items": ["minecraft:stone"]}]}}}
+        }"#).expect("should have read a hand-written advancement");
+        let display = read.display.expect("display should have been read");
+        assert!(display.show_toast && display.announce_to_chat && !display.hidden, "display flags should default like the game's");
+        assert_eq!(display.description, TextComponent::text("Mine ").extra(TextComponent::text("stone").bold()));
+    }
+}
@@ -0,0 +1,159 @@
+use serde::Serialize;
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer};
+use crate::datapack::resource::Resource;
+use crate::error::DatapackError;
+use crate::text::Style;
+use crate::utils::json::json_file;
+
+/// A value a [`ChatDecoration`]'s translation is filled in with, in the order given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[serde(rename_all = "snake_case")]
+pub enum ChatParameter {
+    /// The name of the player or entity that sent the message.
+    Sender,
+    /// The name of the team or player a message like `/teammsg` was sent to.
+    Target,
+    /// The message itself.
+    Content,
+}
+
+/// The keys of a [`Style`], the only ones a decoration's style may have.
+#[cfg(feature = "serde")]
+const STYLE_FIELDS: [&str; 10] = [
+    "color", "font", "bold", "italic", "underlined", "strikethrough", "obfuscated", "insertion", "clickEvent", "hoverEvent",
+];
+
+/// Rejects text component fields such as `text`, which [`Style`] would otherwise ignore.
+#[cfg(feature = "serde")]
+fn deserialize_style<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Style>, D::Error> {
+    let style = serde_json::Map::deserialize(deserializer)?;
+    if let Some(field) = style.keys().find(|field| !STYLE_FIELDS.contains(&field.as_str())) {
+        return Err(de::Error::custom(format!("'{field}' is not a style field")));
+    }
+    serde_json::from_value(style.into()).map(Some).map_err(de::Error::custom)
+}
+
+/// How a chat message is turned into text, through a translation.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize), serde(deny_unknown_fields))]
+pub struct ChatDecoration {
+    pub translation_key: String,
+    pub parameters: Vec<ChatParameter>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "serde", serde(default, deserialize_with = "deserialize_style"))]
+    pub style: Option<Style>,
+}
+
+impl ChatDecoration {
+    pub fn new(translation_key: impl Into<String>, parameters: impl Into<Vec<ChatParameter>>) -> Self {
+        Self { translation_key: translation_key.into(), parameters: parameters.into(), style: None }
+    }
+
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = Some(style);
+        self
+    }
+}
+
+/// A `data/<namespace>/chat_type` file, available since 1.19: how messages of this type
+/// are shown in chat and read by the narrator.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize), serde(deny_unknown_fields))]
+pub struct ChatType {
+    pub chat: ChatDecoration,
+    pub narration: ChatDecoration,
+}
+
+impl ChatType {
+    pub fn new(chat: ChatDecoration, narration: ChatDecoration) -> Self {
+        Self { chat, narration }
+    }
+}
+
+json_file!(ChatType);
+
+impl Resource for ChatType {
+    const DIRECTORY: &'static str = "chat_type";
+    const MIN_PACK_FORMAT: u32 = 10;
+
+    fn validate(&self) -> Result<(), DatapackError> {
+        for (name, decoration) in [("chat", &self.chat), ("narration", &self.narration)] {
+            let invalid = |message: &str| Err(DatapackError::Validation(format!("invalid chat type {name}: {message}")));
+            if decoration.translation_key.is_empty() {
+                return invalid("the translation key is empty");
+            }
+            let parameters = &decoration.parameters;
+            if parameters.iter().enumerate().any(|(i, parameter)| parameters[..i].contains(parameter)) {
+                return invalid("a parameter is repeated");
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+    use crate::color::Color;
+
+    #[test]
+    fn chat_type_display() {
+        let chat_type = ChatType::new(
+            ChatDecoration::new("chat.type.announcement", [ChatParameter::Sender, ChatParameter::Content])
+                .style(Style { color: Some(Color::Gold), italic: Some(true), ..Style::default() }),
+            ChatDecoration::new("chat.type.text.narrate", [ChatParameter::Sender, ChatParameter::Content]),
+        );
+        let json: Value = serde_json::from_str(&chat_type.to_string()).expect("chat type should be valid JSON");
+        let expected = json!({
+            "chat": {
+                "translation_key": "chat.type.announcement",
+                "parameters": ["sender", "content"],
+                "style": {"color": "gold", "italic": true},
+            },
+            "narration": {
+                "translation_key": "chat.type.text.narrate",
+                "parameters": ["sender", "content"],
+            },
+        });
+        assert_eq!(json, expected, "chat type was not rendered correctly");
+    }
+
+    /// `minecraft:chat` from the game's data.
+    #[test]
+    fn vanilla_chat() {
+        let parameters = [ChatParameter::Sender, ChatParameter::Content];
+        let chat_type = ChatType::new(ChatDecoration::new("chat.type.text", parameters), ChatDecoration::new("chat.type.text.narrate", parameters));
+        let json: Value = serde_json::from_str(&chat_type.to_string()).expect("chat type should be valid JSON");
+        let vanilla = json!({
+            "chat": {"parameters": ["sender", "content"], "translation_key": "chat.type.text"},
+            "narration": {"parameters": ["sender", "content"], "translation_key": "chat.type.text.narrate"},
+        });
+        assert_eq!(json, vanilla, "the chat type should have the shape of the vanilla chat");
+        assert!(chat_type.validate().is_ok());
+
+        let repeated = ChatDecoration::new("chat.type.text", [ChatParameter::Sender, ChatParameter::Sender]);
+        assert!(ChatType::new(repeated, ChatDecoration::new("chat.type.text.narrate", [])).validate().is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn read_decoration() {
+        let read = |json: Value| serde_json::from_value::<ChatDecoration>(json);
+        let decoration = read(json!({"translation_key": "chat.type.emote", "parameters": ["sender", "content"], "style": {"color": "gray"}}))
+            .expect("should have read the decoration");
+        assert_eq!(decoration.style, Some(Style { color: Some(Color::Gray), ..Style::default() }));
+        assert!(read(json!({"translation_key": "a", "parameters": ["receiver"]})).is_err(), "unknown parameters should be rejected");
+        assert!(read(json!({"translation_key": "a", "parameters": [], "style": {"text": "hi"}})).is_err(), "text is not a style field");
+    }
+
+    #[test]
+    fn parameters() {
+        let decoration = ChatDecoration::new("chat.type.team.sent", [ChatParameter::Target, ChatParameter::Sender, ChatParameter::Content]);
+        let json = serde_json::to_value(&decoration).expect("should have serialized the decoration");
+        assert_eq!(json["parameters"], json!(["target", "sender", "content"]));
+        assert_eq!(serde_json::to_value(ChatDecoration::new("chat.square_brackets", [])).expect("should have serialized the decoration")["parameters"], json!([]));
+    }
+}
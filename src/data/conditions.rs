@@ -0,0 +1,314 @@
+use serde::Serialize;
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+use serde_json::Value;
+use crate::data::number_provider::NumberProvider;
+use crate::data::predicates::{EntityPredicate, ItemPredicate, LocationPredicate};
+use crate::datapack::resource::{Reference, Resource};
+use crate::range::MinecraftRange;
+use crate::resource_location::ResourceLocation;
+use crate::utils::json::json_file;
+
+/// Which entity of the loot context a condition or function looks at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[serde(rename_all = "snake_case")]
+pub enum LootContextEntity {
+    This,
+    Killer,
+    DirectKiller,
+    KillerPlayer,
+}
+
+/// A loot condition, as used by loot tables, item modifiers and predicate files.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[serde(tag = "condition")]
+pub enum LootCondition {
+    #[serde(rename = "minecraft:random_chance")]
+    RandomChance { chance: f32 },
+    #[serde(rename = "minecraft:random_chance_with_looting")]
+    RandomChanceWithLooting { chance: f32, looting_multiplier: f32 },
+    #[serde(rename = "minecraft:killed_by_player")]
+    KilledByPlayer,
+    #[serde(rename = "minecraft:survives_explosion")]
+    SurvivesExplosion,
+    #[serde(rename = "minecraft:match_tool")]
+    MatchTool { predicate: ItemPredicate },
+    #[serde(rename = "minecraft:entity_properties")]
+    EntityProperties { entity: LootContextEntity, predicate: EntityPredicate },
+    /// Checks the location of the context, moved by the offsets.
+    #[serde(rename = "minecraft:location_check")]
+    LocationCheck {
+        #[serde(rename = "offsetX", skip_serializing_if = "Option::is_none")]
+        offset_x: Option<i32>,
+        #[serde(rename = "offsetY", skip_serializing_if = "Option::is_none")]
+        offset_y: Option<i32>,
+        #[serde(rename = "offsetZ", skip_serializing_if = "Option::is_none")]
+        offset_z: Option<i32>,
+        predicate: LocationPredicate,
+    },
+    #[serde(rename = "minecraft:weather_check")]
+    WeatherCheck {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        raining: Option<bool>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        thundering: Option<bool>,
+    },
+    /// Checks the day time, taken modulo `period` when it is set.
+    #[serde(rename = "minecraft:time_check")]
+    TimeCheck {
+        value: MinecraftRange<i64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        period: Option<i64>,
+    },
+    #[serde(rename = "minecraft:value_check")]
+    ValueCheck { value: NumberProvider, range: MinecraftRange<i32> },
+    #[serde(rename = "minecraft:inverted")]
+    Inverted { term: Box<LootCondition> },
+    #[serde(rename = "minecraft:any_of")]
+    AnyOf { terms: Vec<LootCondition> },
+    #[serde(rename = "minecraft:all_of")]
+    AllOf { terms: Vec<LootCondition> },
+    /// The predicate file with this ID.
+    #[serde(rename = "minecraft:reference")]
+    Reference { name: ResourceLocation },
+    /// Any other condition, written as is. It should include its own `condition`.
+    #[serde(untagged)]
+    Raw(Value),
+}
+
+impl LootCondition {
+    pub fn inverted(term: LootCondition) -> Self {
+        Self::Inverted { term: Box::new(term) }
+    }
+
+    pub fn any_of(terms: impl IntoIterator<Item = LootCondition>) -> Self {
+        Self::AnyOf { terms: terms.into_iter().collect() }
+    }
+
+    pub fn all_of(terms: impl IntoIterator<Item = LootCondition>) -> Self {
+        Self::AllOf { terms: terms.into_iter().collect() }
+    }
+
+    pub fn random_chance(chance: f32) -> Self {
+        Self::RandomChance { chance }
+    }
+
+    pub fn reference(name: ResourceLocation) -> Self {
+        Self::Reference { name }
+    }
+
+    pub fn entity_properties(entity: LootContextEntity, predicate: EntityPredicate) -> Self {
+        Self::EntityProperties { entity, predicate }
+    }
+
+    pub fn location_check(predicate: LocationPredicate) -> Self {
+        Self::LocationCheck { offset_x: None, offset_y: None, offset_z: None, predicate }
+    }
+
+    pub fn time_check(value: impl Into<MinecraftRange<i64>>, period: Option<i64>) -> Self {
+        Self::TimeCheck { value: value.into(), period }
+    }
+
+    /// The predicate files this condition refers to, through its terms too.
+    pub(crate) fn references(&self) -> Vec<Reference> {
+        match self {
+            Self::Reference { name } => vec![Reference::to::<Predicate>(name.clone())],
+            Self::Inverted { term } => term.references(),
+            Self::AnyOf { terms } | Self::AllOf { terms } => terms.iter().flat_map(Self::references).collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// A `data/<namespace>/predicates` file, for `execute if predicate`, the `predicate`
+/// selector argument and `minecraft:reference` conditions.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[serde(untagged)]
+pub enum Predicate {
+    /// Passes when every condition passes.
+    All(Vec<LootCondition>),
+    Single(Box<LootCondition>),
+}
+
+impl From<LootCondition> for Predicate {
+    fn from(condition: LootCondition) -> Self {
+        Self::Single(Box::new(condition))
+    }
+}
+
+impl From<Vec<LootCondition>> for Predicate {
+    fn from(conditions: Vec<LootCondition>) -> Self {
+        Self::All(conditions)
+    }
+}
+
+json_file!(Predicate);
+
+impl Resource for Predicate {
+    const DIRECTORY: &'static str = "predicates";
+
+    /// Renamed to `predicate` in pack format 45.
+    fn directory(pack_format: u32) -> &'static str {
+        if pack_format >= 45 { "predicate" } else { Self::DIRECTORY }
+    }
+
+    fn references(&self) -> Vec<Reference> {
+        match self {
+            Self::All(conditions) => conditions.iter().flat_map(LootCondition::references).collect(),
+            Self::Single(condition) => condition.references(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use crate::command::execute::ExecuteCommand;
+    use crate::command::selector::GameMode;
+    use crate::data::predicates::{DistancePredicate, EffectPredicate, EnchantmentPredicate, EntityEquipment, EntityFlags, PlayerPredicate};
+    use crate::datapack::Datapack;
+    use crate::datapack::pack_meta::PackMeta;
+    use crate::nbt_compound;
+
+    fn location(location: &str) -> ResourceLocation {
+        location.parse().expect("should have parsed the resource location")
+    }
+
+    fn json(predicate: &Predicate) -> Value {
+        serde_json::from_str(&predicate.to_string()).expect("predicate should be valid JSON")
+    }
+
+    #[test]
+    fn single_condition() {
+        let predicate = Predicate::from(LootCondition::all_of([
+            LootCondition::time_check(13000..=23000, Some(24000)),
+            LootCondition::WeatherCheck { raining: Some(false), thundering: None },
+            LootCondition::location_check(LocationPredicate::new().dimension(location("minecraft:overworld")).can_see_sky(true)),
+        ]));
+        assert_eq!(json(&predicate), json!({
+            "condition": "minecraft:all_of",
+            "terms": [
+                {"condition": "minecraft:time_check", "value": {"min": 13000, "max": 23000}, "period": 24000},
+                {"condition": "minecraft:weather_check", "raining": false},
+                {"condition": "minecraft:location_check", "predicate": {"dimension": "minecraft:overworld", "can_see_sky": true}},
+            ],
+        }), "predicate was not rendered correctly");
+    }
+
+    #[test]
+    fn condition_list() {
+        let sword = ItemPredicate::new()
+            .item(location("minecraft:diamond_sword"))
+            .enchantment(EnchantmentPredicate::new(location("minecraft:sharpness")).levels(3..));
+        let predicate = Predicate::from(vec![
+            LootCondition::entity_properties(LootContextEntity::This, EntityPredicate::new()
+                .flags(EntityFlags { is_sneaking: Some(true), ..Default::default() })
+                .equipment(EntityEquipment { mainhand: Some(sword), ..Default::default() })
+                .player(PlayerPredicate::new().gamemode(GameMode::Survival).advancement(location("minecraft:story/root"), true))
+                .vehicle(EntityPredicate::new().entity_type("minecraft:horse"))),
+            LootCondition::inverted(LootCondition::reference(location("test:is_night"))),
+            LootCondition::ValueCheck { value: NumberProvider::uniform(1.0, 10.0), range: (..=5).into() },
+            LootCondition::random_chance(0.5),
+        ]);
+        assert_eq!(json(&predicate), json!([
+            {
+                "condition": "minecraft:entity_properties",
+                "entity": "this",
+                "predicate": {
+                    "flags": {"is_sneaking": true},
+                    "equipment": {
+                        "mainhand": {
+                            "items": ["minecraft:diamond_sword"],
+                            "enchantments": [{"enchantment": "minecraft:sharpness", "levels": {"min": 3}}],
+                        },
+                    },
+                    "vehicle": {"type": "minecraft:horse"},
+                    "player": {"gamemode": "survival", "advancements": {"minecraft:story/root": true}},
+                },
+            },
+            {"condition": "minecraft:inverted", "term": {"condition": "minecraft:reference", "name": "test:is_night"}},
+            {"condition": "minecraft:value_check", "value": {"type": "minecraft:uniform", "min": 1.0, "max": 10.0}, "range": {"max": 5}},
+            {"condition": "minecraft:random_chance", "chance": 0.5},
+        ]), "predicate list was not rendered correctly");
+    }
+
+    #[test]
+    fn match_tool() {
+        let pickaxe = ItemPredicate::new()
+            .tag(location("minecraft:pickaxes"))
+            .count(1)
+            .durability(..=10)
+            .nbt(nbt_compound! { "Unbreakable" => false });
+        let potion = ItemPredicate::new().item(location("minecraft:potion")).potion(location("minecraft:strong_healing"));
+        let predicate = Predicate::from(vec![LootCondition::MatchTool { predicate: pickaxe }, LootCondition::MatchTool { predicate: potion }]);
+        assert_eq!(json(&predicate), json!([
+            {
+                "condition": "minecraft:match_tool",
+                "predicate": {"tag": "minecraft:pickaxes", "count": 1, "durability": {"max": 10}, "nbt": "{Unbreakable:0b}"},
+            },
+            {"condition": "minecraft:match_tool", "predicate": {"items": ["minecraft:potion"], "potion": "minecraft:strong_healing"}},
+        ]));
+    }
+
+    #[test]
+    fn entity_distance_and_effects() {
+        let poisoned = EntityPredicate::new()
+            .entity_type("#minecraft:undead")
+            .distance(DistancePredicate::horizontal(..=8.0))
+            .effect(location("minecraft:poison"), EffectPredicate::new().amplifier(1..).duration(..=200))
+            .effect(location("minecraft:glowing"), EffectPredicate::default());
+        let predicate = Predicate::from(LootCondition::entity_properties(LootContextEntity::This, poisoned));
+        assert_eq!(json(&predicate), json!({
+            "condition": "minecraft:entity_properties",
+            "entity": "this",
+            "predicate": {
+                "type": "#minecraft:undead",
+                "distance": {"horizontal": {"max": 8.0}},
+                "effects": {
+                    "minecraft:glowing": {},
+                    "minecraft:poison": {"amplifier": {"min": 1}, "duration": {"max": 200}},
+                },
+            },
+        }));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn read_single_or_list() {
+        let single: Predicate = serde_json::from_str(r#"{"condition": "minecraft:random_chance", "chance": 0.25}"#)
+            .expect("should have read a single condition");
+        assert_eq!(single, Predicate::from(LootCondition::random_chance(0.25)));
+        let list: Predicate = serde_json::from_str(r#"[{"condition": "minecraft:weather_check", "thundering": true}]"#)
+            .expect("should have read a condition list");
+        assert_eq!(list, Predicate::All(vec![LootCondition::WeatherCheck { raining: None, thundering: Some(true) }]));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn read_match_tool_nbt() {
+        let read: Predicate = serde_json::from_str(r#"{"condition": "minecraft:match_tool", "predicate": {"nbt": "{Damage: 3, display: {Name: '\"Key\"'}}"}}"#)
+            .expect("should have read the condition");
+        let nbt = nbt_compound! { "Damage" => 3, "display" => nbt_compound! { "Name" => r#""Key""# } };
+        assert_eq!(read, Predicate::from(LootCondition::MatchTool { predicate: ItemPredicate::new().nbt(nbt) }));
+        assert!(serde_json::from_str::<ItemPredicate>(r#"{"nbt": "{Damage:"}"#).is_err(), "invalid SNBT should not have been read");
+    }
+
+    #[test]
+    fn namespace_placement() {
+        for (pack_format, path) in [(15, "data/test/predicates/is_night.json"), (48, "data/test/predicate/is_night.json")] {
+            let mut datapack = Datapack::new(PackMeta::new(pack_format, "test"));
+            let predicate = datapack
+                .namespace("test")
+                .expect("should have accepted the namespace")
+                .add_predicate("is_night", LootCondition::time_check(13000..=23000, Some(24000)))
+                .expect("should have added the predicate");
+            assert!(datapack.contains(path), "predicate should have been placed at '{path}'");
+            let command = ExecuteCommand::new().if_predicate(predicate).run("say night").build();
+            assert_eq!(command.expect("should have built the command"), "execute if predicate test:is_night run say night");
+        }
+    }
+}
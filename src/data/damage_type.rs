@@ -0,0 +1,167 @@
+use serde::Serialize;
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+use crate::datapack::resource::Resource;
+use crate::utils::json::json_file;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[serde(rename_all = "snake_case")]
+pub enum DamageScaling {
+    Never,
+    #[serde(rename = "when_caused_by_living_non_player")]
+    WhenCaused,
+    Always,
+}
+
+/// The sound and effect played when an entity takes damage.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[serde(rename_all = "snake_case")]
+pub enum DamageEffects {
+    #[default]
+    Hurt,
+    Thorns,
+    Drowning,
+    Burning,
+    Poking,
+    Freezing,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[serde(rename_all = "snake_case")]
+pub enum DeathMessageType {
+    #[default]
+    Default,
+    FallVariants,
+    IntentionalGameDesign,
+}
+
+/// A `data/<namespace>/damage_type` file, available since 1.19.4.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct DamageType {
+    pub message_id: String,
+    pub scaling: DamageScaling,
+    pub exhaustion: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effects: Option<DamageEffects>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub death_message_type: Option<DeathMessageType>,
+}
+
+impl DamageType {
+    pub fn new(message_id: impl Into<String>, scaling: DamageScaling, exhaustion: f32) -> Self {
+        Self {
+            message_id: message_id.into(),
+            scaling,
+            exhaustion,
+            effects: None,
+            death_message_type: None,
+        }
+    }
+
+    pub fn effects(mut self, effects: DamageEffects) -> Self {
+        self.effects = Some(effects);
+        self
+    }
+
+    pub fn death_message_type(mut self, death_message_type: DeathMessageType) -> Self {
+        self.death_message_type = Some(death_message_type);
+        self
+    }
+}
+
+json_file!(DamageType);
+
+impl Resource for DamageType {
+    const DIRECTORY: &'static str = "damage_type";
+    const MIN_PACK_FORMAT: u32 = 12;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datapack::Datapack;
+    use crate::datapack::pack_meta::PackMeta;
+    use crate::error::DatapackError;
+    use crate::resource_location::ResourceLocation;
+    use crate::utils::file_system::Entry;
+    use tempdir::TempDir;
+
+    #[test]
+    fn damage_type_display() {
+        let damage_type = DamageType::new("test.spikes", DamageScaling::WhenCaused, 0.1)
+            .effects(DamageEffects::Poking)
+            .death_message_type(DeathMessageType::FallVariants);
+        let expected = r#"{
+  "message_id": "test.spikes",
+  "scaling": "when_caused_by_living_non_player",
+  "exhaustion": 0.1,
+  "effects": "poking",
+  "death_message_type": "fall_variants"
+}"#;
+        assert_eq!(damage_type.to_string(), expected, "damage type was not rendered correctly");
+    }
+
+    #[test]
+    fn damage_type_omits_optional_fields() {
+        let damage_type = DamageType::new("test.void", DamageScaling::Never, 0.0);
+        let expected = "{\n  \"message_id\": \"test.void\",\n  \"scaling\": \"never\",\n  \"exhaustion\": 0.0\n}";
+        assert_eq!(damage_type.to_string(), expected, "optional fields should have been omitted");
+    }
+
+    #[test]
+    fn damage_scaling_serialization() {
+        let scalings = [
+            (DamageScaling::Never, "\"never\""),
+            (DamageScaling::WhenCaused, "\"when_caused_by_living_non_player\""),
+            (DamageScaling::Always, "\"always\""),
+        ];
+        for (scaling, expected) in scalings {
+            let json = serde_json::to_string(&scaling).expect("should have serialized the scaling");
+            assert_eq!(json, expected, "{scaling:?} was not serialized correctly");
+        }
+    }
+
+    #[test]
+    fn damage_type_pack_format() {
+        let location = ResourceLocation::new("test", "spikes").expect("should have created the resource location");
+        let damage_type = DamageType::new("test.spikes", DamageScaling::Always, 0.1);
+
+        let mut datapack = Datapack::new(PackMeta::new(10, "test"));
+        match datapack.add(&location, damage_type.clone()) {
+            Err(DatapackError::UnsupportedPackFormat { required: 12, pack_format: 10, .. }) => {}
+            _ => panic!("damage types should have been rejected in pack format 10"),
+        }
+
+        let mut datapack = Datapack::new(PackMeta::new(12, "test"));
+        datapack.add(&location, damage_type).expect("should have added the damage type");
+        assert!(datapack.contains("data/test/damage_type/spikes.json"), "damage type was not placed under 'damage_type/'");
+    }
+
+    #[test]
+    fn damage_type_tags() {
+        let location = |location: &str| location.parse::<ResourceLocation>().expect("should have parsed the resource location");
+        let mut datapack = Datapack::new(PackMeta::new(48, "test"));
+        let mut namespace = datapack.namespace("test").expect("should have accepted the namespace");
+        let bypasses_armor = location("minecraft:bypasses_armor");
+        namespace
+            .add_damage_type_tagged("void", DamageType::new("test.void", DamageScaling::Never, 0.0), [bypasses_armor.clone()])
+            .expect("should have added the damage type");
+        namespace
+            .add_damage_type_tagged("curse", DamageType::new("test.curse", DamageScaling::Never, 0.0), [bypasses_armor, location("minecraft:no_knockback")])
+            .expect("should have added the damage type");
+
+        let temp_dir = TempDir::new(module_path!()).expect("should have created temp dir");
+        let path = temp_dir.path().join("test_datapack");
+        datapack.create(&path).expect("should have created the datapack");
+        let read = |file: &str| std::fs::read_to_string(path.join(file)).expect("should have been able to read the tag");
+        let tag: serde_json::Value = serde_json::from_str(&read("data/minecraft/tags/damage_type/bypasses_armor.json")).expect("tag should be valid JSON");
+        assert_eq!(tag, serde_json::json!({"values": ["test:void", "test:curse"]}));
+        assert!(read("data/minecraft/tags/damage_type/no_knockback.json").contains("test:curse"));
+
+        temp_dir.close().expect("should have closed temp dir");
+    }
+}
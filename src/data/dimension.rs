@@ -0,0 +1,361 @@
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+use crate::data::tag::{BlockTag, Tag};
+use crate::data::worldgen::biome::Biome;
+use crate::data::worldgen::feature::IntProvider;
+use crate::data::worldgen::noise_settings::NoiseSettings;
+use crate::datapack::resource::{Reference, Resource};
+use crate::error::DatapackError;
+use crate::resource_location::ResourceLocation;
+use crate::utils::json::json_file;
+
+/// The highest block a dimension can build at, plus one.
+pub const MAX_BUILD_HEIGHT: i32 = 2032;
+
+fn serialize_tag<S: Serializer>(tag: &ResourceLocation, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(&format_args!("#{tag}"))
+}
+
+/// A `data/<namespace>/dimension_type` file: the rules and build limits of a dimension.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DimensionType {
+    /// Whether water evaporates and lava spreads faster, as in the nether.
+    pub ultrawarm: bool,
+    /// Whether beds set the spawn point and nether portals spawn zombified piglins.
+    pub natural: bool,
+    /// How many overworld blocks one block of this dimension is worth.
+    pub coordinate_scale: f64,
+    pub has_skylight: bool,
+    pub has_ceiling: bool,
+    pub ambient_light: f32,
+    /// The time of day, if it never changes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fixed_time: Option<i64>,
+    /// The light level monsters need to spawn at or below.
+    pub monster_spawn_light_level: IntProvider,
+    pub monster_spawn_block_light_limit: i32,
+    pub piglin_safe: bool,
+    pub bed_works: bool,
+    pub respawn_anchor_works: bool,
+    pub has_raids: bool,
+    /// The lowest block. Must be a multiple of 16.
+    pub min_y: i32,
+    /// The number of blocks above `min_y`. Must be a multiple of 16.
+    pub height: i32,
+    /// How high portals and chorus fruit can take a player, at most `height`.
+    pub logical_height: i32,
+    /// The block tag of blocks that burn forever.
+    #[serde(serialize_with = "serialize_tag")]
+    pub infiniburn: ResourceLocation,
+    /// The sky and fog, such as `minecraft:the_nether`.
+    pub effects: ResourceLocation,
+}
+
+impl DimensionType {
+    /// A dimension type like the overworld's, from `min_y` up to `min_y + height`.
+    pub fn new(min_y: i32, height: i32) -> Self {
+        Self {
+            ultrawarm: false,
+            natural: true,
+            coordinate_scale: 1.0,
+            has_skylight: true,
+            has_ceiling: false,
+            ambient_light: 0.0,
+            fixed_time: None,
+            monster_spawn_light_level: IntProvider::Uniform { min_inclusive: 0, max_inclusive: 7 },
+            monster_spawn_block_light_limit: 0,
+            piglin_safe: false,
+            bed_works: true,
+            respawn_anchor_works: false,
+            has_raids: true,
+            min_y,
+            height,
+            logical_height: height,
+            infiniburn: ResourceLocation::minecraft("infiniburn_overworld").expect("the path is valid"),
+            effects: ResourceLocation::minecraft("overworld").expect("the path is valid"),
+        }
+    }
+
+    pub fn fixed_time(mut self, fixed_time: i64) -> Self {
+        self.fixed_time = Some(fixed_time);
+        self
+    }
+
+    pub fn logical_height(mut self, logical_height: i32) -> Self {
+        self.logical_height = logical_height;
+        self
+    }
+
+    pub fn monster_spawn_light_level(mut self, light_level: impl Into<IntProvider>) -> Self {
+        self.monster_spawn_light_level = light_level.into();
+        self
+    }
+
+    pub fn infiniburn(mut self, tag: ResourceLocation) -> Self {
+        self.infiniburn = tag;
+        self
+    }
+
+    pub fn effects(mut self, effects: ResourceLocation) -> Self {
+        self.effects = effects;
+        self
+    }
+}
+
+/// How a [`Dimension`] picks its biomes.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type")]
+pub enum BiomeSource {
+    #[serde(rename = "minecraft:fixed")]
+    Fixed { biome: ResourceLocation },
+    /// Squares of `2^(scale + 2)` chunks, cycling through `biomes`.
+    #[serde(rename = "minecraft:checkerboard")]
+    Checkerboard { biomes: Vec<ResourceLocation>, scale: u32 },
+    /// The biome layout of a vanilla dimension, such as `minecraft:nether`.
+    #[serde(rename = "minecraft:multi_noise")]
+    MultiNoisePreset { preset: ResourceLocation },
+    #[serde(rename = "minecraft:the_end")]
+    TheEnd,
+    /// Any other biome source, written as is. It should include its own `type`.
+    #[serde(untagged)]
+    Raw(Value),
+}
+
+/// The noise settings of a [`ChunkGenerator::Noise`], either by ID or inline.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum NoiseSettingsReference {
+    Id(ResourceLocation),
+    Inline(Box<NoiseSettings>),
+}
+
+impl From<ResourceLocation> for NoiseSettingsReference {
+    fn from(id: ResourceLocation) -> Self {
+        Self::Id(id)
+    }
+}
+
+impl From<NoiseSettings> for NoiseSettingsReference {
+    fn from(settings: NoiseSettings) -> Self {
+        Self::Inline(Box::new(settings))
+    }
+}
+
+/// A layer of a superflat world, counted from the bottom.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FlatLayer {
+    pub block: ResourceLocation,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FlatSettings {
+    pub layers: Vec<FlatLayer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub biome: Option<ResourceLocation>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub lakes: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub features: bool,
+}
+
+impl FlatSettings {
+    pub fn new() -> Self {
+        Self { layers: Vec::new(), biome: None, lakes: false, features: false }
+    }
+
+    /// Adds `height` layers of `block` on top of the layers so far.
+    pub fn layer(mut self, block: ResourceLocation, height: u32) -> Self {
+        self.layers.push(FlatLayer { block, height });
+        self
+    }
+
+    pub fn biome(mut self, biome: ResourceLocation) -> Self {
+        self.biome = Some(biome);
+        self
+    }
+}
+
+impl Default for FlatSettings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type")]
+pub enum ChunkGenerator {
+    #[serde(rename = "minecraft:noise")]
+    Noise { settings: NoiseSettingsReference, biome_source: BiomeSource },
+    #[serde(rename = "minecraft:flat")]
+    Flat { settings: FlatSettings },
+    /// Every block state laid out in a grid, as in the debug world type.
+    #[serde(rename = "minecraft:debug")]
+    Debug,
+}
+
+impl ChunkGenerator {
+    pub fn noise(settings: impl Into<NoiseSettingsReference>, biome_source: BiomeSource) -> Self {
+        Self::Noise { settings: settings.into(), biome_source }
+    }
+}
+
+/// A `data/<namespace>/dimension` file: a dimension of its type and how its terrain is
+/// generated.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Dimension {
+    #[serde(rename = "type")]
+    pub dimension_type: ResourceLocation,
+    pub generator: ChunkGenerator,
+}
+
+impl Dimension {
+    pub fn new(dimension_type: ResourceLocation, generator: ChunkGenerator) -> Self {
+        Self { dimension_type, generator }
+    }
+}
+
+json_file!(DimensionType, Dimension);
+
+impl Resource for DimensionType {
+    const DIRECTORY: &'static str = "dimension_type";
+
+    fn validate(&self) -> Result<(), DatapackError> {
+        let invalid = |message: String| Err(DatapackError::Validation(format!("invalid dimension type: {message}")));
+        let Self { min_y, height, logical_height, .. } = *self;
+        if min_y % 16 != 0 || height % 16 != 0 {
+            return invalid(format!("min_y {min_y} and height {height} must be multiples of 16"));
+        }
+        if height < 16 || min_y < -MAX_BUILD_HEIGHT || min_y + height > MAX_BUILD_HEIGHT {
+            return invalid(format!("the blocks from {min_y} up to {} must be within ±{MAX_BUILD_HEIGHT}", min_y + height));
+        }
+        if logical_height > height {
+            return invalid(format!("logical_height {logical_height} is more than the height {height}"));
+        }
+        Ok(())
+    }
+
+    fn references(&self) -> Vec<Reference> {
+        vec![Reference::to::<Tag<BlockTag>>(self.infiniburn.clone())]
+    }
+}
+
+impl Resource for Dimension {
+    const DIRECTORY: &'static str = "dimension";
+
+    fn references(&self) -> Vec<Reference> {
+        let mut references = vec![Reference::to::<DimensionType>(self.dimension_type.clone())];
+        match &self.generator {
+            ChunkGenerator::Noise { settings, biome_source } => {
+                if let NoiseSettingsReference::Id(settings) = settings {
+                    references.push(Reference::to::<NoiseSettings>(settings.clone()));
+                }
+                let biomes = match biome_source {
+                    BiomeSource::Fixed { biome } => std::slice::from_ref(biome),
+                    BiomeSource::Checkerboard { biomes, .. } => biomes.as_slice(),
+                    _ => &[],
+                };
+                references.extend(biomes.iter().map(|biome| Reference::to::<Biome>(biome.clone())));
+            }
+            ChunkGenerator::Flat { settings } => {
+                references.extend(settings.biome.iter().map(|biome| Reference::to::<Biome>(biome.clone())));
+            }
+            ChunkGenerator::Debug => {}
+        }
+        references
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use crate::datapack::Datapack;
+    use crate::datapack::pack_meta::PackMeta;
+
+    fn location(location: &str) -> ResourceLocation {
+        location.parse().expect("should have parsed the resource location")
+    }
+
+    #[test]
+    fn custom_dimension() {
+        let mut datapack = Datapack::new(PackMeta::new(48, "test"));
+        let mut namespace = datapack.namespace("test").expect("should have accepted the namespace");
+        let dimension_type = DimensionType::new(0, 256).logical_height(128).fixed_time(6000);
+        let json: Value = serde_json::from_str(&dimension_type.to_string()).expect("dimension type should be valid JSON");
+        assert_eq!(json, json!({
+            "ultrawarm": false,
+            "natural": true,
+            "coordinate_scale": 1.0,
+            "has_skylight": true,
+            "has_ceiling": false,
+            "ambient_light": 0.0,
+            "fixed_time": 6000,
+            "monster_spawn_light_level": {"type": "minecraft:uniform", "min_inclusive": 0, "max_inclusive": 7},
+            "monster_spawn_block_light_limit": 0,
+            "piglin_safe": false,
+            "bed_works": true,
+            "respawn_anchor_works": false,
+            "has_raids": true,
+            "min_y": 0,
+            "height": 256,
+            "logical_height": 128,
+            "infiniburn": "#minecraft:infiniburn_overworld",
+            "effects": "minecraft:overworld",
+        }));
+        let dimension_type = namespace.add_dimension_type("flat", dimension_type).expect("should have added the dimension type");
+
+        let dimension = Dimension::new(dimension_type, ChunkGenerator::Flat {
+            settings: FlatSettings::new()
+                .layer(location("minecraft:bedrock"), 1)
+                .layer(location("minecraft:dirt"), 2)
+                .layer(location("minecraft:grass_block"), 1)
+                .biome(location("minecraft:plains")),
+        });
+        let json: Value = serde_json::from_str(&dimension.to_string()).expect("dimension should be valid JSON");
+        assert_eq!(json, json!({
+            "type": "test:flat",
+            "generator": {
+                "type": "minecraft:flat",
+                "settings": {
+                    "layers": [
+                        {"block": "minecraft:bedrock", "height": 1},
+                        {"block": "minecraft:dirt", "height": 2},
+                        {"block": "minecraft:grass_block", "height": 1},
+                    ],
+                    "biome": "minecraft:plains",
+                },
+            },
+        }));
+        namespace.add_dimension("flat", dimension).expect("should have added the dimension");
+        assert!(datapack.contains("data/test/dimension_type/flat.json"));
+        assert!(datapack.contains("data/test/dimension/flat.json"));
+        assert!(datapack.validate_references().is_empty(), "the dimension type should have been found");
+    }
+
+    #[test]
+    fn noise_generator() {
+        let dimension = Dimension::new(location("minecraft:overworld"), ChunkGenerator::noise(
+            location("test:islands"),
+            BiomeSource::Checkerboard { biomes: vec![location("test:ash"), location("minecraft:desert")], scale: 2 },
+        ));
+        let json = serde_json::to_value(&dimension).expect("should have serialized the dimension");
+        assert_eq!(json["generator"], json!({
+            "type": "minecraft:noise",
+            "settings": "test:islands",
+            "biome_source": {"type": "minecraft:checkerboard", "biomes": ["test:ash", "minecraft:desert"], "scale": 2},
+        }));
+        let references: Vec<String> = dimension.references().iter().map(|reference| reference.location.to_string()).collect();
+        assert_eq!(references, ["minecraft:overworld", "test:islands", "test:ash", "minecraft:desert"]);
+        assert_eq!(serde_json::to_value(ChunkGenerator::Debug).expect("should have serialized the generator"), json!({"type": "minecraft:debug"}));
+    }
+
+    #[test]
+    fn dimension_type_limits() {
+        assert!(DimensionType::new(-64, 384).validate().is_ok());
+        assert!(DimensionType::new(-2032, 4064).validate().is_ok());
+        assert!(DimensionType::new(0, 2048).validate().is_err(), "the top is above {MAX_BUILD_HEIGHT}");
+        assert!(DimensionType::new(0, 250).validate().is_err(), "the height is not a multiple of 16");
+        assert!(DimensionType::new(0, 256).logical_height(320).validate().is_err(), "the logical height is above the height");
+    }
+}
@@ -0,0 +1,470 @@
+use std::collections::BTreeMap;
+use serde::Serialize;
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+use serde_json::Value;
+use crate::datapack::resource::Resource;
+use crate::resource_location::ResourceLocation;
+use crate::text::TextComponent;
+use crate::utils::json::json_file;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[serde(rename_all = "snake_case")]
+pub enum EquipmentSlotGroup {
+    Any,
+    Hand,
+    Mainhand,
+    Offhand,
+    Armor,
+    Feet,
+    Legs,
+    Chest,
+    Head,
+    Body,
+}
+
+/// An enchanting table cost: `base` at level 1, plus `per_level_above_first` for each
+/// level after that.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct EnchantmentCost {
+    pub base: i32,
+    pub per_level_above_first: i32,
+}
+
+impl EnchantmentCost {
+    pub fn new(base: i32, per_level_above_first: i32) -> Self {
+        Self { base, per_level_above_first }
+    }
+}
+
+/// A number that depends on the enchantment level.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[serde(tag = "type")]
+pub enum LevelBasedValue {
+    #[serde(rename = "minecraft:linear")]
+    Linear { base: f32, per_level_above_first: f32 },
+    #[serde(rename = "minecraft:levels_squared")]
+    LevelsSquared { added: f32 },
+    #[serde(rename = "minecraft:clamped")]
+    Clamped { value: Box<LevelBasedValue>, min: f32, max: f32 },
+    #[serde(rename = "minecraft:fraction")]
+    Fraction { numerator: Box<LevelBasedValue>, denominator: Box<LevelBasedValue> },
+    /// The same value at every level, written as a bare number.
+    #[serde(untagged)]
+    Constant(f32),
+}
+
+impl From<f32> for LevelBasedValue {
+    fn from(value: f32) -> Self {
+        Self::Constant(value)
+    }
+}
+
+/// Changes a value such as damage or armor effectiveness.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[serde(tag = "type")]
+pub enum ValueEffect {
+    #[serde(rename = "minecraft:add")]
+    Add { value: LevelBasedValue },
+    #[serde(rename = "minecraft:multiply")]
+    Multiply { factor: LevelBasedValue },
+    #[serde(rename = "minecraft:set")]
+    Set { value: LevelBasedValue },
+    #[serde(rename = "minecraft:remove_binomial")]
+    RemoveBinomial { chance: LevelBasedValue },
+    #[serde(rename = "minecraft:all_of")]
+    AllOf { effects: Vec<ValueEffect> },
+    /// Any other effect, written as is. It should include its own `type`.
+    #[serde(untagged)]
+    Raw(Value),
+}
+
+/// An effect that only applies when its `requirements`, a loot condition, pass.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct ConditionalEffect {
+    pub effect: ValueEffect,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requirements: Option<Value>,
+}
+
+impl From<ValueEffect> for ConditionalEffect {
+    fn from(effect: ValueEffect) -> Self {
+        Self { effect, requirements: None }
+    }
+}
+
+/// Whom a [`TargetedEffect`] is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[serde(rename_all = "snake_case")]
+pub enum EnchantmentTarget {
+    Attacker,
+    /// The entity that dealt the damage, such as an arrow.
+    DamagingEntity,
+    Victim,
+}
+
+/// Something done to an entity, such as giving it a status effect.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[serde(tag = "type")]
+pub enum EntityEffect {
+    /// Gives one of `to_apply`, an ID, a `#tag` or a list of status effects.
+    #[serde(rename = "minecraft:apply_mobeffect")]
+    ApplyMobEffect {
+        to_apply: Value,
+        min_duration: LevelBasedValue,
+        max_duration: LevelBasedValue,
+        min_amplifier: LevelBasedValue,
+        max_amplifier: LevelBasedValue,
+    },
+    #[serde(rename = "minecraft:damage_entity")]
+    DamageEntity { damage_type: ResourceLocation, min_damage: LevelBasedValue, max_damage: LevelBasedValue },
+    /// Sets the entity on fire for `duration` seconds.
+    #[serde(rename = "minecraft:ignite")]
+    Ignite { duration: LevelBasedValue },
+    #[serde(rename = "minecraft:all_of")]
+    AllOf { effects: Vec<EntityEffect> },
+    /// Any other effect, written as is. It should include its own `type`.
+    #[serde(untagged)]
+    Raw(Value),
+}
+
+/// An effect such as the ones of `minecraft:post_attack`, which applies to the `affected`
+/// entity when the `enchanted` one has the enchantment.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct TargetedEffect {
+    pub enchanted: EnchantmentTarget,
+    pub affected: EnchantmentTarget,
+    pub effect: EntityEffect,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requirements: Option<Value>,
+}
+
+impl TargetedEffect {
+    pub fn new(enchanted: EnchantmentTarget, affected: EnchantmentTarget, effect: EntityEffect) -> Self {
+        Self { enchanted, affected, effect, requirements: None }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[serde(rename_all = "snake_case")]
+pub enum AttributeOperation {
+    AddValue,
+    AddMultipliedBase,
+    AddMultipliedTotal,
+}
+
+/// An attribute modifier the enchanted item gives while it is worn or held in one of the
+/// enchantment's slots.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct AttributeEffect {
+    /// The ID of the modifier, unique among the modifiers of `attribute`.
+    pub id: ResourceLocation,
+    pub attribute: ResourceLocation,
+    pub amount: LevelBasedValue,
+    pub operation: AttributeOperation,
+}
+
+/// The value of an effect component, such as `minecraft:damage`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[serde(untagged)]
+pub enum EffectComponent {
+    /// Read before value effects, since their `effect` would also accept these.
+    TargetedEffects(Vec<TargetedEffect>),
+    ValueEffects(Vec<ConditionalEffect>),
+    Attributes(Vec<AttributeEffect>),
+    Raw(Value),
+}
+
+/// A `data/<namespace>/enchantment` file. Enchantments are only defined by datapacks
+/// from 1.21 (pack format 48) on.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct Enchantment {
+    pub description: TextComponent,
+    /// Enchantments this one can't be combined with: an ID, a `#tag` or a list.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exclusive_set: Option<Value>,
+    /// The items this can be applied to: an ID, a `#tag` or a list.
+    pub supported_items: Value,
+    /// The items this can be found on in an enchanting table, when narrower than
+    /// `supported_items`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub primary_items: Option<Value>,
+    pub weight: u32,
+    pub max_level: u32,
+    pub min_cost: EnchantmentCost,
+    pub max_cost: EnchantmentCost,
+    pub anvil_cost: u32,
+    pub slots: Vec<EquipmentSlotGroup>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub effects: BTreeMap<ResourceLocation, EffectComponent>,
+}
+
+impl Enchantment {
+    pub fn new(description: impl Into<TextComponent>, supported_items: impl Into<Value>, weight: u32, max_level: u32) -> Self {
+        Self {
+            description: description.into(),
+            exclusive_set: None,
+            supported_items: supported_items.into(),
+            primary_items: None,
+            weight,
+            max_level,
+            min_cost: EnchantmentCost::new(1, 0),
+            max_cost: EnchantmentCost::new(1, 0),
+            anvil_cost: 1,
+            slots: Vec::new(),
+            effects: BTreeMap::new(),
+        }
+    }
+
+    pub fn costs(mut self, min_cost: EnchantmentCost, max_cost: EnchantmentCost) -> Self {
+        self.min_cost = min_cost;
+        self.max_cost = max_cost;
+        self
+    }
+
+    pub fn anvil_cost(mut self, anvil_cost: u32) -> Self {
+        self.anvil_cost = anvil_cost;
+        self
+    }
+
+    pub fn slot(mut self, slot: EquipmentSlotGroup) -> Self {
+        self.slots.push(slot);
+        self
+    }
+
+    pub fn exclusive_set(mut self, exclusive_set: impl Into<Value>) -> Self {
+        self.exclusive_set = Some(exclusive_set.into());
+        self
+    }
+
+    pub fn primary_items(mut self, primary_items: impl Into<Value>) -> Self {
+        self.primary_items = Some(primary_items.into());
+        self
+    }
+
+    /// Adds `effect` to the value effect component `component`, such as `minecraft:damage`.
+    pub fn value_effect(mut self, component: ResourceLocation, effect: impl Into<ConditionalEffect>) -> Self {
+        let entry = self.effects.entry(component).or_insert_with(|| EffectComponent::ValueEffects(Vec::new()));
+        match entry {
+            EffectComponent::ValueEffects(effects) => effects.push(effect.into()),
+            _ => *entry = EffectComponent::ValueEffects(vec![effect.into()]),
+        }
+        self
+    }
+
+    /// Adds `effect` to the targeted effect component `component`, such as
+    /// `minecraft:post_attack`.
+    pub fn targeted_effect(mut self, component: ResourceLocation, effect: TargetedEffect) -> Self {
+        let entry = self.effects.entry(component).or_insert_with(|| EffectComponent::TargetedEffects(Vec::new()));
+        match entry {
+            EffectComponent::TargetedEffects(effects) => effects.push(effect),
+            _ => *entry = EffectComponent::TargetedEffects(vec![effect]),
+        }
+        self
+    }
+
+    /// Adds `effect` to the `minecraft:attributes` component.
+    pub fn attribute(mut self, effect: AttributeEffect) -> Self {
+        let component = ResourceLocation::minecraft("attributes").expect("the path is valid");
+        let entry = self.effects.entry(component).or_insert_with(|| EffectComponent::Attributes(Vec::new()));
+        match entry {
+            EffectComponent::Attributes(effects) => effects.push(effect),
+            _ => *entry = EffectComponent::Attributes(vec![effect]),
+        }
+        self
+    }
+
+    pub fn effect(mut self, component: ResourceLocation, value: impl Into<Value>) -> Self {
+        self.effects.insert(component, EffectComponent::Raw(value.into()));
+        self
+    }
+}
+
+json_file!(Enchantment);
+
+impl Resource for Enchantment {
+    const DIRECTORY: &'static str = "enchantment";
+    const MIN_PACK_FORMAT: u32 = 48;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use crate::datapack::Datapack;
+    use crate::datapack::pack_meta::PackMeta;
+    use crate::error::DatapackError;
+
+    fn location(location: &str) -> ResourceLocation {
+        location.parse().expect("should have parsed the resource location")
+    }
+
+    #[test]
+    fn minimal_enchantment() {
+        let enchantment = Enchantment::new(TextComponent::translate("enchantment.test.glow"), "#minecraft:enchantable/armor", 2, 1)
+            .slot(EquipmentSlotGroup::Armor);
+        let json: Value = serde_json::from_str(&enchantment.to_string()).expect("enchantment should be valid JSON");
+        assert_eq!(json, json!({
+            "description": {"translate": "enchantment.test.glow"},
+            "supported_items": "#minecraft:enchantable/armor",
+            "weight": 2,
+            "max_level": 1,
+            "min_cost": {"base": 1, "per_level_above_first": 0},
+            "max_cost": {"base": 1, "per_level_above_first": 0},
+            "anvil_cost": 1,
+            "slots": ["armor"],
+        }), "enchantment was not rendered correctly");
+    }
+
+    #[test]
+    fn cost_and_value_effects() {
+        let enchantment = Enchantment::new("Smiting", json!(["minecraft:iron_sword", "minecraft:diamond_sword"]), 5, 5)
+            .costs(EnchantmentCost::new(5, 8), EnchantmentCost::new(25, 8))
+            .anvil_cost(2)
+            .slot(EquipmentSlotGroup::Mainhand)
+            .exclusive_set("#minecraft:exclusive_set/damage")
+            .value_effect(
+                location("minecraft:damage"),
+                ValueEffect::Add { value: LevelBasedValue::Linear { base: 2.5, per_level_above_first: 2.5 } },
+            )
+            .value_effect(location("minecraft:damage"), ConditionalEffect {
+                effect: ValueEffect::Multiply { factor: 1.5.into() },
+                requirements: Some(json!({"condition": "minecraft:random_chance", "chance": 0.5})),
+            });
+        let json = serde_json::to_value(&enchantment).expect("should have serialized the enchantment");
+        assert_eq!(json["min_cost"], json!({"base": 5, "per_level_above_first": 8}));
+        assert_eq!(json["max_cost"], json!({"base": 25, "per_level_above_first": 8}));
+        assert_eq!(json["exclusive_set"], "#minecraft:exclusive_set/damage");
+        assert_eq!(json["effects"], json!({
+            "minecraft:damage": [
+                {"effect": {"type": "minecraft:add", "value": {"type": "minecraft:linear", "base": 2.5, "per_level_above_first": 2.5}}},
+                {
+                    "effect": {"type": "minecraft:multiply", "factor": 1.5},
+                    "requirements": {"condition": "minecraft:random_chance", "chance": 0.5},
+                },
+            ],
+        }));
+    }
+
+    /// `minecraft:smite` from the game's data.
+    #[test]
+    fn vanilla_smite() {
+        let smite = Enchantment::new(TextComponent::translate("enchantment.minecraft.smite"), "#minecraft:enchantable/weapon", 5, 5)
+            .primary_items("#minecraft:enchantable/sword")
+            .exclusive_set("#minecraft:exclusive_set/damage")
+            .costs(EnchantmentCost::new(5, 8), EnchantmentCost::new(25, 8))
+            .anvil_cost(2)
+            .slot(EquipmentSlotGroup::Mainhand)
+            .value_effect(location("minecraft:damage"), ConditionalEffect {
+                effect: ValueEffect::Add { value: LevelBasedValue::Linear { base: 2.5, per_level_above_first: 2.5 } },
+                requirements: Some(json!({
+                    "condition": "minecraft:entity_properties",
+                    "entity": "this",
+                    "predicate": {"type": "#minecraft:sensitive_to_smite"},
+                })),
+            });
+        let json: Value = serde_json::from_str(&smite.to_string()).expect("enchantment should be valid JSON");
+        assert_eq!(json, json!({
+            "anvil_cost": 2,
+            "description": {"translate": "enchantment.minecraft.smite"},
+            "effects": {
+                "minecraft:damage": [
+                    {
+                        "effect": {"type": "minecraft:add", "value": {"type": "minecraft:linear", "base": 2.5, "per_level_above_first": 2.5}},
+                        "requirements": {
+                            "condition": "minecraft:entity_properties",
+                            "entity": "this",
+                            "predicate": {"type": "#minecraft:sensitive_to_smite"},
+                        },
+                    },
+                ],
+            },
+            "exclusive_set": "#minecraft:exclusive_set/damage",
+            "max_cost": {"base": 25, "per_level_above_first": 8},
+            "max_level": 5,
+            "min_cost": {"base": 5, "per_level_above_first": 8},
+            "primary_items": "#minecraft:enchantable/sword",
+            "slots": ["mainhand"],
+            "supported_items": "#minecraft:enchantable/weapon",
+            "weight": 5,
+        }));
+
+        let mut datapack = Datapack::new(PackMeta::new(41, "test"));
+        let mut namespace = datapack.namespace("test").expect("should have accepted the namespace");
+        match namespace.add_enchantment("smite", smite) {
+            Err(error @ DatapackError::UnsupportedPackFormat { required: 48, .. }) => {
+                assert_eq!(error.to_string(), "'enchantment' requires pack format 48 or newer, but the pack format is 41");
+            }
+            _ => panic!("enchantments should have been rejected before 1.21"),
+        }
+    }
+
+    #[test]
+    fn attribute_and_post_attack_effects() {
+        let enchantment = Enchantment::new("Frost Bite", "#minecraft:enchantable/weapon", 2, 3)
+            .attribute(AttributeEffect {
+                id: location("test:frost_bite"),
+                attribute: location("minecraft:generic.attack_speed"),
+                amount: (-0.5).into(),
+                operation: AttributeOperation::AddMultipliedBase,
+            })
+            .targeted_effect(location("minecraft:post_attack"), TargetedEffect::new(
+                EnchantmentTarget::Attacker,
+                EnchantmentTarget::Victim,
+                EntityEffect::ApplyMobEffect {
+                    to_apply: "minecraft:slowness".into(),
+                    min_duration: 1.5.into(),
+                    max_duration: LevelBasedValue::Linear { base: 1.5, per_level_above_first: 0.5 },
+                    min_amplifier: 0.0.into(),
+                    max_amplifier: 1.0.into(),
+                },
+            ));
+        let json = serde_json::to_value(&enchantment).expect("should have serialized the enchantment");
+        assert_eq!(json["effects"], json!({
+            "minecraft:attributes": [{
+                "id": "test:frost_bite",
+                "attribute": "minecraft:generic.attack_speed",
+                "amount": -0.5,
+                "operation": "add_multiplied_base",
+            }],
+            "minecraft:post_attack": [{
+                "enchanted": "attacker",
+                "affected": "victim",
+                "effect": {
+                    "type": "minecraft:apply_mobeffect",
+                    "to_apply": "minecraft:slowness",
+                    "min_duration": 1.5,
+                    "max_duration": {"type": "minecraft:linear", "base": 1.5, "per_level_above_first": 0.5},
+                    "min_amplifier": 0.0,
+                    "max_amplifier": 1.0,
+                },
+            }],
+        }));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn read_effect_components() {
+        let enchantment = Enchantment::new("Frost Bite", "#minecraft:enchantable/weapon", 2, 3)
+            .value_effect(location("minecraft:damage"), ValueEffect::Add { value: 1.0.into() })
+            .targeted_effect(location("minecraft:post_attack"), TargetedEffect::new(
+                EnchantmentTarget::Attacker,
+                EnchantmentTarget::Victim,
+                EntityEffect::Ignite { duration: 4.0.into() },
+            ));
+        let read: Enchantment = serde_json::from_str(&enchantment.to_string()).expect("should have read the enchantment");
+        assert_eq!(read, enchantment, "every effect component should have been read as its own kind");
+    }
+}
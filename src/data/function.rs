@@ -0,0 +1,920 @@
+use std::fmt::{self, Display};
+use std::ops::{Add, AddAssign};
+use crate::command::execute::{ExecuteCommand, ScoreComparison};
+use crate::command::function::call_function;
+use crate::command::return_::{is_return, RETURN_PACK_FORMAT};
+use crate::command::schedule::{GameTime, ScheduleCommand};
+use crate::command::scoreboard::{ScoreHolder, ScoreboardCommand, ScoreboardObjective};
+use crate::data::conditions::Predicate;
+use crate::data::loot_table::LootTable;
+use crate::data::tag::{FunctionTag, Tag, TagValue};
+use crate::datapack::pack_format::{MinecraftVersion, MACRO_PACK_FORMAT};
+use crate::datapack::resource::{Reference, Resource};
+use crate::error::DatapackError;
+use crate::range::MinecraftRange;
+use crate::resource_location::ResourceLocation;
+use crate::utils::file_system::File;
+
+/// [`FunctionBuilder::for_count`] unrolls loops with at most this many iterations.
+pub const UNROLL_LIMIT: u32 = 16;
+
+/// The default of the `maxCommandChainLength` game rule, the most commands a function
+/// can run, including those of the functions it calls.
+const MAX_COMMAND_CHAIN_LENGTH: u64 = 65536;
+
+/// A `data/<namespace>/functions` file: commands run in order, one per line.
+///
+/// Two functions are equal when they have the same commands and parameters, whatever
+/// datapack they were added to and whether they were marked with [`McFunction::returns`].
+#[derive(Debug, Clone, Default)]
+pub struct McFunction {
+    commands: Vec<String>,
+    /// The arguments its macro lines may use, if they were declared.
+    params: Option<Vec<String>>,
+    /// Set when it is added to a datapack, to check that macros are supported.
+    pack_format: Option<u32>,
+    returns_value: bool,
+}
+
+impl PartialEq for McFunction {
+    fn eq(&self, other: &Self) -> bool {
+        self.commands == other.commands && self.params == other.params
+    }
+}
+
+impl Eq for McFunction {}
+
+impl McFunction {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, command: impl Display) {
+        self.commands.push(command.to_string());
+    }
+
+    pub fn command(mut self, command: impl Display) -> Self {
+        self.push(command);
+        self
+    }
+
+    /// Adds a macro line, which is written after a `$` and has its `$(name)` arguments
+    /// replaced by those the function is called with. Needs pack format 18 or newer.
+    pub fn push_macro(&mut self, line: impl Display) {
+        self.commands.push(format!("${line}"));
+    }
+
+    pub fn macro_line(mut self, line: impl Display) -> Self {
+        self.push_macro(line);
+        self
+    }
+
+    /// Declares the arguments its macro lines use, so adding it fails if they use another,
+    /// and warns about declared arguments they don't use.
+    pub fn with_params<S: Into<String>>(mut self, params: impl IntoIterator<Item = S>) -> Self {
+        self.params = Some(params.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Marks it as returning a value with `return`, such as for `execute store result ...
+    /// run function`, so adding it warns if its last command doesn't return.
+    pub fn returns(mut self) -> Self {
+        self.returns_value = true;
+        self
+    }
+
+    pub fn returns_value(&self) -> bool {
+        self.returns_value
+    }
+
+    pub fn params(&self) -> Option<&[String]> {
+        self.params.as_deref()
+    }
+
+    pub fn is_macro(&self) -> bool {
+        self.macro_lines().next().is_some()
+    }
+
+    /// The names of the arguments its macro lines use, in the order they are first used.
+    /// Lines without a leading `$` are written as they are, even with `$(` in them, so
+    /// they aren't counted.
+    pub fn macro_params(&self) -> Vec<&str> {
+        let mut params = Vec::new();
+        for line in self.macro_lines() {
+            for param in macro_arguments(line).unwrap_or_default() {
+                if !params.contains(&param) {
+                    params.push(param);
+                }
+            }
+        }
+        params
+    }
+
+    /// The declared arguments its macro lines don't use.
+    pub fn unused_params(&self) -> Vec<&str> {
+        let used = self.macro_params();
+        let declared = self.params.iter().flatten().map(String::as_str);
+        declared.filter(|param| !used.contains(param)).collect()
+    }
+
+    fn macro_lines(&self) -> impl Iterator<Item = &str> {
+        self.commands.iter().filter_map(|command| command.strip_prefix('$'))
+    }
+
+    /// Reads the lines of a function file, checking each for mistakes the game would reject
+    /// the whole function for. Blank lines are left out, and, since 1.20.2, lines ending in
+    /// `\` are joined with the next line.
+    ///
+    /// Only the form of each line is checked, not the arguments of its command.
+    pub fn from_str_validated(s: &str, version: MinecraftVersion) -> Result<Self, Vec<CommandError>> {
+        let mut commands = Vec::new();
+        let mut errors = Vec::new();
+        let mut lines = s.lines().enumerate().map(|(index, line)| (index + 1, line));
+        while let Some((number, line)) = lines.next() {
+            let mut line = line.to_string();
+            if version >= MinecraftVersion::V1_20_2 {
+                while let Some(start) = line.strip_suffix('\\') {
+                    let Some((_, next)) = lines.next() else {
+                        errors.push(CommandError::new(number, "the last line can't continue on the next line"));
+                        break;
+                    };
+                    line = format!("{start}{}", next.trim_start());
+                }
+            }
+            if line.trim().is_empty() {
+                continue;
+            }
+            match check_line(&line, version) {
+                Ok(()) => commands.push(line),
+                Err(message) => errors.push(CommandError::new(number, message)),
+            }
+        }
+        match errors.is_empty() {
+            true => Ok(Self { commands, ..Self::default() }),
+            false => Err(errors),
+        }
+    }
+
+    /// The commands of `a`, then those of `b`.
+    pub fn concat(a: McFunction, b: McFunction) -> McFunction {
+        a + b
+    }
+
+    pub fn commands(&self) -> &[String] {
+        &self.commands
+    }
+
+    /// The functions and function tags run by its `function` commands, including ones
+    /// after `execute ... run`. Macro lines are skipped, since what they call is only known
+    /// when they run.
+    pub fn calls(&self) -> Vec<TagValue> {
+        let mut calls = Vec::new();
+        for command in &self.commands {
+            let words: Vec<&str> = command.split_whitespace().collect();
+            if words.first().is_some_and(|word| word.starts_with(['#', '$'])) {
+                continue;
+            }
+            for (i, word) in words.iter().enumerate() {
+                if *word != "function" || (i > 0 && words[i - 1] != "run") {
+                    continue;
+                }
+                let Some(called) = words.get(i + 1) else { continue };
+                let call = match called.strip_prefix('#') {
+                    Some(tag) => tag.parse().map(TagValue::Tag),
+                    None => called.parse().map(TagValue::Id),
+                };
+                calls.extend(call.ok());
+            }
+        }
+        calls
+    }
+
+    /// The predicates its commands check, with `execute if|unless predicate` or a
+    /// selector's `predicate` argument, and the loot tables of the `loot` and `fish`
+    /// sources of `loot`. Macro lines are skipped, like in [`McFunction::calls`].
+    fn used_resources(&self) -> Vec<Reference> {
+        let mut references = Vec::new();
+        for command in &self.commands {
+            let words: Vec<&str> = command.split_whitespace().collect();
+            if words.first().is_some_and(|word| word.starts_with(['#', '$'])) {
+                continue;
+            }
+            // Follows `execute ... run` and `return run` to the command they run.
+            let mut rest = &words[..];
+            loop {
+                match rest {
+                    ["execute", arguments @ ..] => {
+                        let end = arguments.iter().position(|word| *word == "run").unwrap_or(arguments.len());
+                        for condition in arguments[..end].windows(3) {
+                            if let ["if" | "unless", "predicate", predicate] = condition {
+                                references.extend(predicate.parse().ok().map(Reference::to::<Predicate>));
+                            }
+                        }
+                        rest = arguments.get(end + 1..).unwrap_or_default();
+                    }
+                    ["return", "run", command @ ..] => rest = command,
+                    ["loot", arguments @ ..] => {
+                        references.extend(loot_source(arguments).map(Reference::to::<LootTable>));
+                        break;
+                    }
+                    _ => break,
+                }
+            }
+            for selector in words.iter().filter(|word| word.starts_with('@')) {
+                for argument in selector.split("predicate=").skip(1) {
+                    let predicate = argument.trim_start_matches('!').split([',', ']']).next().unwrap_or_default();
+                    references.extend(predicate.parse().ok().map(Reference::to::<Predicate>));
+                }
+            }
+        }
+        references
+    }
+}
+
+/// The loot table of the `loot` or `fish` source of a `loot` command, from the words after
+/// `loot`: its target, then its source.
+fn loot_source(arguments: &[&str]) -> Option<ResourceLocation> {
+    let source = match arguments {
+        ["give", ..] => 2,
+        ["insert" | "spawn", ..] => 4,
+        ["replace", "block", ..] => 6,
+        ["replace", "entity", ..] => 4,
+        _ => return None,
+    };
+    // `replace` may have a count before the source.
+    let source = match arguments.get(source).is_some_and(|word| word.parse::<u32>().is_ok()) {
+        true => source + 1,
+        false => source,
+    };
+    match arguments.get(source..)? {
+        ["fish" | "loot", loot_table, ..] => loot_table.parse().ok(),
+        _ => None,
+    }
+}
+
+/// Builds the function at a location along with the auxiliary functions it needs, such as
+/// those of [`FunctionBuilder::run_later`]. Added to a datapack with
+/// [`Namespace::add_function_with`](crate::datapack::namespace::Namespace::add_function_with).
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionBuilder {
+    location: ResourceLocation,
+    function: McFunction,
+    auxiliary: Vec<(ResourceLocation, McFunction)>,
+    deferred: usize,
+    labels: Vec<String>,
+    objectives: Vec<ScoreboardObjective>,
+    unroll_limit: u32,
+}
+
+impl FunctionBuilder {
+    pub fn new(location: ResourceLocation) -> Self {
+        Self {
+            location,
+            function: McFunction::new(),
+            auxiliary: Vec::new(),
+            deferred: 0,
+            labels: Vec::new(),
+            objectives: Vec::new(),
+            unroll_limit: UNROLL_LIMIT,
+        }
+    }
+
+    /// Sets the most iterations [`FunctionBuilder::for_count`] unrolls, instead of
+    /// [`UNROLL_LIMIT`]. Loops in `body` closures use it too.
+    pub fn unroll_limit(&mut self, limit: u32) -> &mut Self {
+        self.unroll_limit = limit;
+        self
+    }
+
+    pub fn location(&self) -> &ResourceLocation {
+        &self.location
+    }
+
+    pub fn push(&mut self, command: impl Display) -> &mut Self {
+        self.function.push(command);
+        self
+    }
+
+    /// Marks the function as returning a value, like [`McFunction::returns`].
+    pub fn returns(&mut self) -> &mut Self {
+        self.function.returns_value = true;
+        self
+    }
+
+    /// Moves the commands `body` pushes to an auxiliary function, and schedules it to run
+    /// after `delay`. Returns the auxiliary function's location, such as for a
+    /// `schedule clear`.
+    ///
+    /// The auxiliary functions are named `<path>/deferred_<n>`, counting from 0 in the order
+    /// they are made, so the same builder calls always make the same files. `body` can use
+    /// `run_later` too.
+    pub fn run_later(&mut self, delay: GameTime, body: impl FnOnce(&mut FunctionBuilder)) -> Result<ResourceLocation, DatapackError> {
+        let location = ResourceLocation::new(self.location.namespace(), format!("{}/deferred_{}", self.location.path(), self.deferred))?;
+        self.deferred += 1;
+        let later = self.nested(location.clone(), body);
+        self.auxiliary.push((location.clone(), later));
+        self.function.push(ScheduleCommand::function(location.clone(), delay));
+        Ok(location)
+    }
+
+    /// Runs the commands `body` pushes `count` times, with the score of the fake player
+    /// `#<namespace>:<path>/<label>` for `objective` counting from 0 to `count - 1`, and
+    /// `count` after the loop. Named after the whole location, so loops of other functions
+    /// with the same label don't share their counter.
+    ///
+    /// Loops of at most [`UNROLL_LIMIT`] iterations, or the
+    /// [limit](FunctionBuilder::unroll_limit) set, are unrolled. Longer ones run the
+    /// auxiliary function `<path>/<label>`, which calls itself until the counter reaches
+    /// `count`. Every iteration counts towards the `maxCommandChainLength` game rule, so a
+    /// warning is logged when the loop would run more commands than its default allows.
+    ///
+    /// `objective` is listed by [`FunctionBuilder::objectives`]. Fails if `label` is already used
+    /// by another loop of this builder or isn't a valid path, if `objective` isn't a
+    /// valid objective name, or if `count` is more than a score can hold.
+    pub fn for_count(
+        &mut self,
+        label: &str,
+        objective: &str,
+        count: u32,
+        body: impl FnOnce(&mut FunctionBuilder),
+    ) -> Result<&mut Self, DatapackError> {
+        let Ok(last) = i32::try_from(count) else {
+            return Err(DatapackError::Validation(format!("invalid loop '{label}': it counts to {count}, more than a score can hold")));
+        };
+        let location = self.label(label)?;
+        self.declare_objective(ScoreboardObjective::dummy(objective)?);
+        let counter = format!("#{location}");
+        if count == 0 {
+            self.function.push(ScoreboardCommand::set(counter.as_str(), objective, 0));
+            return Ok(self);
+        }
+        let mut iteration = self.nested(location.clone(), body);
+        let commands = u64::from(count) * (iteration.commands().len() as u64 + 2);
+        if commands > MAX_COMMAND_CHAIN_LENGTH {
+            log::warn!("the loop '{location}' runs {commands} commands, more than the {MAX_COMMAND_CHAIN_LENGTH} of maxCommandChainLength by default");
+        }
+
+        if count <= self.unroll_limit {
+            for i in 0..last {
+                self.function.push(ScoreboardCommand::set(counter.as_str(), objective, i));
+                self.function.extend(iteration.commands());
+            }
+            self.function.push(ScoreboardCommand::set(counter.as_str(), objective, last));
+            return Ok(self);
+        }
+        iteration.push(ScoreboardCommand::add(counter.as_str(), objective, 1));
+        let repeat = ExecuteCommand::new()
+            .if_score(counter.as_str(), objective, ScoreComparison::Matches(MinecraftRange::AtMost(last - 1)))
+            .run_function(&location);
+        iteration.push(repeat.build()?);
+        self.auxiliary.push((location.clone(), iteration));
+        self.function.push(ScoreboardCommand::set(counter.as_str(), objective, 0));
+        self.function.push(call_function(&location));
+        Ok(self)
+    }
+
+    /// Runs the commands `body` pushes for as long as the score of `holder` for
+    /// `objective` is in `range`, checked before each run. They are moved to the auxiliary
+    /// function `<path>/<label>`, which calls itself, so the loop counts towards the
+    /// `maxCommandChainLength` game rule like [`FunctionBuilder::for_count`].
+    pub fn while_score(
+        &mut self,
+        label: &str,
+        holder: impl Into<ScoreHolder>,
+        objective: &str,
+        range: impl Into<MinecraftRange<i32>>,
+        body: impl FnOnce(&mut FunctionBuilder),
+    ) -> Result<&mut Self, DatapackError> {
+        let location = self.label(label)?;
+        let guard = ExecuteCommand::new().if_score(holder, objective, ScoreComparison::Matches(range.into())).run_function(&location).build()?;
+        let mut iteration = self.nested(location.clone(), body);
+        iteration.push(&guard);
+        self.auxiliary.push((location, iteration));
+        self.function.push(guard);
+        Ok(self)
+    }
+
+    /// The objectives of the loops, which have to be added before the function runs.
+    /// [`Namespace::add_function_with`](crate::datapack::namespace::Namespace::add_function_with)
+    /// declares them in the namespace, so its load function adds them and its uninstall
+    /// function removes them.
+    pub fn objectives(&self) -> &[ScoreboardObjective] {
+        &self.objectives
+    }
+
+    /// The function and its auxiliary functions, with their locations, starting with the
+    /// function.
+    pub fn build(self) -> Vec<(ResourceLocation, McFunction)> {
+        [(self.location, self.function)].into_iter().chain(self.auxiliary).collect()
+    }
+
+    /// The commands pushed so far.
+    pub(crate) fn function(&self) -> &McFunction {
+        &self.function
+    }
+
+    /// Takes the auxiliary functions made so far and the objectives of the loops, so a
+    /// builder that is built in several calls can add them as it goes.
+    pub(crate) fn take_auxiliary(&mut self) -> (Vec<(ResourceLocation, McFunction)>, Vec<ScoreboardObjective>) {
+        (std::mem::take(&mut self.auxiliary), std::mem::take(&mut self.objectives))
+    }
+
+    /// The location of the auxiliary function of a loop labelled `label`.
+    fn label(&mut self, label: &str) -> Result<ResourceLocation, DatapackError> {
+        if label.starts_with("deferred_") || label.contains('/') || self.labels.iter().any(|used| used == label) {
+            return Err(DatapackError::Validation(format!(
+                "invalid loop label '{label}': it must be unique, have no '/', and not start with 'deferred_'",
+            )));
+        }
+        let location = ResourceLocation::new(self.location.namespace(), format!("{}/{label}", self.location.path()))?;
+        self.labels.push(label.to_string());
+        Ok(location)
+    }
+
+    fn declare_objective(&mut self, objective: ScoreboardObjective) {
+        if !self.objectives.iter().any(|declared| declared.name() == objective.name()) {
+            self.objectives.push(objective);
+        }
+    }
+
+    /// Builds `body` as the function at `location`, keeping its auxiliary functions and
+    /// objectives, and returns its commands.
+    fn nested(&mut self, location: ResourceLocation, body: impl FnOnce(&mut FunctionBuilder)) -> McFunction {
+        let mut nested = FunctionBuilder::new(location);
+        nested.unroll_limit = self.unroll_limit;
+        body(&mut nested);
+        for objective in std::mem::take(&mut nested.objectives) {
+            self.declare_objective(objective);
+        }
+        let mut functions = nested.build().into_iter();
+        let (_, function) = functions.next().expect("a builder always builds its function");
+        self.auxiliary.extend(functions);
+        function
+    }
+}
+
+/// The names of the `$(name)` arguments of a macro line, or why they are invalid.
+fn macro_arguments(line: &str) -> Result<Vec<&str>, String> {
+    let mut arguments = Vec::new();
+    let mut rest = line;
+    while let Some(start) = rest.find("$(") {
+        let after = &rest[start + 2..];
+        let end = after.find(')').ok_or_else(|| format!("the macro line '${line}' has an unclosed '$('"))?;
+        let name = &after[..end];
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(format!("'{name}' isn't a valid macro argument name"));
+        }
+        arguments.push(name);
+        rest = &after[end + 1..];
+    }
+    Ok(arguments)
+}
+
+fn check_line(line: &str, version: MinecraftVersion) -> Result<(), String> {
+    if line.starts_with(char::is_whitespace) {
+        return Err("commands can't start with whitespace".to_string());
+    }
+    if line.starts_with('#') {
+        return Ok(());
+    }
+    let command = match line.strip_prefix('$') {
+        Some(_) if version < MinecraftVersion::V1_20_2 => return Err(format!("macro lines need 1.20.2 or newer, not {version}")),
+        Some(_) if !line.contains("$(") => return Err("macro lines need at least one '$(name)' argument".to_string()),
+        Some(command) => command,
+        None => line,
+    };
+    let command = match command.strip_prefix('/') {
+        Some(_) if version >= MinecraftVersion::V1_20_2 => return Err("commands can't start with '/' since 1.20.2".to_string()),
+        Some(command) => command,
+        None => command,
+    };
+    let name = command.split_whitespace().next().unwrap_or_default();
+    let valid_name = name.starts_with(|c: char| c.is_ascii_lowercase()) && name.chars().all(|c| c.is_ascii_lowercase() || c == '_' || c == '-');
+    if !valid_name {
+        return Err(format!("'{name}' isn't a command"));
+    }
+    Ok(())
+}
+
+/// A line of a function file that the game wouldn't accept, as found by
+/// [`McFunction::from_str_validated`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandError {
+    /// Counted from 1.
+    pub line: usize,
+    pub message: String,
+}
+
+impl CommandError {
+    fn new(line: usize, message: impl Into<String>) -> Self {
+        Self { line, message: message.into() }
+    }
+}
+
+impl Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl<C: Display> FromIterator<C> for McFunction {
+    fn from_iter<I: IntoIterator<Item = C>>(commands: I) -> Self {
+        Self { commands: commands.into_iter().map(|command| command.to_string()).collect(), ..Self::default() }
+    }
+}
+
+impl<C: Display> From<Vec<C>> for McFunction {
+    fn from(commands: Vec<C>) -> Self {
+        commands.into_iter().collect()
+    }
+}
+
+impl<C: Display> Extend<C> for McFunction {
+    fn extend<I: IntoIterator<Item = C>>(&mut self, commands: I) {
+        self.commands.extend(commands.into_iter().map(|command| command.to_string()));
+    }
+}
+
+impl Add for McFunction {
+    type Output = Self;
+
+    fn add(mut self, other: Self) -> Self {
+        self += other;
+        self
+    }
+}
+
+impl AddAssign for McFunction {
+    fn add_assign(&mut self, other: Self) {
+        self.commands.extend(other.commands);
+    }
+}
+
+impl Display for McFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for command in &self.commands {
+            writeln!(f, "{command}")?;
+        }
+        Ok(())
+    }
+}
+
+impl File for McFunction {}
+
+impl Resource for McFunction {
+    const DIRECTORY: &'static str = "functions";
+    const EXTENSION: &'static str = "mcfunction";
+
+    /// Renamed to `function` in pack format 45.
+    fn directory(pack_format: u32) -> &'static str {
+        if pack_format >= 45 { "function" } else { Self::DIRECTORY }
+    }
+
+    fn set_pack_format(&mut self, pack_format: u32) {
+        self.pack_format = Some(pack_format);
+    }
+
+    /// Checks the macro lines: that the pack format supports them, that each has valid
+    /// arguments, and that those were declared, if any were.
+    fn validate(&self) -> Result<(), DatapackError> {
+        let invalid = |message: String| Err(DatapackError::Validation(format!("invalid function: {message}")));
+        let mut used = Vec::new();
+        for line in self.macro_lines() {
+            if let Some(pack_format) = self.pack_format.filter(|pack_format| *pack_format < MACRO_PACK_FORMAT) {
+                return invalid(format!("macro lines need pack format {MACRO_PACK_FORMAT} or newer, but the pack format is {pack_format}"));
+            }
+            let arguments = match macro_arguments(line) {
+                Ok(arguments) if arguments.is_empty() => return invalid(format!("the macro line '${line}' has no arguments")),
+                Ok(arguments) => arguments,
+                Err(message) => return invalid(message),
+            };
+            used.extend(arguments);
+        }
+        if self.returns_value {
+            if let Some(pack_format) = self.pack_format.filter(|pack_format| *pack_format < RETURN_PACK_FORMAT) {
+                return invalid(format!("functions return values since pack format {RETURN_PACK_FORMAT}, but the pack format is {pack_format}"));
+            }
+            let last = self.commands.iter().rev().find(|command| !command.starts_with('#') && !command.trim().is_empty());
+            if !last.is_some_and(|last| is_return(last)) {
+                log::warn!("the function should return a value, but its last command doesn't return");
+            }
+        }
+        if let Some(params) = &self.params {
+            if let Some(undeclared) = used.iter().find(|param| !params.iter().any(|declared| declared == *param)) {
+                return invalid(format!("'$({undeclared})' isn't a declared parameter"));
+            }
+            for unused in self.unused_params() {
+                log::warn!("the function parameter '{unused}' is declared, but never used");
+            }
+        }
+        Ok(())
+    }
+
+    /// The functions and function tags it calls, then the predicates and loot tables its
+    /// commands use.
+    fn references(&self) -> Vec<Reference> {
+        self.calls()
+            .into_iter()
+            .map(|call| match call {
+                TagValue::Id(function) => Reference::to::<McFunction>(function),
+                TagValue::Tag(tag) => Reference::to::<Tag<FunctionTag>>(tag),
+            })
+            .chain(self.used_resources())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use crate::datapack::Datapack;
+    use crate::datapack::pack_meta::PackMeta;
+
+    #[test]
+    fn function_display() {
+        let function = McFunction::new().command("say hi").command(format_args!("scoreboard players set @s {} 1", "test"));
+        assert_eq!(function.to_string(), "say hi\nscoreboard players set @s test 1\n");
+        assert_eq!(McFunction::new().to_string(), "", "an empty function should be an empty file");
+    }
+
+    #[test]
+    fn concat() {
+        let setup = McFunction::new().command("scoreboard objectives add timer dummy");
+        let logic = McFunction::new().command("say hi").command("say bye");
+        let mut function = McFunction::concat(setup.clone(), logic.clone());
+        assert_eq!(function.commands(), ["scoreboard objectives add timer dummy", "say hi", "say bye"]);
+        assert_eq!(setup.clone() + logic.clone(), function);
+
+        function += McFunction::new().command("say again");
+        function.extend(["say one", "say two"]);
+        assert_eq!(function.commands()[3..], ["say again", "say one", "say two"]);
+    }
+
+    #[cfg(feature = "macros")]
+    #[test]
+    fn mcfunction_macro() {
+        let function = crate::mcfunction! {
+            # Lifts everyone up
+            execute as @a[tag=!flying] at @s run tp ~ ~1 ~-0.5;
+            say Hello "world";
+            scoreboard players set #global test.timer 5;
+            $tp @s $(x) 64 $(z);
+            save-all
+        };
+        assert_eq!(function.commands(), [
+            "# Lifts everyone up",
+            "execute as @a[tag=!flying] at @s run tp ~ ~1 ~-0.5",
+            "say Hello \"world\"",
+            "scoreboard players set #global test.timer 5",
+            "$tp @s $(x) 64 $(z)",
+            "save-all",
+        ]);
+        assert_eq!(crate::mcfunction! {}, McFunction::new());
+    }
+
+    #[test]
+    fn calls() {
+        let function = McFunction::new()
+            .command("# function test:commented")
+            .command("function test:first")
+            .command("execute as @a run function #test:each_player")
+            .command("$function test:$(name)")
+            .command("schedule function test:later 1t")
+            .command("say function test:said");
+        let location = |location: &str| location.parse().expect("should have parsed the resource location");
+        assert_eq!(function.calls(), [TagValue::Id(location("test:first")), TagValue::Tag(location("test:each_player"))]);
+    }
+
+    #[test]
+    fn validated() {
+        let source = "# Gives everyone a diamond\n\nsay hi\nexecute as @a \\\n    run give @s diamond\n$say $(message)\n";
+        let function = McFunction::from_str_validated(source, MinecraftVersion::V1_21).expect("the function should be valid");
+        assert_eq!(function.commands(), ["# Gives everyone a diamond", "say hi", "execute as @a run give @s diamond", "$say $(message)"]);
+
+        let source = "/say old\n  say indented\nSay hi\n$say no arguments\n@a\nsay fine";
+        let errors = McFunction::from_str_validated(source, MinecraftVersion::V1_21).expect_err("the function should be invalid");
+        let lines: Vec<usize> = errors.iter().map(|error| error.line).collect();
+        assert_eq!(lines, [1, 2, 3, 4, 5]);
+        assert_eq!(errors[2].to_string(), "line 3: 'Say' isn't a command");
+
+        let errors = McFunction::from_str_validated("/say old\n$say $(message)", MinecraftVersion::V1_20).expect_err("macros are newer than 1.20");
+        assert_eq!(errors, [CommandError::new(2, "macro lines need 1.20.2 or newer, not 1.20")]);
+        assert!(McFunction::from_str_validated("say \\", MinecraftVersion::V1_21).is_err(), "there is no line to continue on");
+    }
+
+    #[test]
+    fn run_later() {
+        let mut datapack = Datapack::new(PackMeta::new(48, "test"));
+        let mut deferred = Vec::new();
+        datapack
+            .namespace("test")
+            .expect("should have accepted the namespace")
+            .add_function_with("countdown", |function| {
+                function.push("say 3");
+                let seconds = |seconds| GameTime::seconds(seconds).expect("should have accepted the time");
+                let later = function.run_later(seconds(1), |later| {
+                    later.push("say 2");
+                    deferred.extend(later.run_later(seconds(1), |later| {
+                        later.push("say 1");
+                    }));
+                });
+                deferred.extend(later);
+            })
+            .expect("should have added the functions");
+
+        assert_eq!(deferred[0].to_string(), "test:countdown/deferred_0/deferred_0");
+        assert_eq!(deferred[1].to_string(), "test:countdown/deferred_0");
+        for path in [
+            "data/test/function/countdown.mcfunction",
+            "data/test/function/countdown/deferred_0.mcfunction",
+            "data/test/function/countdown/deferred_0/deferred_0.mcfunction",
+        ] {
+            assert!(datapack.contains(path), "a function should have been placed at '{path}'");
+        }
+
+        let mut builder = FunctionBuilder::new("test:main".parse().expect("should have parsed the resource location"));
+        builder.run_later(GameTime::ticks(5).expect("should have accepted the time"), |later| {
+            later.push("say later");
+        }).expect("should have made the auxiliary function");
+        let functions = builder.build();
+        assert_eq!(functions[0].1.commands(), ["schedule function test:main/deferred_0 5t"]);
+        assert_eq!(functions[1].1.commands(), ["say later"]);
+    }
+
+    /// Runs `function` from `functions`, following the commands loops are made of, and
+    /// returns every other command with the scores when it ran.
+    fn run(functions: &[(ResourceLocation, McFunction)], function: &str, scores: &mut BTreeMap<String, i32>, log: &mut Vec<String>) {
+        let (_, function) = functions.iter().find(|(location, _)| location.to_string() == function).expect("the function should exist");
+        for command in function.commands() {
+            run_command(functions, command, scores, log);
+        }
+    }
+
+    fn run_command(functions: &[(ResourceLocation, McFunction)], command: &str, scores: &mut BTreeMap<String, i32>, log: &mut Vec<String>) {
+        let words: Vec<&str> = command.split(' ').collect();
+        match words[..] {
+            ["scoreboard", "players", "set", holder, objective, value] => {
+                scores.insert(format!("{holder} {objective}"), value.parse().expect("the score should be a number"));
+            }
+            ["scoreboard", "players", "add", holder, objective, value] => {
+                *scores.entry(format!("{holder} {objective}")).or_default() += value.parse::<i32>().expect("the score should be a number");
+            }
+            ["function", function] => run(functions, function, scores, log),
+            ["execute", "if", "score", holder, objective, "matches", range, "run", ..] => {
+                let (min, max) = range.split_once("..").expect("the range should have bounds");
+                let score = scores.get(&format!("{holder} {objective}")).copied().unwrap_or_default();
+                if min.parse().map_or(true, |min: i32| score >= min) && max.parse().map_or(true, |max: i32| score <= max) {
+                    run_command(functions, &words[8..].join(" "), scores, log);
+                }
+            }
+            _ => log.push(format!("{command} {scores:?}")),
+        }
+    }
+
+    #[test]
+    fn loops() {
+        let location = |location: &str| location.parse::<ResourceLocation>().expect("should have parsed the resource location");
+        let counted = |unroll_limit| {
+            let mut builder = FunctionBuilder::new(location("test:main"));
+            builder
+                .unroll_limit(unroll_limit)
+                .for_count("spin", "loop", 3, |body| {
+                    body.push("say spin");
+                })
+                .expect("should have made the loop");
+            builder.push("say done");
+            builder
+        };
+        let unrolled = counted(16);
+        assert_eq!(unrolled.clone().build().len(), 1, "a short loop should have been unrolled");
+        let recursive = counted(2);
+        let functions = recursive.clone().build();
+        assert_eq!(functions[1].0, location("test:main/spin"));
+        assert_eq!(functions[1].1.commands(), [
+            "say spin",
+            "scoreboard players add #test:main/spin loop 1",
+            "execute if score #test:main/spin loop matches ..2 run function test:main/spin",
+        ]);
+
+        let observe = |builder: FunctionBuilder| {
+            let (mut scores, mut log) = (BTreeMap::new(), Vec::new());
+            run(&builder.build(), "test:main", &mut scores, &mut log);
+            (scores, log)
+        };
+        let (scores, log) = observe(unrolled);
+        assert_eq!(log, [
+            "say spin {\"#test:main/spin loop\": 0}",
+            "say spin {\"#test:main/spin loop\": 1}",
+            "say spin {\"#test:main/spin loop\": 2}",
+            "say done {\"#test:main/spin loop\": 3}",
+        ]);
+        assert_eq!((scores, log), observe(recursive), "both variants should run the same commands");
+
+        let mut builder = FunctionBuilder::new(location("test:main"));
+        builder.unroll_limit(0).for_count("never", "loop", 0, |body| {
+            body.push("say never");
+        }).expect("should have made the loop");
+        assert_eq!(builder.clone().build().len(), 1, "an empty loop needs no function");
+        assert_eq!(builder.function().commands(), ["scoreboard players set #test:main/never loop 0"]);
+        let too_long = builder.for_count("forever", "loop", u32::MAX, |_| {});
+        assert!(matches!(too_long, Err(DatapackError::Validation(_))), "a score can't count that far");
+
+        let mut builder = FunctionBuilder::new(location("test:main"));
+        builder.while_score("drain", "#water", "level", 1.., |body| {
+            body.push("scoreboard players remove #water level 1");
+        }).expect("should have made the loop");
+        assert!(builder.for_count("drain", "loop", 20, |_| {}).is_err(), "the label is taken");
+        assert!(builder.for_count("deferred_0", "loop", 20, |_| {}).is_err(), "the label is reserved");
+        let functions = builder.build();
+        assert_eq!(functions[0].1.commands(), ["execute if score #water level matches 1.. run function test:main/drain"]);
+        assert_eq!(functions[1].1.commands(), [
+            "scoreboard players remove #water level 1",
+            "execute if score #water level matches 1.. run function test:main/drain",
+        ]);
+    }
+
+    #[test]
+    fn loop_helpers() {
+        let mut datapack = Datapack::new(PackMeta::new(48, "test"));
+        datapack
+            .namespace("test")
+            .expect("should have accepted the namespace")
+            .add_function_with("fill", |function| {
+                function.for_count("rows", "row", 20, |row| {
+                    row.for_count("columns", "column", 4, |column| {
+                        column.push("setblock ~ ~ ~ minecraft:stone");
+                    }).expect("should have made the inner loop");
+                }).expect("should have made the outer loop");
+            })
+            .expect("should have added the functions");
+        for path in [
+            "data/test/function/fill.mcfunction",
+            "data/test/function/fill/rows.mcfunction",
+            "data/test/function/load.mcfunction",
+            "data/test/function/uninstall.mcfunction",
+            "data/minecraft/tags/function/load.json",
+        ] {
+            assert!(datapack.contains(path), "a function should have been placed at '{path}'");
+        }
+        assert!(!datapack.contains("data/test/function/fill/setup.mcfunction"), "the load function adds the objectives");
+        assert!(!datapack.contains("data/test/function/fill/rows/columns.mcfunction"), "the inner loop should have been unrolled");
+    }
+
+    #[test]
+    fn macros() {
+        let function = McFunction::new()
+            .command("say costs $(5), not a macro")
+            .macro_line("tp @s $(x) $(y) $(x)")
+            .with_params(["x", "y", "z"]);
+        assert_eq!(function.commands(), ["say costs $(5), not a macro", "$tp @s $(x) $(y) $(x)"]);
+        assert_eq!(function.macro_params(), ["x", "y"], "only macro lines have arguments");
+        assert_eq!(function.unused_params(), ["z"]);
+        assert!(function.is_macro());
+        assert!(function.validate().is_ok());
+
+        let undeclared = McFunction::new().macro_line("say $(name)").with_params(["other"]);
+        assert!(undeclared.validate().is_err());
+        for line in ["say no arguments", "say $(unclosed", "say $(has space)"] {
+            assert!(McFunction::new().macro_line(line).validate().is_err(), "'${line}' should have been rejected");
+        }
+
+        let mut datapack = Datapack::new(PackMeta::new(15, "test"));
+        let mut namespace = datapack.namespace("test").expect("should have accepted the namespace");
+        assert!(namespace.add_function("plain", McFunction::new().command("say $(5)")).is_ok());
+        let result = namespace.add_function("macro", McFunction::new().macro_line("say $(name)"));
+        assert!(result.is_err(), "macros need pack format 18");
+    }
+
+    #[test]
+    fn returns() {
+        let function = McFunction::new().command("execute if entity @s run return 1").command("return fail").returns();
+        assert!(function.returns_value());
+        assert!(function.validate().is_ok());
+
+        let mut datapack = Datapack::new(PackMeta::new(12, "test"));
+        let mut namespace = datapack.namespace("test").expect("should have accepted the namespace");
+        assert!(namespace.add_function("count", function).is_err(), "functions return values since 1.20");
+        let result = namespace.add_function_with("builder", |function| {
+            function.push("return 1").returns();
+        });
+        assert!(result.is_err(), "the builder's function returns a value too");
+
+        let mut added = McFunction::new().command("return 1").returns();
+        added.set_pack_format(48);
+        assert_eq!(added, McFunction::new().command("return 1"), "only the commands and parameters are compared");
+    }
+
+    #[test]
+    fn namespace_placement() {
+        for (pack_format, path) in [(26, "data/test/functions/main.mcfunction"), (48, "data/test/function/main.mcfunction")] {
+            let mut datapack = Datapack::new(PackMeta::new(pack_format, "test"));
+            datapack
+                .namespace("test")
+                .expect("should have accepted the namespace")
+                .add_function("main", ["say hi"].into_iter().collect())
+                .expect("should have added the function");
+            assert!(datapack.contains(path), "function should have been placed at '{path}'");
+        }
+    }
+}
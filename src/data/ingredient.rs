@@ -0,0 +1,100 @@
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer};
+use crate::resource_location::ResourceLocation;
+
+/// What a recipe slot accepts: an item, any item of a tag, or any of several
+/// ingredients.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Ingredient {
+    Item(ResourceLocation),
+    Tag(ResourceLocation),
+    Any(Vec<Ingredient>),
+}
+
+impl From<ResourceLocation> for Ingredient {
+    fn from(item: ResourceLocation) -> Self {
+        Self::Item(item)
+    }
+}
+
+/// Accepts any of `ingredients`, written as an array.
+impl From<Vec<Ingredient>> for Ingredient {
+    fn from(ingredients: Vec<Ingredient>) -> Self {
+        Self::Any(ingredients)
+    }
+}
+
+impl Serialize for Ingredient {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Item(item) => {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry("item", item)?;
+                map.end()
+            }
+            Self::Tag(tag) => {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry("tag", tag)?;
+                map.end()
+            }
+            Self::Any(ingredients) => ingredients.serialize(serializer),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Ingredient {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Item { item: ResourceLocation },
+            Tag { tag: ResourceLocation },
+            Any(Vec<Ingredient>),
+        }
+
+        Ok(match Repr::deserialize(deserializer)? {
+            Repr::Item { item } => Self::Item(item),
+            Repr::Tag { tag } => Self::Tag(tag),
+            Repr::Any(ingredients) => Self::Any(ingredients),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn location(location: &str) -> ResourceLocation {
+        location.parse().expect("should have parsed the resource location")
+    }
+
+    #[test]
+    fn ingredient_forms() {
+        let json = |ingredient: &Ingredient| serde_json::to_value(ingredient).expect("should have serialized the ingredient");
+        let item = Ingredient::from(location("minecraft:stick"));
+        let tag = Ingredient::Tag(location("minecraft:planks"));
+        let any = Ingredient::from(vec![item.clone(), tag.clone()]);
+        assert_eq!(json(&item), json!({"item": "minecraft:stick"}));
+        assert_eq!(json(&tag), json!({"tag": "minecraft:planks"}));
+        assert_eq!(json(&any), json!([{"item": "minecraft:stick"}, {"tag": "minecraft:planks"}]));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn read_ingredient() {
+        let read = |json: serde_json::Value| serde_json::from_value::<Ingredient>(json).expect("should have read the ingredient");
+        assert_eq!(read(json!({"tag": "minecraft:coals"})), Ingredient::Tag(location("minecraft:coals")));
+        assert_eq!(
+            read(json!([{"item": "minecraft:coal"}, [{"tag": "minecraft:logs"}]])),
+            Ingredient::Any(vec![
+                Ingredient::Item(location("minecraft:coal")),
+                Ingredient::Any(vec![Ingredient::Tag(location("minecraft:logs"))]),
+            ]),
+        );
+        assert!(serde_json::from_value::<Ingredient>(json!({"count": 2})).is_err(), "an ingredient needs an item or a tag");
+    }
+}
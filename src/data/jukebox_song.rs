@@ -0,0 +1,70 @@
+use serde::Serialize;
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+use serde_json::Value;
+use crate::datapack::resource::Resource;
+use crate::error::DatapackError;
+use crate::text::TextComponent;
+use crate::utils::json::json_file;
+
+/// A `data/<namespace>/jukebox_song` file, available since 1.21: a song an item with the
+/// `minecraft:jukebox_playable` component plays in a jukebox.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct JukeboxSong {
+    /// A sound event ID, or an inline `{"sound_id": ...}` sound event.
+    pub sound_event: Value,
+    /// Shown when the song starts, and in the item's tooltip.
+    pub description: TextComponent,
+    /// How long the jukebox plays, after which it stops even if the sound goes on.
+    pub length_in_seconds: f32,
+    /// The signal strength of a comparator reading the jukebox, from 0 to 15.
+    pub comparator_output: u32,
+}
+
+impl JukeboxSong {
+    pub fn new(sound_event: impl Into<Value>, description: impl Into<TextComponent>, length_in_seconds: f32, comparator_output: u32) -> Self {
+        Self { sound_event: sound_event.into(), description: description.into(), length_in_seconds, comparator_output }
+    }
+}
+
+json_file!(JukeboxSong);
+
+impl Resource for JukeboxSong {
+    const DIRECTORY: &'static str = "jukebox_song";
+    const MIN_PACK_FORMAT: u32 = 48;
+
+    fn validate(&self) -> Result<(), DatapackError> {
+        let invalid = |message: String| Err(DatapackError::Validation(format!("invalid jukebox song: {message}")));
+        if self.comparator_output > 15 {
+            return invalid(format!("the comparator output {} must be from 0 to 15", self.comparator_output));
+        }
+        if self.length_in_seconds <= 0.0 {
+            return invalid(format!("the length {} must be positive", self.length_in_seconds));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// `minecraft:13` from the game's data.
+    #[test]
+    fn vanilla_13() {
+        let song = JukeboxSong::new("minecraft:music_disc.13", TextComponent::translate("jukebox_song.minecraft.13"), 178.0, 1);
+        let json: Value = serde_json::from_str(&song.to_string()).expect("jukebox song should be valid JSON");
+        assert_eq!(json, json!({
+            "comparator_output": 1,
+            "description": {"translate": "jukebox_song.minecraft.13"},
+            "length_in_seconds": 178.0,
+            "sound_event": "minecraft:music_disc.13",
+        }));
+        assert!(song.validate().is_ok());
+
+        assert!(JukeboxSong::new("test:song", "Song", 60.0, 16).validate().is_err(), "comparators output at most 15");
+        assert!(JukeboxSong::new("test:song", "Song", 0.0, 0).validate().is_err(), "songs take some time");
+    }
+}
@@ -0,0 +1,251 @@
+use serde::Serialize;
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+use serde_json::Value;
+use crate::data::conditions::{LootCondition, LootContextEntity};
+use crate::data::number_provider::NumberProvider;
+use crate::datapack::resource::{Reference, Resource};
+use crate::resource_location::ResourceLocation;
+use crate::text::TextComponent;
+use crate::utils::json::json_file;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[serde(rename_all = "snake_case")]
+pub enum CopyNbtStrategy {
+    Replace,
+    Append,
+    Merge,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct CopyNbtOperation {
+    pub source: String,
+    pub target: String,
+    pub op: CopyNbtStrategy,
+}
+
+/// What a [`LootFunction`] does to the item stack.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[serde(tag = "function")]
+pub enum LootFunctionKind {
+    #[serde(rename = "minecraft:set_count")]
+    SetCount { count: NumberProvider, add: bool },
+    /// SNBT merged into the item's tag, before pack format 41.
+    #[serde(rename = "minecraft:set_nbt")]
+    SetNbt { tag: String },
+    /// Data components set on the item, from pack format 41 on.
+    #[serde(rename = "minecraft:set_components")]
+    SetComponents { components: Value },
+    #[serde(rename = "minecraft:enchant_with_levels")]
+    EnchantWithLevels {
+        levels: NumberProvider,
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        treasure: bool,
+    },
+    #[serde(rename = "minecraft:looting_enchant")]
+    LootingEnchant {
+        count: NumberProvider,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        limit: Option<i32>,
+    },
+    #[serde(rename = "minecraft:set_name")]
+    SetName {
+        name: Box<TextComponent>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        entity: Option<LootContextEntity>,
+    },
+    #[serde(rename = "minecraft:copy_nbt")]
+    CopyNbt { source: String, ops: Vec<CopyNbtOperation> },
+    #[serde(rename = "minecraft:explosion_decay")]
+    ExplosionDecay,
+    #[serde(rename = "minecraft:furnace_smelt")]
+    FurnaceSmelt,
+    /// Applies the item modifier file with this ID.
+    #[serde(rename = "minecraft:reference")]
+    Reference { name: ResourceLocation },
+    /// Any other function, written as is. It should include its own `function`.
+    #[serde(untagged)]
+    Raw(Value),
+}
+
+/// An item function, as used by loot tables and item modifiers, applied when all of its
+/// `conditions` pass.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct LootFunction {
+    #[serde(flatten)]
+    pub kind: LootFunctionKind,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conditions: Vec<LootCondition>,
+}
+
+impl LootFunction {
+    pub fn new(kind: LootFunctionKind) -> Self {
+        Self { kind, conditions: Vec::new() }
+    }
+
+    pub fn set_count(count: impl Into<NumberProvider>) -> Self {
+        Self::new(LootFunctionKind::SetCount { count: count.into(), add: false })
+    }
+
+    pub fn looting_enchant(count: impl Into<NumberProvider>) -> Self {
+        Self::new(LootFunctionKind::LootingEnchant { count: count.into(), limit: None })
+    }
+
+    pub fn set_name(name: impl Into<TextComponent>) -> Self {
+        Self::new(LootFunctionKind::SetName { name: Box::new(name.into()), entity: None })
+    }
+
+    /// Applies the item modifier file `name`, such as one added with
+    /// [`Namespace::add_item_modifier`](crate::datapack::namespace::Namespace::add_item_modifier).
+    pub fn reference(name: ResourceLocation) -> Self {
+        Self::new(LootFunctionKind::Reference { name })
+    }
+
+    pub fn condition(mut self, condition: LootCondition) -> Self {
+        self.conditions.push(condition);
+        self
+    }
+
+    /// The item modifier this function applies, if it is a reference, and the predicates
+    /// of its conditions.
+    pub(crate) fn references(&self) -> Vec<Reference> {
+        let mut references: Vec<Reference> = self.conditions.iter().flat_map(LootCondition::references).collect();
+        if let LootFunctionKind::Reference { name } = &self.kind {
+            references.push(Reference::to::<ItemModifier>(name.clone()));
+        }
+        references
+    }
+}
+
+impl From<LootFunctionKind> for LootFunction {
+    fn from(kind: LootFunctionKind) -> Self {
+        Self::new(kind)
+    }
+}
+
+/// A `data/<namespace>/item_modifiers` file, for `item modify`: one function, or a list
+/// applied in order.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[serde(untagged)]
+pub enum ItemModifier {
+    All(Vec<LootFunction>),
+    Single(Box<LootFunction>),
+}
+
+impl ItemModifier {
+    /// A single function when there is one, otherwise a list.
+    pub fn new(functions: impl IntoIterator<Item = LootFunction>) -> Self {
+        let mut functions: Vec<LootFunction> = functions.into_iter().collect();
+        match functions.len() {
+            1 => functions.pop().expect("there is one function").into(),
+            _ => Self::All(functions),
+        }
+    }
+
+    pub fn functions(&self) -> &[LootFunction] {
+        match self {
+            Self::All(functions) => functions,
+            Self::Single(function) => std::slice::from_ref(function),
+        }
+    }
+}
+
+impl From<LootFunction> for ItemModifier {
+    fn from(function: LootFunction) -> Self {
+        Self::Single(Box::new(function))
+    }
+}
+
+impl From<LootFunctionKind> for ItemModifier {
+    fn from(kind: LootFunctionKind) -> Self {
+        LootFunction::from(kind).into()
+    }
+}
+
+impl From<Vec<LootFunction>> for ItemModifier {
+    fn from(functions: Vec<LootFunction>) -> Self {
+        Self::All(functions)
+    }
+}
+
+json_file!(ItemModifier);
+
+impl Resource for ItemModifier {
+    const DIRECTORY: &'static str = "item_modifiers";
+    const MIN_PACK_FORMAT: u32 = 7;
+
+    /// Renamed to `item_modifier` in pack format 45.
+    fn directory(pack_format: u32) -> &'static str {
+        if pack_format >= 45 { "item_modifier" } else { Self::DIRECTORY }
+    }
+
+    fn references(&self) -> Vec<Reference> {
+        self.functions().iter().flat_map(LootFunction::references).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use crate::color::Color;
+    use crate::command::item::{ItemCommand, ItemTarget};
+    use crate::command::selector::{Selector, SelectorBase};
+    use crate::datapack::Datapack;
+    use crate::datapack::pack_meta::PackMeta;
+
+    #[test]
+    fn item_modifier() {
+        let modifier = ItemModifier::from(vec![
+            LootFunction::set_name(TextComponent::text("Excalibur").color(Color::Gold).bold()),
+            LootFunction::set_count(NumberProvider::uniform(1.0, 3.0)),
+        ]);
+        let json: Value = serde_json::from_str(&modifier.to_string()).expect("item modifier should be valid JSON");
+        assert_eq!(json, json!([
+            {"function": "minecraft:set_name", "name": {"text": "Excalibur", "color": "gold", "bold": true}},
+            {"function": "minecraft:set_count", "count": {"type": "minecraft:uniform", "min": 1.0, "max": 3.0}, "add": false},
+        ]), "item modifier was not rendered correctly");
+
+        let single = ItemModifier::from(LootFunctionKind::ExplosionDecay);
+        assert_eq!(single.to_string(), "{\n  \"function\": \"minecraft:explosion_decay\"\n}");
+        assert_eq!(ItemModifier::new([LootFunction::from(LootFunctionKind::ExplosionDecay)]), single, "one function should be written as an object");
+        assert_eq!(ItemModifier::new([]).to_string(), "[]");
+    }
+
+    #[test]
+    fn reference_from_loot_table() {
+        let mut datapack = Datapack::new(PackMeta::new(48, "test"));
+        let mut namespace = datapack.namespace("test").expect("should have accepted the namespace");
+        let modifier = namespace
+            .add_item_modifier("enchanted", ItemModifier::new([LootFunction::from(LootFunctionKind::EnchantWithLevels {
+                levels: 30.0.into(),
+                treasure: true,
+            })]))
+            .expect("should have added the item modifier");
+        let function = LootFunction::reference(modifier);
+        assert_eq!(
+            serde_json::to_value(&function).expect("should have serialized the function"),
+            json!({"function": "minecraft:reference", "name": "test:enchanted"}),
+        );
+    }
+
+    #[test]
+    fn namespace_placement() {
+        for (pack_format, path) in [(26, "data/test/item_modifiers/rename.json"), (48, "data/test/item_modifier/rename.json")] {
+            let mut datapack = Datapack::new(PackMeta::new(pack_format, "test"));
+            let modifier = datapack
+                .namespace("test")
+                .expect("should have accepted the namespace")
+                .add_item_modifier("rename", LootFunction::set_name("Renamed"))
+                .expect("should have added the item modifier");
+            assert!(datapack.contains(path), "item modifier should have been placed at '{path}'");
+            let command = ItemCommand::modify(ItemTarget::Entity(Selector::new(SelectorBase::ExecutingEntity)), "weapon.mainhand", modifier);
+            assert_eq!(command.to_string(), "item modify entity @s weapon.mainhand test:rename");
+        }
+    }
+}
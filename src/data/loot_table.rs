@@ -0,0 +1,475 @@
+use serde::Serialize;
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+use serde_json::Value;
+use crate::data::conditions::LootCondition;
+use crate::data::loot_function::LootFunction;
+use crate::data::number_provider::NumberProvider;
+use crate::datapack::resource::{Reference, Resource};
+use crate::resource_location::ResourceLocation;
+use crate::utils::json::json_file;
+use crate::utils::weighted_list::{Weighted, WeightedList};
+
+/// What an entry adds to the loot, or which entries it chooses between.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[serde(tag = "type")]
+pub enum LootEntryKind {
+    #[serde(rename = "minecraft:item")]
+    Item { name: ResourceLocation },
+    /// With `expand`, each item of the tag is a separate entry; without, all of them drop.
+    #[serde(rename = "minecraft:tag")]
+    Tag { name: ResourceLocation, expand: bool },
+    #[serde(rename = "minecraft:loot_table")]
+    LootTable { name: ResourceLocation },
+    /// Every child whose conditions pass.
+    #[serde(rename = "minecraft:group")]
+    Group { children: Vec<LootEntry> },
+    /// The first child whose conditions pass.
+    #[serde(rename = "minecraft:alternatives")]
+    Alternatives { children: Vec<LootEntry> },
+    /// Children in order, until one's conditions fail.
+    #[serde(rename = "minecraft:sequence")]
+    Sequence { children: Vec<LootEntry> },
+    #[serde(rename = "minecraft:empty")]
+    Empty,
+    /// Block-specific drops, such as `contents` for shulker boxes.
+    #[serde(rename = "minecraft:dynamic")]
+    Dynamic { name: String },
+    /// Any other entry, written as is. It should include its own `type`.
+    #[serde(untagged)]
+    Raw(Value),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct LootEntry {
+    #[serde(flatten)]
+    pub kind: LootEntryKind,
+    /// Left out, the game picks it with a weight of 1.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weight: Option<u32>,
+    /// Added to `weight` for each level of luck.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quality: Option<i32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conditions: Vec<LootCondition>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub functions: Vec<LootFunction>,
+}
+
+impl LootEntry {
+    pub fn new(kind: LootEntryKind) -> Self {
+        Self { kind, weight: None, quality: None, conditions: Vec::new(), functions: Vec::new() }
+    }
+
+    pub fn item(name: ResourceLocation) -> Self {
+        Self::new(LootEntryKind::Item { name })
+    }
+
+    pub fn tag(name: ResourceLocation, expand: bool) -> Self {
+        Self::new(LootEntryKind::Tag { name, expand })
+    }
+
+    pub fn loot_table(name: ResourceLocation) -> Self {
+        Self::new(LootEntryKind::LootTable { name })
+    }
+
+    pub fn group(children: impl IntoIterator<Item = LootEntry>) -> Self {
+        Self::new(LootEntryKind::Group { children: children.into_iter().collect() })
+    }
+
+    pub fn alternatives(children: impl IntoIterator<Item = LootEntry>) -> Self {
+        Self::new(LootEntryKind::Alternatives { children: children.into_iter().collect() })
+    }
+
+    pub fn sequence(children: impl IntoIterator<Item = LootEntry>) -> Self {
+        Self::new(LootEntryKind::Sequence { children: children.into_iter().collect() })
+    }
+
+    pub fn empty() -> Self {
+        Self::new(LootEntryKind::Empty)
+    }
+
+    pub fn dynamic(name: impl Into<String>) -> Self {
+        Self::new(LootEntryKind::Dynamic { name: name.into() })
+    }
+
+    pub fn weight(mut self, weight: u32) -> Self {
+        self.weight = Some(weight);
+        self
+    }
+
+    pub fn quality(mut self, quality: i32) -> Self {
+        self.quality = Some(quality);
+        self
+    }
+
+    pub fn condition(mut self, condition: LootCondition) -> Self {
+        self.conditions.push(condition);
+        self
+    }
+
+    pub fn function(mut self, function: impl Into<LootFunction>) -> Self {
+        self.functions.push(function.into());
+        self
+    }
+
+    /// The loot tables, predicates and item modifiers this entry and its children refer to.
+    fn references(&self) -> Vec<Reference> {
+        let mut references = match &self.kind {
+            LootEntryKind::LootTable { name } => vec![Reference::to::<LootTable>(name.clone())],
+            LootEntryKind::Group { children } | LootEntryKind::Alternatives { children } | LootEntryKind::Sequence { children } => {
+                children.iter().flat_map(Self::references).collect()
+            }
+            _ => Vec::new(),
+        };
+        references.extend(self.conditions.iter().flat_map(LootCondition::references));
+        references.extend(self.functions.iter().flat_map(LootFunction::references));
+        references
+    }
+}
+
+impl Weighted for LootEntry {
+    fn weight(&self) -> u32 {
+        self.weight.unwrap_or(1)
+    }
+
+    fn set_weight(&mut self, weight: u32) {
+        self.weight = Some(weight);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct LootPool {
+    pub rolls: NumberProvider,
+    /// Extra rolls for each level of luck.
+    #[serde(default = "no_bonus_rolls")]
+    pub bonus_rolls: NumberProvider,
+    /// The entries one is picked from for each roll, by weight.
+    pub entries: WeightedList<LootEntry>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conditions: Vec<LootCondition>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub functions: Vec<LootFunction>,
+}
+
+#[cfg(feature = "serde")]
+fn no_bonus_rolls() -> NumberProvider {
+    NumberProvider::Constant(0.0)
+}
+
+impl LootPool {
+    pub fn new(rolls: impl Into<NumberProvider>) -> Self {
+        Self {
+            rolls: rolls.into(),
+            bonus_rolls: NumberProvider::Constant(0.0),
+            entries: WeightedList::new(),
+            conditions: Vec::new(),
+            functions: Vec::new(),
+        }
+    }
+
+    pub fn bonus_rolls(mut self, bonus_rolls: impl Into<NumberProvider>) -> Self {
+        self.bonus_rolls = bonus_rolls.into();
+        self
+    }
+
+    /// Adds `entry` with the weight it has.
+    pub fn entry(mut self, entry: LootEntry) -> Self {
+        self.entries.push_value(entry);
+        self
+    }
+
+    pub fn weighted_entry(mut self, entry: LootEntry, weight: u32) -> Self {
+        self.entries.push(entry, weight);
+        self
+    }
+
+    pub fn condition(mut self, condition: LootCondition) -> Self {
+        self.conditions.push(condition);
+        self
+    }
+
+    pub fn function(mut self, function: impl Into<LootFunction>) -> Self {
+        self.functions.push(function.into());
+        self
+    }
+}
+
+/// The context a loot table is rolled in, which decides what its conditions and
+/// functions can look at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub enum LootTableType {
+    #[serde(rename = "minecraft:empty")]
+    Empty,
+    #[serde(rename = "minecraft:chest")]
+    Chest,
+    #[serde(rename = "minecraft:command")]
+    Command,
+    #[serde(rename = "minecraft:selector")]
+    Selector,
+    #[serde(rename = "minecraft:fishing")]
+    Fishing,
+    #[serde(rename = "minecraft:entity")]
+    Entity,
+    #[serde(rename = "minecraft:archaeology")]
+    Archaeology,
+    #[serde(rename = "minecraft:gift")]
+    Gift,
+    #[serde(rename = "minecraft:barter")]
+    Barter,
+    #[serde(rename = "minecraft:advancement_reward")]
+    AdvancementReward,
+    #[serde(rename = "minecraft:generic")]
+    Generic,
+    #[serde(rename = "minecraft:block")]
+    Block,
+}
+
+/// A `data/<namespace>/loot_tables` file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct LootTable {
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub table_type: Option<LootTableType>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pools: Vec<LootPool>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub functions: Vec<LootFunction>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub random_sequence: Option<ResourceLocation>,
+}
+
+impl LootTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A chest's loot: one roll of a pool of `entries`, picked by weight. A `Vec` of
+    /// entries keeps the weight each has.
+    pub fn chest(entries: impl Into<WeightedList<LootEntry>>) -> Self {
+        Self::single_pool(LootTableType::Chest, entries.into())
+    }
+
+    /// An entity's drops: one roll of a pool of `entries`, picked by weight.
+    pub fn entity(entries: impl Into<WeightedList<LootEntry>>) -> Self {
+        Self::single_pool(LootTableType::Entity, entries.into())
+    }
+
+    /// A block that drops `item` when mined, unless destroyed by an explosion, like most
+    /// of the game's blocks.
+    pub fn block(item: ResourceLocation) -> Self {
+        let pool = LootPool::new(1.0).entry(LootEntry::item(item)).condition(LootCondition::SurvivesExplosion);
+        Self::new().table_type(LootTableType::Block).pool(pool)
+    }
+
+    fn single_pool(table_type: LootTableType, entries: WeightedList<LootEntry>) -> Self {
+        let pool = LootPool { entries, ..LootPool::new(1.0) };
+        Self::new().table_type(table_type).pool(pool)
+    }
+
+    pub fn table_type(mut self, table_type: LootTableType) -> Self {
+        self.table_type = Some(table_type);
+        self
+    }
+
+    pub fn pool(mut self, pool: LootPool) -> Self {
+        self.pools.push(pool);
+        self
+    }
+
+    pub fn function(mut self, function: impl Into<LootFunction>) -> Self {
+        self.functions.push(function.into());
+        self
+    }
+
+    pub fn random_sequence(mut self, random_sequence: ResourceLocation) -> Self {
+        self.random_sequence = Some(random_sequence);
+        self
+    }
+}
+
+json_file!(LootTable);
+
+impl Resource for LootTable {
+    const DIRECTORY: &'static str = "loot_tables";
+
+    /// Renamed to `loot_table` in pack format 45.
+    fn directory(pack_format: u32) -> &'static str {
+        if pack_format >= 45 { "loot_table" } else { Self::DIRECTORY }
+    }
+
+    fn references(&self) -> Vec<Reference> {
+        let pools = self.pools.iter().flat_map(|pool| {
+            let entries = pool.entries.values().flat_map(LootEntry::references);
+            let conditions = pool.conditions.iter().flat_map(LootCondition::references);
+            entries.chain(conditions).chain(pool.functions.iter().flat_map(LootFunction::references))
+        });
+        pools.chain(self.functions.iter().flat_map(LootFunction::references)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use crate::data::loot_function::LootFunctionKind;
+    use crate::datapack::Datapack;
+    use crate::datapack::pack_meta::PackMeta;
+
+    fn location(location: &str) -> ResourceLocation {
+        location.parse().expect("should have parsed the resource location")
+    }
+
+    #[test]
+    fn domain_constructors() {
+        let gems = vec![LootEntry::item(location("minecraft:diamond")).weight(1), LootEntry::item(location("minecraft:emerald")).weight(3)];
+        let chest = LootTable::chest(gems.clone());
+        assert_eq!(chest, LootTable::new().table_type(LootTableType::Chest).pool(LootPool::new(1.0).entry(gems[0].clone()).entry(gems[1].clone())));
+        let weighted = LootTable::chest(vec![(LootEntry::item(location("minecraft:diamond")), 1), (LootEntry::item(location("minecraft:emerald")), 3)]);
+        assert_eq!(weighted, chest);
+        assert_eq!(LootTable::entity(gems).table_type, Some(LootTableType::Entity));
+
+        let block = LootTable::block(location("test:ruby_ore")).random_sequence(location("test:blocks/ruby_ore"));
+        let json: Value = serde_json::from_str(&block.to_string()).expect("loot table should be valid JSON");
+        assert_eq!(json, json!({
+            "type": "minecraft:block",
+            "pools": [{
+                "bonus_rolls": 0.0,
+                "conditions": [{"condition": "minecraft:survives_explosion"}],
+                "entries": [{"type": "minecraft:item", "name": "test:ruby_ore"}],
+                "rolls": 1.0,
+            }],
+            "random_sequence": "test:blocks/ruby_ore",
+        }));
+    }
+
+    #[test]
+    fn vanilla_zombie() {
+        let table = LootTable::new()
+            .table_type(LootTableType::Entity)
+            .pool(LootPool::new(1.0).entry(
+                LootEntry::item(location("minecraft:rotten_flesh"))
+                    .function(LootFunction::set_count(NumberProvider::uniform(0.0, 2.0)))
+                    .function(LootFunction::looting_enchant(NumberProvider::uniform(0.0, 1.0))),
+            ))
+            .pool(
+                LootPool::new(1.0)
+                    .condition(LootCondition::KilledByPlayer)
+                    .condition(LootCondition::RandomChanceWithLooting { chance: 0.025, looting_multiplier: 0.01 })
+                    .entry(LootEntry::item(location("minecraft:iron_ingot")))
+                    .entry(LootEntry::item(location("minecraft:carrot")))
+                    .entry(LootEntry::item(location("minecraft:potato")).function(
+                        LootFunction::new(LootFunctionKind::FurnaceSmelt).condition(LootCondition::Raw(json!({
+                            "condition": "minecraft:entity_properties",
+                            "entity": "this",
+                            "predicate": {"flags": {"is_on_fire": true}},
+                        }))),
+                    )),
+            )
+            .random_sequence(location("minecraft:entities/zombie"));
+
+        let json: Value = serde_json::from_str(&table.to_string()).expect("loot table should be valid JSON");
+        let expected = json!({
+            "type": "minecraft:entity",
+            "pools": [
+                {
+                    "bonus_rolls": 0.0,
+                    "entries": [
+                        {
+                            "type": "minecraft:item",
+                            "functions": [
+                                {"add": false, "count": {"type": "minecraft:uniform", "max": 2.0, "min": 0.0}, "function": "minecraft:set_count"},
+                                {"count": {"type": "minecraft:uniform", "max": 1.0, "min": 0.0}, "function": "minecraft:looting_enchant"},
+                            ],
+                            "name": "minecraft:rotten_flesh",
+                        },
+                    ],
+                    "rolls": 1.0,
+                },
+                {
+                    "bonus_rolls": 0.0,
+                    "conditions": [
+                        {"condition": "minecraft:killed_by_player"},
+                        {"chance": 0.025, "condition": "minecraft:random_chance_with_looting", "looting_multiplier": 0.01},
+                    ],
+                    "entries": [
+                        {"type": "minecraft:item", "name": "minecraft:iron_ingot"},
+                        {"type": "minecraft:item", "name": "minecraft:carrot"},
+                        {
+                            "type": "minecraft:item",
+                            "functions": [
+                                {
+                                    "conditions": [
+                                        {"condition": "minecraft:entity_properties", "entity": "this", "predicate": {"flags": {"is_on_fire": true}}},
+                                    ],
+                                    "function": "minecraft:furnace_smelt",
+                                },
+                            ],
+                            "name": "minecraft:potato",
+                        },
+                    ],
+                    "rolls": 1.0,
+                },
+            ],
+            "random_sequence": "minecraft:entities/zombie",
+        });
+        assert_eq!(json, expected, "loot table was not rendered correctly");
+    }
+
+    #[test]
+    fn composite_entries() {
+        let entry = LootEntry::alternatives([
+            LootEntry::tag(location("minecraft:music_discs"), true).weight(3).quality(-1),
+            LootEntry::loot_table(location("test:chests/common")),
+            LootEntry::empty().weight(10),
+        ])
+        .condition(LootCondition::inverted(LootCondition::SurvivesExplosion));
+        let json = serde_json::to_value(entry).expect("should have serialized the entry");
+        assert_eq!(json, json!({
+            "type": "minecraft:alternatives",
+            "children": [
+                {"type": "minecraft:tag", "name": "minecraft:music_discs", "expand": true, "weight": 3, "quality": -1},
+                {"type": "minecraft:loot_table", "name": "test:chests/common"},
+                {"type": "minecraft:empty", "weight": 10},
+            ],
+            "conditions": [{"condition": "minecraft:inverted", "term": {"condition": "minecraft:survives_explosion"}}],
+        }));
+    }
+
+    #[test]
+    fn namespace_placement() {
+        for (pack_format, path) in [(15, "data/test/loot_tables/blocks/ruby.json"), (48, "data/test/loot_table/blocks/ruby.json")] {
+            let mut datapack = Datapack::new(PackMeta::new(pack_format, "test"));
+            let location = datapack
+                .namespace("test")
+                .expect("should have accepted the namespace")
+                .add_loot_table("blocks/ruby", LootTable::new().table_type(LootTableType::Block))
+                .expect("should have added the loot table");
+            assert_eq!(location, self::location("test:blocks/ruby"));
+            assert!(datapack.contains(path), "loot table should have been placed at '{path}'");
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn read_back() {
+        let table = LootTable::new()
+            .table_type(LootTableType::Chest)
+            .pool(
+                LootPool::new(NumberProvider::uniform(1.0, 3.0))
+                    .entry(LootEntry::item(location("minecraft:diamond")).weight(1).function(LootFunction::set_count(2.0)))
+                    .weighted_entry(LootEntry::tag(location("minecraft:logs"), true).condition(LootCondition::RandomChance { chance: 0.5 }), 5)
+                    .entry(LootEntry::alternatives([LootEntry::empty(), LootEntry::loot_table(location("test:chests/common"))])),
+            );
+        let read: LootTable = serde_json::from_str(&table.to_string()).expect("should have read the loot table back");
+        assert_eq!(read, table, "loot table did not survive a round trip");
+
+        let read: LootTable = serde_json::from_str(r#"{"pools": [{"rolls": 1, "entries": [{"type": "minecraft:empty"}]}]}"#)
+            .expect("should have read a loot table without bonus rolls");
+        assert_eq!(read.pools[0].bonus_rolls, NumberProvider::Constant(0.0));
+    }
+}
@@ -0,0 +1,55 @@
+use serde::Serialize;
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+use serde_json::Value;
+
+/// A number that loot tables and predicates pick when they're evaluated.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[serde(tag = "type")]
+pub enum NumberProvider {
+    #[serde(rename = "minecraft:uniform")]
+    Uniform { min: Box<NumberProvider>, max: Box<NumberProvider> },
+    /// The number of successes out of `n` tries with probability `p` each.
+    #[serde(rename = "minecraft:binomial")]
+    Binomial { n: Box<NumberProvider>, p: Box<NumberProvider> },
+    /// Written as a bare number.
+    #[serde(untagged)]
+    Constant(f32),
+    /// Any other provider, written as is. It should include its own `type`.
+    #[serde(untagged)]
+    Raw(Value),
+}
+
+impl NumberProvider {
+    pub fn uniform(min: impl Into<NumberProvider>, max: impl Into<NumberProvider>) -> Self {
+        Self::Uniform { min: Box::new(min.into()), max: Box::new(max.into()) }
+    }
+
+    pub fn binomial(n: impl Into<NumberProvider>, p: impl Into<NumberProvider>) -> Self {
+        Self::Binomial { n: Box::new(n.into()), p: Box::new(p.into()) }
+    }
+}
+
+impl From<f32> for NumberProvider {
+    fn from(value: f32) -> Self {
+        Self::Constant(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn number_provider_json() {
+        let json = |provider: NumberProvider| -> Value {
+            serde_json::from_str(&serde_json::to_string(&provider).expect("should have serialized the provider"))
+                .expect("provider should be valid JSON")
+        };
+        assert_eq!(json(2.0.into()), json!(2.0));
+        assert_eq!(json(NumberProvider::uniform(0.0, 2.5)), json!({"type": "minecraft:uniform", "min": 0.0, "max": 2.5}));
+        assert_eq!(json(NumberProvider::binomial(3.0, 0.5)), json!({"type": "minecraft:binomial", "n": 3.0, "p": 0.5}));
+    }
+}
@@ -0,0 +1,98 @@
+use serde::Serialize;
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+use crate::datapack::resource::Resource;
+use crate::error::DatapackError;
+use crate::resource_location::ResourceLocation;
+use crate::text::TextComponent;
+use crate::utils::json::json_file;
+
+/// A `data/<namespace>/painting_variant` file, available since 1.21: a painting that can
+/// be picked in the creative inventory, or placed at random if it is in the
+/// `minecraft:placeable` tag.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct PaintingVariant {
+    /// The texture, such as `minecraft:kebab` for `textures/painting/kebab.png`.
+    pub asset_id: ResourceLocation,
+    /// In blocks, from 1 to 16.
+    pub width: u32,
+    /// In blocks, from 1 to 16.
+    pub height: u32,
+    /// Shown in the painting's tooltip since 1.21.2.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<TextComponent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<TextComponent>,
+}
+
+impl PaintingVariant {
+    pub fn new(asset_id: ResourceLocation, width: u32, height: u32) -> Self {
+        Self { asset_id, width, height, title: None, author: None }
+    }
+
+    pub fn title(mut self, title: impl Into<TextComponent>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn author(mut self, author: impl Into<TextComponent>) -> Self {
+        self.author = Some(author.into());
+        self
+    }
+}
+
+json_file!(PaintingVariant);
+
+impl Resource for PaintingVariant {
+    const DIRECTORY: &'static str = "painting_variant";
+    const MIN_PACK_FORMAT: u32 = 48;
+
+    fn validate(&self) -> Result<(), DatapackError> {
+        if !(1..=16).contains(&self.width) || !(1..=16).contains(&self.height) {
+            return Err(DatapackError::Validation(format!(
+                "invalid painting variant: the size {}x{} must be from 1 to 16 blocks",
+                self.width, self.height,
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+    use crate::datapack::Datapack;
+    use crate::datapack::pack_meta::PackMeta;
+
+    fn location(location: &str) -> ResourceLocation {
+        location.parse().expect("should have parsed the resource location")
+    }
+
+    /// `minecraft:kebab` from the game's data.
+    #[test]
+    fn vanilla_kebab() {
+        let kebab = PaintingVariant::new(location("minecraft:kebab"), 1, 1);
+        let json: Value = serde_json::from_str(&kebab.to_string()).expect("painting variant should be valid JSON");
+        assert_eq!(json, json!({"asset_id": "minecraft:kebab", "height": 1, "width": 1}));
+        assert!(kebab.validate().is_ok());
+
+        assert!(PaintingVariant::new(location("test:mural"), 17, 4).validate().is_err(), "paintings are at most 16 blocks wide");
+        assert!(PaintingVariant::new(location("test:mural"), 4, 0).validate().is_err(), "paintings are at least 1 block high");
+    }
+
+    #[test]
+    fn placeable() {
+        let mut datapack = Datapack::new(PackMeta::new(41, "test"));
+        let mut namespace = datapack.namespace("test").expect("should have accepted the namespace");
+        assert!(namespace.add_painting_variant("mural", PaintingVariant::new(location("test:mural"), 4, 2)).is_err(), "painting variants need 1.21");
+
+        let mut datapack = Datapack::new(PackMeta::new(48, "test"));
+        let mut namespace = datapack.namespace("test").expect("should have accepted the namespace");
+        namespace.add_painting_variant_placeable("mural", PaintingVariant::new(location("test:mural"), 4, 2)).expect("should have added the variant");
+        assert!(datapack.contains("data/test/painting_variant/mural.json"));
+        assert!(datapack.contains("data/minecraft/tags/painting_variant/placeable.json"));
+        assert!(datapack.validate_references().is_empty(), "the tag's variant exists");
+    }
+}
@@ -0,0 +1,404 @@
+use std::collections::BTreeMap;
+use serde::Serialize;
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+use serde_json::Value;
+use crate::command::selector::GameMode;
+use crate::nbt::NbtCompound;
+use crate::range::{IntRange, MinecraftRange};
+use crate::resource_location::ResourceLocation;
+
+/// Matches an enchantment on an item. Unset fields match any enchantment.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct EnchantmentPredicate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enchantment: Option<ResourceLocation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub levels: Option<IntRange>,
+}
+
+impl EnchantmentPredicate {
+    pub fn new(enchantment: ResourceLocation) -> Self {
+        Self { enchantment: Some(enchantment), levels: None }
+    }
+
+    pub fn levels(mut self, levels: impl Into<IntRange>) -> Self {
+        self.levels = Some(levels.into());
+        self
+    }
+}
+
+/// Matches an item stack. Unset fields match anything.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct ItemPredicate {
+    /// Any of these items. The game replaced the single `item` of older versions with
+    /// this list in 1.17, so [`ItemPredicate::item`] adds to it instead of setting one.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub items: Vec<ResourceLocation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<ResourceLocation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<IntRange>,
+    /// The durability the item has left, before item components.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub durability: Option<IntRange>,
+    /// The potion of a potion, splash potion or tipped arrow, before item components.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub potion: Option<ResourceLocation>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub enchantments: Vec<EnchantmentPredicate>,
+    /// What the item's tag must contain, before item components. Written as SNBT.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nbt: Option<NbtCompound>,
+    /// Components the item must have with exactly these values, from pack format 41.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub components: BTreeMap<ResourceLocation, Value>,
+}
+
+impl ItemPredicate {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn item(mut self, item: ResourceLocation) -> Self {
+        self.items.push(item);
+        self
+    }
+
+    pub fn tag(mut self, tag: ResourceLocation) -> Self {
+        self.tag = Some(tag);
+        self
+    }
+
+    pub fn count(mut self, count: impl Into<IntRange>) -> Self {
+        self.count = Some(count.into());
+        self
+    }
+
+    pub fn durability(mut self, durability: impl Into<IntRange>) -> Self {
+        self.durability = Some(durability.into());
+        self
+    }
+
+    pub fn potion(mut self, potion: ResourceLocation) -> Self {
+        self.potion = Some(potion);
+        self
+    }
+
+    pub fn enchantment(mut self, enchantment: EnchantmentPredicate) -> Self {
+        self.enchantments.push(enchantment);
+        self
+    }
+
+    pub fn nbt(mut self, nbt: NbtCompound) -> Self {
+        self.nbt = Some(nbt);
+        self
+    }
+
+    pub fn component(mut self, component: ResourceLocation, value: impl Into<Value>) -> Self {
+        self.components.insert(component, value.into());
+        self
+    }
+}
+
+/// Matches a position. Unset axes match any coordinate.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct PositionPredicate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x: Option<MinecraftRange<f64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub y: Option<MinecraftRange<f64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub z: Option<MinecraftRange<f64>>,
+}
+
+/// Matches how far an entity is from where the predicate is checked, such as the player
+/// of an advancement trigger. Unset fields match any distance.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct DistancePredicate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub absolute: Option<MinecraftRange<f64>>,
+    /// Ignoring the height difference.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub horizontal: Option<MinecraftRange<f64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x: Option<MinecraftRange<f64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub y: Option<MinecraftRange<f64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub z: Option<MinecraftRange<f64>>,
+}
+
+impl DistancePredicate {
+    pub fn absolute(absolute: impl Into<MinecraftRange<f64>>) -> Self {
+        Self { absolute: Some(absolute.into()), ..Self::default() }
+    }
+
+    pub fn horizontal(horizontal: impl Into<MinecraftRange<f64>>) -> Self {
+        Self { horizontal: Some(horizontal.into()), ..Self::default() }
+    }
+}
+
+/// Matches an effect an entity has. Unset fields match any instance of the effect.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct EffectPredicate {
+    /// The level of the effect minus 1.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amplifier: Option<IntRange>,
+    /// The ticks left.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<IntRange>,
+    /// Whether it is from a beacon or conduit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ambient: Option<bool>,
+    /// Whether it shows particles.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visible: Option<bool>,
+}
+
+impl EffectPredicate {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn amplifier(mut self, amplifier: impl Into<IntRange>) -> Self {
+        self.amplifier = Some(amplifier.into());
+        self
+    }
+
+    pub fn duration(mut self, duration: impl Into<IntRange>) -> Self {
+        self.duration = Some(duration.into());
+        self
+    }
+}
+
+/// Matches where something is. Unset fields match anywhere.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct LocationPredicate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub biome: Option<ResourceLocation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub structure: Option<ResourceLocation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dimension: Option<ResourceLocation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<PositionPredicate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub can_see_sky: Option<bool>,
+    /// Whether the location is close above a campfire.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub smokey: Option<bool>,
+}
+
+impl LocationPredicate {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn biome(mut self, biome: ResourceLocation) -> Self {
+        self.biome = Some(biome);
+        self
+    }
+
+    pub fn structure(mut self, structure: ResourceLocation) -> Self {
+        self.structure = Some(structure);
+        self
+    }
+
+    pub fn dimension(mut self, dimension: ResourceLocation) -> Self {
+        self.dimension = Some(dimension);
+        self
+    }
+
+    pub fn y(mut self, y: impl Into<MinecraftRange<f64>>) -> Self {
+        self.position.get_or_insert_with(PositionPredicate::default).y = Some(y.into());
+        self
+    }
+
+    pub fn can_see_sky(mut self, can_see_sky: bool) -> Self {
+        self.can_see_sky = Some(can_see_sky);
+        self
+    }
+}
+
+/// Matches the state of an entity. Unset flags match either way.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct EntityFlags {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_on_fire: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_sneaking: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_sprinting: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_swimming: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_baby: Option<bool>,
+}
+
+/// Matches what an entity wears and holds. Unset slots match anything.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct EntityEquipment {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub head: Option<ItemPredicate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chest: Option<ItemPredicate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub legs: Option<ItemPredicate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub feet: Option<ItemPredicate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mainhand: Option<ItemPredicate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offhand: Option<ItemPredicate>,
+}
+
+/// Matches player-specific state. Unset fields match any player.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct PlayerPredicate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gamemode: Option<GameMode>,
+    /// The experience level.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub level: Option<IntRange>,
+    /// Advancements the player must have done, or not done when `false`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub advancements: BTreeMap<ResourceLocation, bool>,
+    /// Recipes the player must have unlocked, or not unlocked when `false`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub recipes: BTreeMap<ResourceLocation, bool>,
+    /// The entity the player is looking at.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub looking_at: Option<Box<EntityPredicate>>,
+}
+
+impl PlayerPredicate {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn gamemode(mut self, gamemode: GameMode) -> Self {
+        self.gamemode = Some(gamemode);
+        self
+    }
+
+    pub fn level(mut self, level: impl Into<IntRange>) -> Self {
+        self.level = Some(level.into());
+        self
+    }
+
+    pub fn advancement(mut self, advancement: ResourceLocation, done: bool) -> Self {
+        self.advancements.insert(advancement, done);
+        self
+    }
+
+    pub fn recipe(mut self, recipe: ResourceLocation, unlocked: bool) -> Self {
+        self.recipes.insert(recipe, unlocked);
+        self
+    }
+}
+
+/// Matches an entity. Unset fields match any entity.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct EntityPredicate {
+    /// An entity type, or an entity type tag when written with a leading `#`.
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub entity_type: Option<String>,
+    /// SNBT the entity's data must contain.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nbt: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<Box<LocationPredicate>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distance: Option<Box<DistancePredicate>>,
+    /// The effects the entity must have, by their ID.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub effects: BTreeMap<ResourceLocation, EffectPredicate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flags: Option<EntityFlags>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub equipment: Option<Box<EntityEquipment>>,
+    /// The entity being ridden.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vehicle: Option<Box<EntityPredicate>>,
+    /// An entity riding this one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub passenger: Option<Box<EntityPredicate>>,
+    /// The entity a mob is attacking.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub targeted_entity: Option<Box<EntityPredicate>>,
+    /// Only matches players.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub player: Option<Box<PlayerPredicate>>,
+}
+
+impl EntityPredicate {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn entity_type(mut self, entity_type: impl Into<String>) -> Self {
+        self.entity_type = Some(entity_type.into());
+        self
+    }
+
+    pub fn nbt(mut self, nbt: impl ToString) -> Self {
+        self.nbt = Some(nbt.to_string());
+        self
+    }
+
+    pub fn location(mut self, location: LocationPredicate) -> Self {
+        self.location = Some(Box::new(location));
+        self
+    }
+
+    pub fn distance(mut self, distance: DistancePredicate) -> Self {
+        self.distance = Some(Box::new(distance));
+        self
+    }
+
+    pub fn effect(mut self, effect: ResourceLocation, predicate: EffectPredicate) -> Self {
+        self.effects.insert(effect, predicate);
+        self
+    }
+
+    pub fn flags(mut self, flags: EntityFlags) -> Self {
+        self.flags = Some(flags);
+        self
+    }
+
+    pub fn equipment(mut self, equipment: EntityEquipment) -> Self {
+        self.equipment = Some(Box::new(equipment));
+        self
+    }
+
+    pub fn vehicle(mut self, vehicle: EntityPredicate) -> Self {
+        self.vehicle = Some(Box::new(vehicle));
+        self
+    }
+
+    pub fn passenger(mut self, passenger: EntityPredicate) -> Self {
+        self.passenger = Some(Box::new(passenger));
+        self
+    }
+
+    pub fn targeted_entity(mut self, targeted_entity: EntityPredicate) -> Self {
+        self.targeted_entity = Some(Box::new(targeted_entity));
+        self
+    }
+
+    pub fn player(mut self, player: PlayerPredicate) -> Self {
+        self.player = Some(Box::new(player));
+        self
+    }
+}
@@ -0,0 +1,665 @@
+use std::collections::{BTreeMap, BTreeSet};
+use serde::ser::SerializeMap;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer};
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+use crate::data::ingredient::Ingredient;
+use crate::datapack::pack_format::ITEM_COMPONENTS_PACK_FORMAT;
+use crate::datapack::resource::Resource;
+use crate::error::DatapackError;
+use crate::resource_location::ResourceLocation;
+use crate::utils::json::json_file;
+
+/// The item stack a recipe makes.
+///
+/// Rendered as `{"id", "count", "components"}`, or as `{"item", "count"}` for pack formats
+/// before item components, which [`Datapack::add`](crate::datapack::Datapack::add) selects.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecipeResult {
+    pub item: ResourceLocation,
+    pub count: u32,
+    pub components: BTreeMap<ResourceLocation, Value>,
+    legacy: bool,
+}
+
+impl RecipeResult {
+    pub fn new(item: ResourceLocation, count: u32) -> Self {
+        Self { item, count, components: BTreeMap::new(), legacy: false }
+    }
+
+    pub fn component(mut self, component: ResourceLocation, value: impl Into<Value>) -> Self {
+        self.components.insert(component, value.into());
+        self
+    }
+
+    fn validate(&self) -> Result<(), DatapackError> {
+        if !(1..=99).contains(&self.count) {
+            return Err(DatapackError::Validation(format!("recipe result count {} is not between 1 and 99", self.count)));
+        }
+        if self.legacy && !self.components.is_empty() {
+            return Err(DatapackError::Validation(format!(
+                "recipe result components need pack format {ITEM_COMPONENTS_PACK_FORMAT}",
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl From<ResourceLocation> for RecipeResult {
+    fn from(item: ResourceLocation) -> Self {
+        Self::new(item, 1)
+    }
+}
+
+impl Serialize for RecipeResult {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry(if self.legacy { "item" } else { "id" }, &self.item)?;
+        if self.count != 1 {
+            map.serialize_entry("count", &self.count)?;
+        }
+        if !self.components.is_empty() {
+            map.serialize_entry("components", &self.components)?;
+        }
+        map.end()
+    }
+}
+
+/// Reads either rendering, or a bare item ID as older cooking recipes write it. A result
+/// read from the older rendering stays in it.
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for RecipeResult {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Id(ResourceLocation),
+            Stack {
+                id: ResourceLocation,
+                #[serde(default = "default_count")]
+                count: u32,
+                #[serde(default)]
+                components: BTreeMap<ResourceLocation, Value>,
+            },
+            Legacy {
+                item: ResourceLocation,
+                #[serde(default = "default_count")]
+                count: u32,
+            },
+        }
+
+        fn default_count() -> u32 {
+            1
+        }
+
+        Ok(match Repr::deserialize(deserializer)? {
+            Repr::Id(item) => Self { legacy: true, ..Self::from(item) },
+            Repr::Stack { id, count, components } => Self { components, ..Self::new(id, count) },
+            Repr::Legacy { item, count } => Self { legacy: true, ..Self::new(item, count) },
+        })
+    }
+}
+
+/// Serializes a result that older pack formats write as a bare item ID.
+fn serialize_item_id<S: Serializer>(result: &RecipeResult, serializer: S) -> Result<S::Ok, S::Error> {
+    if result.legacy {
+        result.item.serialize(serializer)
+    } else {
+        result.serialize(serializer)
+    }
+}
+
+/// The recipe book tab of a crafting or stonecutting recipe.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[serde(rename_all = "snake_case")]
+pub enum CraftingCategory {
+    Building,
+    Redstone,
+    Equipment,
+    #[default]
+    Misc,
+}
+
+/// The recipe book tab of a cooking recipe.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[serde(rename_all = "snake_case")]
+pub enum CookingCategory {
+    Food,
+    Blocks,
+    #[default]
+    Misc,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct ShapedRecipe {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    #[serde(default)]
+    pub category: CraftingCategory,
+    /// Up to three rows of up to three characters, where a space is an empty slot.
+    pub pattern: Vec<String>,
+    pub key: BTreeMap<char, Ingredient>,
+    pub result: RecipeResult,
+}
+
+impl ShapedRecipe {
+    pub fn new<S: Into<String>>(pattern: impl IntoIterator<Item = S>, result: impl Into<RecipeResult>) -> Self {
+        Self {
+            group: None,
+            category: CraftingCategory::default(),
+            pattern: pattern.into_iter().map(Into::into).collect(),
+            key: BTreeMap::new(),
+            result: result.into(),
+        }
+    }
+
+    pub fn key(mut self, symbol: char, ingredient: impl Into<Ingredient>) -> Self {
+        self.key.insert(symbol, ingredient.into());
+        self
+    }
+
+    pub fn group(mut self, group: impl Into<String>) -> Self {
+        self.group = Some(group.into());
+        self
+    }
+
+    pub fn category(mut self, category: CraftingCategory) -> Self {
+        self.category = category;
+        self
+    }
+
+    fn validate(&self) -> Result<(), DatapackError> {
+        let invalid = |message: String| Err(DatapackError::Validation(format!("invalid shaped recipe: {message}")));
+        let width = self.pattern.first().map_or(0, |row| row.chars().count());
+        if !(1..=3).contains(&self.pattern.len()) || !(1..=3).contains(&width) {
+            return invalid("the pattern must have 1 to 3 rows of 1 to 3 symbols".to_string());
+        }
+        if self.pattern.iter().any(|row| row.chars().count() != width) {
+            return invalid("the pattern rows must have the same length".to_string());
+        }
+        if self.key.contains_key(&' ') {
+            return invalid("' ' is reserved for empty slots".to_string());
+        }
+        let used: BTreeSet<char> = self.pattern.iter().flat_map(|row| row.chars()).filter(|symbol| *symbol != ' ').collect();
+        if let Some(symbol) = used.iter().find(|symbol| !self.key.contains_key(symbol)) {
+            return invalid(format!("'{symbol}' is in the pattern but not in the key"));
+        }
+        if let Some(symbol) = self.key.keys().find(|symbol| !used.contains(symbol)) {
+            return invalid(format!("'{symbol}' is in the key but not in the pattern"));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct ShapelessRecipe {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    #[serde(default)]
+    pub category: CraftingCategory,
+    pub ingredients: Vec<Ingredient>,
+    pub result: RecipeResult,
+}
+
+impl ShapelessRecipe {
+    pub fn new(result: impl Into<RecipeResult>) -> Self {
+        Self { group: None, category: CraftingCategory::default(), ingredients: Vec::new(), result: result.into() }
+    }
+
+    pub fn ingredient(mut self, ingredient: impl Into<Ingredient>) -> Self {
+        self.ingredients.push(ingredient.into());
+        self
+    }
+
+    pub fn group(mut self, group: impl Into<String>) -> Self {
+        self.group = Some(group.into());
+        self
+    }
+
+    pub fn category(mut self, category: CraftingCategory) -> Self {
+        self.category = category;
+        self
+    }
+}
+
+/// A smelting, blasting, smoking or campfire cooking recipe, depending on the
+/// [`Recipe`] variant it's in.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct CookingRecipe {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    #[serde(default)]
+    pub category: CookingCategory,
+    pub ingredient: Ingredient,
+    #[serde(serialize_with = "serialize_item_id")]
+    pub result: RecipeResult,
+    pub experience: f32,
+    /// In ticks. Left out, the game uses 200 for smelting and 100 for the others.
+    #[serde(rename = "cookingtime", skip_serializing_if = "Option::is_none")]
+    pub cooking_time: Option<u32>,
+}
+
+impl CookingRecipe {
+    pub fn new(ingredient: impl Into<Ingredient>, result: impl Into<RecipeResult>, experience: f32) -> Self {
+        Self {
+            group: None,
+            category: CookingCategory::default(),
+            ingredient: ingredient.into(),
+            result: result.into(),
+            experience,
+            cooking_time: None,
+        }
+    }
+
+    pub fn cooking_time(mut self, cooking_time: u32) -> Self {
+        self.cooking_time = Some(cooking_time);
+        self
+    }
+
+    pub fn group(mut self, group: impl Into<String>) -> Self {
+        self.group = Some(group.into());
+        self
+    }
+
+    pub fn category(mut self, category: CookingCategory) -> Self {
+        self.category = category;
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "StonecuttingRecipeRepr"))]
+pub struct StonecuttingRecipe {
+    pub group: Option<String>,
+    pub ingredient: Ingredient,
+    pub result: RecipeResult,
+}
+
+impl StonecuttingRecipe {
+    pub fn new(ingredient: impl Into<Ingredient>, result: impl Into<RecipeResult>) -> Self {
+        Self { group: None, ingredient: ingredient.into(), result: result.into() }
+    }
+
+    pub fn group(mut self, group: impl Into<String>) -> Self {
+        self.group = Some(group.into());
+        self
+    }
+}
+
+/// Older pack formats write the count next to the result ID instead of inside it.
+impl Serialize for StonecuttingRecipe {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        if let Some(group) = &self.group {
+            map.serialize_entry("group", group)?;
+        }
+        map.serialize_entry("ingredient", &self.ingredient)?;
+        if self.result.legacy {
+            map.serialize_entry("result", &self.result.item)?;
+            map.serialize_entry("count", &self.result.count)?;
+        } else {
+            map.serialize_entry("result", &self.result)?;
+        }
+        map.end()
+    }
+}
+
+/// A stonecutting recipe as read, with the count next to the result in older pack formats.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct StonecuttingRecipeRepr {
+    group: Option<String>,
+    ingredient: Ingredient,
+    result: RecipeResult,
+    count: Option<u32>,
+}
+
+#[cfg(feature = "serde")]
+impl From<StonecuttingRecipeRepr> for StonecuttingRecipe {
+    fn from(repr: StonecuttingRecipeRepr) -> Self {
+        let mut result = repr.result;
+        if let Some(count) = repr.count {
+            result.count = count;
+        }
+        Self { group: repr.group, ingredient: repr.ingredient, result }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct SmithingTransformRecipe {
+    pub template: Ingredient,
+    pub base: Ingredient,
+    pub addition: Ingredient,
+    pub result: RecipeResult,
+}
+
+impl SmithingTransformRecipe {
+    pub fn new(
+        template: impl Into<Ingredient>,
+        base: impl Into<Ingredient>,
+        addition: impl Into<Ingredient>,
+        result: impl Into<RecipeResult>,
+    ) -> Self {
+        Self { template: template.into(), base: base.into(), addition: addition.into(), result: result.into() }
+    }
+}
+
+/// Applies the armor trim of `template` to `base`, in the material of `addition`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct SmithingTrimRecipe {
+    pub template: Ingredient,
+    pub base: Ingredient,
+    pub addition: Ingredient,
+}
+
+impl SmithingTrimRecipe {
+    pub fn new(template: impl Into<Ingredient>, base: impl Into<Ingredient>, addition: impl Into<Ingredient>) -> Self {
+        Self { template: template.into(), base: base.into(), addition: addition.into() }
+    }
+}
+
+/// A `data/<namespace>/recipes` file.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[serde(tag = "type")]
+pub enum Recipe {
+    #[serde(rename = "minecraft:crafting_shaped")]
+    Shaped(ShapedRecipe),
+    #[serde(rename = "minecraft:crafting_shapeless")]
+    Shapeless(ShapelessRecipe),
+    #[serde(rename = "minecraft:smelting")]
+    Smelting(CookingRecipe),
+    #[serde(rename = "minecraft:blasting")]
+    Blasting(CookingRecipe),
+    #[serde(rename = "minecraft:smoking")]
+    Smoking(CookingRecipe),
+    #[serde(rename = "minecraft:campfire_cooking")]
+    CampfireCooking(CookingRecipe),
+    #[serde(rename = "minecraft:stonecutting")]
+    Stonecutting(StonecuttingRecipe),
+    #[serde(rename = "minecraft:smithing_transform")]
+    SmithingTransform(SmithingTransformRecipe),
+    #[serde(rename = "minecraft:smithing_trim")]
+    SmithingTrim(SmithingTrimRecipe),
+}
+
+impl Recipe {
+    fn result_mut(&mut self) -> Option<&mut RecipeResult> {
+        match self {
+            Self::Shaped(recipe) => Some(&mut recipe.result),
+            Self::Shapeless(recipe) => Some(&mut recipe.result),
+            Self::Smelting(recipe) | Self::Blasting(recipe) | Self::Smoking(recipe) | Self::CampfireCooking(recipe) => {
+                Some(&mut recipe.result)
+            }
+            Self::Stonecutting(recipe) => Some(&mut recipe.result),
+            Self::SmithingTransform(recipe) => Some(&mut recipe.result),
+            Self::SmithingTrim(_) => None,
+        }
+    }
+
+    fn result(&self) -> Option<&RecipeResult> {
+        match self {
+            Self::Shaped(recipe) => Some(&recipe.result),
+            Self::Shapeless(recipe) => Some(&recipe.result),
+            Self::Smelting(recipe) | Self::Blasting(recipe) | Self::Smoking(recipe) | Self::CampfireCooking(recipe) => {
+                Some(&recipe.result)
+            }
+            Self::Stonecutting(recipe) => Some(&recipe.result),
+            Self::SmithingTransform(recipe) => Some(&recipe.result),
+            Self::SmithingTrim(_) => None,
+        }
+    }
+}
+
+impl From<ShapedRecipe> for Recipe {
+    fn from(recipe: ShapedRecipe) -> Self {
+        Self::Shaped(recipe)
+    }
+}
+
+impl From<ShapelessRecipe> for Recipe {
+    fn from(recipe: ShapelessRecipe) -> Self {
+        Self::Shapeless(recipe)
+    }
+}
+
+impl From<StonecuttingRecipe> for Recipe {
+    fn from(recipe: StonecuttingRecipe) -> Self {
+        Self::Stonecutting(recipe)
+    }
+}
+
+impl From<SmithingTransformRecipe> for Recipe {
+    fn from(recipe: SmithingTransformRecipe) -> Self {
+        Self::SmithingTransform(recipe)
+    }
+}
+
+impl From<SmithingTrimRecipe> for Recipe {
+    fn from(recipe: SmithingTrimRecipe) -> Self {
+        Self::SmithingTrim(recipe)
+    }
+}
+
+json_file!(Recipe);
+
+impl Resource for Recipe {
+    const DIRECTORY: &'static str = "recipes";
+
+    /// Renamed to `recipe` in pack format 45.
+    fn directory(pack_format: u32) -> &'static str {
+        if pack_format >= 45 { "recipe" } else { Self::DIRECTORY }
+    }
+
+    fn set_pack_format(&mut self, pack_format: u32) {
+        if let Some(result) = self.result_mut() {
+            result.legacy = pack_format < ITEM_COMPONENTS_PACK_FORMAT;
+        }
+    }
+
+    /// Checks the shape of shaped recipes, that shapeless recipes have 1 to 9 ingredients,
+    /// and that results have a valid count and only use components where supported.
+    fn validate(&self) -> Result<(), DatapackError> {
+        match self {
+            Self::Shaped(recipe) => recipe.validate()?,
+            Self::Shapeless(recipe) if !(1..=9).contains(&recipe.ingredients.len()) => {
+                return Err(DatapackError::Validation("a shapeless recipe needs 1 to 9 ingredients".to_string()));
+            }
+            _ => {}
+        }
+        self.result().map_or(Ok(()), RecipeResult::validate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use crate::datapack::Datapack;
+    use crate::datapack::pack_meta::PackMeta;
+
+    fn location(location: &str) -> ResourceLocation {
+        location.parse().expect("should have parsed the resource location")
+    }
+
+    fn json(recipe: &Recipe) -> Value {
+        serde_json::from_str(&recipe.to_string()).expect("recipe should be valid JSON")
+    }
+
+    fn legacy(mut recipe: Recipe) -> Recipe {
+        recipe.set_pack_format(15);
+        recipe
+    }
+
+    #[test]
+    fn shaped() {
+        let recipe = Recipe::from(
+            ShapedRecipe::new(["RRR", " S ", " S "], RecipeResult::new(location("test:ruby_pickaxe"), 1))
+                .key('R', location("test:ruby"))
+                .key('S', Ingredient::Tag(location("minecraft:planks")))
+                .category(CraftingCategory::Equipment),
+        );
+        assert_eq!(json(&recipe), json!({
+            "type": "minecraft:crafting_shaped",
+            "category": "equipment",
+            "pattern": ["RRR", " S ", " S "],
+            "key": {"R": {"item": "test:ruby"}, "S": {"tag": "minecraft:planks"}},
+            "result": {"id": "test:ruby_pickaxe"},
+        }));
+        assert_eq!(json(&legacy(recipe))["result"], json!({"item": "test:ruby_pickaxe"}));
+    }
+
+    #[test]
+    fn shapeless() {
+        let recipe = Recipe::from(
+            ShapelessRecipe::new(RecipeResult::new(location("test:ruby"), 9))
+                .ingredient(location("test:ruby_block"))
+                .group("rubies"),
+        );
+        assert_eq!(json(&recipe), json!({
+            "type": "minecraft:crafting_shapeless",
+            "group": "rubies",
+            "category": "misc",
+            "ingredients": [{"item": "test:ruby_block"}],
+            "result": {"id": "test:ruby", "count": 9},
+        }));
+    }
+
+    #[test]
+    fn cooking() {
+        let ore = Ingredient::Any(vec![location("test:ruby_ore").into(), location("test:deepslate_ruby_ore").into()]);
+        let recipe = Recipe::Blasting(CookingRecipe::new(ore, location("test:ruby"), 1.0).cooking_time(100).category(CookingCategory::Blocks));
+        let expected = json!({
+            "type": "minecraft:blasting",
+            "category": "blocks",
+            "ingredient": [{"item": "test:ruby_ore"}, {"item": "test:deepslate_ruby_ore"}],
+            "result": {"id": "test:ruby"},
+            "experience": 1.0,
+            "cookingtime": 100,
+        });
+        assert_eq!(json(&recipe), expected);
+        assert_eq!(json(&legacy(recipe))["result"], "test:ruby");
+    }
+
+    #[test]
+    fn stonecutting() {
+        let recipe = Recipe::from(StonecuttingRecipe::new(location("minecraft:stone"), RecipeResult::new(location("minecraft:stone_slab"), 2)));
+        assert_eq!(json(&recipe), json!({
+            "type": "minecraft:stonecutting",
+            "ingredient": {"item": "minecraft:stone"},
+            "result": {"id": "minecraft:stone_slab", "count": 2},
+        }));
+        assert_eq!(json(&legacy(recipe)), json!({
+            "type": "minecraft:stonecutting",
+            "ingredient": {"item": "minecraft:stone"},
+            "result": "minecraft:stone_slab",
+            "count": 2,
+        }));
+    }
+
+    #[test]
+    fn smithing() {
+        let template = Ingredient::Item(location("minecraft:netherite_upgrade_smithing_template"));
+        let transform = Recipe::from(SmithingTransformRecipe::new(
+            template,
+            location("test:ruby_sword"),
+            location("minecraft:netherite_ingot"),
+            RecipeResult::new(location("test:netherite_ruby_sword"), 1).component(location("minecraft:rarity"), "epic"),
+        ));
+        assert_eq!(json(&transform), json!({
+            "type": "minecraft:smithing_transform",
+            "template": {"item": "minecraft:netherite_upgrade_smithing_template"},
+            "base": {"item": "test:ruby_sword"},
+            "addition": {"item": "minecraft:netherite_ingot"},
+            "result": {"id": "test:netherite_ruby_sword", "components": {"minecraft:rarity": "epic"}},
+        }));
+
+        let trim = Recipe::from(SmithingTrimRecipe::new(
+            Ingredient::Tag(location("minecraft:trim_templates")),
+            Ingredient::Tag(location("minecraft:trimmable_armor")),
+            Ingredient::Tag(location("minecraft:trim_materials")),
+        ));
+        assert!(json(&trim).get("result").is_none(), "a trim recipe has no result");
+        assert!(trim.result().is_none());
+        assert_eq!(json(&trim), json!({
+            "type": "minecraft:smithing_trim",
+            "template": {"tag": "minecraft:trim_templates"},
+            "base": {"tag": "minecraft:trimmable_armor"},
+            "addition": {"tag": "minecraft:trim_materials"},
+        }));
+    }
+
+    #[test]
+    fn validation() {
+        let result = || RecipeResult::from(location("test:thing"));
+        let invalid: [Recipe; 7] = [
+            ShapedRecipe::new(["AA", "A"], result()).key('A', location("test:a")).into(),
+            ShapedRecipe::new(["AAAA"], result()).key('A', location("test:a")).into(),
+            ShapedRecipe::new(["AB"], result()).key('A', location("test:a")).into(),
+            ShapedRecipe::new(["A"], result()).key('A', location("test:a")).key('B', location("test:b")).into(),
+            ShapelessRecipe::new(result()).into(),
+            ShapelessRecipe::new(RecipeResult::new(location("test:thing"), 100)).ingredient(location("test:a")).into(),
+            ShapelessRecipe::new(result().component(location("minecraft:rarity"), "epic")).ingredient(location("test:a")).into(),
+        ];
+        for recipe in invalid {
+            let mut datapack = Datapack::new(PackMeta::new(15, "test"));
+            match datapack.namespace("test").and_then(|mut namespace| namespace.add_recipe("invalid", recipe)) {
+                Err(DatapackError::Validation(_)) => {}
+                result => panic!("recipe should have been rejected, got {result:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn namespace_placement() {
+        for (pack_format, path) in [(15, "data/test/recipes/ruby.json"), (48, "data/test/recipe/ruby.json")] {
+            let mut datapack = Datapack::new(PackMeta::new(pack_format, "test"));
+            let recipe = ShapelessRecipe::new(RecipeResult::new(location("test:ruby"), 9)).ingredient(location("test:ruby_block"));
+            datapack
+                .namespace("test")
+                .and_then(|mut namespace| namespace.add_recipe("ruby", recipe))
+                .expect("should have added the recipe");
+            assert!(datapack.contains(path), "recipe should have been placed at '{path}'");
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn read_back() {
+        let recipe: Recipe = ShapedRecipe::new(["##", "##"], RecipeResult::new(location("minecraft:stone_bricks"), 4))
+            .key('#', location("minecraft:stone"))
+            .into();
+        let read: Recipe = serde_json::from_str(&recipe.to_string()).expect("should have read the recipe back");
+        assert_eq!(read, recipe, "recipe did not survive a round trip");
+
+        let smelting: Recipe = serde_json::from_str(
+            r#"{"type": "minecraft:smelting", "ingredient": {"item": "minecraft:sand"}, "result": "minecraft:glass", "experience": 0.1}"#,
+        ).expect("should have read the legacy smelting recipe");
+        assert_eq!(json(&smelting), json!({
+            "type": "minecraft:smelting",
+            "category": "misc",
+            "ingredient": {"item": "minecraft:sand"},
+            "result": "minecraft:glass",
+            "experience": 0.1,
+        }), "legacy smelting recipe was not written back in its format");
+
+        let stonecutting: Recipe = serde_json::from_str(
+            r#"{"type": "minecraft:stonecutting", "ingredient": {"tag": "minecraft:logs"}, "result": "minecraft:stick", "count": 4}"#,
+        ).expect("should have read the legacy stonecutting recipe");
+        assert_eq!(json(&stonecutting), json!({
+            "type": "minecraft:stonecutting",
+            "ingredient": {"tag": "minecraft:logs"},
+            "result": "minecraft:stick",
+            "count": 4,
+        }), "legacy stonecutting recipe was not written back in its format");
+    }
+}
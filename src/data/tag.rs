@@ -0,0 +1,350 @@
+use std::borrow::Cow;
+use std::fmt::{self, Display};
+use std::marker::PhantomData;
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer};
+use crate::data::function::McFunction;
+use crate::data::painting_variant::PaintingVariant;
+use crate::datapack::resource::{Reference, Resource};
+use crate::resource_location::ResourceLocation;
+use crate::utils::file_system::File;
+use crate::utils::json::{fmt_json, JsonStyle};
+
+/// The kind of thing a [`Tag`] groups, which decides where the tag file goes.
+pub trait TagKind {
+    const DIRECTORY: &'static str;
+
+    /// The directory for `pack_format`. Tag directories were renamed to the singular in
+    /// pack format 45.
+    fn directory(pack_format: u32) -> &'static str;
+
+    /// The resource a tag value refers to, for kinds whose values are resources of this
+    /// datapack rather than registry entries.
+    fn reference(_location: ResourceLocation) -> Option<Reference> {
+        None
+    }
+}
+
+macro_rules! tag_kinds {
+    ($($(#[$attribute:meta])* $kind:ident => $directory:literal, $renamed:literal;)+) => {
+        $(
+            $(#[$attribute])*
+            #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+            pub struct $kind;
+
+            impl TagKind for $kind {
+                const DIRECTORY: &'static str = $directory;
+
+                fn directory(pack_format: u32) -> &'static str {
+                    if pack_format >= 45 { $renamed } else { Self::DIRECTORY }
+                }
+            }
+        )+
+    };
+}
+
+tag_kinds! {
+    BlockTag => "tags/blocks", "tags/block";
+    ItemTag => "tags/items", "tags/item";
+    EntityTypeTag => "tags/entity_types", "tags/entity_type";
+    FluidTag => "tags/fluids", "tags/fluid";
+    GameEventTag => "tags/game_events", "tags/game_event";
+}
+
+/// Groups damage types, such as `minecraft:bypasses_armor`, which is how most of their
+/// behavior is set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DamageTypeTag;
+
+impl TagKind for DamageTypeTag {
+    const DIRECTORY: &'static str = "tags/damage_type";
+
+    fn directory(_pack_format: u32) -> &'static str {
+        Self::DIRECTORY
+    }
+}
+
+/// Groups painting variants, such as `minecraft:placeable`, the ones placed at random.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PaintingVariantTag;
+
+impl TagKind for PaintingVariantTag {
+    const DIRECTORY: &'static str = "tags/painting_variant";
+
+    fn directory(_pack_format: u32) -> &'static str {
+        Self::DIRECTORY
+    }
+
+    fn reference(location: ResourceLocation) -> Option<Reference> {
+        Some(Reference::to::<PaintingVariant>(location))
+    }
+}
+
+/// Groups functions, such as the `minecraft:tick` and `minecraft:load` tags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FunctionTag;
+
+impl TagKind for FunctionTag {
+    const DIRECTORY: &'static str = "tags/functions";
+
+    fn directory(pack_format: u32) -> &'static str {
+        if pack_format >= 45 { "tags/function" } else { Self::DIRECTORY }
+    }
+
+    fn reference(location: ResourceLocation) -> Option<Reference> {
+        Some(Reference::to::<McFunction>(location))
+    }
+}
+
+impl FunctionTag {
+    /// The `minecraft:tick` tag, whose functions run at the start of every tick.
+    pub fn tick(functions: Vec<ResourceLocation>) -> Tag<FunctionTag> {
+        functions.into_iter().fold(Tag::new(), Tag::value)
+    }
+
+    /// The `minecraft:load` tag, whose functions run when the datapack is loaded or
+    /// reloaded.
+    pub fn load(functions: Vec<ResourceLocation>) -> Tag<FunctionTag> {
+        functions.into_iter().fold(Tag::new(), Tag::value)
+    }
+}
+
+/// An ID or another tag, written with a leading `#`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TagValue {
+    Id(ResourceLocation),
+    Tag(ResourceLocation),
+}
+
+impl Display for TagValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Id(location) => write!(f, "{location}"),
+            Self::Tag(location) => write!(f, "#{location}"),
+        }
+    }
+}
+
+impl From<ResourceLocation> for TagValue {
+    fn from(location: ResourceLocation) -> Self {
+        Self::Id(location)
+    }
+}
+
+/// A value of a [`Tag`]. The game fails to load a tag with a missing required value, but
+/// skips missing optional ones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagEntry {
+    pub value: TagValue,
+    pub required: bool,
+}
+
+impl TagEntry {
+    pub fn optional(value: impl Into<TagValue>) -> Self {
+        Self { value: value.into(), required: false }
+    }
+}
+
+impl<T: Into<TagValue>> From<T> for TagEntry {
+    fn from(value: T) -> Self {
+        Self { value: value.into(), required: true }
+    }
+}
+
+/// Written as `"id"`, or as `{"id": "id", "required": false}` when optional.
+impl Serialize for TagEntry {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.required {
+            return serializer.collect_str(&self.value);
+        }
+        let mut map = serializer.serialize_map(Some(2))?;
+        map.serialize_entry("id", &self.value.to_string())?;
+        map.serialize_entry("required", &false)?;
+        map.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for TagEntry {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Required(String),
+            Entry { id: String, required: bool },
+        }
+
+        let (id, required) = match Repr::deserialize(deserializer)? {
+            Repr::Required(id) => (id, true),
+            Repr::Entry { id, required } => (id, required),
+        };
+        let value = match id.strip_prefix('#') {
+            Some(tag) => TagValue::Tag(tag.parse().map_err(de::Error::custom)?),
+            None => TagValue::Id(id.parse().map_err(de::Error::custom)?),
+        };
+        Ok(Self { value, required })
+    }
+}
+
+/// A `data/<namespace>/tags/<kind>` file, grouping IDs of the kind `K` under one `#` name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct Tag<K> {
+    /// Whether this tag replaces the values other datapacks give it, instead of adding to them.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub replace: bool,
+    pub values: Vec<TagEntry>,
+    #[serde(skip)]
+    kind: PhantomData<K>,
+}
+
+impl<K> Tag<K> {
+    pub fn new() -> Self {
+        Self { replace: false, values: Vec::new(), kind: PhantomData }
+    }
+
+    pub fn replace(mut self, replace: bool) -> Self {
+        self.replace = replace;
+        self
+    }
+
+    pub fn value(mut self, value: impl Into<TagEntry>) -> Self {
+        self.values.push(value.into());
+        self
+    }
+
+    pub fn tag(self, tag: ResourceLocation) -> Self {
+        self.value(TagValue::Tag(tag))
+    }
+}
+
+impl<K> Default for Tag<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K> Display for Tag<K> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_styled(f, JsonStyle::default())
+    }
+}
+
+impl<K> File for Tag<K> {
+    fn fmt_styled(&self, f: &mut fmt::Formatter, style: JsonStyle) -> fmt::Result {
+        fmt_json(self, f, style)
+    }
+}
+
+/// The values of a tag made by [`Datapack::add_to_tag`](crate::datapack::Datapack::add_to_tag),
+/// as they are when the datapack is written.
+pub(crate) struct TagValues<'v>(pub Cow<'v, [TagEntry]>);
+
+impl Display for TagValues<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_styled(f, JsonStyle::default())
+    }
+}
+
+impl File for TagValues<'_> {
+    fn fmt_styled(&self, f: &mut fmt::Formatter, style: JsonStyle) -> fmt::Result {
+        #[derive(Serialize)]
+        struct Repr<'a> {
+            values: &'a [TagEntry],
+        }
+
+        fmt_json(&Repr { values: &self.0 }, f, style)
+    }
+}
+
+impl<K: TagKind> Resource for Tag<K> {
+    const DIRECTORY: &'static str = K::DIRECTORY;
+
+    fn directory(pack_format: u32) -> &'static str {
+        K::directory(pack_format)
+    }
+
+    /// Required values only, since the game skips missing optional ones.
+    fn references(&self) -> Vec<Reference> {
+        self.values.iter().filter_map(|entry| entry.reference::<K>()).collect()
+    }
+}
+
+impl TagEntry {
+    /// What this value of a tag of the kind `K` refers to, if it must exist.
+    pub(crate) fn reference<K: TagKind>(&self) -> Option<Reference> {
+        if !self.required {
+            return None;
+        }
+        match &self.value {
+            TagValue::Id(location) => K::reference(location.clone()),
+            TagValue::Tag(location) => Some(Reference::to::<Tag<K>>(location.clone())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datapack::Datapack;
+    use crate::datapack::pack_meta::PackMeta;
+
+    fn location(location: &str) -> ResourceLocation {
+        location.parse().expect("should have parsed the resource location")
+    }
+
+    #[test]
+    fn tag_display() {
+        let tag = Tag::<BlockTag>::new()
+            .value(location("minecraft:stone"))
+            .tag(location("minecraft:logs"))
+            .value(TagEntry::optional(location("other:marble")));
+        let expected = r##"{
+  "values": [
+    "minecraft:stone",
+    "#minecraft:logs",
+    {
+      "id": "other:marble",
+      "required": false
+    }
+  ]
+}"##;
+        assert_eq!(tag.to_string(), expected, "tag was not rendered correctly");
+        assert!(tag.replace(true).to_string().starts_with("{\n  \"replace\": true,"), "replace was not rendered");
+    }
+
+    #[test]
+    fn namespace_placement() {
+        for (pack_format, path) in [(26, "data/minecraft/tags/functions/tick.json"), (48, "data/minecraft/tags/function/tick.json")] {
+            let mut datapack = Datapack::new(PackMeta::new(pack_format, "test"));
+            datapack
+                .namespace("minecraft")
+                .expect("should have accepted the namespace")
+                .add_tag("tick", Tag::<FunctionTag>::new().value(location("test:tick")))
+                .expect("should have added the tag");
+            assert!(datapack.contains(path), "function tag should have been placed at '{path}'");
+        }
+    }
+
+    #[test]
+    fn tick_and_load() {
+        let tag = FunctionTag::load(vec![location("test:init"), location("test:reset")]);
+        assert!(!tag.replace, "load should add to other datapacks' functions");
+        assert_eq!(tag, Tag::new().value(location("test:init")).value(location("test:reset")));
+
+        let mut datapack = Datapack::new(PackMeta::new(48, "test"));
+        let mut namespace = datapack.namespace("test").expect("should have accepted the namespace");
+        let main = namespace.add_function("main", McFunction::new()).expect("should have added the function");
+        let init = namespace.add_function("init", McFunction::new()).expect("should have added the function");
+        namespace.add_to_tick(main.clone()).expect("should have added the function to tick");
+        namespace.add_to_load(init).expect("should have added the function to load");
+        namespace.add_to_load(main).expect("should have added the function to load");
+        assert!(datapack.validate_references().is_empty(), "every function in the tags exists");
+
+        let directory = datapack.into_directory();
+        let data = directory.get(std::ffi::OsStr::new("data")).expect("should have a data directory").stats();
+        assert_eq!(data.files, 4, "there should be two functions and two tags");
+    }
+}
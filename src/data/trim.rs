@@ -0,0 +1,261 @@
+use serde::Serialize;
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use crate::datapack::resource::Resource;
+use crate::error::DatapackError;
+use crate::resource_location::ResourceLocation;
+use crate::text::TextComponent;
+use crate::utils::json::json_file;
+
+/// The pack format of 1.21.4, where trim materials lost their `item_model_index`, since
+/// item models pick the trim by its asset name.
+const NO_ITEM_MODEL_INDEX_PACK_FORMAT: u32 = 61;
+
+/// A `data/<namespace>/trim_material` file, which makes an item usable as an armor trim color.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct TrimMaterial {
+    /// The suffix of the trim's textures and item models, such as `amethyst`.
+    pub asset_name: String,
+    pub ingredient: ResourceLocation,
+    /// Which of the armor items' trim model overrides to use. Left out from pack format 61,
+    /// which doesn't read it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub item_model_index: Option<f32>,
+    /// Usually colored, since trim tooltips show it in its own color.
+    pub description: TextComponent,
+    /// Asset names to use instead of `asset_name` on armor of the same material, keyed by
+    /// armor material: `iron`, or `minecraft:iron` from 1.21 on.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub override_armor_materials: BTreeMap<String, String>,
+}
+
+impl TrimMaterial {
+    pub fn new(asset_name: impl Into<String>, ingredient: ResourceLocation, item_model_index: f32, description: impl Into<TextComponent>) -> Self {
+        Self {
+            asset_name: asset_name.into(),
+            ingredient,
+            item_model_index: Some(item_model_index),
+            description: description.into(),
+            override_armor_materials: BTreeMap::new(),
+        }
+    }
+
+    pub fn override_armor_material(mut self, armor_material: impl Into<String>, asset_name: impl Into<String>) -> Self {
+        self.override_armor_materials.insert(armor_material.into(), asset_name.into());
+        self
+    }
+}
+
+/// A `data/<namespace>/trim_pattern` file, which makes a smithing template apply an armor trim.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct TrimPattern {
+    pub asset_id: ResourceLocation,
+    pub template_item: ResourceLocation,
+    pub description: TextComponent,
+    /// Whether the trim is drawn over the armor's texture without replacing it, like
+    /// vanilla's netherite-style trims. Needs pack format 18.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub decal: bool,
+}
+
+impl TrimPattern {
+    pub fn new(asset_id: ResourceLocation, template_item: ResourceLocation, description: impl Into<TextComponent>) -> Self {
+        Self {
+            asset_id,
+            template_item,
+            description: description.into(),
+            decal: false,
+        }
+    }
+
+    pub fn decal(mut self, decal: bool) -> Self {
+        self.decal = decal;
+        self
+    }
+}
+
+json_file!(TrimMaterial, TrimPattern);
+
+/// Checks that an asset name could be the path of the textures it names.
+fn validate_asset_name(asset_name: &str) -> Result<(), DatapackError> {
+    ResourceLocation::minecraft(asset_name)
+        .map(|_| ())
+        .map_err(|_| DatapackError::Validation(format!("invalid trim material: '{asset_name}' is not a valid asset name")))
+}
+
+impl Resource for TrimMaterial {
+    const DIRECTORY: &'static str = "trim_material";
+    const MIN_PACK_FORMAT: u32 = 12;
+    const STABLE_PACK_FORMAT: u32 = 15;
+
+    fn set_pack_format(&mut self, pack_format: u32) {
+        if pack_format >= NO_ITEM_MODEL_INDEX_PACK_FORMAT {
+            self.item_model_index = None;
+        }
+    }
+
+    fn validate(&self) -> Result<(), DatapackError> {
+        validate_asset_name(&self.asset_name)?;
+        self.override_armor_materials.values().try_for_each(|asset_name| validate_asset_name(asset_name))
+    }
+}
+
+impl Resource for TrimPattern {
+    const DIRECTORY: &'static str = "trim_pattern";
+    const MIN_PACK_FORMAT: u32 = 12;
+    const STABLE_PACK_FORMAT: u32 = 15;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+    use crate::data::tag::{ItemTag, Tag};
+    use crate::datapack::Datapack;
+    use crate::datapack::pack_meta::PackMeta;
+    use crate::utils::file_system::Entry;
+    use serde_json::{json, Value};
+    use std::fs;
+    use tempdir::TempDir;
+
+    fn location(location: &str) -> ResourceLocation {
+        location.parse().expect("should have parsed the resource location")
+    }
+
+    #[test]
+    fn trim_material_display() {
+        let material = TrimMaterial::new(
+            "ruby",
+            location("test:ruby"),
+            0.85,
+            TextComponent::translate("trim_material.test.ruby").color(Color::Hex(0xE0115F)),
+        );
+        let expected = r##"{
+  "asset_name": "ruby",
+  "ingredient": "test:ruby",
+  "item_model_index": 0.85,
+  "description": {
+    "translate": "trim_material.test.ruby",
+    "color": "#E0115F"
+  }
+}"##;
+        assert_eq!(material.to_string(), expected, "trim material was not rendered correctly");
+    }
+
+    #[test]
+    fn trim_material_overrides() {
+        let material = TrimMaterial::new("ruby", location("test:ruby"), 0.85, "Ruby")
+            .override_armor_material("minecraft:iron", "ruby_darker")
+            .override_armor_material("minecraft:gold", "ruby_darker");
+        let expected = r#"{
+  "asset_name": "ruby",
+  "ingredient": "test:ruby",
+  "item_model_index": 0.85,
+  "description": "Ruby",
+  "override_armor_materials": {
+    "minecraft:gold": "ruby_darker",
+    "minecraft:iron": "ruby_darker"
+  }
+}"#;
+        assert_eq!(material.to_string(), expected, "trim material overrides were not rendered correctly");
+    }
+
+    #[test]
+    fn trim_pattern_display() {
+        let pattern = TrimPattern::new(
+            location("test:spiral"),
+            location("test:spiral_armor_trim_smithing_template"),
+            "Spiral",
+        );
+        let expected = r#"{
+  "asset_id": "test:spiral",
+  "template_item": "test:spiral_armor_trim_smithing_template",
+  "description": "Spiral"
+}"#;
+        assert_eq!(pattern.to_string(), expected, "trim pattern was not rendered correctly");
+
+        let decal = pattern.decal(true).to_string();
+        assert!(decal.ends_with("\"description\": \"Spiral\",\n  \"decal\": true\n}"), "decal was not rendered correctly");
+    }
+
+    #[test]
+    fn trim_pack_format() {
+        let pattern = TrimPattern::new(location("test:spiral"), location("test:template"), "Spiral");
+
+        let mut datapack = Datapack::new(PackMeta::new(10, "test"));
+        assert!(datapack.add(&location("test:spiral"), pattern.clone()).is_err(), "trims don't exist in pack format 10");
+
+        let mut datapack = Datapack::new(PackMeta::new(12, "test"));
+        datapack.add(&location("test:spiral"), pattern).expect("experimental trims should only warn");
+        assert!(datapack.contains("data/test/trim_pattern/spiral.json"), "trim pattern was not placed under 'trim_pattern/'");
+    }
+
+    /// The amethyst trim material of 1.20, added to its own namespace along with the
+    /// `minecraft:trim_materials` tag that lets the smithing table accept it.
+    #[test]
+    fn amethyst_trim_pack() {
+        let material = TrimMaterial::new(
+            "amethyst",
+            location("minecraft:amethyst_shard"),
+            1.0,
+            TextComponent::translate("trim_material.minecraft.amethyst").color(Color::Hex(0x9A5CC6)),
+        );
+        let mut datapack = Datapack::new(PackMeta::new(15, "Amethyst trims"));
+        datapack
+            .namespace("test")
+            .expect("should have accepted the namespace")
+            .add_trim_material_tagged("amethyst", material.clone())
+            .expect("should have added the trim material");
+
+        let temp_dir = TempDir::new(module_path!()).expect("should have created temp dir");
+        let path = temp_dir.path().join("amethyst_trims");
+        datapack.create(&path).expect("should have created the datapack");
+        let read = |file: &str| -> Value {
+            let contents = fs::read_to_string(path.join(file)).expect("should have been able to read the file");
+            serde_json::from_str(&contents).expect("file should be valid JSON")
+        };
+        assert_eq!(read("data/test/trim_material/amethyst.json"), json!({
+            "asset_name": "amethyst",
+            "description": {"color": "#9A5CC6", "translate": "trim_material.minecraft.amethyst"},
+            "ingredient": "minecraft:amethyst_shard",
+            "item_model_index": 1.0,
+        }));
+        assert_eq!(read("data/minecraft/tags/items/trim_materials.json"), json!({"values": ["minecraft:amethyst_shard"]}));
+        temp_dir.close().expect("should have closed temp dir");
+
+        let mut datapack = Datapack::new(PackMeta::new(61, "Amethyst trims"));
+        datapack.add(&location("test:amethyst"), material).expect("should have added the trim material");
+        let data = datapack.into_directory();
+        assert_eq!(data.len(), 2, "only the material should have been added, without the tag");
+
+        let invalid = TrimMaterial::new("Amethyst", location("minecraft:amethyst_shard"), 1.0, "Amethyst");
+        assert!(invalid.validate().is_err(), "asset names can't have capitals");
+    }
+
+    #[test]
+    fn tagged_trim_rolled_back() {
+        let mut datapack = Datapack::new(PackMeta::new(15, "test"));
+        datapack
+            .namespace("minecraft")
+            .expect("should have accepted the namespace")
+            .add_tag("trim_materials", Tag::<ItemTag>::new().value(location("minecraft:diamond")))
+            .expect("should have added the tag");
+        let material = TrimMaterial::new("amethyst", location("minecraft:amethyst_shard"), 1.0, "Amethyst");
+        let result = datapack.namespace("test").expect("should have accepted the namespace").add_trim_material_tagged("amethyst", material);
+        assert!(matches!(result, Err(DatapackError::DuplicateEntry(_))), "the tag was added whole, so it can't be added to");
+        assert!(!datapack.contains("data/test/trim_material/amethyst.json"), "the material should not have been added without its tag");
+    }
+
+    #[test]
+    fn item_model_index_removed() {
+        let mut material = TrimMaterial::new("ruby", location("test:ruby"), 0.85, "Ruby");
+        material.set_pack_format(48);
+        assert_eq!(material.item_model_index, Some(0.85));
+        material.set_pack_format(61);
+        let json: Value = serde_json::from_str(&material.to_string()).expect("trim material should be valid JSON");
+        assert_eq!(json, json!({"asset_name": "ruby", "ingredient": "test:ruby", "description": "Ruby"}));
+    }
+}
@@ -0,0 +1,62 @@
+use serde::Serialize;
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+use serde_json::Value;
+use crate::datapack::resource::Resource;
+use crate::resource_location::ResourceLocation;
+use crate::utils::json::json_file;
+
+/// A `data/<namespace>/wolf_variant` file, available since 1.20.5: the textures of the
+/// wolves that spawn in some biomes.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct WolfVariant {
+    /// The texture of untamed wolves, such as `minecraft:entity/wolf/wolf` for
+    /// `textures/entity/wolf/wolf.png`.
+    pub wild_texture: ResourceLocation,
+    pub tame_texture: ResourceLocation,
+    pub angry_texture: ResourceLocation,
+    /// Where the variant spawns: a biome ID, a `#` biome tag, or a list of IDs.
+    pub biomes: Value,
+}
+
+impl WolfVariant {
+    pub fn new(wild_texture: ResourceLocation, tame_texture: ResourceLocation, angry_texture: ResourceLocation, biomes: impl Into<Value>) -> Self {
+        Self { wild_texture, tame_texture, angry_texture, biomes: biomes.into() }
+    }
+}
+
+json_file!(WolfVariant);
+
+impl Resource for WolfVariant {
+    const DIRECTORY: &'static str = "wolf_variant";
+    const MIN_PACK_FORMAT: u32 = 41;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn location(location: &str) -> ResourceLocation {
+        location.parse().expect("should have parsed the resource location")
+    }
+
+    /// `minecraft:pale` from the game's data.
+    #[test]
+    fn vanilla_pale() {
+        let pale = WolfVariant::new(
+            location("minecraft:entity/wolf/wolf"),
+            location("minecraft:entity/wolf/wolf_tame"),
+            location("minecraft:entity/wolf/wolf_angry"),
+            "minecraft:taiga",
+        );
+        let json: Value = serde_json::from_str(&pale.to_string()).expect("wolf variant should be valid JSON");
+        assert_eq!(json, json!({
+            "angry_texture": "minecraft:entity/wolf/wolf_angry",
+            "biomes": "minecraft:taiga",
+            "tame_texture": "minecraft:entity/wolf/wolf_tame",
+            "wild_texture": "minecraft:entity/wolf/wolf",
+        }));
+    }
+}
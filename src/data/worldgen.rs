@@ -0,0 +1,10 @@
+pub mod biome;
+pub mod feature;
+pub mod noise_settings;
+pub mod height_provider;
+pub mod block_predicate;
+pub mod carver;
+pub mod structure;
+pub mod structure_set;
+pub mod template_pool;
+pub mod density_function;
@@ -0,0 +1,475 @@
+use std::collections::BTreeMap;
+use serde::{Serialize, Serializer};
+use crate::color::{serialize_packed, serialize_packed_option, Color};
+use crate::data::worldgen::carver::ConfiguredCarver;
+use crate::datapack::resource::{Reference, Resource};
+use crate::error::DatapackError;
+use crate::resource_location::ResourceLocation;
+use crate::utils::json::json_file;
+
+/// The steps features are placed in, in the order the game runs them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GenerationStep {
+    RawGeneration,
+    Lakes,
+    LocalModifications,
+    UndergroundStructures,
+    SurfaceStructures,
+    Strongholds,
+    UndergroundOres,
+    UndergroundDecoration,
+    FluidSprings,
+    VegetalDecoration,
+    TopLayerModification,
+}
+
+impl GenerationStep {
+    pub const COUNT: usize = 11;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MobCategory {
+    Monster,
+    Creature,
+    Ambient,
+    Axolotls,
+    UndergroundWaterCreature,
+    WaterCreature,
+    WaterAmbient,
+    Misc,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CarvingStep {
+    Air,
+    Liquid,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Spawner {
+    #[serde(rename = "type")]
+    pub entity_type: ResourceLocation,
+    pub weight: u32,
+    #[serde(rename = "minCount")]
+    pub min_count: u32,
+    #[serde(rename = "maxCount")]
+    pub max_count: u32,
+}
+
+impl Spawner {
+    pub fn new(entity_type: ResourceLocation, weight: u32, min_count: u32, max_count: u32) -> Self {
+        Self { entity_type, weight, min_count, max_count }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct SpawnCost {
+    pub energy_budget: f64,
+    pub charge: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BiomeParticle {
+    pub particle: ResourceLocation,
+    pub probability: f32,
+}
+
+#[derive(Serialize)]
+struct ParticleOptions<'a> {
+    #[serde(rename = "type")]
+    particle_type: &'a ResourceLocation,
+}
+
+#[derive(Serialize)]
+struct BiomeParticleRepr<'a> {
+    options: ParticleOptions<'a>,
+    probability: f32,
+}
+
+impl Serialize for BiomeParticle {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        BiomeParticleRepr {
+            options: ParticleOptions { particle_type: &self.particle },
+            probability: self.probability,
+        }.serialize(serializer)
+    }
+}
+
+/// Changes the temperature used for snow and ice, so parts of a biome can freeze.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TemperatureModifier {
+    #[default]
+    None,
+    Frozen,
+}
+
+impl TemperatureModifier {
+    fn is_none(&self) -> bool {
+        *self == Self::None
+    }
+}
+
+/// Changes the grass color computed from the climate, as in dark forests and swamps.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GrassColorModifier {
+    #[default]
+    None,
+    DarkForest,
+    Swamp,
+}
+
+impl GrassColorModifier {
+    fn is_none(&self) -> bool {
+        *self == Self::None
+    }
+}
+
+/// A sound played in dark places near the player, such as cave noises.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MoodSound {
+    pub sound: ResourceLocation,
+    pub tick_delay: u32,
+    pub block_search_extent: u32,
+    pub offset: f64,
+}
+
+impl MoodSound {
+    /// The cave noises of the overworld biomes.
+    pub fn cave() -> Self {
+        Self {
+            sound: ResourceLocation::minecraft("ambient.cave").expect("the path is valid"),
+            tick_delay: 6000,
+            block_search_extent: 8,
+            offset: 2.0,
+        }
+    }
+}
+
+/// A sound played at random, with a chance of `tick_chance` each tick.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AdditionsSound {
+    pub sound: ResourceLocation,
+    pub tick_chance: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BiomeMusic {
+    pub sound: ResourceLocation,
+    pub min_delay: u32,
+    pub max_delay: u32,
+    pub replace_current_music: bool,
+}
+
+/// The colors, particles and music of a [`Biome`]. Colors are written as packed integers.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BiomeEffects {
+    #[serde(serialize_with = "serialize_packed")]
+    pub fog_color: Color,
+    #[serde(serialize_with = "serialize_packed")]
+    pub sky_color: Color,
+    #[serde(serialize_with = "serialize_packed")]
+    pub water_color: Color,
+    #[serde(serialize_with = "serialize_packed")]
+    pub water_fog_color: Color,
+    #[serde(serialize_with = "serialize_packed_option", skip_serializing_if = "Option::is_none")]
+    pub grass_color: Option<Color>,
+    #[serde(serialize_with = "serialize_packed_option", skip_serializing_if = "Option::is_none")]
+    pub foliage_color: Option<Color>,
+    #[serde(skip_serializing_if = "GrassColorModifier::is_none")]
+    pub grass_color_modifier: GrassColorModifier,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub particle: Option<BiomeParticle>,
+    /// A sound looped while the player is in the biome.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ambient_sound: Option<ResourceLocation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mood_sound: Option<MoodSound>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub additions_sound: Option<AdditionsSound>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub music: Option<BiomeMusic>,
+}
+
+impl BiomeEffects {
+    /// Takes [`Color`]s or `0xRRGGBB` integers.
+    pub fn new(fog_color: impl Into<Color>, sky_color: impl Into<Color>, water_color: impl Into<Color>, water_fog_color: impl Into<Color>) -> Self {
+        Self {
+            fog_color: fog_color.into(),
+            sky_color: sky_color.into(),
+            water_color: water_color.into(),
+            water_fog_color: water_fog_color.into(),
+            grass_color: None,
+            foliage_color: None,
+            grass_color_modifier: GrassColorModifier::None,
+            particle: None,
+            ambient_sound: None,
+            mood_sound: None,
+            additions_sound: None,
+            music: None,
+        }
+    }
+
+    pub fn grass_color(mut self, grass_color: impl Into<Color>, modifier: GrassColorModifier) -> Self {
+        self.grass_color = Some(grass_color.into());
+        self.grass_color_modifier = modifier;
+        self
+    }
+
+    pub fn foliage_color(mut self, foliage_color: impl Into<Color>) -> Self {
+        self.foliage_color = Some(foliage_color.into());
+        self
+    }
+
+    pub fn particle(mut self, particle: ResourceLocation, probability: f32) -> Self {
+        self.particle = Some(BiomeParticle { particle, probability });
+        self
+    }
+
+    pub fn ambient_sound(mut self, sound: ResourceLocation) -> Self {
+        self.ambient_sound = Some(sound);
+        self
+    }
+
+    pub fn mood_sound(mut self, mood_sound: MoodSound) -> Self {
+        self.mood_sound = Some(mood_sound);
+        self
+    }
+
+    pub fn additions_sound(mut self, sound: ResourceLocation, tick_chance: f64) -> Self {
+        self.additions_sound = Some(AdditionsSound { sound, tick_chance });
+        self
+    }
+
+    pub fn music(mut self, music: BiomeMusic) -> Self {
+        self.music = Some(music);
+        self
+    }
+}
+
+/// The colors and cave noises of the plains.
+impl Default for BiomeEffects {
+    fn default() -> Self {
+        Self::new(0xC0D8FF, 0x78A7FF, 0x3F76E4, 0x050533).mood_sound(MoodSound::cave())
+    }
+}
+
+/// A `data/<namespace>/worldgen/biome` file.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Biome {
+    pub has_precipitation: bool,
+    pub temperature: f32,
+    #[serde(skip_serializing_if = "TemperatureModifier::is_none")]
+    pub temperature_modifier: TemperatureModifier,
+    pub downfall: f32,
+    pub effects: BiomeEffects,
+    pub spawners: BTreeMap<MobCategory, Vec<Spawner>>,
+    pub spawn_costs: BTreeMap<ResourceLocation, SpawnCost>,
+    pub carvers: BTreeMap<CarvingStep, Vec<ResourceLocation>>,
+    /// The placed features of each [`GenerationStep`], indexed by step.
+    pub features: Vec<Vec<ResourceLocation>>,
+}
+
+impl Biome {
+    pub fn new(temperature: f32, downfall: f32, has_precipitation: bool, effects: BiomeEffects) -> Self {
+        Self {
+            has_precipitation,
+            temperature,
+            temperature_modifier: TemperatureModifier::None,
+            downfall,
+            effects,
+            spawners: BTreeMap::new(),
+            spawn_costs: BTreeMap::new(),
+            carvers: BTreeMap::new(),
+            features: vec![Vec::new(); GenerationStep::COUNT],
+        }
+    }
+
+    pub fn temperature_modifier(mut self, temperature_modifier: TemperatureModifier) -> Self {
+        self.temperature_modifier = temperature_modifier;
+        self
+    }
+
+    pub fn spawner(mut self, category: MobCategory, spawner: Spawner) -> Self {
+        self.spawners.entry(category).or_default().push(spawner);
+        self
+    }
+
+    pub fn spawn_cost(mut self, entity_type: ResourceLocation, energy_budget: f64, charge: f64) -> Self {
+        self.spawn_costs.insert(entity_type, SpawnCost { energy_budget, charge });
+        self
+    }
+
+    pub fn carver(mut self, step: CarvingStep, carver: ResourceLocation) -> Self {
+        self.carvers.entry(step).or_default().push(carver);
+        self
+    }
+
+    pub fn feature(mut self, step: GenerationStep, feature: ResourceLocation) -> Self {
+        self.features[step as usize].push(feature);
+        self
+    }
+}
+
+/// The climate and effects of the plains, without any mobs, carvers or features.
+impl Default for Biome {
+    fn default() -> Self {
+        Self::new(0.8, 0.4, true, BiomeEffects::default())
+    }
+}
+
+json_file!(Biome);
+
+impl Resource for Biome {
+    const DIRECTORY: &'static str = "worldgen/biome";
+    const MIN_PACK_FORMAT: u32 = 6;
+
+    fn validate(&self) -> Result<(), DatapackError> {
+        if self.features.len() != GenerationStep::COUNT {
+            return Err(DatapackError::Validation(format!(
+                "a biome needs features for {} generation steps, but has {}",
+                GenerationStep::COUNT,
+                self.features.len(),
+            )));
+        }
+        Ok(())
+    }
+
+    fn references(&self) -> Vec<Reference> {
+        self.carvers.values().flatten().map(|carver| Reference::to::<ConfiguredCarver>(carver.clone())).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn location(location: &str) -> ResourceLocation {
+        location.parse().expect("should have parsed the resource location")
+    }
+
+    #[test]
+    fn color_integers() {
+        let mut effects = BiomeEffects::new(0xC0D8FF, 0x78A7FF, 0x3F76E4, 0x050533);
+        effects.foliage_color = Some(Color::Green);
+        let json = serde_json::to_value(effects).expect("should have serialized the effects");
+        assert_eq!(json["fog_color"], 12638463);
+        assert_eq!(json["sky_color"], 7907327);
+        assert_eq!(json["water_color"], 4159204);
+        assert_eq!(json["water_fog_color"], 329011);
+        assert_eq!(json["foliage_color"], 0x55FF55);
+        assert!(json.get("grass_color").is_none(), "unset colors should have been omitted");
+    }
+
+    #[test]
+    fn plains_like_biome() {
+        let mut effects = BiomeEffects::new(0xC0D8FF, 0x78A7FF, 0x3F76E4, 0x050533);
+        effects.grass_color = Some(Color::Hex(0x91BD59));
+        effects.particle = Some(BiomeParticle { particle: location("minecraft:white_ash"), probability: 0.01 });
+
+        let biome = Biome::new(0.8, 0.4, true, effects)
+            .spawner(MobCategory::Creature, Spawner::new(location("minecraft:sheep"), 12, 4, 4))
+            .spawner(MobCategory::Monster, Spawner::new(location("minecraft:zombie"), 95, 4, 4))
+            .spawn_cost(location("minecraft:sheep"), 0.12, 1.0)
+            .carver(CarvingStep::Air, location("minecraft:cave"))
+            .feature(GenerationStep::Lakes, location("minecraft:lake_lava_underground"))
+            .feature(GenerationStep::VegetalDecoration, location("minecraft:patch_grass_plain"));
+
+        let json: Value = serde_json::from_str(&biome.to_string()).expect("biome should be valid JSON");
+        let expected = json!({
+            "has_precipitation": true,
+            "temperature": 0.8,
+            "downfall": 0.4,
+            "effects": {
+                "fog_color": 12638463,
+                "sky_color": 7907327,
+                "water_color": 4159204,
+                "water_fog_color": 329011,
+                "grass_color": 9551193,
+                "particle": {"options": {"type": "minecraft:white_ash"}, "probability": 0.01},
+            },
+            "spawners": {
+                "monster": [{"type": "minecraft:zombie", "weight": 95, "minCount": 4, "maxCount": 4}],
+                "creature": [{"type": "minecraft:sheep", "weight": 12, "minCount": 4, "maxCount": 4}],
+            },
+            "spawn_costs": {"minecraft:sheep": {"energy_budget": 0.12, "charge": 1.0}},
+            "carvers": {"air": ["minecraft:cave"]},
+            "features": [
+                [],
+                ["minecraft:lake_lava_underground"],
+                [], [], [], [], [], [], [],
+                ["minecraft:patch_grass_plain"],
+                [],
+            ],
+        });
+        assert_eq!(json, expected, "biome was not rendered correctly");
+    }
+
+    /// `minecraft:plains` from the game's data, with one feature per step and the
+    /// empty mob categories left out.
+    #[test]
+    fn vanilla_plains() {
+        let step_features = [
+            (GenerationStep::Lakes, "minecraft:lake_lava_underground"),
+            (GenerationStep::LocalModifications, "minecraft:amethyst_geode"),
+            (GenerationStep::UndergroundStructures, "minecraft:monster_room"),
+            (GenerationStep::UndergroundOres, "minecraft:ore_dirt"),
+            (GenerationStep::UndergroundDecoration, "minecraft:ore_infested"),
+            (GenerationStep::FluidSprings, "minecraft:spring_water"),
+            (GenerationStep::VegetalDecoration, "minecraft:glow_lichen"),
+            (GenerationStep::TopLayerModification, "minecraft:freeze_top_layer"),
+        ];
+        let biome = step_features
+            .into_iter()
+            .fold(Biome::default(), |biome, (step, feature)| biome.feature(step, location(feature)))
+            .spawner(MobCategory::Ambient, Spawner::new(location("minecraft:bat"), 10, 8, 8))
+            .carver(CarvingStep::Air, location("minecraft:cave"))
+            .carver(CarvingStep::Air, location("minecraft:cave_extra_underground"))
+            .carver(CarvingStep::Air, location("minecraft:canyon"));
+        assert!(biome.validate().is_ok());
+
+        let json: Value = serde_json::from_str(&biome.to_string()).expect("biome should be valid JSON");
+        let vanilla = json!({
+            "carvers": {"air": ["minecraft:cave", "minecraft:cave_extra_underground", "minecraft:canyon"]},
+            "downfall": 0.4,
+            "effects": {
+                "fog_color": 12638463,
+                "mood_sound": {"block_search_extent": 8, "offset": 2.0, "sound": "minecraft:ambient.cave", "tick_delay": 6000},
+                "sky_color": 7907327,
+                "water_color": 4159204,
+                "water_fog_color": 329011,
+            },
+            "features": [
+                [],
+                ["minecraft:lake_lava_underground"],
+                ["minecraft:amethyst_geode"],
+                ["minecraft:monster_room"],
+                [],
+                [],
+                ["minecraft:ore_dirt"],
+                ["minecraft:ore_infested"],
+                ["minecraft:spring_water"],
+                ["minecraft:glow_lichen"],
+                ["minecraft:freeze_top_layer"],
+            ],
+            "has_precipitation": true,
+            "spawn_costs": {},
+            "spawners": {"ambient": [{"type": "minecraft:bat", "maxCount": 8, "minCount": 8, "weight": 10}]},
+            "temperature": 0.8,
+        });
+        assert_eq!(json, vanilla, "the biome should have the shape of the vanilla plains");
+
+        let mut biome = biome.temperature_modifier(TemperatureModifier::Frozen);
+        biome.effects = biome.effects.grass_color(0x6A7039, GrassColorModifier::Swamp);
+        let json = serde_json::to_value(&biome).expect("should have serialized the biome");
+        assert_eq!(json["temperature_modifier"], "frozen");
+        assert_eq!(json["effects"]["grass_color_modifier"], "swamp");
+        biome.features.pop();
+        assert!(biome.validate().is_err(), "a biome without every generation step should be rejected");
+    }
+}
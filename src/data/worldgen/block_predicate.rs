@@ -0,0 +1,98 @@
+use std::ops::Not;
+use serde::Serialize;
+use serde_json::Value;
+use crate::block_state::BlockState;
+use crate::resource_location::ResourceLocation;
+
+/// A check of the block at, or `offset` from, the position a feature is placed at.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type")]
+pub enum BlockPredicate {
+    #[serde(rename = "minecraft:matching_blocks")]
+    MatchingBlocks {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        offset: Option<[i32; 3]>,
+        blocks: Vec<ResourceLocation>,
+    },
+    #[serde(rename = "minecraft:matching_block_tag")]
+    MatchingBlockTag {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        offset: Option<[i32; 3]>,
+        tag: ResourceLocation,
+    },
+    #[serde(rename = "minecraft:matching_fluids")]
+    MatchingFluids {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        offset: Option<[i32; 3]>,
+        fluids: Vec<ResourceLocation>,
+    },
+    #[serde(rename = "minecraft:solid")]
+    Solid,
+    #[serde(rename = "minecraft:replaceable")]
+    Replaceable,
+    /// Whether `state` could stay where it is placed, like a flower on grass.
+    #[serde(rename = "minecraft:would_survive")]
+    WouldSurvive { state: BlockState },
+    #[serde(rename = "minecraft:all_of")]
+    AllOf { predicates: Vec<BlockPredicate> },
+    #[serde(rename = "minecraft:any_of")]
+    AnyOf { predicates: Vec<BlockPredicate> },
+    #[serde(rename = "minecraft:not")]
+    Not { predicate: Box<BlockPredicate> },
+    #[serde(rename = "minecraft:true")]
+    True,
+    /// Any other predicate, written as is. It should include its own `type`.
+    #[serde(untagged)]
+    Raw(Value),
+}
+
+impl BlockPredicate {
+    pub fn matching_blocks(blocks: impl IntoIterator<Item = ResourceLocation>) -> Self {
+        Self::MatchingBlocks { offset: None, blocks: blocks.into_iter().collect() }
+    }
+
+    pub fn matching_block_tag(tag: ResourceLocation) -> Self {
+        Self::MatchingBlockTag { offset: None, tag }
+    }
+
+    pub fn would_survive(state: impl Into<BlockState>) -> Self {
+        Self::WouldSurvive { state: state.into() }
+    }
+}
+
+impl Not for BlockPredicate {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self::Not { predicate: Box::new(self) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn location(location: &str) -> ResourceLocation {
+        location.parse().expect("should have parsed the resource location")
+    }
+
+    #[test]
+    fn block_predicate_json() {
+        let predicate = BlockPredicate::AllOf {
+            predicates: vec![
+                BlockPredicate::Replaceable,
+                BlockPredicate::MatchingBlocks { offset: Some([0, -1, 0]), blocks: vec![location("minecraft:grass_block")] },
+                !BlockPredicate::matching_block_tag(location("minecraft:leaves")),
+            ],
+        };
+        assert_eq!(serde_json::to_value(&predicate).expect("should have serialized the predicate"), json!({
+            "type": "minecraft:all_of",
+            "predicates": [
+                {"type": "minecraft:replaceable"},
+                {"type": "minecraft:matching_blocks", "offset": [0, -1, 0], "blocks": ["minecraft:grass_block"]},
+                {"type": "minecraft:not", "predicate": {"type": "minecraft:matching_block_tag", "tag": "minecraft:leaves"}},
+            ],
+        }));
+    }
+}
@@ -0,0 +1,259 @@
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+use crate::data::worldgen::height_provider::{HeightProvider, VerticalAnchor};
+use crate::datapack::resource::Resource;
+use crate::error::DatapackError;
+use crate::resource_location::ResourceLocation;
+use crate::utils::json::json_file;
+
+/// A number that is either constant or picked for each carved tunnel.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FloatProvider {
+    Constant(f32),
+    Uniform { min_inclusive: f32, max_exclusive: f32 },
+}
+
+impl From<f32> for FloatProvider {
+    fn from(value: f32) -> Self {
+        Self::Constant(value)
+    }
+}
+
+impl Serialize for FloatProvider {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match *self {
+            Self::Constant(value) => serializer.serialize_f32(value),
+            Self::Uniform { min_inclusive, max_exclusive } => {
+                let mut state = serializer.serialize_struct("FloatProvider", 3)?;
+                state.serialize_field("type", "minecraft:uniform")?;
+                state.serialize_field("min_inclusive", &min_inclusive)?;
+                state.serialize_field("max_exclusive", &max_exclusive)?;
+                state.end()
+            }
+        }
+    }
+}
+
+/// What every carver is configured with.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CarverConfig {
+    /// The chance of a chunk starting a carve, from 0 to 1.
+    pub probability: f32,
+    pub y: HeightProvider,
+    /// How much carves are stretched vertically.
+    #[serde(rename = "yScale")]
+    pub y_scale: FloatProvider,
+    /// Air carved at or below this level is filled with lava instead.
+    pub lava_level: VerticalAnchor,
+    /// The blocks the carver may remove: a block ID, a `#` block tag, or a list of IDs.
+    pub replaceable: Value,
+    /// Replaces carved blocks with visible ones, such as `{"air_state": {"Name": "minecraft:acacia_button"}}`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub debug_settings: Option<Value>,
+    /// Whether carves are filled by aquifers. Only read by 1.17, and left out when unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aquifers_enabled: Option<bool>,
+}
+
+impl CarverConfig {
+    pub fn new(probability: f32, y: impl Into<HeightProvider>, y_scale: impl Into<FloatProvider>, lava_level: VerticalAnchor, replaceable: impl Into<Value>) -> Self {
+        Self {
+            probability,
+            y: y.into(),
+            y_scale: y_scale.into(),
+            lava_level,
+            replaceable: replaceable.into(),
+            debug_settings: None,
+            aquifers_enabled: None,
+        }
+    }
+
+    pub fn debug_settings(mut self, debug_settings: Value) -> Self {
+        self.debug_settings = Some(debug_settings);
+        self
+    }
+
+    pub fn aquifers_enabled(mut self, aquifers_enabled: bool) -> Self {
+        self.aquifers_enabled = Some(aquifers_enabled);
+        self
+    }
+}
+
+/// The config of `minecraft:cave` and `minecraft:nether_cave`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CaveConfig {
+    #[serde(flatten)]
+    pub carver: CarverConfig,
+    pub horizontal_radius_multiplier: FloatProvider,
+    pub vertical_radius_multiplier: FloatProvider,
+    /// How flat the bottom of caves is, from -1 to 1.
+    pub floor_level: FloatProvider,
+}
+
+impl CaveConfig {
+    pub fn new(carver: CarverConfig) -> Self {
+        Self {
+            carver,
+            horizontal_radius_multiplier: 1.0.into(),
+            vertical_radius_multiplier: 1.0.into(),
+            floor_level: (-0.7).into(),
+        }
+    }
+
+    pub fn radius_multipliers(mut self, horizontal: impl Into<FloatProvider>, vertical: impl Into<FloatProvider>) -> Self {
+        self.horizontal_radius_multiplier = horizontal.into();
+        self.vertical_radius_multiplier = vertical.into();
+        self
+    }
+
+    pub fn floor_level(mut self, floor_level: impl Into<FloatProvider>) -> Self {
+        self.floor_level = floor_level.into();
+        self
+    }
+}
+
+/// The cross-section of a canyon along its length.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct CanyonShape {
+    pub distance_factor: FloatProvider,
+    pub thickness: FloatProvider,
+    pub width_smoothness: u32,
+    pub horizontal_radius_factor: FloatProvider,
+    pub vertical_radius_default_factor: f32,
+    pub vertical_radius_center_factor: f32,
+}
+
+/// The config of `minecraft:canyon`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CanyonConfig {
+    #[serde(flatten)]
+    pub carver: CarverConfig,
+    pub vertical_rotation: FloatProvider,
+    pub shape: CanyonShape,
+}
+
+/// A `data/<namespace>/worldgen/configured_carver` file: caves or canyons cut into the
+/// terrain of the biomes that list it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfiguredCarver {
+    Cave(CaveConfig),
+    /// Caves without aquifers, so they are never flooded.
+    NetherCave(CaveConfig),
+    Canyon(CanyonConfig),
+    /// Any other carver type, with its `config` written as is.
+    Raw { carver_type: ResourceLocation, config: Value },
+}
+
+impl ConfiguredCarver {
+    fn config(&self) -> Option<&CarverConfig> {
+        match self {
+            Self::Cave(config) | Self::NetherCave(config) => Some(&config.carver),
+            Self::Canyon(config) => Some(&config.carver),
+            Self::Raw { .. } => None,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ConfiguredCarverRepr<'a, C> {
+    #[serde(rename = "type")]
+    carver_type: String,
+    config: &'a C,
+}
+
+impl Serialize for ConfiguredCarver {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        fn repr<C: Serialize, S: Serializer>(serializer: S, carver_type: impl ToString, config: &C) -> Result<S::Ok, S::Error> {
+            ConfiguredCarverRepr { carver_type: carver_type.to_string(), config }.serialize(serializer)
+        }
+
+        match self {
+            Self::Cave(config) => repr(serializer, "minecraft:cave", config),
+            Self::NetherCave(config) => repr(serializer, "minecraft:nether_cave", config),
+            Self::Canyon(config) => repr(serializer, "minecraft:canyon", config),
+            Self::Raw { carver_type, config } => repr(serializer, carver_type, config),
+        }
+    }
+}
+
+json_file!(ConfiguredCarver);
+
+impl Resource for ConfiguredCarver {
+    const DIRECTORY: &'static str = "worldgen/configured_carver";
+    const MIN_PACK_FORMAT: u32 = 6;
+
+    fn validate(&self) -> Result<(), DatapackError> {
+        match self.config() {
+            Some(config) if !(0.0..=1.0).contains(&config.probability) => Err(DatapackError::Validation(format!(
+                "invalid configured carver: the probability {} must be from 0 to 1",
+                config.probability,
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn uniform(min_inclusive: f32, max_exclusive: f32) -> FloatProvider {
+        FloatProvider::Uniform { min_inclusive, max_exclusive }
+    }
+
+    /// `minecraft:cave` from the game's data.
+    #[test]
+    fn vanilla_cave() {
+        let config = CarverConfig::new(
+            0.15,
+            HeightProvider::uniform(VerticalAnchor::AboveBottom(8), VerticalAnchor::Absolute(180)),
+            uniform(0.1, 0.9),
+            VerticalAnchor::AboveBottom(8),
+            "#minecraft:overworld_carver_replaceables",
+        );
+        let cave = ConfiguredCarver::Cave(CaveConfig::new(config).radius_multipliers(uniform(0.7, 1.4), uniform(0.8, 1.3)).floor_level(uniform(-1.0, -0.4)));
+        let json: Value = serde_json::from_str(&cave.to_string()).expect("carver should be valid JSON");
+        assert_eq!(json, json!({
+            "type": "minecraft:cave",
+            "config": {
+                "probability": 0.15,
+                "y": {"type": "minecraft:uniform", "min_inclusive": {"above_bottom": 8}, "max_inclusive": {"absolute": 180}},
+                "yScale": {"type": "minecraft:uniform", "min_inclusive": 0.1, "max_exclusive": 0.9},
+                "lava_level": {"above_bottom": 8},
+                "replaceable": "#minecraft:overworld_carver_replaceables",
+                "horizontal_radius_multiplier": {"type": "minecraft:uniform", "min_inclusive": 0.7, "max_exclusive": 1.4},
+                "vertical_radius_multiplier": {"type": "minecraft:uniform", "min_inclusive": 0.8, "max_exclusive": 1.3},
+                "floor_level": {"type": "minecraft:uniform", "min_inclusive": -1.0, "max_exclusive": -0.4},
+            },
+        }));
+        assert!(cave.validate().is_ok());
+    }
+
+    #[test]
+    fn disabled_canyon() {
+        let config = CarverConfig::new(0.0, VerticalAnchor::Absolute(20), 3.0, VerticalAnchor::AboveBottom(8), json!(["minecraft:stone"]))
+            .aquifers_enabled(false);
+        let shape = CanyonShape {
+            distance_factor: uniform(0.75, 1.0),
+            thickness: 2.0.into(),
+            width_smoothness: 3,
+            horizontal_radius_factor: uniform(0.75, 1.0),
+            vertical_radius_default_factor: 1.0,
+            vertical_radius_center_factor: 0.0,
+        };
+        let canyon = ConfiguredCarver::Canyon(CanyonConfig { carver: config, vertical_rotation: uniform(-0.125, 0.125), shape });
+        let json = serde_json::to_value(&canyon).expect("should have serialized the carver");
+        assert_eq!(json["config"]["probability"], 0.0);
+        assert_eq!(json["config"]["aquifers_enabled"], false);
+        assert_eq!(json["config"]["shape"]["thickness"], 2.0);
+        assert!(json["config"].get("debug_settings").is_none(), "unset debug settings should be left out");
+
+        let mut canyon = canyon;
+        if let ConfiguredCarver::Canyon(config) = &mut canyon {
+            config.carver.probability = 1.5;
+        }
+        assert!(canyon.validate().is_err(), "the probability is a chance");
+    }
+}
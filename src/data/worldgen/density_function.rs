@@ -0,0 +1,282 @@
+use serde::Serialize;
+use crate::datapack::resource::{Reference, Resource};
+use crate::error::DatapackError;
+use crate::resource_location::ResourceLocation;
+use crate::utils::json::json_file;
+
+/// A value of a [`Spline`]: a constant, or another spline.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum SplineValue {
+    Constant(f32),
+    Spline(Box<Spline>),
+}
+
+impl From<f32> for SplineValue {
+    fn from(value: f32) -> Self {
+        Self::Constant(value)
+    }
+}
+
+impl From<Spline> for SplineValue {
+    fn from(spline: Spline) -> Self {
+        Self::Spline(Box::new(spline))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SplinePoint {
+    /// Where on the coordinate this point is.
+    pub location: f32,
+    pub value: SplineValue,
+    /// The slope of the spline at this point.
+    pub derivative: f32,
+}
+
+/// A curve through points of `coordinate`, smoothly interpolated between them.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Spline {
+    pub coordinate: DensityFunction,
+    pub points: Vec<SplinePoint>,
+}
+
+impl Spline {
+    pub fn new(coordinate: impl Into<DensityFunction>) -> Self {
+        Self { coordinate: coordinate.into(), points: Vec::new() }
+    }
+
+    pub fn point(mut self, location: f32, value: impl Into<SplineValue>, derivative: f32) -> Self {
+        self.points.push(SplinePoint { location, value: value.into(), derivative });
+        self
+    }
+
+    fn referenced(&self, references: &mut Vec<ResourceLocation>) {
+        for coordinate in self.coordinates() {
+            coordinate.referenced(references);
+        }
+    }
+
+    /// The coordinate of this spline and those of the splines in its points.
+    fn coordinates(&self) -> Vec<&DensityFunction> {
+        let mut coordinates = vec![&self.coordinate];
+        for point in &self.points {
+            if let SplineValue::Spline(spline) = &point.value {
+                coordinates.extend(spline.coordinates());
+            }
+        }
+        coordinates
+    }
+}
+
+/// A `data/<namespace>/worldgen/density_function` file, available since 1.18.2: a density
+/// at each position, computed from a tree of these. Terrain is solid where the final
+/// density of the noise router is positive.
+///
+/// A constant is written as a bare number and a reference to another density function as
+/// its location, as the game writes them.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type")]
+pub enum DensityFunction {
+    /// The noise `noise`, with the coordinates scaled first.
+    #[serde(rename = "minecraft:noise")]
+    Noise { noise: ResourceLocation, xz_scale: f64, y_scale: f64 },
+    #[serde(rename = "minecraft:add")]
+    Add { argument1: Box<DensityFunction>, argument2: Box<DensityFunction> },
+    #[serde(rename = "minecraft:mul")]
+    Mul { argument1: Box<DensityFunction>, argument2: Box<DensityFunction> },
+    #[serde(rename = "minecraft:min")]
+    Min { argument1: Box<DensityFunction>, argument2: Box<DensityFunction> },
+    #[serde(rename = "minecraft:max")]
+    Max { argument1: Box<DensityFunction>, argument2: Box<DensityFunction> },
+    #[serde(rename = "minecraft:abs")]
+    Abs { argument: Box<DensityFunction> },
+    #[serde(rename = "minecraft:square")]
+    Square { argument: Box<DensityFunction> },
+    #[serde(rename = "minecraft:clamp")]
+    Clamp { input: Box<DensityFunction>, min: f64, max: f64 },
+    /// From `from_value` at `from_y` to `to_value` at `to_y`, and the nearest of them
+    /// beyond.
+    #[serde(rename = "minecraft:y_clamped_gradient")]
+    YClampedGradient { from_y: i32, to_y: i32, from_value: f64, to_value: f64 },
+    /// Computed once for each cell and interpolated in between, for smoother terrain.
+    #[serde(rename = "minecraft:interpolated")]
+    Interpolated { argument: Box<DensityFunction> },
+    #[serde(rename = "minecraft:spline")]
+    Spline { spline: Box<Spline> },
+    #[serde(untagged)]
+    Constant(f64),
+    #[serde(untagged)]
+    Reference(ResourceLocation),
+}
+
+impl DensityFunction {
+    pub fn noise(noise: ResourceLocation) -> Self {
+        Self::Noise { noise, xz_scale: 1.0, y_scale: 1.0 }
+    }
+
+    pub fn add(argument1: impl Into<Self>, argument2: impl Into<Self>) -> Self {
+        Self::Add { argument1: Box::new(argument1.into()), argument2: Box::new(argument2.into()) }
+    }
+
+    pub fn mul(argument1: impl Into<Self>, argument2: impl Into<Self>) -> Self {
+        Self::Mul { argument1: Box::new(argument1.into()), argument2: Box::new(argument2.into()) }
+    }
+
+    pub fn min(argument1: impl Into<Self>, argument2: impl Into<Self>) -> Self {
+        Self::Min { argument1: Box::new(argument1.into()), argument2: Box::new(argument2.into()) }
+    }
+
+    pub fn max(argument1: impl Into<Self>, argument2: impl Into<Self>) -> Self {
+        Self::Max { argument1: Box::new(argument1.into()), argument2: Box::new(argument2.into()) }
+    }
+
+    pub fn clamp(input: impl Into<Self>, min: f64, max: f64) -> Self {
+        Self::Clamp { input: Box::new(input.into()), min, max }
+    }
+
+    pub fn spline(spline: Spline) -> Self {
+        Self::Spline { spline: Box::new(spline) }
+    }
+
+    /// The density functions this one and its arguments refer to.
+    fn referenced(&self, references: &mut Vec<ResourceLocation>) {
+        match self {
+            Self::Reference(location) => references.push(location.clone()),
+            Self::Add { argument1, argument2 }
+            | Self::Mul { argument1, argument2 }
+            | Self::Min { argument1, argument2 }
+            | Self::Max { argument1, argument2 } => {
+                argument1.referenced(references);
+                argument2.referenced(references);
+            }
+            Self::Abs { argument } | Self::Square { argument } | Self::Interpolated { argument } | Self::Clamp { input: argument, .. } => {
+                argument.referenced(references);
+            }
+            Self::Spline { spline } => spline.referenced(references),
+            Self::Noise { .. } | Self::YClampedGradient { .. } | Self::Constant(_) => {}
+        }
+    }
+}
+
+impl From<f64> for DensityFunction {
+    fn from(value: f64) -> Self {
+        Self::Constant(value)
+    }
+}
+
+impl From<ResourceLocation> for DensityFunction {
+    fn from(location: ResourceLocation) -> Self {
+        Self::Reference(location)
+    }
+}
+
+json_file!(DensityFunction);
+
+impl Resource for DensityFunction {
+    const DIRECTORY: &'static str = "worldgen/density_function";
+    const MIN_PACK_FORMAT: u32 = 9;
+
+    /// Checks that every `clamp` has its minimum at most its maximum.
+    fn validate(&self) -> Result<(), DatapackError> {
+        let mut stack = vec![self];
+        while let Some(function) = stack.pop() {
+            match function {
+                Self::Clamp { min, max, .. } if min > max => {
+                    return Err(DatapackError::Validation(format!("invalid density function: the clamp minimum {min} is above its maximum {max}")));
+                }
+                Self::Add { argument1, argument2 }
+                | Self::Mul { argument1, argument2 }
+                | Self::Min { argument1, argument2 }
+                | Self::Max { argument1, argument2 } => stack.extend([&**argument1, &**argument2]),
+                Self::Abs { argument } | Self::Square { argument } | Self::Interpolated { argument } | Self::Clamp { input: argument, .. } => {
+                    stack.push(argument);
+                }
+                Self::Spline { spline } => stack.extend(spline.coordinates()),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// The density functions it refers to.
+    fn references(&self) -> Vec<Reference> {
+        let mut references = Vec::new();
+        self.referenced(&mut references);
+        references.into_iter().map(Reference::to::<DensityFunction>).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn location(location: &str) -> ResourceLocation {
+        location.parse().expect("should have parsed the resource location")
+    }
+
+    fn to_json(function: &DensityFunction) -> Value {
+        serde_json::from_str(&function.to_string()).expect("the density function should be valid JSON")
+    }
+
+    #[test]
+    fn constant() {
+        assert_eq!(to_json(&DensityFunction::from(0.5)), json!(0.5));
+    }
+
+    #[test]
+    fn add_noises() {
+        let function = DensityFunction::add(
+            DensityFunction::noise(location("minecraft:ridge")),
+            DensityFunction::Noise { noise: location("minecraft:jagged"), xz_scale: 1500.0, y_scale: 0.0 },
+        );
+        assert_eq!(to_json(&function), json!({
+            "type": "minecraft:add",
+            "argument1": {"type": "minecraft:noise", "noise": "minecraft:ridge", "xz_scale": 1.0, "y_scale": 1.0},
+            "argument2": {"type": "minecraft:noise", "noise": "minecraft:jagged", "xz_scale": 1500.0, "y_scale": 0.0},
+        }));
+        assert!(function.references().is_empty(), "noises aren't density functions");
+    }
+
+    #[test]
+    fn reference() {
+        let function = DensityFunction::mul(location("minecraft:overworld/depth"), DensityFunction::clamp(location("test:hills"), -1.0, 1.0));
+        assert_eq!(to_json(&function), json!({
+            "type": "minecraft:mul",
+            "argument1": "minecraft:overworld/depth",
+            "argument2": {"type": "minecraft:clamp", "input": "test:hills", "min": -1.0, "max": 1.0},
+        }));
+        let references: Vec<String> = function.references().iter().map(|reference| reference.location.to_string()).collect();
+        assert_eq!(references, ["minecraft:overworld/depth", "test:hills"]);
+        assert!(function.validate().is_ok());
+        assert!(DensityFunction::clamp(0.0, 1.0, -1.0).validate().is_err());
+    }
+
+    #[test]
+    fn spline() {
+        let spline = Spline::new(location("minecraft:overworld/continents"))
+            .point(-1.0, 0.0, 0.0)
+            .point(1.0, Spline::new(location("test:erosion")).point(0.0, 0.5, 0.1), 0.0);
+        let function = DensityFunction::spline(spline);
+        assert_eq!(to_json(&function), json!({
+            "type": "minecraft:spline",
+            "spline": {
+                "coordinate": "minecraft:overworld/continents",
+                "points": [
+                    {"location": -1.0, "value": 0.0, "derivative": 0.0},
+                    {
+                        "location": 1.0,
+                        "value": {"coordinate": "test:erosion", "points": [{"location": 0.0, "value": 0.5, "derivative": 0.1}]},
+                        "derivative": 0.0,
+                    },
+                ],
+            },
+        }));
+        assert_eq!(function.references().len(), 2);
+        assert!(function.validate().is_ok());
+
+        let nested = Spline::new(DensityFunction::clamp(location("test:erosion"), 1.0, -1.0)).point(0.0, 0.5, 0.0);
+        let invalid = DensityFunction::spline(Spline::new(location("minecraft:overworld/continents")).point(1.0, nested, 0.0));
+        assert!(invalid.validate().is_err(), "the clamp of the nested spline's coordinate should have been checked");
+    }
+}
@@ -0,0 +1,582 @@
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use serde_json::{Map, Value};
+use crate::block_state::BlockState;
+use crate::data::worldgen::block_predicate::BlockPredicate;
+use crate::data::worldgen::height_provider::HeightProvider;
+use crate::datapack::resource::{Reference, Resource};
+use crate::resource_location::ResourceLocation;
+use crate::utils::json::json_file;
+
+/// Decides which blocks an ore may replace.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "predicate_type")]
+pub enum RuleTest {
+    #[serde(rename = "minecraft:always_true")]
+    AlwaysTrue,
+    #[serde(rename = "minecraft:block_match")]
+    BlockMatch { block: ResourceLocation },
+    #[serde(rename = "minecraft:blockstate_match")]
+    BlockStateMatch { block_state: BlockState },
+    #[serde(rename = "minecraft:tag_match")]
+    TagMatch { tag: ResourceLocation },
+    #[serde(rename = "minecraft:random_block_match")]
+    RandomBlockMatch { block: ResourceLocation, probability: f32 },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OreTarget {
+    pub target: RuleTest,
+    pub state: BlockState,
+}
+
+impl OreTarget {
+    pub fn new(target: RuleTest, state: impl Into<BlockState>) -> Self {
+        Self { target, state: state.into() }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OreConfig {
+    pub size: u32,
+    pub discard_chance_on_air_exposure: f32,
+    pub targets: Vec<OreTarget>,
+}
+
+impl OreConfig {
+    pub fn new(size: u32, discard_chance_on_air_exposure: f32) -> Self {
+        Self { size, discard_chance_on_air_exposure, targets: Vec::new() }
+    }
+
+    pub fn target(mut self, target: OreTarget) -> Self {
+        self.targets.push(target);
+        self
+    }
+}
+
+/// The block states a feature places.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type")]
+pub enum BlockStateProvider {
+    #[serde(rename = "minecraft:simple_state_provider")]
+    Simple { state: BlockState },
+    /// One of the states, picked by weight for every block.
+    #[serde(rename = "minecraft:weighted_state_provider")]
+    Weighted { entries: Vec<WeightedState> },
+    /// Any other provider, written as is. It should include its own `type`.
+    #[serde(untagged)]
+    Raw(Value),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WeightedState {
+    pub data: BlockState,
+    pub weight: u32,
+}
+
+impl<T: Into<BlockState>> From<T> for BlockStateProvider {
+    fn from(state: T) -> Self {
+        Self::Simple { state: state.into() }
+    }
+}
+
+/// How a tree's trunk grows, such as `minecraft:straight_trunk_placer`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TrunkPlacer {
+    #[serde(rename = "type")]
+    pub placer_type: ResourceLocation,
+    pub base_height: u32,
+    pub height_rand_a: u32,
+    pub height_rand_b: u32,
+}
+
+impl TrunkPlacer {
+    /// A trunk of `base_height` plus up to `height_rand_a` and `height_rand_b` blocks.
+    pub fn straight(base_height: u32, height_rand_a: u32, height_rand_b: u32) -> Self {
+        Self {
+            placer_type: ResourceLocation::minecraft("straight_trunk_placer").expect("the path is valid"),
+            base_height,
+            height_rand_a,
+            height_rand_b,
+        }
+    }
+}
+
+/// How a tree's leaves are laid out, such as `minecraft:blob_foliage_placer`. Settings
+/// specific to the placer type are written as is.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FoliagePlacer {
+    #[serde(rename = "type")]
+    pub placer_type: ResourceLocation,
+    pub radius: IntProvider,
+    pub offset: IntProvider,
+    #[serde(flatten)]
+    pub settings: Map<String, Value>,
+}
+
+impl FoliagePlacer {
+    /// The round leaves of oaks and birches, `height` layers tall.
+    pub fn blob(radius: impl Into<IntProvider>, offset: impl Into<IntProvider>, height: u32) -> Self {
+        Self {
+            placer_type: ResourceLocation::minecraft("blob_foliage_placer").expect("the path is valid"),
+            radius: radius.into(),
+            offset: offset.into(),
+            settings: Map::from_iter([("height".to_string(), Value::from(height))]),
+        }
+    }
+}
+
+/// The space a tree needs: `lower_size` blocks around the trunk below `limit`, and
+/// `upper_size` above it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeatureSize {
+    pub limit: u32,
+    pub lower_size: u32,
+    pub upper_size: u32,
+}
+
+impl Serialize for FeatureSize {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("FeatureSize", 4)?;
+        state.serialize_field("type", "minecraft:two_layers_feature_size")?;
+        state.serialize_field("limit", &self.limit)?;
+        state.serialize_field("lower_size", &self.lower_size)?;
+        state.serialize_field("upper_size", &self.upper_size)?;
+        state.end()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TreeConfig {
+    pub dirt_provider: BlockStateProvider,
+    pub trunk_provider: BlockStateProvider,
+    pub foliage_provider: BlockStateProvider,
+    pub trunk_placer: TrunkPlacer,
+    pub foliage_placer: FoliagePlacer,
+    pub minimum_size: FeatureSize,
+    /// Tree decorators, such as beehives and vines, written as is.
+    pub decorators: Vec<Value>,
+    pub ignore_vines: bool,
+    pub force_dirt: bool,
+}
+
+impl TreeConfig {
+    pub fn new(
+        trunk: impl Into<BlockStateProvider>,
+        trunk_placer: TrunkPlacer,
+        foliage: impl Into<BlockStateProvider>,
+        foliage_placer: FoliagePlacer,
+    ) -> Self {
+        Self {
+            dirt_provider: BlockState::new(ResourceLocation::minecraft("dirt").expect("the path is valid")).into(),
+            trunk_provider: trunk.into(),
+            foliage_provider: foliage.into(),
+            trunk_placer,
+            foliage_placer,
+            minimum_size: FeatureSize { limit: 1, lower_size: 0, upper_size: 1 },
+            decorators: Vec::new(),
+            ignore_vines: true,
+            force_dirt: false,
+        }
+    }
+}
+
+/// The placed feature tried by a random patch, either by ID or inline.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum PlacedFeatureReference {
+    Id(ResourceLocation),
+    Inline(Box<PlacedFeature>),
+}
+
+impl From<ResourceLocation> for PlacedFeatureReference {
+    fn from(id: ResourceLocation) -> Self {
+        Self::Id(id)
+    }
+}
+
+impl From<PlacedFeature> for PlacedFeatureReference {
+    fn from(feature: PlacedFeature) -> Self {
+        Self::Inline(Box::new(feature))
+    }
+}
+
+/// Places `feature` up to `tries` times around the origin, as grass and flowers are.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RandomPatchConfig {
+    pub tries: u32,
+    pub xz_spread: u32,
+    pub y_spread: u32,
+    pub feature: PlacedFeatureReference,
+}
+
+impl RandomPatchConfig {
+    /// The spread of vanilla patches: 128 tries, 7 blocks across and 3 up or down.
+    pub fn new(feature: impl Into<PlacedFeatureReference>) -> Self {
+        Self { tries: 128, xz_spread: 7, y_spread: 3, feature: feature.into() }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SimpleBlockConfig {
+    pub to_place: BlockStateProvider,
+}
+
+/// A pool of `fluid` lined with `barrier`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LakeConfig {
+    pub fluid: BlockStateProvider,
+    pub barrier: BlockStateProvider,
+}
+
+/// A `data/<namespace>/worldgen/configured_feature` file: a feature type and its settings.
+///
+/// Feature types without a variant here, such as `minecraft:geode`, can be written with
+/// [`ConfiguredFeature::Raw`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfiguredFeature {
+    Ore(OreConfig),
+    ScatteredOre(OreConfig),
+    Tree(Box<TreeConfig>),
+    RandomPatch(RandomPatchConfig),
+    /// A random patch of the flowers of the biome, for bone meal.
+    Flower(RandomPatchConfig),
+    SimpleBlock(SimpleBlockConfig),
+    Lake(LakeConfig),
+    /// Any other feature type, with its `config` written as is.
+    Raw { feature_type: ResourceLocation, config: Value },
+}
+
+#[derive(Serialize)]
+struct ConfiguredFeatureRepr<'a, C> {
+    #[serde(rename = "type")]
+    feature_type: String,
+    config: &'a C,
+}
+
+impl Serialize for ConfiguredFeature {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        fn repr<C: Serialize, S: Serializer>(serializer: S, feature_type: impl ToString, config: &C) -> Result<S::Ok, S::Error> {
+            ConfiguredFeatureRepr { feature_type: feature_type.to_string(), config }.serialize(serializer)
+        }
+
+        match self {
+            Self::Ore(config) => repr(serializer, "minecraft:ore", config),
+            Self::ScatteredOre(config) => repr(serializer, "minecraft:scattered_ore", config),
+            Self::Tree(config) => repr(serializer, "minecraft:tree", config),
+            Self::RandomPatch(config) => repr(serializer, "minecraft:random_patch", config),
+            Self::Flower(config) => repr(serializer, "minecraft:flower", config),
+            Self::SimpleBlock(config) => repr(serializer, "minecraft:simple_block", config),
+            Self::Lake(config) => repr(serializer, "minecraft:lake", config),
+            Self::Raw { feature_type, config } => repr(serializer, feature_type, config),
+        }
+    }
+}
+
+/// An integer that is either constant or picked when the feature is placed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntProvider {
+    Constant(i32),
+    Uniform { min_inclusive: i32, max_inclusive: i32 },
+}
+
+impl From<i32> for IntProvider {
+    fn from(value: i32) -> Self {
+        Self::Constant(value)
+    }
+}
+
+impl Serialize for IntProvider {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match *self {
+            Self::Constant(value) => serializer.serialize_i32(value),
+            Self::Uniform { min_inclusive, max_inclusive } => {
+                let mut state = serializer.serialize_struct("IntProvider", 3)?;
+                state.serialize_field("type", "minecraft:uniform")?;
+                state.serialize_field("min_inclusive", &min_inclusive)?;
+                state.serialize_field("max_inclusive", &max_inclusive)?;
+                state.end()
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Heightmap {
+    WorldSurfaceWg,
+    WorldSurface,
+    OceanFloorWg,
+    OceanFloor,
+    MotionBlocking,
+    MotionBlockingNoLeaves,
+}
+
+/// A step of a [`PlacedFeature`]'s placement, which moves, multiplies or filters positions.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type")]
+pub enum PlacementModifier {
+    #[serde(rename = "minecraft:count")]
+    Count { count: IntProvider },
+    #[serde(rename = "minecraft:in_square")]
+    InSquare,
+    #[serde(rename = "minecraft:heightmap")]
+    Heightmap { heightmap: Heightmap },
+    #[serde(rename = "minecraft:biome")]
+    Biome,
+    /// Keeps the position with a chance of 1 in `chance`.
+    #[serde(rename = "minecraft:rarity_filter")]
+    RarityFilter { chance: u32 },
+    /// Moves the position to a height picked by `height`.
+    #[serde(rename = "minecraft:height_range")]
+    HeightRange { height: HeightProvider },
+    #[serde(rename = "minecraft:block_predicate_filter")]
+    BlockPredicateFilter { predicate: BlockPredicate },
+    #[serde(rename = "minecraft:surface_water_depth_filter")]
+    SurfaceWaterDepthFilter { max_water_depth: i32 },
+    #[serde(rename = "minecraft:random_offset")]
+    RandomOffset { xz_spread: IntProvider, y_spread: IntProvider },
+    /// Any other modifier, written as is. It should include its own `type`.
+    #[serde(untagged)]
+    Raw(Value),
+}
+
+impl PlacementModifier {
+    pub fn count(count: impl Into<IntProvider>) -> Self {
+        Self::Count { count: count.into() }
+    }
+
+    pub fn height_range(height: impl Into<HeightProvider>) -> Self {
+        Self::HeightRange { height: height.into() }
+    }
+}
+
+/// The configured feature a [`PlacedFeature`] places, either by ID or inline.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum FeatureReference {
+    Id(ResourceLocation),
+    Inline(Box<ConfiguredFeature>),
+}
+
+impl From<ResourceLocation> for FeatureReference {
+    fn from(id: ResourceLocation) -> Self {
+        Self::Id(id)
+    }
+}
+
+impl From<ConfiguredFeature> for FeatureReference {
+    fn from(feature: ConfiguredFeature) -> Self {
+        Self::Inline(Box::new(feature))
+    }
+}
+
+/// A `data/<namespace>/worldgen/placed_feature` file, which decides where a configured feature goes.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlacedFeature {
+    pub feature: FeatureReference,
+    pub placement: Vec<PlacementModifier>,
+}
+
+impl PlacedFeature {
+    pub fn new(feature: impl Into<FeatureReference>) -> Self {
+        Self { feature: feature.into(), placement: Vec::new() }
+    }
+
+    pub fn modifier(mut self, modifier: PlacementModifier) -> Self {
+        self.placement.push(modifier);
+        self
+    }
+}
+
+json_file!(ConfiguredFeature, PlacedFeature);
+
+impl Resource for ConfiguredFeature {
+    const DIRECTORY: &'static str = "worldgen/configured_feature";
+    const MIN_PACK_FORMAT: u32 = 6;
+
+    fn references(&self) -> Vec<Reference> {
+        match self {
+            Self::RandomPatch(config) | Self::Flower(config) => match &config.feature {
+                PlacedFeatureReference::Id(feature) => vec![Reference::to::<PlacedFeature>(feature.clone())],
+                PlacedFeatureReference::Inline(feature) => feature.references(),
+            },
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl Resource for PlacedFeature {
+    const DIRECTORY: &'static str = "worldgen/placed_feature";
+    const MIN_PACK_FORMAT: u32 = 8;
+
+    fn references(&self) -> Vec<Reference> {
+        match &self.feature {
+            FeatureReference::Id(feature) => vec![Reference::to::<ConfiguredFeature>(feature.clone())],
+            FeatureReference::Inline(feature) => feature.references(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use crate::data::worldgen::height_provider::VerticalAnchor;
+    use crate::datapack::Datapack;
+    use crate::datapack::pack_meta::PackMeta;
+
+    fn location(location: &str) -> ResourceLocation {
+        location.parse().expect("should have parsed the resource location")
+    }
+
+    #[test]
+    fn ore_configured_feature() {
+        let feature = ConfiguredFeature::Ore(
+            OreConfig::new(9, 0.0)
+                .target(OreTarget::new(RuleTest::TagMatch { tag: location("minecraft:stone_ore_replaceables") }, location("test:ruby_ore")))
+                .target(OreTarget::new(
+                    RuleTest::TagMatch { tag: location("minecraft:deepslate_ore_replaceables") },
+                    BlockState::new(location("test:deepslate_ruby_ore")).property("lit", "false"),
+                )),
+        );
+        let json: Value = serde_json::from_str(&feature.to_string()).expect("feature should be valid JSON");
+        let expected = json!({
+            "type": "minecraft:ore",
+            "config": {
+                "size": 9,
+                "discard_chance_on_air_exposure": 0.0,
+                "targets": [
+                    {
+                        "target": {"predicate_type": "minecraft:tag_match", "tag": "minecraft:stone_ore_replaceables"},
+                        "state": {"Name": "test:ruby_ore"},
+                    },
+                    {
+                        "target": {"predicate_type": "minecraft:tag_match", "tag": "minecraft:deepslate_ore_replaceables"},
+                        "state": {"Name": "test:deepslate_ruby_ore", "Properties": {"lit": "false"}},
+                    },
+                ],
+            },
+        });
+        assert_eq!(json, expected, "configured feature was not rendered correctly");
+    }
+
+    #[test]
+    fn placed_feature_modifiers() {
+        let feature = PlacedFeature::new(location("test:ore_ruby"))
+            .modifier(PlacementModifier::count(IntProvider::Uniform { min_inclusive: 2, max_inclusive: 6 }))
+            .modifier(PlacementModifier::InSquare)
+            .modifier(PlacementModifier::Heightmap { heightmap: Heightmap::OceanFloorWg })
+            .modifier(PlacementModifier::Raw(json!({"type": "minecraft:random_offset", "xz_spread": 0, "y_spread": 1})))
+            .modifier(PlacementModifier::Biome);
+        let json: Value = serde_json::from_str(&feature.to_string()).expect("feature should be valid JSON");
+        let expected = json!({
+            "feature": "test:ore_ruby",
+            "placement": [
+                {"type": "minecraft:count", "count": {"type": "minecraft:uniform", "min_inclusive": 2, "max_inclusive": 6}},
+                {"type": "minecraft:in_square"},
+                {"type": "minecraft:heightmap", "heightmap": "OCEAN_FLOOR_WG"},
+                {"type": "minecraft:random_offset", "xz_spread": 0, "y_spread": 1},
+                {"type": "minecraft:biome"},
+            ],
+        });
+        assert_eq!(json, expected, "placed feature was not rendered correctly");
+    }
+
+    #[test]
+    fn inline_raw_feature() {
+        let feature = PlacedFeature::new(ConfiguredFeature::Raw {
+            feature_type: location("minecraft:simple_block"),
+            config: json!({"to_place": {"type": "minecraft:simple_state_provider", "state": {"Name": "minecraft:grass"}}}),
+        })
+        .modifier(PlacementModifier::count(3));
+        let json = serde_json::to_value(&feature).expect("should have serialized the feature");
+        assert_eq!(json["feature"]["type"], "minecraft:simple_block");
+        assert_eq!(json["feature"]["config"]["to_place"]["state"]["Name"], "minecraft:grass");
+        assert_eq!(json["placement"][0], json!({"type": "minecraft:count", "count": 3}));
+    }
+
+    /// `minecraft:ore_diamond_small` and `minecraft:ore_diamond` from the game's data.
+    #[test]
+    fn vanilla_ore_pair() {
+        let mut datapack = Datapack::new(PackMeta::new(48, "test"));
+        let mut namespace = datapack.namespace("test").expect("should have accepted the namespace");
+        let configured = ConfiguredFeature::Ore(
+            OreConfig::new(4, 0.5)
+                .target(OreTarget::new(RuleTest::TagMatch { tag: location("minecraft:stone_ore_replaceables") }, location("minecraft:diamond_ore")))
+                .target(OreTarget::new(
+                    RuleTest::TagMatch { tag: location("minecraft:deepslate_ore_replaceables") },
+                    location("minecraft:deepslate_diamond_ore"),
+                )),
+        );
+        let json: Value = serde_json::from_str(&configured.to_string()).expect("feature should be valid JSON");
+        assert_eq!(json, json!({
+            "type": "minecraft:ore",
+            "config": {
+                "discard_chance_on_air_exposure": 0.5,
+                "size": 4,
+                "targets": [
+                    {
+                        "state": {"Name": "minecraft:diamond_ore"},
+                        "target": {"predicate_type": "minecraft:tag_match", "tag": "minecraft:stone_ore_replaceables"},
+                    },
+                    {
+                        "state": {"Name": "minecraft:deepslate_diamond_ore"},
+                        "target": {"predicate_type": "minecraft:tag_match", "tag": "minecraft:deepslate_ore_replaceables"},
+                    },
+                ],
+            },
+        }));
+        let configured = namespace.add_configured_feature("ore_diamond_small", configured).expect("should have added the feature");
+
+        let placed = PlacedFeature::new(configured)
+            .modifier(PlacementModifier::count(7))
+            .modifier(PlacementModifier::InSquare)
+            .modifier(PlacementModifier::height_range(HeightProvider::trapezoid(VerticalAnchor::AboveBottom(-80), VerticalAnchor::AboveBottom(80))))
+            .modifier(PlacementModifier::Biome);
+        let json: Value = serde_json::from_str(&placed.to_string()).expect("feature should be valid JSON");
+        assert_eq!(json, json!({
+            "feature": "test:ore_diamond_small",
+            "placement": [
+                {"type": "minecraft:count", "count": 7},
+                {"type": "minecraft:in_square"},
+                {
+                    "type": "minecraft:height_range",
+                    "height": {"type": "minecraft:trapezoid", "max_inclusive": {"above_bottom": 80}, "min_inclusive": {"above_bottom": -80}},
+                },
+                {"type": "minecraft:biome"},
+            ],
+        }));
+        namespace.add_placed_feature("ore_diamond", placed).expect("should have added the feature");
+        assert!(datapack.contains("data/test/worldgen/placed_feature/ore_diamond.json"));
+        assert!(datapack.validate_references().is_empty(), "the configured feature should have been found");
+    }
+
+    #[test]
+    fn tree_and_patch() {
+        let tree = ConfiguredFeature::Tree(Box::new(TreeConfig::new(
+            location("minecraft:oak_log"),
+            TrunkPlacer::straight(4, 2, 0),
+            BlockState::new(location("minecraft:oak_leaves")).property("persistent", "false"),
+            FoliagePlacer::blob(2, 0, 3),
+        )));
+        let json = serde_json::to_value(&tree).expect("should have serialized the feature");
+        assert_eq!(json["type"], "minecraft:tree");
+        assert_eq!(json["config"]["trunk_provider"], json!({"type": "minecraft:simple_state_provider", "state": {"Name": "minecraft:oak_log"}}));
+        assert_eq!(json["config"]["trunk_placer"], json!({
+            "type": "minecraft:straight_trunk_placer", "base_height": 4, "height_rand_a": 2, "height_rand_b": 0,
+        }));
+        assert_eq!(json["config"]["foliage_placer"], json!({"type": "minecraft:blob_foliage_placer", "radius": 2, "offset": 0, "height": 3}));
+        assert_eq!(json["config"]["minimum_size"], json!({"type": "minecraft:two_layers_feature_size", "limit": 1, "lower_size": 0, "upper_size": 1}));
+
+        let flower = PlacedFeature::new(ConfiguredFeature::SimpleBlock(SimpleBlockConfig { to_place: location("minecraft:poppy").into() }))
+            .modifier(PlacementModifier::BlockPredicateFilter { predicate: BlockPredicate::Replaceable });
+        let patch = ConfiguredFeature::Flower(RandomPatchConfig::new(flower));
+        let json = serde_json::to_value(&patch).expect("should have serialized the feature");
+        assert_eq!(json["type"], "minecraft:flower");
+        assert_eq!(json["config"]["tries"], 128);
+        assert_eq!(json["config"]["feature"]["feature"]["config"]["to_place"]["state"]["Name"], "minecraft:poppy");
+        assert_eq!(json["config"]["feature"]["placement"][0], json!({"type": "minecraft:block_predicate_filter", "predicate": {"type": "minecraft:replaceable"}}));
+        assert!(patch.references().is_empty(), "an inline feature is not a reference");
+    }
+}
@@ -0,0 +1,84 @@
+use serde::Serialize;
+
+/// A Y coordinate, either absolute or relative to the bottom or top of the world.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VerticalAnchor {
+    Absolute(i32),
+    /// Blocks above the lowest block of the world.
+    AboveBottom(i32),
+    /// Blocks below the highest block of the world.
+    BelowTop(i32),
+}
+
+impl VerticalAnchor {
+    pub fn bottom() -> Self {
+        Self::AboveBottom(0)
+    }
+
+    pub fn top() -> Self {
+        Self::BelowTop(0)
+    }
+}
+
+/// A Y coordinate picked when a feature is placed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "type")]
+pub enum HeightProvider {
+    #[serde(rename = "minecraft:constant")]
+    Constant { value: VerticalAnchor },
+    /// Any height from `min_inclusive` to `max_inclusive`, with the same chance.
+    #[serde(rename = "minecraft:uniform")]
+    Uniform { min_inclusive: VerticalAnchor, max_inclusive: VerticalAnchor },
+    /// Heights near the middle are more likely, and the `plateau` blocks around the middle
+    /// are all as likely.
+    #[serde(rename = "minecraft:trapezoid")]
+    Trapezoid {
+        min_inclusive: VerticalAnchor,
+        max_inclusive: VerticalAnchor,
+        #[serde(skip_serializing_if = "is_zero")]
+        plateau: u32,
+    },
+}
+
+fn is_zero(plateau: &u32) -> bool {
+    *plateau == 0
+}
+
+impl HeightProvider {
+    pub fn uniform(min_inclusive: VerticalAnchor, max_inclusive: VerticalAnchor) -> Self {
+        Self::Uniform { min_inclusive, max_inclusive }
+    }
+
+    pub fn trapezoid(min_inclusive: VerticalAnchor, max_inclusive: VerticalAnchor) -> Self {
+        Self::Trapezoid { min_inclusive, max_inclusive, plateau: 0 }
+    }
+}
+
+impl From<VerticalAnchor> for HeightProvider {
+    fn from(value: VerticalAnchor) -> Self {
+        Self::Constant { value }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn height_provider_json() {
+        let trapezoid = HeightProvider::trapezoid(VerticalAnchor::AboveBottom(-80), VerticalAnchor::AboveBottom(80));
+        assert_eq!(serde_json::to_value(trapezoid).expect("should have serialized the provider"), json!({
+            "type": "minecraft:trapezoid",
+            "min_inclusive": {"above_bottom": -80},
+            "max_inclusive": {"above_bottom": 80},
+        }));
+        let uniform = HeightProvider::uniform(VerticalAnchor::Absolute(-16), VerticalAnchor::top());
+        assert_eq!(serde_json::to_value(uniform).expect("should have serialized the provider"), json!({
+            "type": "minecraft:uniform",
+            "min_inclusive": {"absolute": -16},
+            "max_inclusive": {"below_top": 0},
+        }));
+    }
+}
@@ -0,0 +1,183 @@
+use serde::Serialize;
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+use serde_json::{json, Value};
+use crate::block_state::BlockState;
+use crate::datapack::resource::Resource;
+use crate::error::DatapackError;
+use crate::utils::json::json_file;
+
+/// The density functions a [`NoiseSettings`] routes into, all constant 0 so no terrain is
+/// generated.
+const EMPTY_NOISE_ROUTER: [&str; 15] = [
+    "barrier",
+    "fluid_level_floodedness",
+    "fluid_level_spread",
+    "lava",
+    "temperature",
+    "vegetation",
+    "continents",
+    "erosion",
+    "depth",
+    "ridges",
+    "initial_density_without_jaggedness",
+    "final_density",
+    "vein_toggle",
+    "vein_ridged",
+    "vein_gap",
+];
+
+/// The vertical extent of the terrain and the size of its noise cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct NoiseConfig {
+    /// The lowest block that can generate. Must be a multiple of 16.
+    pub min_y: i32,
+    /// The number of blocks that can generate above `min_y`. Must be a multiple of 16.
+    pub height: u32,
+    /// The width of a noise cell, in quarters of a chunk, from 1 to 4.
+    pub size_horizontal: u32,
+    /// The height of a noise cell, in quarters of a chunk, from 1 to 4.
+    pub size_vertical: u32,
+}
+
+impl NoiseConfig {
+    pub fn new(min_y: i32, height: u32) -> Self {
+        Self { min_y, height, size_horizontal: 1, size_vertical: 2 }
+    }
+
+    pub fn cell_size(mut self, size_horizontal: u32, size_vertical: u32) -> Self {
+        self.size_horizontal = size_horizontal;
+        self.size_vertical = size_vertical;
+        self
+    }
+}
+
+/// A `data/<namespace>/worldgen/noise_settings` file: the shape of a dimension's terrain.
+///
+/// The `noise_router` and `surface_rule` are written as is. Until they are set, they
+/// generate no terrain and place no surface blocks.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct NoiseSettings {
+    pub sea_level: i32,
+    pub disable_mob_generation: bool,
+    pub aquifers_enabled: bool,
+    pub ore_veins_enabled: bool,
+    pub legacy_random_source: bool,
+    pub default_block: BlockState,
+    pub default_fluid: BlockState,
+    pub noise: NoiseConfig,
+    pub noise_router: Value,
+    pub surface_rule: Value,
+    #[serde(default)]
+    pub spawn_target: Vec<Value>,
+}
+
+impl NoiseSettings {
+    pub fn new(noise: NoiseConfig, default_block: impl Into<BlockState>, default_fluid: impl Into<BlockState>) -> Self {
+        Self {
+            sea_level: 63,
+            disable_mob_generation: false,
+            aquifers_enabled: false,
+            ore_veins_enabled: false,
+            legacy_random_source: false,
+            default_block: default_block.into(),
+            default_fluid: default_fluid.into(),
+            noise,
+            noise_router: EMPTY_NOISE_ROUTER.iter().map(|name| (name.to_string(), json!(0))).collect(),
+            surface_rule: json!({"type": "minecraft:sequence", "sequence": []}),
+            spawn_target: Vec::new(),
+        }
+    }
+
+    pub fn sea_level(mut self, sea_level: i32) -> Self {
+        self.sea_level = sea_level;
+        self
+    }
+
+    pub fn disable_mob_generation(mut self) -> Self {
+        self.disable_mob_generation = true;
+        self
+    }
+
+    pub fn aquifers(mut self) -> Self {
+        self.aquifers_enabled = true;
+        self
+    }
+
+    pub fn ore_veins(mut self) -> Self {
+        self.ore_veins_enabled = true;
+        self
+    }
+
+    pub fn noise_router(mut self, noise_router: Value) -> Self {
+        self.noise_router = noise_router;
+        self
+    }
+
+    pub fn surface_rule(mut self, surface_rule: Value) -> Self {
+        self.surface_rule = surface_rule;
+        self
+    }
+}
+
+json_file!(NoiseSettings);
+
+impl Resource for NoiseSettings {
+    const DIRECTORY: &'static str = "worldgen/noise_settings";
+    const MIN_PACK_FORMAT: u32 = 6;
+
+    fn validate(&self) -> Result<(), DatapackError> {
+        let invalid = |message: String| Err(DatapackError::Validation(format!("invalid noise settings: {message}")));
+        let NoiseConfig { min_y, height, size_horizontal, size_vertical } = self.noise;
+        if min_y % 16 != 0 || height % 16 != 0 {
+            return invalid(format!("min_y {min_y} and height {height} must be multiples of 16"));
+        }
+        if !(1..=4).contains(&size_horizontal) || !(1..=4).contains(&size_vertical) {
+            return invalid(format!("the noise cell size {size_horizontal}x{size_vertical} must be from 1 to 4"));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resource_location::ResourceLocation;
+
+    fn location(location: &str) -> ResourceLocation {
+        location.parse().expect("should have parsed the resource location")
+    }
+
+    fn settings(noise: NoiseConfig) -> NoiseSettings {
+        NoiseSettings::new(noise, location("minecraft:stone"), location("minecraft:water"))
+    }
+
+    #[test]
+    fn noise_block() {
+        let settings = settings(NoiseConfig::new(-64, 384).cell_size(1, 2)).sea_level(0).aquifers();
+        let json: Value = serde_json::from_str(&settings.to_string()).expect("noise settings should be valid JSON");
+        assert_eq!(json["noise"], json!({"min_y": -64, "height": 384, "size_horizontal": 1, "size_vertical": 2}));
+        assert_eq!(json["sea_level"], 0);
+        assert_eq!(json["aquifers_enabled"], true);
+        assert_eq!(json["noise_router"]["final_density"], 0);
+        assert!(settings.validate().is_ok());
+
+        assert!(self::settings(NoiseConfig::new(-60, 384)).validate().is_err(), "min_y should be a multiple of 16");
+        assert!(self::settings(NoiseConfig::new(0, 256).cell_size(5, 2)).validate().is_err(), "cells should be at most 4 wide");
+    }
+
+    #[test]
+    fn default_block_state() {
+        let settings = settings(NoiseConfig::new(0, 256));
+        let json: Value = serde_json::from_str(&settings.to_string()).expect("noise settings should be valid JSON");
+        assert_eq!(json["default_block"], json!({"Name": "minecraft:stone"}));
+        assert_eq!(json["default_fluid"], json!({"Name": "minecraft:water"}));
+
+        let mut settings = settings;
+        settings.default_fluid = BlockState::new(location("minecraft:lava")).property("level", "0");
+        let json = serde_json::to_value(&settings).expect("should have serialized the noise settings");
+        assert_eq!(json["default_fluid"], json!({"Name": "minecraft:lava", "Properties": {"level": "0"}}));
+    }
+}
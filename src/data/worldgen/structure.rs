@@ -0,0 +1,268 @@
+use std::collections::BTreeMap;
+use serde::{Serialize, Serializer};
+use serde_json::{Map, Value};
+use crate::data::worldgen::biome::{Biome, GenerationStep, MobCategory, Spawner};
+use crate::data::worldgen::feature::Heightmap;
+use crate::data::worldgen::height_provider::HeightProvider;
+use crate::data::worldgen::template_pool::TemplatePool;
+use crate::datapack::resource::{Reference, Resource};
+use crate::error::DatapackError;
+use crate::resource_location::ResourceLocation;
+use crate::utils::json::json_file;
+
+/// The biomes a structure may generate in.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Biomes {
+    /// The biomes of a biome tag, written with a `#`.
+    Tag(ResourceLocation),
+    List(Vec<ResourceLocation>),
+}
+
+impl Serialize for Biomes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Tag(tag) => serializer.serialize_str(&format!("#{tag}")),
+            Self::List(biomes) => biomes.serialize(serializer),
+        }
+    }
+}
+
+impl Biomes {
+    /// References to the listed biomes. Biome tags have no tag kind yet, so they aren't
+    /// followed.
+    fn references(&self) -> Vec<Reference> {
+        match self {
+            Self::Tag(_) => Vec::new(),
+            Self::List(biomes) => biomes.iter().map(|biome| Reference::to::<Biome>(biome.clone())).collect(),
+        }
+    }
+}
+
+/// How the terrain around a structure is changed to fit it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TerrainAdaptation {
+    /// Fills the space under the structure and clears the space above it, like villages.
+    BeardThin,
+    /// Like [`TerrainAdaptation::BeardThin`], but fills the whole box, like ancient cities.
+    BeardBox,
+    /// Buries the structure, like strongholds.
+    Bury,
+    /// Surrounds the structure with terrain, like trail ruins.
+    Encapsulate,
+}
+
+/// Which part of a structure its spawn overrides apply to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BoundingBox {
+    /// Only inside its pieces, like the witch huts.
+    Piece,
+    /// Anywhere in its outer bounds, like pillager outposts.
+    Full,
+}
+
+/// The mobs that spawn in a structure instead of those of its biome.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SpawnOverride {
+    pub bounding_box: BoundingBox,
+    pub spawns: Vec<Spawner>,
+}
+
+impl SpawnOverride {
+    pub fn new(bounding_box: BoundingBox) -> Self {
+        Self { bounding_box, spawns: Vec::new() }
+    }
+
+    pub fn spawn(mut self, spawner: Spawner) -> Self {
+        self.spawns.push(spawner);
+        self
+    }
+}
+
+/// The config of a `minecraft:jigsaw` structure, which is built from pieces of template
+/// pools, starting from `start_pool`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct JigsawConfig {
+    pub start_pool: ResourceLocation,
+    /// How many pieces away from the start the structure may reach, from 0 to 20.
+    pub size: u32,
+    pub start_height: HeightProvider,
+    /// When set, `start_height` is relative to this heightmap.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_start_to_heightmap: Option<Heightmap>,
+    pub max_distance_from_center: u32,
+    /// Whether pieces may overlap the way they did before 1.18, which villages rely on.
+    pub use_expansion_hack: bool,
+    /// The jigsaw block of the start piece the structure is built from, instead of the
+    /// piece's origin.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_jigsaw_name: Option<ResourceLocation>,
+}
+
+impl JigsawConfig {
+    pub fn new(start_pool: ResourceLocation, size: u32, start_height: impl Into<HeightProvider>) -> Self {
+        Self {
+            start_pool,
+            size,
+            start_height: start_height.into(),
+            project_start_to_heightmap: None,
+            max_distance_from_center: 80,
+            use_expansion_hack: false,
+            start_jigsaw_name: None,
+        }
+    }
+
+    pub fn project_start_to_heightmap(mut self, heightmap: Heightmap) -> Self {
+        self.project_start_to_heightmap = Some(heightmap);
+        self
+    }
+
+    pub fn max_distance_from_center(mut self, max_distance_from_center: u32) -> Self {
+        self.max_distance_from_center = max_distance_from_center;
+        self
+    }
+
+    pub fn use_expansion_hack(mut self) -> Self {
+        self.use_expansion_hack = true;
+        self
+    }
+
+    pub fn start_jigsaw_name(mut self, name: ResourceLocation) -> Self {
+        self.start_jigsaw_name = Some(name);
+        self
+    }
+}
+
+/// What kind of structure a [`Structure`] is, with its type-specific config.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type")]
+pub enum StructureType {
+    #[serde(rename = "minecraft:jigsaw")]
+    Jigsaw(JigsawConfig),
+    /// Any other structure type, with its config written as is. It should include its own
+    /// `type`.
+    #[serde(untagged)]
+    Raw(Map<String, Value>),
+}
+
+/// A `data/<namespace>/worldgen/structure` file, available since 1.19: what a structure is
+/// and where it may generate. Where it is attempted is decided by the
+/// [`StructureSet`](crate::data::worldgen::structure_set::StructureSet)s that list it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Structure {
+    #[serde(flatten)]
+    pub structure_type: StructureType,
+    pub biomes: Biomes,
+    pub step: GenerationStep,
+    pub spawn_overrides: BTreeMap<MobCategory, SpawnOverride>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub terrain_adaptation: Option<TerrainAdaptation>,
+}
+
+impl Structure {
+    pub fn new(structure_type: StructureType, biomes: Biomes, step: GenerationStep) -> Self {
+        Self { structure_type, biomes, step, spawn_overrides: BTreeMap::new(), terrain_adaptation: None }
+    }
+
+    pub fn jigsaw(config: JigsawConfig, biomes: Biomes, step: GenerationStep) -> Self {
+        Self::new(StructureType::Jigsaw(config), biomes, step)
+    }
+
+    pub fn spawn_override(mut self, category: MobCategory, spawn_override: SpawnOverride) -> Self {
+        self.spawn_overrides.insert(category, spawn_override);
+        self
+    }
+
+    pub fn terrain_adaptation(mut self, terrain_adaptation: TerrainAdaptation) -> Self {
+        self.terrain_adaptation = Some(terrain_adaptation);
+        self
+    }
+}
+
+json_file!(Structure);
+
+impl Resource for Structure {
+    const DIRECTORY: &'static str = "worldgen/structure";
+    const MIN_PACK_FORMAT: u32 = 10;
+
+    fn validate(&self) -> Result<(), DatapackError> {
+        match &self.structure_type {
+            StructureType::Jigsaw(config) if config.size > 20 => Err(DatapackError::Validation(format!(
+                "invalid structure: a jigsaw size of {} is more than 20",
+                config.size,
+            ))),
+            StructureType::Raw(config) if !config.contains_key("type") => {
+                Err(DatapackError::Validation("invalid structure: the raw config has no type".to_string()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn references(&self) -> Vec<Reference> {
+        let mut references = self.biomes.references();
+        if let StructureType::Jigsaw(config) = &self.structure_type {
+            references.push(Reference::to::<TemplatePool>(config.start_pool.clone()));
+        }
+        references
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use crate::data::worldgen::height_provider::VerticalAnchor;
+
+    fn location(location: &str) -> ResourceLocation {
+        location.parse().expect("should have parsed the resource location")
+    }
+
+    /// `minecraft:village_plains` from the game's data.
+    #[test]
+    fn vanilla_village() {
+        let config = JigsawConfig::new(location("minecraft:village/plains/town_centers"), 6, VerticalAnchor::Absolute(0))
+            .project_start_to_heightmap(Heightmap::WorldSurfaceWg)
+            .use_expansion_hack();
+        let village = Structure::jigsaw(config, Biomes::Tag(location("minecraft:has_structure/village_plains")), GenerationStep::SurfaceStructures)
+            .terrain_adaptation(TerrainAdaptation::BeardThin);
+        let json: Value = serde_json::from_str(&village.to_string()).expect("structure should be valid JSON");
+        assert_eq!(json, json!({
+            "type": "minecraft:jigsaw",
+            "biomes": "#minecraft:has_structure/village_plains",
+            "step": "surface_structures",
+            "spawn_overrides": {},
+            "terrain_adaptation": "beard_thin",
+            "start_pool": "minecraft:village/plains/town_centers",
+            "size": 6,
+            "start_height": {"type": "minecraft:constant", "value": {"absolute": 0}},
+            "project_start_to_heightmap": "WORLD_SURFACE_WG",
+            "max_distance_from_center": 80,
+            "use_expansion_hack": true,
+        }));
+        assert!(village.validate().is_ok());
+    }
+
+    #[test]
+    fn spawn_overrides_and_references() {
+        let config = JigsawConfig::new(location("test:tower/start"), 21, VerticalAnchor::Absolute(64));
+        let tower = Structure::jigsaw(config, Biomes::List(vec![location("test:haunted_forest")]), GenerationStep::SurfaceStructures)
+            .spawn_override(
+                MobCategory::Monster,
+                SpawnOverride::new(BoundingBox::Piece).spawn(Spawner::new(location("minecraft:witch"), 1, 1, 1)),
+            );
+        let json = serde_json::to_value(&tower).expect("should have serialized the structure");
+        assert_eq!(json["biomes"], json!(["test:haunted_forest"]));
+        assert_eq!(json["spawn_overrides"], json!({
+            "monster": {"bounding_box": "piece", "spawns": [{"type": "minecraft:witch", "weight": 1, "minCount": 1, "maxCount": 1}]},
+        }));
+        assert!(json.get("terrain_adaptation").is_none(), "an unset terrain adaptation should be left out");
+        assert!(tower.validate().is_err(), "jigsaw structures are at most 20 pieces deep");
+
+        let references = tower.references();
+        assert_eq!(references.len(), 2);
+        assert!(references[0].is_to::<Biome>());
+        assert_eq!(references[0].location, location("test:haunted_forest"));
+        assert!(references[1].is_to::<TemplatePool>(), "the start pool should be referenced");
+    }
+}
@@ -0,0 +1,160 @@
+use serde::Serialize;
+use crate::data::worldgen::structure::{Biomes, Structure};
+use crate::datapack::resource::{Reference, Resource};
+use crate::error::DatapackError;
+use crate::resource_location::ResourceLocation;
+use crate::utils::json::json_file;
+
+/// How the position of a structure is picked in each region of a
+/// [`StructurePlacement::RandomSpread`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpreadType {
+    Linear,
+    /// Positions near the middle of the region are more likely.
+    Triangular,
+}
+
+/// Where the structures of a [`StructureSet`] are attempted.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type")]
+pub enum StructurePlacement {
+    /// Once in each square region of `spacing` chunks, at least `separation` chunks from
+    /// the edge of the next one.
+    #[serde(rename = "minecraft:random_spread")]
+    RandomSpread {
+        spacing: u32,
+        separation: u32,
+        /// Mixed into the seed, so sets with the same spacing don't line up.
+        salt: i32,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        spread_type: Option<SpreadType>,
+    },
+    /// `count` structures spread over rings around the world origin, like strongholds.
+    #[serde(rename = "minecraft:concentric_rings")]
+    ConcentricRings {
+        /// How far apart the rings are, in chunks times 6.
+        distance: u32,
+        /// How many structures are in the first ring.
+        spread: u32,
+        count: u32,
+        /// The biomes each structure is moved towards.
+        preferred_biomes: Biomes,
+        salt: i32,
+    },
+}
+
+impl StructurePlacement {
+    pub fn random_spread(spacing: u32, separation: u32, salt: i32) -> Self {
+        Self::RandomSpread { spacing, separation, salt, spread_type: None }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WeightedStructure {
+    pub structure: ResourceLocation,
+    pub weight: u32,
+}
+
+/// A `data/<namespace>/worldgen/structure_set` file, available since 1.19: where a group of
+/// structures is attempted, picking one of them by weight each time.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StructureSet {
+    pub structures: Vec<WeightedStructure>,
+    pub placement: StructurePlacement,
+}
+
+impl StructureSet {
+    pub fn new(placement: StructurePlacement) -> Self {
+        Self { structures: Vec::new(), placement }
+    }
+
+    pub fn structure(mut self, structure: ResourceLocation, weight: u32) -> Self {
+        self.structures.push(WeightedStructure { structure, weight });
+        self
+    }
+}
+
+json_file!(StructureSet);
+
+impl Resource for StructureSet {
+    const DIRECTORY: &'static str = "worldgen/structure_set";
+    const MIN_PACK_FORMAT: u32 = 10;
+
+    fn validate(&self) -> Result<(), DatapackError> {
+        let invalid = |message: String| Err(DatapackError::Validation(format!("invalid structure set: {message}")));
+        if self.structures.is_empty() {
+            return invalid("it has no structures".to_string());
+        }
+        if self.structures.iter().any(|structure| structure.weight == 0) {
+            return invalid("structure weights must be at least 1".to_string());
+        }
+        match self.placement {
+            StructurePlacement::RandomSpread { spacing, separation, .. } if separation >= spacing => {
+                invalid(format!("the separation {separation} must be less than the spacing {spacing}"))
+            }
+            StructurePlacement::RandomSpread { spacing, .. } if spacing > 4096 => {
+                invalid(format!("the spacing {spacing} is more than 4096"))
+            }
+            StructurePlacement::ConcentricRings { count: 0, .. } => invalid("concentric rings need a count of at least 1".to_string()),
+            _ => Ok(()),
+        }
+    }
+
+    fn references(&self) -> Vec<Reference> {
+        self.structures.iter().map(|structure| Reference::to::<Structure>(structure.structure.clone())).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn location(location: &str) -> ResourceLocation {
+        location.parse().expect("should have parsed the resource location")
+    }
+
+    /// `minecraft:villages` from the game's data.
+    #[test]
+    fn vanilla_villages() {
+        let villages = ["plains", "desert", "savanna", "snowy", "taiga"]
+            .into_iter()
+            .fold(StructureSet::new(StructurePlacement::random_spread(34, 8, 10387312)), |set, village| {
+                set.structure(location(&format!("minecraft:village_{village}")), 1)
+            });
+        let json: Value = serde_json::from_str(&villages.to_string()).expect("structure set should be valid JSON");
+        assert_eq!(json, json!({
+            "structures": [
+                {"structure": "minecraft:village_plains", "weight": 1},
+                {"structure": "minecraft:village_desert", "weight": 1},
+                {"structure": "minecraft:village_savanna", "weight": 1},
+                {"structure": "minecraft:village_snowy", "weight": 1},
+                {"structure": "minecraft:village_taiga", "weight": 1},
+            ],
+            "placement": {"type": "minecraft:random_spread", "spacing": 34, "separation": 8, "salt": 10387312},
+        }));
+        assert!(villages.validate().is_ok());
+        assert!(villages.references().iter().all(Reference::is_to::<Structure>));
+    }
+
+    #[test]
+    fn placements() {
+        let placement = StructurePlacement::RandomSpread { spacing: 8, separation: 8, salt: 1, spread_type: Some(SpreadType::Triangular) };
+        let set = StructureSet::new(placement).structure(location("test:tower"), 1);
+        assert_eq!(serde_json::to_value(&set).expect("should have serialized the set")["placement"]["spread_type"], "triangular");
+        assert!(set.validate().is_err(), "the separation must be less than the spacing");
+        assert!(StructureSet::new(StructurePlacement::random_spread(8, 4, 1)).validate().is_err(), "a set needs structures");
+
+        let rings = StructurePlacement::ConcentricRings {
+            distance: 32,
+            spread: 3,
+            count: 128,
+            preferred_biomes: Biomes::Tag(location("minecraft:stronghold_biased_to")),
+            salt: 0,
+        };
+        let json = serde_json::to_value(rings).expect("should have serialized the placement");
+        assert_eq!(json["type"], "minecraft:concentric_rings");
+        assert_eq!(json["preferred_biomes"], "#minecraft:stronghold_biased_to");
+    }
+}
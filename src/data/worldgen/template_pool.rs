@@ -0,0 +1,215 @@
+use serde::Serialize;
+use crate::data::worldgen::feature::PlacedFeature;
+use crate::datapack::resource::{Reference, Resource};
+use crate::error::DatapackError;
+use crate::resource_location::ResourceLocation;
+use crate::utils::json::json_file;
+use crate::utils::weighted_list::{Weighted, WeightedList};
+
+/// How a piece is placed on the terrain.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Projection {
+    /// Placed flat, as built.
+    #[default]
+    Rigid,
+    /// Each column is moved to the surface, like village paths.
+    TerrainMatching,
+}
+
+fn empty_processors() -> ResourceLocation {
+    ResourceLocation::minecraft("empty").expect("the path is valid")
+}
+
+/// A piece a [`TemplatePool`] can pick.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "element_type")]
+pub enum StructurePoolElement {
+    /// The structure template at `location`, changed by the processor list `processors`.
+    #[serde(rename = "minecraft:single_pool_element")]
+    Single { location: ResourceLocation, processors: ResourceLocation, projection: Projection },
+    /// Like [`StructurePoolElement::Single`], but air in the template doesn't replace
+    /// blocks, as before 1.16. The game's villages still use it for houses.
+    #[serde(rename = "minecraft:legacy_single_pool_element")]
+    LegacySingle { location: ResourceLocation, processors: ResourceLocation, projection: Projection },
+    /// A placed feature, such as a lamp post.
+    #[serde(rename = "minecraft:feature_pool_element")]
+    Feature { feature: ResourceLocation, projection: Projection },
+    /// Several elements placed at the same spot.
+    #[serde(rename = "minecraft:list_pool_element")]
+    List { elements: Vec<StructurePoolElement>, projection: Projection },
+    #[serde(rename = "minecraft:empty_pool_element")]
+    Empty,
+}
+
+impl StructurePoolElement {
+    /// The template at `location`, without processors, placed rigidly.
+    pub fn single(location: ResourceLocation) -> Self {
+        Self::Single { location, processors: empty_processors(), projection: Projection::Rigid }
+    }
+
+    /// Like [`StructurePoolElement::single`], as a legacy element.
+    pub fn legacy_single(location: ResourceLocation) -> Self {
+        Self::LegacySingle { location, processors: empty_processors(), projection: Projection::Rigid }
+    }
+
+    pub fn feature(feature: ResourceLocation, projection: Projection) -> Self {
+        Self::Feature { feature, projection }
+    }
+
+    /// Sets the processor list of a single element. Other elements have none.
+    pub fn processors(mut self, processor_list: ResourceLocation) -> Self {
+        if let Self::Single { processors, .. } | Self::LegacySingle { processors, .. } = &mut self {
+            *processors = processor_list;
+        }
+        self
+    }
+
+    /// Sets the projection of any element but an empty one.
+    pub fn projection(mut self, projection: Projection) -> Self {
+        match &mut self {
+            Self::Single { projection: existing, .. }
+            | Self::LegacySingle { projection: existing, .. }
+            | Self::Feature { projection: existing, .. }
+            | Self::List { projection: existing, .. } => *existing = projection,
+            Self::Empty => {}
+        }
+        self
+    }
+
+    fn references(&self) -> Vec<Reference> {
+        match self {
+            Self::Feature { feature, .. } => vec![Reference::to::<PlacedFeature>(feature.clone())],
+            Self::List { elements, .. } => elements.iter().flat_map(Self::references).collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WeightedElement {
+    pub element: StructurePoolElement,
+    /// From 1 to 150.
+    pub weight: u32,
+}
+
+impl Weighted for WeightedElement {
+    fn weight(&self) -> u32 {
+        self.weight
+    }
+
+    fn set_weight(&mut self, weight: u32) {
+        self.weight = weight;
+    }
+}
+
+/// A `data/<namespace>/worldgen/template_pool` file: the pieces a jigsaw block can pick
+/// from, by weight.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TemplatePool {
+    /// The pool used instead once a structure reaches its size, usually
+    /// `minecraft:empty`.
+    pub fallback: ResourceLocation,
+    pub elements: WeightedList<WeightedElement>,
+}
+
+impl TemplatePool {
+    pub fn new() -> Self {
+        Self { fallback: ResourceLocation::minecraft("empty").expect("the path is valid"), elements: WeightedList::new() }
+    }
+
+    pub fn fallback(mut self, fallback: ResourceLocation) -> Self {
+        self.fallback = fallback;
+        self
+    }
+
+    pub fn element(mut self, element: StructurePoolElement, weight: u32) -> Self {
+        self.elements.push_value(WeightedElement { element, weight });
+        self
+    }
+}
+
+impl Default for TemplatePool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+json_file!(TemplatePool);
+
+impl Resource for TemplatePool {
+    const DIRECTORY: &'static str = "worldgen/template_pool";
+    const MIN_PACK_FORMAT: u32 = 6;
+
+    fn validate(&self) -> Result<(), DatapackError> {
+        match self.elements.iter().find(|(_, weight)| !(1..=150).contains(weight)) {
+            Some((_, weight)) => Err(DatapackError::Validation(format!("invalid template pool: the weight {weight} must be from 1 to 150"))),
+            None => Ok(()),
+        }
+    }
+
+    fn references(&self) -> Vec<Reference> {
+        let elements = self.elements.values().flat_map(|element| element.element.references());
+        elements.chain([Reference::to::<TemplatePool>(self.fallback.clone())]).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn location(location: &str) -> ResourceLocation {
+        location.parse().expect("should have parsed the resource location")
+    }
+
+    #[test]
+    fn single_element_pool() {
+        let pool = TemplatePool::new().element(StructurePoolElement::single(location("test:tower/base")), 1);
+        let json: Value = serde_json::from_str(&pool.to_string()).expect("template pool should be valid JSON");
+        assert_eq!(json, json!({
+            "fallback": "minecraft:empty",
+            "elements": [{
+                "element": {
+                    "element_type": "minecraft:single_pool_element",
+                    "location": "test:tower/base",
+                    "processors": "minecraft:empty",
+                    "projection": "rigid",
+                },
+                "weight": 1,
+            }],
+        }));
+        assert!(pool.validate().is_ok());
+        assert!(TemplatePool::new().element(StructurePoolElement::Empty, 151).validate().is_err(), "weights are at most 150");
+    }
+
+    /// Part of `minecraft:village/plains/houses` from the game's data.
+    #[test]
+    fn legacy_elements() {
+        let house = StructurePoolElement::legacy_single(location("minecraft:village/plains/houses/plains_small_house_1"))
+            .processors(location("minecraft:mossify_10_percent"));
+        let path = StructurePoolElement::single(location("minecraft:village/plains/streets/corner_01"))
+            .projection(Projection::TerrainMatching);
+        let pool = TemplatePool::new()
+            .fallback(location("minecraft:village/plains/terminators"))
+            .element(house, 2)
+            .element(path, 1)
+            .element(StructurePoolElement::feature(location("test:lamp_post"), Projection::Rigid), 1)
+            .element(StructurePoolElement::Empty, 5);
+        let json = serde_json::to_value(&pool).expect("should have serialized the pool");
+        assert_eq!(json["elements"][0]["element"], json!({
+            "element_type": "minecraft:legacy_single_pool_element",
+            "location": "minecraft:village/plains/houses/plains_small_house_1",
+            "processors": "minecraft:mossify_10_percent",
+            "projection": "rigid",
+        }));
+        assert_eq!(json["elements"][1]["element"]["element_type"], "minecraft:single_pool_element");
+        assert_eq!(json["elements"][1]["element"]["projection"], "terrain_matching");
+        assert_eq!(json["elements"][3]["element"], json!({"element_type": "minecraft:empty_pool_element"}));
+
+        let references = pool.references();
+        assert!(references[0].is_to::<PlacedFeature>());
+        assert!(references[1].is_to::<TemplatePool>());
+        assert_eq!(references.len(), 2);
+    }
+}
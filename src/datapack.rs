@@ -0,0 +1,1263 @@
+pub mod namespace;
+pub mod pack_icon;
+pub mod pack_format;
+pub mod pack_meta;
+pub mod resource;
+pub mod call_graph;
+pub mod diff;
+pub mod lifecycle;
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
+use std::ffi::{OsStr, OsString};
+use std::fmt::{self, Display};
+use std::fs::{self, create_dir};
+use std::io::Result;
+use std::iter;
+use std::mem;
+use std::ops::Bound;
+use std::path::{Component, Path, PathBuf};
+use crate::data::advancement::Advancement;
+use crate::data::chat_type::ChatType;
+use crate::data::conditions::Predicate;
+use crate::data::damage_type::DamageType;
+use crate::data::dimension::{Dimension, DimensionType};
+use crate::data::enchantment::Enchantment;
+use crate::data::function::McFunction;
+use crate::data::jukebox_song::JukeboxSong;
+use crate::data::loot_function::ItemModifier;
+use crate::data::loot_table::LootTable;
+use crate::data::painting_variant::PaintingVariant;
+use crate::data::recipe::Recipe;
+use crate::data::trim::{TrimMaterial, TrimPattern};
+use crate::data::wolf_variant::WolfVariant;
+use crate::data::worldgen::biome::Biome;
+use crate::data::worldgen::carver::ConfiguredCarver;
+use crate::data::worldgen::density_function::DensityFunction;
+use crate::data::worldgen::feature::{ConfiguredFeature, PlacedFeature};
+use crate::data::worldgen::noise_settings::NoiseSettings;
+use crate::data::worldgen::structure::Structure;
+use crate::data::worldgen::structure_set::StructureSet;
+use crate::data::worldgen::template_pool::TemplatePool;
+use crate::data::tag::{Tag, TagEntry, TagKind, TagValues};
+use crate::error::DatapackError;
+use crate::resource_location::{is_valid_namespace, ResourceLocation, DEFAULT_NAMESPACE};
+use crate::utils::file_system::{back_up, with_style, Directory, Entry, PackStats, WriteOptions};
+#[cfg(feature = "tokio")]
+use crate::utils::file_system::CreateFuture;
+use crate::utils::json::{JsonStyle, Styled};
+use call_graph::CallGraph;
+use lifecycle::{Lifecycle, LifecycleFunction, Stage};
+use namespace::Namespace;
+use pack_icon::{PackIcon, PACK_ICON_FILE_NAME};
+use pack_meta::{FormatRange, OverlayEntry, PackMeta, OVERLAYS_PACK_FORMAT, PACK_META_FILE_NAME};
+use resource::{Reference, Resource};
+
+/// What [`Datapack::insert`] does when an entry already exists at the resolved path.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    /// Reject the new entry with [`DatapackError::DuplicateEntry`].
+    #[default]
+    Error,
+    /// Keep the entry that was inserted first and log a warning.
+    KeepFirst,
+    /// Replace the existing entry with the new one and log a warning.
+    KeepLast,
+}
+
+/// A reference from the file at `path` to a resource that isn't in the datapack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DanglingReference {
+    pub path: PathBuf,
+    pub location: ResourceLocation,
+}
+
+/// How sure [`Datapack::validate`] is that a reference won't resolve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// The reference is into the `minecraft` namespace, or a namespace this datapack has
+    /// no files in, so the game or another datapack may provide it.
+    Warning,
+    /// The reference is into a namespace of this datapack, so it is most likely a renamed
+    /// or misspelled resource.
+    Error,
+}
+
+/// A reference [`Datapack::validate`] couldn't resolve.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationIssue {
+    pub severity: Severity,
+    /// The file with the reference.
+    pub path: PathBuf,
+    pub location: ResourceLocation,
+    /// Where the referenced resource would be in this datapack.
+    pub expected: PathBuf,
+}
+
+impl Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "'{}' refers to '{}', but there is nothing at '{}'",
+            self.path.display(),
+            self.location,
+            self.expected.display(),
+        )
+    }
+}
+
+/// A file that the oldest pack format it is used with doesn't load, as found by
+/// [`Datapack::check_compatibility`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Incompatibility {
+    pub path: PathBuf,
+    /// The directory of the file's type of resource.
+    pub directory: &'static str,
+    pub required: u32,
+    pub pack_format: u32,
+}
+
+impl Display for Incompatibility {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "'{}' is in '{}', which requires pack format {} or newer, but it is used with pack format {}",
+            self.path.display(),
+            self.directory,
+            self.required,
+            self.pack_format,
+        )
+    }
+}
+
+/// What is at a path of a [`Datapack`]: an inserted entry, or a file the datapack renders
+/// from its own state when it is written, so that it has everything added by then.
+enum Slot<'a> {
+    Entry(Box<dyn Entry + 'a>),
+    /// A tag made by [`Datapack::add_to_tag`], with the values added so far.
+    Tag(Vec<TagEntry>),
+    /// The function of `stage` of the lifecycle of the namespace `namespace`.
+    Lifecycle { namespace: String, stage: Stage },
+}
+
+/// What [`Datapack::transaction`] restores if it fails.
+struct Transaction<'a> {
+    references: BTreeMap<PathBuf, Vec<Reference>>,
+    lifecycles: BTreeMap<String, Lifecycle>,
+    /// Each path whose slot changed, in order, with the slot it had before.
+    entries: Vec<(PathBuf, Option<Slot<'a>>)>,
+}
+
+pub struct Datapack<'a> {
+    meta: PackMeta,
+    icon: Option<PackIcon>,
+    entries: BTreeMap<PathBuf, Slot<'a>>,
+    /// What each resource added with [`Datapack::add`] refers to, by its path.
+    references: BTreeMap<PathBuf, Vec<Reference>>,
+    /// The lifecycle functions of each namespace, by its name.
+    lifecycles: BTreeMap<String, Lifecycle>,
+    duplicate_policy: DuplicatePolicy,
+    json_style: JsonStyle,
+    transaction: Option<Transaction<'a>>,
+}
+
+impl<'a> Datapack<'a> {
+    pub fn new(meta: PackMeta) -> Self {
+        Self {
+            meta,
+            icon: None,
+            entries: BTreeMap::new(),
+            references: BTreeMap::new(),
+            lifecycles: BTreeMap::new(),
+            duplicate_policy: DuplicatePolicy::default(),
+            json_style: JsonStyle::default(),
+            transaction: None,
+        }
+    }
+
+    pub fn with_duplicate_policy(mut self, duplicate_policy: DuplicatePolicy) -> Self {
+        self.duplicate_policy = duplicate_policy;
+        self
+    }
+
+    /// Adds the PNG image `bytes` as the `pack.png` icon of the datapack.
+    pub fn with_icon(mut self, bytes: Vec<u8>) -> Self {
+        self.icon = Some(PackIcon::new(bytes));
+        self
+    }
+
+    /// The layout of every JSON file of the datapack, including `pack.mcmeta`.
+    pub fn with_json_style(mut self, json_style: JsonStyle) -> Self {
+        self.json_style = json_style;
+        self
+    }
+
+    pub fn json_style(&self) -> JsonStyle {
+        self.json_style
+    }
+
+    pub fn meta(&self) -> &PackMeta {
+        &self.meta
+    }
+
+    /// Inserts `entry` at `path`, relative to the root of the datapack.
+    ///
+    /// Collisions are detected on the resolved path, so `data/a/./b` and `data/a/b`
+    /// are the same entry. `pack.mcmeta` is generated from the [`PackMeta`] and can
+    /// never be inserted, and neither can `pack.png`, which is set with
+    /// [`Datapack::with_icon`].
+    pub fn insert(&mut self, path: impl AsRef<Path>, entry: impl Entry + 'a) -> std::result::Result<(), DatapackError> {
+        self.insert_slot(path.as_ref(), Slot::Entry(Box::new(entry)))
+    }
+
+    fn insert_slot(&mut self, path: &Path, slot: Slot<'a>) -> std::result::Result<(), DatapackError> {
+        let path = resolve(path)?;
+        if path.starts_with(PACK_META_FILE_NAME) || path.starts_with(PACK_ICON_FILE_NAME) {
+            return Err(DatapackError::DuplicateEntry(path));
+        }
+        if let Some(existing) = self.enclosing_entry(&path) {
+            return Err(DatapackError::DuplicateEntry(existing));
+        }
+        if self.entries.contains_key(&path) {
+            match self.duplicate_policy {
+                DuplicatePolicy::Error => return Err(DatapackError::DuplicateEntry(path)),
+                DuplicatePolicy::KeepFirst => {
+                    log::warn!("an entry already exists at '{}', keeping the first one", path.display());
+                    return Ok(());
+                }
+                DuplicatePolicy::KeepLast => {
+                    log::warn!("an entry already exists at '{}', replacing it", path.display());
+                    self.references.remove(&path);
+                }
+            }
+        }
+        let previous = self.entries.insert(path.clone(), slot);
+        self.record(path, previous);
+        Ok(())
+    }
+
+    /// Runs `f`, and undoes everything it added if it fails, so functions adding several
+    /// resources, such as a resource and its tag, add all of them or none.
+    pub(crate) fn transaction<T>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> std::result::Result<T, DatapackError>,
+    ) -> std::result::Result<T, DatapackError> {
+        let transaction = Transaction { references: self.references.clone(), lifecycles: self.lifecycles.clone(), entries: Vec::new() };
+        let outer = self.transaction.replace(transaction);
+        let result = f(self);
+        let transaction = mem::replace(&mut self.transaction, outer).expect("the transaction should still be open");
+        match &result {
+            Ok(_) => {
+                if let Some(outer) = &mut self.transaction {
+                    outer.entries.extend(transaction.entries);
+                }
+            }
+            Err(_) => {
+                self.references = transaction.references;
+                self.lifecycles = transaction.lifecycles;
+                for (path, previous) in transaction.entries.into_iter().rev() {
+                    match previous {
+                        Some(slot) => self.entries.insert(path, slot),
+                        None => self.entries.remove(&path),
+                    };
+                }
+            }
+        }
+        result
+    }
+
+    /// Remembers that the slot at `path` was `previous`, for the open transaction to undo.
+    fn record(&mut self, path: PathBuf, previous: Option<Slot<'a>>) {
+        if let Some(transaction) = &mut self.transaction {
+            transaction.entries.push((path, previous));
+        }
+    }
+
+    /// An entry that would have to be a directory to hold one at `path`, or one inside the
+    /// directory `path` would have to be, since a path can't be both a file and a directory.
+    fn enclosing_entry(&self, path: &Path) -> Option<PathBuf> {
+        if let Some(parent) = path.ancestors().skip(1).find(|parent| self.entries.contains_key(*parent)) {
+            return Some(parent.to_path_buf());
+        }
+        let (next, _) = self.entries.range::<Path, _>((Bound::Excluded(path), Bound::Unbounded)).next()?;
+        next.starts_with(path).then(|| next.clone())
+    }
+
+    /// Moves the entries of `overlay` into `directory`, next to `data`, and lists the
+    /// directory in `pack.mcmeta` so the game uses its files instead of the ones in `data`
+    /// when its pack format is in `formats`.
+    ///
+    /// The resources of `overlay` are placed for the overlay's pack format, so an overlay for
+    /// older versions can use the older directory names. Its `pack.mcmeta` is not used.
+    pub fn add_overlay(
+        &mut self,
+        formats: impl Into<FormatRange>,
+        directory: impl Into<String>,
+        mut overlay: Datapack<'a>,
+    ) -> std::result::Result<(), DatapackError> {
+        let directory = directory.into();
+        let pack_format = self.meta.pack_format;
+        if pack_format < OVERLAYS_PACK_FORMAT {
+            return Err(DatapackError::UnsupportedPackFormat { directory: "overlays", required: OVERLAYS_PACK_FORMAT, pack_format });
+        }
+        let is_valid_name = |c: char| matches!(c, 'a'..='z' | '0'..='9' | '_' | '-');
+        if directory.is_empty() || !directory.chars().all(is_valid_name) || directory == "data" {
+            return Err(DatapackError::InvalidPath(PathBuf::from(directory)));
+        }
+        for (path, slot) in overlay.take_slots() {
+            self.insert_slot(&Path::new(&directory).join(path), slot)?;
+        }
+        self.meta.overlays.push(OverlayEntry { formats: formats.into(), directory });
+        Ok(())
+    }
+
+    pub fn namespace(&mut self, name: impl Into<String>) -> std::result::Result<Namespace<'_, 'a>, DatapackError> {
+        let name = name.into();
+        if !is_valid_namespace(&name) {
+            return Err(DatapackError::InvalidResourceLocation(name));
+        }
+        Ok(Namespace::new(self, name))
+    }
+
+    /// Inserts `resource` at the path its type and `location` resolve to, once
+    /// [`Resource::validate`] accepts it.
+    pub fn add<R: Resource + 'a>(&mut self, location: &ResourceLocation, mut resource: R) -> std::result::Result<(), DatapackError> {
+        let pack_format = self.meta.pack_format;
+        if pack_format < R::MIN_PACK_FORMAT {
+            return Err(DatapackError::UnsupportedPackFormat {
+                directory: R::directory(pack_format),
+                required: R::MIN_PACK_FORMAT,
+                pack_format,
+            });
+        }
+        resource.set_pack_format(pack_format);
+        resource.validate()?;
+        if pack_format < R::STABLE_PACK_FORMAT {
+            log::warn!(
+                "'{}' is experimental before pack format {}, but the pack format is {pack_format}",
+                R::directory(pack_format),
+                R::STABLE_PACK_FORMAT,
+            );
+        }
+        let path = resource_path::<R>(location, pack_format);
+        let references = resource.references();
+        let kept_first = self.duplicate_policy == DuplicatePolicy::KeepFirst && self.entries.contains_key(&path);
+        self.insert(&path, resource)?;
+        if !kept_first && !references.is_empty() {
+            self.references.insert(path, references);
+        }
+        Ok(())
+    }
+
+    /// Adds `value` to the tag `tag`, creating the tag the first time, so several parts of
+    /// a generator can add to a tag such as `minecraft:tick` without building it together.
+    ///
+    /// A tag added with [`Datapack::add`] can't be added to, and is a duplicate entry for
+    /// the [`DuplicatePolicy`].
+    pub fn add_to_tag<K: TagKind>(&mut self, tag: &ResourceLocation, value: impl Into<TagEntry>) -> std::result::Result<(), DatapackError> {
+        let value = value.into();
+        let path = resource_path::<Tag<K>>(tag, self.meta.pack_format);
+        let reference = value.reference::<K>();
+        match self.entries.get_mut(&path) {
+            Some(Slot::Tag(values)) => {
+                let previous = self.transaction.is_some().then(|| Slot::Tag(values.clone()));
+                values.push(value);
+                if let Some(previous) = previous {
+                    self.record(path.clone(), Some(previous));
+                }
+            }
+            existing => {
+                let kept_first = self.duplicate_policy == DuplicatePolicy::KeepFirst && existing.is_some();
+                self.insert_slot(&path, Slot::Tag(vec![value]))?;
+                if kept_first {
+                    return Ok(());
+                }
+            }
+        }
+        self.references.entry(path).or_default().extend(reference);
+        Ok(())
+    }
+
+    /// The references of added resources, such as the functions of function tags, to
+    /// resources that aren't in the datapack, including those into namespaces it has no
+    /// files in, which [`Datapack::validate`] only warns about.
+    ///
+    /// References into the `minecraft` namespace are skipped, since the game provides
+    /// them.
+    pub fn validate_references(&self) -> Vec<DanglingReference> {
+        self.validate()
+            .into_iter()
+            .filter(|issue| issue.location.namespace() != DEFAULT_NAMESPACE)
+            .map(|issue| DanglingReference { path: issue.path, location: issue.location })
+            .collect()
+    }
+
+    /// Checks, before writing, that everything added with [`Datapack::add`] and
+    /// [`Datapack::add_to_tag`] refers to resources of this datapack: the parents and
+    /// rewards of advancements, the predicates, item modifiers and loot tables of loot
+    /// tables, the values of tags, the functions functions call and the predicates and
+    /// loot tables their commands use, and so on.
+    ///
+    /// References into the `minecraft` namespace, or into a namespace this datapack has no
+    /// files in, are only [warnings](Severity::Warning), since the game or another datapack
+    /// may provide them.
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        let pack_format = self.meta.pack_format;
+        let namespaces: BTreeSet<&OsStr> = self.entries
+            .keys()
+            .filter_map(|path| path.strip_prefix("data").ok()?.iter().next())
+            .collect();
+        let mut issues = Vec::new();
+        for (path, references) in &self.references {
+            for reference in references {
+                let expected = reference.path(pack_format);
+                if self.entries.contains_key(&expected) {
+                    continue;
+                }
+                let namespace = reference.location.namespace();
+                let severity = match namespace != DEFAULT_NAMESPACE && namespaces.contains(OsStr::new(namespace)) {
+                    true => Severity::Error,
+                    false => Severity::Warning,
+                };
+                issues.push(ValidationIssue { severity, path: path.clone(), location: reference.location.clone(), expected });
+            }
+        }
+        issues
+    }
+
+    /// The files whose type of resource the pack format they are used with doesn't load,
+    /// such as an enchantment in a pack for 1.20. Files in `data` are checked against the
+    /// pack format, and those in an overlay against the oldest pack format of the overlay.
+    ///
+    /// [`Datapack::add`] already rejects such resources, so this finds those inserted as
+    /// files with [`Datapack::insert`] and those of overlays listed for older versions than
+    /// their own pack format.
+    pub fn check_compatibility(&self) -> Vec<Incompatibility> {
+        let mut incompatibilities = Vec::new();
+        for path in self.entries.keys() {
+            let (pack_format, data) = match path.strip_prefix("data") {
+                Ok(data) => (self.meta.pack_format, data),
+                Err(_) => {
+                    let mut components = path.iter();
+                    let Some(directory) = components.next() else { continue };
+                    let Some(overlay) = self.meta.overlays.iter().find(|overlay| overlay.directory.as_str() == directory) else { continue };
+                    let Ok(data) = components.as_path().strip_prefix("data") else { continue };
+                    (overlay.formats.min_inclusive, data)
+                }
+            };
+            // Skips the namespace.
+            let resource = data.iter().skip(1).collect::<PathBuf>();
+            let requirements = format_requirements(pack_format);
+            let requirement = requirements.iter().find(|(directory, _)| resource.starts_with(directory));
+            if let Some(&(directory, required)) = requirement.filter(|(_, required)| pack_format < *required) {
+                incompatibilities.push(Incompatibility { path: path.clone(), directory, required, pack_format });
+            }
+        }
+        incompatibilities
+    }
+
+    /// Which functions call which, from the `function` commands of the functions added
+    /// with [`Datapack::add`].
+    pub fn call_graph(&self) -> CallGraph {
+        let pack_format = self.meta.pack_format;
+        let mut calls: BTreeMap<ResourceLocation, BTreeSet<ResourceLocation>> = BTreeMap::new();
+        for path in self.entries.keys() {
+            let Some(namespace) = path.strip_prefix("data").ok().and_then(|path| path.iter().next()) else { continue };
+            let functions = Path::new("data").join(namespace).join(McFunction::directory(pack_format));
+            if path.extension().is_none_or(|extension| extension != McFunction::EXTENSION) {
+                continue;
+            }
+            let Ok(function) = ResourceLocation::try_from_path(path, &functions) else { continue };
+            let called = self.references
+                .get(path)
+                .into_iter()
+                .flatten()
+                .filter(|reference| reference.is_to::<McFunction>())
+                .map(|reference| reference.location.clone());
+            calls.entry(function).or_default().extend(called);
+        }
+        CallGraph::new(calls)
+    }
+
+    pub fn contains(&self, path: impl AsRef<Path>) -> bool {
+        resolve(path.as_ref()).is_ok_and(|path| self.entries.contains_key(&path))
+    }
+
+    /// Removes `root` if it exists and then creates the datapack there, so no stale
+    /// files from a previous generation are left behind.
+    ///
+    /// Fails without removing anything if `root` exists but has no `pack.mcmeta`.
+    pub fn write_clean(&self, root: &Path) -> std::result::Result<(), DatapackError> {
+        if root.exists() {
+            if !root.join(PACK_META_FILE_NAME).is_file() {
+                return Err(DatapackError::NotADatapack(root.to_path_buf()));
+            }
+            fs::remove_dir_all(root)?;
+        }
+        Ok(self.create(root)?)
+    }
+
+    /// Each entry of the directory keeps the datapack's [`JsonStyle`].
+    pub fn into_directory(mut self) -> Directory<'a> {
+        let style = self.json_style;
+        let mut root = Node::default();
+        for (path, slot) in self.take_slots() {
+            let entry = match slot {
+                Slot::Entry(entry) => entry,
+                Slot::Tag(values) => Box::new(TagValues(Cow::Owned(values))),
+                Slot::Lifecycle { .. } => unreachable!("lifecycle functions should have been rendered"),
+            };
+            root.insert(&path, Box::new(Styled { entry, style }));
+        }
+        let mut directory = root.into_directory();
+        directory.insert(OsString::from(PACK_META_FILE_NAME), Box::new(Styled { entry: Box::new(self.meta), style }));
+        if let Some(icon) = self.icon {
+            directory.insert(OsString::from(PACK_ICON_FILE_NAME), Box::new(icon));
+        }
+        directory
+    }
+
+    /// Takes the entries of the datapack by their path, with the lifecycle functions
+    /// rendered, since nothing can be added to them anymore.
+    fn take_slots(&mut self) -> impl Iterator<Item = (PathBuf, Slot<'a>)> {
+        let lifecycles = mem::take(&mut self.lifecycles);
+        mem::take(&mut self.entries).into_iter().map(move |(path, slot)| match slot {
+            Slot::Lifecycle { namespace, stage } => (path, Slot::Entry(Box::new(lifecycles[&namespace].function(stage)))),
+            slot => (path, slot),
+        })
+    }
+
+    /// `pack.mcmeta`, `pack.png` if the pack has an icon, then the added entries by their
+    /// whole path, each with its JSON laid out in the datapack's [`JsonStyle`].
+    fn styled_entries(&self) -> impl Iterator<Item = (&Path, Box<dyn Entry + '_>)> {
+        let meta = (Path::new(PACK_META_FILE_NAME), &self.meta as &dyn Entry);
+        let icon = self.icon.as_ref().map(|icon| (Path::new(PACK_ICON_FILE_NAME), icon as &dyn Entry));
+        let files = iter::once(meta).chain(icon).map(|(path, entry)| (path, with_style(entry, self.json_style)));
+        files.chain(self.entries.iter().map(|(path, slot)| (path.as_path(), self.render(slot))))
+    }
+
+    fn render<'s>(&'s self, slot: &'s Slot<'a>) -> Box<dyn Entry + 's> {
+        let style = self.json_style;
+        match slot {
+            Slot::Entry(entry) => with_style(&**entry, style),
+            Slot::Tag(values) => Box::new(Styled { entry: Box::new(TagValues(Cow::Borrowed(values))), style }),
+            Slot::Lifecycle { namespace, stage } => Box::new(LifecycleFunction { lifecycle: &self.lifecycles[namespace], stage: *stage }),
+        }
+    }
+}
+
+impl Entry for Datapack<'_> {
+    fn create(&self, path: &Path) -> Result<()> {
+        create_dir(path)?;
+        for (relative_path, entry) in self.styled_entries() {
+            let entry_path = path.join(relative_path);
+            if let Some(parent) = entry_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            entry.create(&entry_path)?;
+        }
+        Ok(())
+    }
+
+    fn stats(&self) -> PackStats {
+        let parents: BTreeSet<&Path> = self.entries
+            .keys()
+            .flat_map(|path| path.ancestors().skip(1))
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .collect();
+        let directories = PackStats { directories: 1 + parents.len(), ..PackStats::default() };
+        directories + self.styled_entries().map(|(_, entry)| entry.stats()).sum()
+    }
+
+    /// Backs up replaced files one by one, like a [`Directory`] does.
+    fn create_with_backup(&self, path: &Path) -> std::result::Result<Option<PathBuf>, DatapackError> {
+        let backup = if path.is_dir() {
+            None
+        } else {
+            let backup = back_up(path)?;
+            create_dir(path)?;
+            backup
+        };
+        for (relative_path, entry) in self.styled_entries() {
+            let entry_path = path.join(relative_path);
+            if let Some(parent) = entry_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            entry.create_with_backup(&entry_path)?;
+        }
+        Ok(backup)
+    }
+
+    fn create_with_options(&self, path: &Path, options: &WriteOptions) -> std::result::Result<(), DatapackError> {
+        options.create_dir(path)?;
+        let mut pending = Vec::new();
+        for (relative_path, entry) in self.styled_entries() {
+            let entry_path = path.join(relative_path);
+            for directory in relative_path.ancestors().skip(1).collect::<Vec<_>>().into_iter().rev().skip(1) {
+                let directory = path.join(directory);
+                if !directory.is_dir() {
+                    options.create_dir(&directory)?;
+                }
+            }
+            options.create_entry(&entry_path, &*entry, &mut pending)?;
+        }
+        Ok(options.write_pending(pending)?)
+    }
+
+    #[cfg(feature = "tokio")]
+    fn create_async<'e>(&'e self, path: &'e Path) -> CreateFuture<'e> {
+        Box::pin(async move {
+            tokio::fs::create_dir(path).await?;
+            for (relative_path, entry) in self.styled_entries() {
+                let entry_path = path.join(relative_path);
+                if let Some(parent) = entry_path.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
+                }
+                entry.create_async(&entry_path).await?;
+            }
+            Ok(())
+        })
+    }
+
+    /// `pack.mcmeta`, `pack.png` if the pack has an icon, then the added entries by their
+    /// whole path, without the directories between them, in the pack's [`JsonStyle`].
+    fn for_each_entry(&self, f: &mut dyn FnMut(&Path, &dyn Entry)) {
+        for (path, entry) in self.styled_entries() {
+            f(path, &*entry);
+        }
+    }
+}
+
+/// The directory of each type of resource that needs a newer pack format than the first,
+/// with the oldest pack format that loads it, by the [`Resource::MIN_PACK_FORMAT`] of
+/// every type of resource. Tags are left out, since every pack format loads them.
+///
+/// Directories are named as in `pack_format`, since some were renamed.
+fn format_requirements(pack_format: u32) -> Vec<(&'static str, u32)> {
+    fn requirement<R: Resource>(pack_format: u32) -> (&'static str, u32) {
+        (R::directory(pack_format), R::MIN_PACK_FORMAT)
+    }
+
+    [
+        requirement::<Advancement>(pack_format),
+        requirement::<Biome>(pack_format),
+        requirement::<ChatType>(pack_format),
+        requirement::<ConfiguredCarver>(pack_format),
+        requirement::<ConfiguredFeature>(pack_format),
+        requirement::<DamageType>(pack_format),
+        requirement::<DensityFunction>(pack_format),
+        requirement::<Dimension>(pack_format),
+        requirement::<DimensionType>(pack_format),
+        requirement::<Enchantment>(pack_format),
+        requirement::<ItemModifier>(pack_format),
+        requirement::<JukeboxSong>(pack_format),
+        requirement::<LootTable>(pack_format),
+        requirement::<McFunction>(pack_format),
+        requirement::<NoiseSettings>(pack_format),
+        requirement::<PaintingVariant>(pack_format),
+        requirement::<PlacedFeature>(pack_format),
+        requirement::<Predicate>(pack_format),
+        requirement::<Recipe>(pack_format),
+        requirement::<Structure>(pack_format),
+        requirement::<StructureSet>(pack_format),
+        requirement::<TemplatePool>(pack_format),
+        requirement::<TrimMaterial>(pack_format),
+        requirement::<TrimPattern>(pack_format),
+        requirement::<WolfVariant>(pack_format),
+    ]
+    .into_iter()
+    .filter(|(_, required)| *required > 1)
+    .collect()
+}
+
+pub fn resource_path<R: Resource>(location: &ResourceLocation, pack_format: u32) -> PathBuf {
+    Path::new("data")
+        .join(location.namespace())
+        .join(R::directory(pack_format))
+        .join(format!("{}.{}", location.path(), R::EXTENSION))
+}
+
+fn resolve(path: &Path) -> std::result::Result<PathBuf, DatapackError> {
+    let mut resolved = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(name) => resolved.push(name),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !resolved.pop() {
+                    return Err(DatapackError::InvalidPath(path.to_path_buf()));
+                }
+            }
+            Component::RootDir | Component::Prefix(_) => {
+                return Err(DatapackError::InvalidPath(path.to_path_buf()));
+            }
+        }
+    }
+    if resolved.as_os_str().is_empty() {
+        return Err(DatapackError::InvalidPath(path.to_path_buf()));
+    }
+    Ok(resolved)
+}
+
+#[derive(Default)]
+struct Node<'a> {
+    children: BTreeMap<OsString, Node<'a>>,
+    entry: Option<Box<dyn Entry + 'a>>,
+}
+
+impl<'a> Node<'a> {
+    fn insert(&mut self, path: &Path, entry: Box<dyn Entry + 'a>) {
+        let node = path.iter().fold(self, |node, name| node.children.entry(name.to_os_string()).or_default());
+        node.entry = Some(entry);
+    }
+
+    fn into_directory(self) -> Directory<'a> {
+        self.children
+            .into_iter()
+            .map(|(name, node)| match node.entry {
+                Some(entry) => (name, entry),
+                None => (name, Box::new(node.into_directory()) as Box<dyn Entry>),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::advancement::{Advancement, AdvancementDisplay, AdvancementRewards, Trigger};
+    use crate::data::conditions::LootCondition;
+    use crate::data::function::McFunction;
+    use crate::data::loot_table::{LootEntry, LootPool, LootTable};
+    use crate::data::recipe::{Recipe, RecipeResult, StonecuttingRecipe};
+    use crate::data::tag::{FunctionTag, Tag, TagEntry};
+
+    use tempdir::TempDir;
+
+    struct StringFile(&'static str);
+
+    impl std::fmt::Display for StringFile {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            f.write_str(self.0)
+        }
+    }
+
+    impl crate::utils::file_system::File for StringFile {}
+
+    const ENTRY_PATH: &str = "data/test/function/duplicate.mcfunction";
+
+    fn test_datapack<'a>(duplicate_policy: DuplicatePolicy) -> Datapack<'a> {
+        Datapack::new(PackMeta::new(48, "test")).with_duplicate_policy(duplicate_policy)
+    }
+
+    fn created_contents(datapack: &Datapack) -> String {
+        let temp_dir = TempDir::new(module_path!()).expect("should have created temp dir");
+        let path = temp_dir.path().join("test_datapack");
+        datapack.create(&path).expect("should have created the datapack");
+        let contents = fs::read_to_string(path.join(ENTRY_PATH))
+            .expect("should have been able to read the file");
+        temp_dir.close().expect("should have closed temp dir");
+        contents
+    }
+
+    #[test]
+    fn duplicate_error_policy() {
+        let mut datapack = test_datapack(DuplicatePolicy::Error);
+        datapack.insert(ENTRY_PATH, StringFile("first")).expect("should have inserted the first entry");
+
+        let result = datapack.insert("data/test/./function/../function/duplicate.mcfunction", StringFile("second"));
+        match result {
+            Err(DatapackError::DuplicateEntry(path)) => assert_eq!(path, Path::new(ENTRY_PATH)),
+            _ => panic!("should have reported a duplicate entry at '{ENTRY_PATH}'"),
+        }
+        assert_eq!(created_contents(&datapack), "first", "the first entry should not have been replaced");
+    }
+
+    #[test]
+    fn duplicate_keep_first_policy() {
+        let mut datapack = test_datapack(DuplicatePolicy::KeepFirst);
+        datapack.insert(ENTRY_PATH, StringFile("first")).expect("should have inserted the first entry");
+        datapack.insert(ENTRY_PATH, StringFile("second")).expect("should have skipped the second entry");
+        assert_eq!(created_contents(&datapack), "first", "the first entry should have been kept");
+    }
+
+    #[test]
+    fn duplicate_keep_last_policy() {
+        let mut datapack = test_datapack(DuplicatePolicy::KeepLast);
+        datapack.insert(ENTRY_PATH, StringFile("first")).expect("should have inserted the first entry");
+        datapack.insert(ENTRY_PATH, StringFile("second")).expect("should have replaced the first entry");
+        assert_eq!(created_contents(&datapack), "second", "the last entry should have been kept");
+    }
+
+    #[test]
+    fn same_leaf_in_different_namespaces() {
+        let mut datapack = test_datapack(DuplicatePolicy::Error);
+        datapack.insert("data/a/function/main.mcfunction", StringFile("a")).expect("should have inserted 'a'");
+        datapack.insert("data/b/function/main.mcfunction", StringFile("b")).expect("should have inserted 'b'");
+    }
+
+    #[test]
+    fn file_and_directory_at_same_path() {
+        let (file, nested) = ("data/test/function/a.mcfunction", "data/test/function/a.mcfunction/b.mcfunction");
+        for policy in [DuplicatePolicy::Error, DuplicatePolicy::KeepLast] {
+            let mut datapack = test_datapack(policy);
+            datapack.insert(file, StringFile("a")).expect("should have inserted the file");
+            let error = datapack.insert(nested, StringFile("b")).expect_err("the file can't be a directory too");
+            assert!(matches!(&error, DatapackError::DuplicateEntry(path) if path == Path::new(file)), "unexpected error: {error}");
+
+            let mut datapack = test_datapack(policy);
+            datapack.insert(nested, StringFile("b")).expect("should have inserted the nested file");
+            let error = datapack.insert(file, StringFile("a")).expect_err("the directory can't be a file too");
+            assert!(matches!(&error, DatapackError::DuplicateEntry(path) if path == Path::new(nested)), "unexpected error: {error}");
+            datapack.insert("data/test/function/a", StringFile("c")).expect("a path that only starts with the same name doesn't collide");
+        }
+    }
+
+    #[test]
+    fn invalid_paths() {
+        let mut datapack = test_datapack(DuplicatePolicy::Error);
+        for path in ["../outside", "/absolute", ".", PACK_META_FILE_NAME, "pack.png/inside"] {
+            assert!(datapack.insert(path, StringFile("")).is_err(), "'{path}' should have been rejected");
+        }
+    }
+
+    #[test]
+    fn write_clean_removes_stale_files() {
+        let temp_dir = TempDir::new(module_path!()).expect("should have created temp dir");
+        let path = temp_dir.path().join("test_datapack");
+
+        let mut datapack = test_datapack(DuplicatePolicy::Error);
+        datapack.insert("data/test/function/old.mcfunction", StringFile("old")).expect("should have inserted the entry");
+        datapack.write_clean(&path).expect("should have created the datapack");
+
+        let mut datapack = test_datapack(DuplicatePolicy::Error);
+        datapack.insert(ENTRY_PATH, StringFile("new")).expect("should have inserted the entry");
+        datapack.write_clean(&path).expect("should have recreated the datapack");
+        assert!(!path.join("data/test/function/old.mcfunction").exists(), "stale file was not removed");
+        assert!(path.join(ENTRY_PATH).exists(), "didn't create '{ENTRY_PATH}'");
+
+        temp_dir.close().expect("should have closed temp dir");
+    }
+
+    #[test]
+    fn write_clean_refuses_non_datapacks() {
+        let temp_dir = TempDir::new(module_path!()).expect("should have created temp dir");
+        let path = temp_dir.path().join("not_a_datapack");
+        fs::create_dir(&path).expect("should have created the directory");
+        fs::write(path.join("important.txt"), "keep me").expect("should have written the file");
+
+        let datapack = test_datapack(DuplicatePolicy::Error);
+        match datapack.write_clean(&path) {
+            Err(DatapackError::NotADatapack(_)) => {}
+            _ => panic!("should have refused to remove a directory without '{PACK_META_FILE_NAME}'"),
+        }
+        assert!(path.join("important.txt").exists(), "the directory should have been left untouched");
+
+        temp_dir.close().expect("should have closed temp dir");
+    }
+
+    #[test]
+    fn datapack_stats() {
+        let mut datapack = test_datapack(DuplicatePolicy::Error);
+        datapack.insert(ENTRY_PATH, StringFile("say hi")).expect("should have inserted the entry");
+        datapack.insert("data/test/function/nested/other.mcfunction", StringFile("say bye")).expect("should have inserted the entry");
+
+        let stats = datapack.stats();
+        let meta_bytes = datapack.meta().to_string().len() as u64;
+        assert_eq!(stats, PackStats { bytes: meta_bytes + 13, files: 3, directories: 5 }, "stats of the datapack are incorrect");
+        assert_eq!(datapack.into_directory().stats(), stats, "the directory should have the same stats as the datapack");
+    }
+
+    #[test]
+    fn create_with_options_reports_directories() {
+        use crate::utils::file_system::ProgressEvent;
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let mut datapack = test_datapack(DuplicatePolicy::Error);
+        datapack.insert(ENTRY_PATH, StringFile("say hi")).expect("should have inserted the entry");
+        datapack.insert("data/test/function/other.mcfunction", StringFile("say bye")).expect("should have inserted the entry");
+        let temp_dir = TempDir::new(module_path!()).expect("should have created temp dir");
+        let path = temp_dir.path().join("test_datapack");
+
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let recorded = Rc::clone(&events);
+        let options = WriteOptions::default().on_progress(move |event| recorded.borrow_mut().push(event));
+        datapack.create_with_options(&path, &options).expect("should have created the datapack");
+        let directories: Vec<_> = events
+            .borrow()
+            .iter()
+            .filter_map(|event| match event {
+                ProgressEvent::DirectoryCreated { path } => Some(path.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(directories, [path.clone(), path.join("data"), path.join("data/test"), path.join("data/test/function")]);
+
+        temp_dir.close().expect("should have closed temp dir");
+    }
+
+    #[test]
+    fn into_directory_create() {
+        let mut datapack = test_datapack(DuplicatePolicy::Error);
+        datapack.insert(ENTRY_PATH, StringFile("say hi")).expect("should have inserted the entry");
+        let directory = datapack.into_directory();
+
+        let temp_dir = TempDir::new(module_path!()).expect("should have created temp dir");
+        let path = temp_dir.path().join("test_datapack");
+        directory.create(&path).expect("should have created the directory");
+        assert!(path.join(PACK_META_FILE_NAME).exists(), "didn't create '{PACK_META_FILE_NAME}'");
+        let contents = fs::read_to_string(path.join(ENTRY_PATH))
+            .expect("should have been able to read the file");
+        assert_eq!(contents, "say hi", "'{ENTRY_PATH}' does not contain the correct contents");
+
+        temp_dir.close().expect("should have closed temp dir");
+    }
+
+    #[test]
+    fn json_styles() {
+        let tag_path = "data/minecraft/tags/function/load.json";
+        let function_path = "data/test/function/load.mcfunction";
+        let location = |location: &str| location.parse::<ResourceLocation>().expect("should have parsed the resource location");
+        let styled = |style: JsonStyle| {
+            let mut datapack = test_datapack(DuplicatePolicy::Error).with_json_style(style);
+            let mut namespace = datapack.namespace("test").expect("should have accepted the namespace");
+            let text = crate::text::TextComponent::text("loaded").bold();
+            let load = namespace.add_function("load", McFunction::new().command(format_args!("tellraw @a {text}")))
+                .expect("should have added the function");
+            datapack
+                .namespace("minecraft")
+                .expect("should have accepted the namespace")
+                .add_tag("load", Tag::<FunctionTag>::new().value(load).value(TagEntry::optional(location("other:load"))))
+                .expect("should have added the tag");
+            datapack
+        };
+
+        let temp_dir = TempDir::new(module_path!()).expect("should have created temp dir");
+        let read = |name: &str, file: &str| {
+            fs::read_to_string(temp_dir.path().join(name).join(file)).expect("should have been able to read the file")
+        };
+        let pretty = styled(JsonStyle::Pretty { indent: 4 });
+        let compact = styled(JsonStyle::Compact);
+        let pretty_stats = pretty.stats();
+        assert!(compact.stats().bytes < pretty_stats.bytes, "the compact datapack should be smaller");
+        pretty.create(&temp_dir.path().join("pretty")).expect("should have created the datapack");
+        assert!(compact.meta().to_string().contains('\n'), "a file displayed outside of a datapack should be in the default style");
+        compact.into_directory()
+            .styled(JsonStyle::Pretty { indent: 4 })
+            .expect("a directory has a styled version")
+            .create(&temp_dir.path().join("compact"))
+            .expect("should have created the directory");
+
+        for file in [PACK_META_FILE_NAME, tag_path] {
+            let (pretty, compact) = (read("pretty", file), read("compact", file));
+            assert!(pretty.starts_with("{\n    \""), "'{file}' should have been indented by 4 spaces");
+            assert!(!compact.contains('\n'), "'{file}' should have been on one line");
+            let value = |json: &str| serde_json::from_str::<serde_json::Value>(json).expect("should have been valid JSON");
+            assert_eq!(value(&pretty), value(&compact), "'{file}' should have had the same contents in both styles");
+        }
+        assert_eq!(read("pretty", function_path), read("compact", function_path), "commands should not depend on the style");
+        assert_eq!(read("pretty", function_path), "tellraw @a {\"text\":\"loaded\",\"bold\":true}\n");
+        assert_eq!(pretty_stats, pretty.stats(), "the style should be the same every time");
+
+        temp_dir.close().expect("should have closed temp dir");
+    }
+
+    #[test]
+    fn icon() {
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+        let datapack = test_datapack(DuplicatePolicy::Error).with_icon(png.clone());
+        let meta_bytes = datapack.meta().to_string().len() as u64;
+        assert_eq!(datapack.stats(), PackStats { bytes: meta_bytes + png.len() as u64, files: 2, directories: 1 });
+
+        let temp_dir = TempDir::new(module_path!()).expect("should have created temp dir");
+        let path = temp_dir.path().join("test_datapack");
+        datapack.into_directory().create(&path).expect("should have created the directory");
+        assert_eq!(fs::read(path.join(PACK_ICON_FILE_NAME)).expect("should have been able to read the icon"), png);
+
+        let mut datapack = test_datapack(DuplicatePolicy::KeepLast);
+        assert!(matches!(datapack.insert(PACK_ICON_FILE_NAME, StringFile("")), Err(DatapackError::DuplicateEntry(_))));
+
+        temp_dir.close().expect("should have closed temp dir");
+    }
+
+    #[test]
+    fn overlays() {
+        let mut overlay = Datapack::new(PackMeta::new(41, "ignored"));
+        overlay.add(&"test:main".parse().expect("should have parsed the resource location"), McFunction::new().command("say old"))
+            .expect("should have added the function");
+        let mut datapack = test_datapack(DuplicatePolicy::Error);
+        datapack.insert(ENTRY_PATH, StringFile("say new")).expect("should have inserted the entry");
+        datapack.add_overlay(18..=41, "legacy", overlay).expect("should have added the overlay");
+        assert!(datapack.contains("legacy/data/test/functions/main.mcfunction"), "the overlay should use its own pack format");
+        assert_eq!(datapack.meta().overlays, [OverlayEntry { formats: FormatRange::new(18, 41), directory: "legacy".to_string() }]);
+
+        let empty = || Datapack::new(PackMeta::new(41, "ignored"));
+        assert!(matches!(datapack.add_overlay(48, "Upper", empty()), Err(DatapackError::InvalidPath(_))));
+        assert!(matches!(datapack.add_overlay(48, "data", empty()), Err(DatapackError::InvalidPath(_))));
+        let mut old = Datapack::new(PackMeta::new(15, "test"));
+        assert!(matches!(old.add_overlay(15, "old", empty()), Err(DatapackError::UnsupportedPackFormat { .. })));
+    }
+
+    #[test]
+    fn check_compatibility() {
+        let location = |location: &str| location.parse::<ResourceLocation>().expect("should have parsed the resource location");
+        let mut datapack = Datapack::new(PackMeta::new(10, "test"));
+        let recipe = StonecuttingRecipe::new(location("minecraft:stone"), RecipeResult::new(location("minecraft:stone_slab"), 2));
+        datapack.add(&location("test:slab"), Recipe::from(recipe)).expect("should have added the recipe");
+        datapack.insert("data/test/enchantment/sharper.json", StringFile("{}")).expect("should have inserted the enchantment");
+        assert_eq!(datapack.check_compatibility(), [Incompatibility {
+            path: PathBuf::from("data/test/enchantment/sharper.json"),
+            directory: "enchantment",
+            required: 48,
+            pack_format: 10,
+        }], "only the enchantment needs a newer pack format");
+
+        let mut datapack = Datapack::new(PackMeta::new(8, "test"));
+        datapack.insert("data/test/worldgen/density_function/hills.json", StringFile("0.5")).expect("should have inserted the density function");
+        let required: Vec<u32> = datapack.check_compatibility().iter().map(|incompatibility| incompatibility.required).collect();
+        assert_eq!(required, [9], "density functions were added in 1.18.2");
+
+        let mut datapack = Datapack::new(PackMeta::new(6, "test"));
+        datapack.insert("data/test/worldgen/biome/plains.json", StringFile("{}")).expect("should have inserted the biome");
+        datapack.insert("data/test/worldgen/placed_feature/ore.json", StringFile("{}")).expect("should have inserted the placed feature");
+        datapack.insert("data/test/item_modifiers/shine.json", StringFile("{}")).expect("should have inserted the item modifier");
+        let incompatible: Vec<(&str, u32)> = datapack.check_compatibility().iter().map(|incompatibility| (incompatibility.directory, incompatibility.required)).collect();
+        assert_eq!(incompatible, [("item_modifiers", 7), ("worldgen/placed_feature", 8)], "custom biomes load from 1.16.2, but not the rest");
+
+        let mut overlay = Datapack::new(PackMeta::new(48, "ignored"));
+        overlay.insert("data/test/enchantment/sharper.json", StringFile("{}")).expect("should have inserted the enchantment");
+        overlay.insert("data/test/damage_type/zap.json", StringFile("{}")).expect("should have inserted the damage type");
+        let mut datapack = test_datapack(DuplicatePolicy::Error);
+        datapack.add_overlay(41..=48, "newer", overlay).expect("should have added the overlay");
+        let incompatibilities: Vec<String> = datapack.check_compatibility().iter().map(|incompatibility| incompatibility.path.display().to_string()).collect();
+        assert_eq!(incompatibilities, ["newer/data/test/enchantment/sharper.json"], "1.20.5 uses the overlay, but can't load enchantments");
+    }
+
+    #[test]
+    fn create_overlays() {
+        let mut overlay = Datapack::new(PackMeta::new(48, "ignored"));
+        overlay.insert(ENTRY_PATH, StringFile("say newer")).expect("should have inserted the entry");
+        let mut datapack = test_datapack(DuplicatePolicy::Error);
+        datapack.insert(ENTRY_PATH, StringFile("say new")).expect("should have inserted the entry");
+        datapack.add_overlay((48, 61), "v48", overlay).expect("should have added the overlay");
+
+        let temp_dir = TempDir::new(module_path!()).expect("should have created temp dir");
+        let path = temp_dir.path().join("test_datapack");
+        datapack.create(&path).expect("should have created the datapack");
+        let overlay_contents = fs::read_to_string(path.join("v48").join(ENTRY_PATH)).expect("should have been able to read the overlay file");
+        assert_eq!(overlay_contents, "say newer");
+        assert_eq!(created_contents(&datapack), "say new", "the overlay should not replace the file in 'data'");
+
+        let meta = fs::read_to_string(path.join(PACK_META_FILE_NAME)).expect("should have been able to read the pack.mcmeta");
+        let meta: serde_json::Value = serde_json::from_str(&meta).expect("pack.mcmeta should be valid JSON");
+        assert_eq!(meta["overlays"]["entries"], serde_json::json!([
+            {"formats": {"min_inclusive": 48, "max_inclusive": 61}, "directory": "v48"},
+        ]));
+
+        temp_dir.close().expect("should have closed temp dir");
+    }
+
+    #[test]
+    fn function_tag_references() {
+        let location = |location: &str| location.parse::<ResourceLocation>().expect("should have parsed the resource location");
+        let mut datapack = test_datapack(DuplicatePolicy::Error);
+        datapack.add(&location("test:tick"), McFunction::new().command("say tick")).expect("should have added the function");
+        datapack
+            .add(&location("test:all"), Tag::<FunctionTag>::new().value(location("test:tick")))
+            .expect("should have added the tag");
+        datapack
+            .add(&location("minecraft:tick"), Tag::<FunctionTag>::new()
+                .value(location("test:tick"))
+                .value(location("test:tikc"))
+                .value(location("minecraft:vanilla"))
+                .value(TagEntry::optional(location("other:maybe")))
+                .tag(location("test:all"))
+                .tag(location("test:missing")))
+            .expect("should have added the tag");
+
+        let tick_path = PathBuf::from("data/minecraft/tags/function/tick.json");
+        assert_eq!(datapack.validate_references(), [
+            DanglingReference { path: tick_path.clone(), location: location("test:tikc") },
+            DanglingReference { path: tick_path, location: location("test:missing") },
+        ], "only the misspelled function and the missing tag should be dangling");
+    }
+
+    #[test]
+    fn validate() {
+        let location = |location: &str| location.parse::<ResourceLocation>().expect("should have parsed the resource location");
+        let mut datapack = test_datapack(DuplicatePolicy::Error);
+        let mut namespace = datapack.namespace("test").expect("should have accepted the namespace");
+        let root = namespace.add("gems/root", Advancement::new().criterion("tick", Trigger::tick())).expect("should have added the root");
+        let rewards = AdvancementRewards { loot: vec![location("test:gems/bonus")], ..AdvancementRewards::default() };
+        namespace
+            .add("gems/ruby", Advancement::new().parent(root).criterion("tick", Trigger::tick()).rewards(rewards))
+            .expect("should have added the advancement");
+        namespace
+            .add("gems/orphan", Advancement::new().parent(location("test:gems/roto")).criterion("tick", Trigger::tick()))
+            .expect("should have added the advancement");
+        namespace.add_predicate("is_night", LootCondition::time_check(13000..=23000, Some(24000))).expect("should have added the predicate");
+        let pool = LootPool::new(1.0)
+            .entry(LootEntry::item(location("minecraft:ruby")).condition(LootCondition::reference(location("test:is_night"))))
+            .entry(LootEntry::loot_table(location("minecraft:chests/simple_dungeon")))
+            .condition(LootCondition::inverted(LootCondition::reference(location("test:is_raining"))));
+        namespace.add_loot_table("gems/bonus", LootTable::new().pool(pool)).expect("should have added the loot table");
+
+        let issues = datapack.validate();
+        assert_eq!(issues, [
+            ValidationIssue {
+                severity: Severity::Error,
+                path: PathBuf::from("data/test/advancement/gems/orphan.json"),
+                location: location("test:gems/roto"),
+                expected: PathBuf::from("data/test/advancement/gems/roto.json"),
+            },
+            ValidationIssue {
+                severity: Severity::Warning,
+                path: PathBuf::from("data/test/loot_table/gems/bonus.json"),
+                location: location("minecraft:chests/simple_dungeon"),
+                expected: PathBuf::from("data/minecraft/loot_table/chests/simple_dungeon.json"),
+            },
+            ValidationIssue {
+                severity: Severity::Error,
+                path: PathBuf::from("data/test/loot_table/gems/bonus.json"),
+                location: location("test:is_raining"),
+                expected: PathBuf::from("data/test/predicate/is_raining.json"),
+            },
+        ], "the misspelled parent and the missing predicate should be errors, and the vanilla loot table a warning");
+        assert_eq!(
+            issues[2].to_string(),
+            "'data/test/loot_table/gems/bonus.json' refers to 'test:is_raining', but there is nothing at 'data/test/predicate/is_raining.json'",
+        );
+    }
+
+    #[test]
+    fn validate_functions() {
+        let location = |location: &str| location.parse::<ResourceLocation>().expect("should have parsed the resource location");
+        let mut datapack = test_datapack(DuplicatePolicy::Error);
+        let mut namespace = datapack.namespace("test").expect("should have accepted the namespace");
+        namespace.add_function("start", McFunction::new().command("function test:middle")).expect("should have added the function");
+        namespace.add_function("middle", McFunction::new().command("execute as @a run function test:end")).expect("should have added the function");
+        namespace.add_function("end", McFunction::new().command("say done")).expect("should have added the function");
+        namespace.add_function("broken", McFunction::new().command("function test:renamed")).expect("should have added the function");
+
+        let issues = datapack.validate();
+        assert_eq!(issues.len(), 1, "only the call to the renamed function should be an issue: {issues:?}");
+        assert_eq!(issues[0].severity, Severity::Error);
+        assert_eq!(issues[0].path, PathBuf::from("data/test/function/broken.mcfunction"));
+        assert_eq!(issues[0].location, location("test:renamed"));
+
+        let mut namespace = datapack.namespace("test").expect("should have accepted the namespace");
+        let uses = McFunction::new()
+            .command("execute if predicate test:is_night run say night")
+            .command("kill @e[type=minecraft:zombie,predicate=!test:is_day]")
+            .command("loot give @s loot test:gems/bonus")
+            .command("execute as @a run loot replace entity @s weapon.mainhand 1 fish test:gems/fished ~ ~ ~")
+            .command("say if predicate test:not_a_command and loot test:not_a_source")
+            .command("function other:library/init");
+        namespace.add_function("uses", uses).expect("should have added the function");
+        namespace.add_predicate("is_day", LootCondition::time_check(0..=12000, Some(24000))).expect("should have added the predicate");
+        let missing: Vec<(Severity, String)> = datapack
+            .validate()
+            .into_iter()
+            .filter(|issue| issue.path.ends_with("uses.mcfunction"))
+            .map(|issue| (issue.severity, issue.location.to_string()))
+            .collect();
+        assert_eq!(missing, [
+            (Severity::Warning, "other:library/init".to_string()),
+            (Severity::Error, "test:is_night".to_string()),
+            (Severity::Error, "test:gems/bonus".to_string()),
+            (Severity::Error, "test:gems/fished".to_string()),
+        ], "another pack may provide the library, but the predicate and loot tables should be in this one");
+        let dangling = datapack.validate_references().into_iter().map(|dangling| dangling.location.to_string()).collect::<Vec<_>>();
+        assert!(dangling.contains(&"other:library/init".to_string()), "dangling references into other namespaces are kept: {dangling:?}");
+    }
+
+    /// Every JSON file is written by serde, so it should parse, and leave out the fields
+    /// that aren't set instead of writing `null`.
+    #[test]
+    fn json_files_omit_unset_fields() {
+        fn contains_null(value: &serde_json::Value) -> bool {
+            match value {
+                serde_json::Value::Null => true,
+                serde_json::Value::Array(values) => values.iter().any(contains_null),
+                serde_json::Value::Object(map) => map.values().any(contains_null),
+                _ => false,
+            }
+        }
+
+        let location = |location: &str| location.parse::<ResourceLocation>().expect("should have parsed the resource location");
+        let mut datapack = test_datapack(DuplicatePolicy::Error);
+        let mut namespace = datapack.namespace("test").expect("should have accepted the namespace");
+        let display = AdvancementDisplay::new(location("minecraft:ruby"), "Ruby", "Find a ruby");
+        namespace
+            .add("ruby", Advancement::new().display(display).criterion("tick", Trigger::tick()))
+            .expect("should have added the advancement");
+        namespace
+            .add_loot_table("ruby", LootTable::new().pool(LootPool::new(1.0).entry(LootEntry::item(location("minecraft:ruby")))))
+            .expect("should have added the loot table");
+        namespace.add_predicate("is_night", LootCondition::time_check(13000..=23000, None)).expect("should have added the predicate");
+        namespace.add_tag("gems", Tag::<FunctionTag>::new().value(location("test:ruby"))).expect("should have added the tag");
+
+        let mut files = Vec::new();
+        datapack.visit(&mut |path, entry| {
+            if path.extension().is_some_and(|extension| extension == "json" || extension == "mcmeta") {
+                files.push((path.to_path_buf(), entry.contents().expect("JSON files should know their contents")));
+            }
+        });
+        assert_eq!(files.len(), 5);
+        for (path, contents) in files {
+            let json: serde_json::Value = serde_json::from_slice(&contents).unwrap_or_else(|error| panic!("'{}' isn't valid JSON: {error}", path.display()));
+            assert!(!contains_null(&json), "'{}' shouldn't have nulls: {json}", path.display());
+            if path.ends_with("advancement/ruby.json") {
+                assert!(json["display"].get("background").is_none(), "an unset background should be left out");
+                assert!(json.get("parent").is_none(), "an unset parent should be left out");
+            }
+        }
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn create_async() {
+        let mut datapack = test_datapack(DuplicatePolicy::Error).with_json_style(JsonStyle::Compact);
+        datapack.insert(ENTRY_PATH, StringFile("say hi")).expect("should have inserted the entry");
+        let mut namespace = datapack.namespace("test").expect("should have accepted the namespace");
+        let function = namespace.location("duplicate").expect("the path is valid");
+        namespace.add_tag("load", Tag::<FunctionTag>::new().value(function)).expect("should have added the tag");
+
+        let temp_dir = TempDir::new(module_path!()).expect("should have created temp dir");
+        let (sync_path, async_path) = (temp_dir.path().join("sync"), temp_dir.path().join("async"));
+        datapack.create(&sync_path).expect("should have created the datapack");
+        let runtime = tokio::runtime::Builder::new_current_thread().build().expect("should have built the runtime");
+        runtime.block_on(datapack.create_async(&async_path)).expect("should have created the datapack");
+
+        let read = |path: &Path| fs::read_to_string(path).expect("should have been able to read the file");
+        for file in [PACK_META_FILE_NAME, ENTRY_PATH, "data/test/tags/function/load.json"] {
+            assert_eq!(read(&async_path.join(file)), read(&sync_path.join(file)), "'{file}' should be the same either way");
+        }
+        assert!(!read(&async_path.join(PACK_META_FILE_NAME)).contains('\n'), "the datapack's style should have been used");
+        assert!(runtime.block_on(datapack.create_async(&async_path)).is_err(), "an existing datapack should not be overwritten");
+
+        temp_dir.close().expect("should have closed temp dir");
+    }
+
+    #[test]
+    fn create_with_backup() {
+        let temp_dir = TempDir::new(module_path!()).expect("should have created temp dir");
+        let path = temp_dir.path().join("test_datapack");
+
+        let mut datapack = test_datapack(DuplicatePolicy::Error);
+        datapack.insert(ENTRY_PATH, StringFile("old")).expect("should have inserted the entry");
+        datapack.create(&path).expect("should have created the datapack");
+
+        let mut datapack = test_datapack(DuplicatePolicy::Error);
+        datapack.insert(ENTRY_PATH, StringFile("new")).expect("should have inserted the entry");
+        let backup = datapack.create_with_backup(&path).expect("should have recreated the datapack");
+        assert_eq!(backup, None, "the existing datapack directory should be kept");
+        let read = |path: PathBuf| fs::read_to_string(path).expect("should have been able to read the file");
+        assert_eq!(read(path.join(ENTRY_PATH)), "new");
+        assert_eq!(read(path.join(format!("{ENTRY_PATH}.bak"))), "old", "the replaced function should have been backed up");
+        assert!(path.join("pack.mcmeta.bak").exists(), "the replaced pack.mcmeta should have been backed up");
+
+        temp_dir.close().expect("should have closed temp dir");
+    }
+}
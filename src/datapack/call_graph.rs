@@ -0,0 +1,198 @@
+use std::collections::{BTreeMap, BTreeSet};
+use crate::resource_location::{ResourceLocation, DEFAULT_NAMESPACE};
+
+/// Which functions of a datapack run which others right away with `function`, returned by
+/// [`Datapack::call_graph`](crate::datapack::Datapack::call_graph).
+///
+/// Calls through function tags and `schedule` are not part of it, since they don't run
+/// the called function before the caller finishes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CallGraph {
+    calls: BTreeMap<ResourceLocation, BTreeSet<ResourceLocation>>,
+}
+
+impl CallGraph {
+    pub(crate) fn new(calls: BTreeMap<ResourceLocation, BTreeSet<ResourceLocation>>) -> Self {
+        Self { calls }
+    }
+
+    /// Every function of the datapack, including ones that call nothing.
+    pub fn functions(&self) -> impl Iterator<Item = &ResourceLocation> {
+        self.calls.keys()
+    }
+
+    /// The functions `function` calls, in order of their location.
+    pub fn calls(&self, function: &ResourceLocation) -> impl Iterator<Item = &ResourceLocation> {
+        self.calls.get(function).into_iter().flatten()
+    }
+
+    /// Every call as a `(caller, called)` pair.
+    pub fn edges(&self) -> impl Iterator<Item = (&ResourceLocation, &ResourceLocation)> {
+        self.calls.iter().flat_map(|(caller, called)| called.iter().map(move |called| (caller, called)))
+    }
+
+    /// The calls to functions that aren't in the datapack. Calls into the `minecraft`
+    /// namespace are skipped, like in
+    /// [`Datapack::validate_references`](crate::datapack::Datapack::validate_references).
+    pub fn missing(&self) -> Vec<(&ResourceLocation, &ResourceLocation)> {
+        self.edges()
+            .filter(|(_, called)| called.namespace() != DEFAULT_NAMESPACE && !self.calls.contains_key(*called))
+            .collect()
+    }
+
+    /// The groups of functions that call each other in a loop, which the game runs until
+    /// it hits the command limit. Each group is sorted, and a function that calls itself is
+    /// a group of its own.
+    pub fn detect_cycles(&self) -> Vec<Vec<ResourceLocation>> {
+        let mut search = CycleSearch {
+            graph: self,
+            index: BTreeMap::new(),
+            low_link: BTreeMap::new(),
+            stack: Vec::new(),
+            on_stack: BTreeSet::new(),
+            cycles: Vec::new(),
+        };
+        for function in self.calls.keys() {
+            if !search.index.contains_key(function) {
+                search.visit(function);
+            }
+        }
+        search.cycles
+    }
+}
+
+/// Tarjan's search for strongly connected components, keeping only the ones that loop.
+struct CycleSearch<'g> {
+    graph: &'g CallGraph,
+    index: BTreeMap<&'g ResourceLocation, usize>,
+    low_link: BTreeMap<&'g ResourceLocation, usize>,
+    stack: Vec<&'g ResourceLocation>,
+    on_stack: BTreeSet<&'g ResourceLocation>,
+    cycles: Vec<Vec<ResourceLocation>>,
+}
+
+impl<'g> CycleSearch<'g> {
+    /// Searches from `root` with a stack of the functions being visited and the calls
+    /// each has left, rather than by recursion, so long call chains can't overflow the
+    /// stack.
+    fn visit(&mut self, root: &'g ResourceLocation) {
+        let mut path = vec![self.enter(root)];
+        while let Some((function, calls)) = path.last_mut() {
+            let function = *function;
+            match calls.next() {
+                Some(called) if !self.graph.calls.contains_key(called) => {}
+                Some(called) if !self.index.contains_key(called) => path.push(self.enter(called)),
+                Some(called) => {
+                    if self.on_stack.contains(called) {
+                        let low_link = self.low_link[function].min(self.index[called]);
+                        self.low_link.insert(function, low_link);
+                    }
+                }
+                None => {
+                    path.pop();
+                    if let Some((caller, _)) = path.last() {
+                        let low_link = self.low_link[*caller].min(self.low_link[function]);
+                        self.low_link.insert(caller, low_link);
+                    }
+                    self.leave(function);
+                }
+            }
+        }
+    }
+
+    fn enter(&mut self, function: &'g ResourceLocation) -> (&'g ResourceLocation, impl Iterator<Item = &'g ResourceLocation>) {
+        let index = self.index.len();
+        self.index.insert(function, index);
+        self.low_link.insert(function, index);
+        self.stack.push(function);
+        self.on_stack.insert(function);
+        (function, self.graph.calls(function))
+    }
+
+    /// Pops the component `function` is the root of once all its calls are visited.
+    fn leave(&mut self, function: &'g ResourceLocation) {
+        if self.low_link[function] != self.index[function] {
+            return;
+        }
+        let start = self.stack.iter().rposition(|f| *f == function).expect("the function is on the stack");
+        let component = self.stack.split_off(start);
+        for member in &component {
+            self.on_stack.remove(member);
+        }
+        let calls_itself = self.graph.calls.get(function).is_some_and(|called| called.contains(function));
+        if component.len() > 1 || calls_itself {
+            let mut component: Vec<ResourceLocation> = component.into_iter().cloned().collect();
+            component.sort();
+            self.cycles.push(component);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::function::call_function;
+    use crate::data::function::McFunction;
+    use crate::datapack::Datapack;
+    use crate::datapack::pack_meta::PackMeta;
+
+    fn location(location: &str) -> ResourceLocation {
+        location.parse().expect("should have parsed the resource location")
+    }
+
+    fn datapack(functions: &[(&str, &[&str])]) -> Datapack<'static> {
+        let mut datapack = Datapack::new(PackMeta::new(48, "test"));
+        let mut namespace = datapack.namespace("test").expect("should have accepted the namespace");
+        for (path, called) in functions {
+            let function = called.iter().map(|called| call_function(&location(called))).collect();
+            namespace.add_function(path, function).expect("should have added the function");
+        }
+        datapack
+    }
+
+    #[test]
+    fn self_cycle() {
+        let datapack = datapack(&[("loop", &["test:loop"]), ("main", &["test:loop"])]);
+        let graph = datapack.call_graph();
+        assert_eq!(graph.detect_cycles(), [vec![location("test:loop")]], "'loop' calls itself forever");
+
+        let datapack = self::datapack(&[("a", &["test:b"]), ("b", &["test:c"]), ("c", &["test:a"])]);
+        assert_eq!(
+            datapack.call_graph().detect_cycles(),
+            [vec![location("test:a"), location("test:b"), location("test:c")]],
+            "the three functions call each other in a loop",
+        );
+    }
+
+    #[test]
+    fn acyclic_graph() {
+        let datapack = datapack(&[
+            ("main", &["test:setup", "test:tick/players"]),
+            ("setup", &[]),
+            ("tick/players", &["test:setup", "test:missing", "minecraft:builtin"]),
+        ]);
+        let graph = datapack.call_graph();
+        assert!(graph.detect_cycles().is_empty(), "no function calls back into one that called it");
+        assert_eq!(graph.functions().count(), 3);
+        assert_eq!(graph.calls(&location("test:main")).collect::<Vec<_>>(), [&location("test:setup"), &location("test:tick/players")]);
+        assert_eq!(graph.missing(), [(&location("test:tick/players"), &location("test:missing"))]);
+    }
+
+    #[test]
+    fn long_call_chain() {
+        let functions: Vec<ResourceLocation> = (0..100_000).map(|n| location(&format!("test:f{n}"))).collect();
+        let mut calls: BTreeMap<_, _> = functions.windows(2).map(|pair| (pair[0].clone(), BTreeSet::from([pair[1].clone()]))).collect();
+        calls.insert(functions[functions.len() - 1].clone(), BTreeSet::from([functions[50_000].clone()]));
+        let cycles = CallGraph::new(calls).detect_cycles();
+        assert_eq!(cycles.len(), 1, "the last half of the chain loops");
+        assert_eq!(cycles[0].len(), 50_000);
+    }
+
+    #[test]
+    fn legacy_directory() {
+        let mut datapack = Datapack::new(PackMeta::new(26, "test"));
+        let function = McFunction::new().command("execute if entity @a run function test:loop");
+        datapack.add(&location("test:loop"), function).expect("should have added the function");
+        assert_eq!(datapack.call_graph().detect_cycles(), [vec![location("test:loop")]], "'functions/' should be read too");
+    }
+}
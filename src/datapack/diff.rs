@@ -0,0 +1,181 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Display};
+use std::path::{Path, PathBuf};
+use crate::utils::file_system::Entry;
+
+/// The files of a tree, by their path, with their contents if they are known.
+fn files(tree: &dyn Entry) -> BTreeMap<PathBuf, Option<Vec<u8>>> {
+    let mut files = BTreeMap::new();
+    tree.visit(&mut |path: &Path, entry: &dyn Entry| {
+        let stats = entry.stats();
+        if stats.files == 1 && stats.directories == 0 {
+            files.insert(path.to_path_buf(), entry.contents());
+        }
+    });
+    files
+}
+
+/// How many lines of `new` aren't in `old`, and how many lines of `old` aren't in `new`,
+/// or `None` if either isn't text. Moved lines aren't counted.
+fn line_changes(old: &[u8], new: &[u8]) -> Option<(usize, usize)> {
+    let (old, new) = (std::str::from_utf8(old).ok()?, std::str::from_utf8(new).ok()?);
+    let mut counts: HashMap<&str, isize> = HashMap::new();
+    for line in old.lines() {
+        *counts.entry(line).or_default() -= 1;
+    }
+    for line in new.lines() {
+        *counts.entry(line).or_default() += 1;
+    }
+    let insertions = counts.values().filter(|count| **count > 0).sum::<isize>();
+    let deletions = -counts.values().filter(|count| **count < 0).sum::<isize>();
+    Some((insertions as usize, deletions as usize))
+}
+
+/// How the files of one tree differ from those of another, returned by [`diff`].
+///
+/// Displayed like `git diff --stat`, without scaling the `+` and `-` to a fixed width.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DatapackDiff {
+    added: Vec<PathBuf>,
+    removed: Vec<PathBuf>,
+    modified: Vec<PathBuf>,
+    /// Inserted and deleted lines by path, for the text files that changed.
+    lines: BTreeMap<PathBuf, (usize, usize)>,
+}
+
+impl DatapackDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+
+    /// Files only in the new tree, in the order of their paths.
+    pub fn added(&self) -> &[PathBuf] {
+        &self.added
+    }
+
+    /// Files only in the old tree.
+    pub fn removed(&self) -> &[PathBuf] {
+        &self.removed
+    }
+
+    /// Files in both trees with different contents.
+    pub fn modified(&self) -> &[PathBuf] {
+        &self.modified
+    }
+}
+
+/// The files that differ between `old` and `new`, such as a datapack read with
+/// [`FromPath`](crate::utils::file_system::FromPath) and one generated again. Both can be any
+/// entry, such as a [`Directory`](crate::utils::file_system::Directory) or a
+/// [`Datapack`](crate::datapack::Datapack). Directories are only compared by the files in
+/// them, and a file whose contents can't be known without writing it, like a
+/// [`LazyFile`](crate::utils::file_system::LazyFile), always counts as modified.
+pub fn diff(old: &dyn Entry, new: &dyn Entry) -> DatapackDiff {
+    let (old, mut new) = (files(old), files(new));
+    let mut diff = DatapackDiff::default();
+    for (path, old_contents) in old {
+        let Some(new_contents) = new.remove(&path) else {
+            if let Some(lines) = old_contents.as_deref().and_then(|old| line_changes(old, b"")) {
+                diff.lines.insert(path.clone(), lines);
+            }
+            diff.removed.push(path);
+            continue;
+        };
+        match (old_contents, new_contents) {
+            (Some(old_contents), Some(new_contents)) if old_contents == new_contents => {}
+            (old_contents, new_contents) => {
+                if let (Some(old_contents), Some(new_contents)) = (old_contents, new_contents) {
+                    if let Some(lines) = line_changes(&old_contents, &new_contents) {
+                        diff.lines.insert(path.clone(), lines);
+                    }
+                }
+                diff.modified.push(path);
+            }
+        }
+    }
+    for (path, new_contents) in new {
+        if let Some(lines) = new_contents.as_deref().and_then(|new| line_changes(b"", new)) {
+            diff.lines.insert(path.clone(), lines);
+        }
+        diff.added.push(path);
+    }
+    diff
+}
+
+impl Display for DatapackDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut paths: Vec<&PathBuf> = self.added.iter().chain(&self.removed).chain(&self.modified).collect();
+        paths.sort();
+        let width = paths.iter().map(|path| path.display().to_string().chars().count()).max().unwrap_or(0);
+        for path in &paths {
+            let name = path.display().to_string();
+            match self.lines.get(*path) {
+                Some(&(insertions, deletions)) => writeln!(
+                    f,
+                    " {name:width$} | {} {}{}",
+                    insertions + deletions,
+                    "+".repeat(insertions),
+                    "-".repeat(deletions),
+                )?,
+                None => writeln!(f, " {name:width$} | Bin")?,
+            }
+        }
+        let (insertions, deletions) = self.lines.values().fold((0, 0), |(i, d), (insertions, deletions)| (i + insertions, d + deletions));
+        let plural = |count: usize, word: &str| format!("{count} {word}{}", if count == 1 { "" } else { "s" });
+        write!(f, " {} changed", plural(paths.len(), "file"))?;
+        if insertions > 0 || deletions == 0 {
+            write!(f, ", {}(+)", plural(insertions, "insertion"))?;
+        }
+        if deletions > 0 || insertions == 0 {
+            write!(f, ", {}(-)", plural(deletions, "deletion"))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::OsString;
+    use tempdir::TempDir;
+    use crate::utils::file_system::{Directory, FromPath, OwnedStringFile};
+
+    /// A tree of `files` at paths like `parent/name`.
+    fn directory(files: &[(&str, &str)]) -> Directory<'static> {
+        let mut parents: BTreeMap<&str, Directory> = BTreeMap::new();
+        for (path, contents) in files {
+            let (parent, name) = path.split_once('/').expect("files should be in a directory");
+            let file: Box<dyn Entry> = Box::new(OwnedStringFile(contents.to_string()));
+            parents.entry(parent).or_default().insert(OsString::from(name), file);
+        }
+        parents.into_iter().map(|(parent, directory)| (OsString::from(parent), Box::new(directory) as Box<dyn Entry>)).collect()
+    }
+
+    #[test]
+    fn changed_files() {
+        let old = directory(&[("functions/a.mcfunction", "say a\nsay b"), ("functions/gone.mcfunction", "say gone"), ("tags/t.json", "{}")]);
+        let new = directory(&[("functions/a.mcfunction", "say a\nsay c\nsay d"), ("functions/new.mcfunction", "say new"), ("tags/t.json", "{}")]);
+        let diff = diff(&old, &new);
+        assert_eq!(diff.added(), [PathBuf::from("functions/new.mcfunction")]);
+        assert_eq!(diff.removed(), [PathBuf::from("functions/gone.mcfunction")]);
+        assert_eq!(diff.modified(), [PathBuf::from("functions/a.mcfunction")]);
+        assert_eq!(diff.to_string(), [
+            " functions/a.mcfunction    | 3 ++-",
+            " functions/gone.mcfunction | 1 -",
+            " functions/new.mcfunction  | 1 +",
+            " 3 files changed, 3 insertions(+), 2 deletions(-)",
+        ].join("\n"));
+    }
+
+    #[test]
+    fn round_trip() {
+        let tree = directory(&[("a/b", "same"), ("a/c", "also the same")]);
+        let temp_dir = TempDir::new(module_path!()).expect("should have created temp dir");
+        tree.create(&temp_dir.path().join("tree")).expect("should have created the tree");
+        let read = Directory::from_path(&temp_dir.path().join("tree")).expect("should have read the tree");
+        let diff = diff(&tree, &read);
+        assert!(diff.is_empty(), "a tree read back should be the same, but was:\n{diff}");
+        assert_eq!(diff.to_string(), " 0 files changed, 0 insertions(+), 0 deletions(-)");
+        temp_dir.close().expect("should have closed temp dir");
+    }
+}
@@ -0,0 +1,274 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+use crate::command::bossbar::BossbarRegistry;
+use crate::command::scoreboard::{ScoreboardCommand, ScoreboardObjective};
+use crate::data::function::{FunctionBuilder, McFunction};
+use crate::error::DatapackError;
+use crate::resource_location::ResourceLocation;
+use crate::utils::file_system::File;
+
+/// A function of a namespace's lifecycle, added at the path of the same name by
+/// [`Namespace::on_load`](crate::datapack::namespace::Namespace::on_load) and the hooks
+/// next to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Stage {
+    /// `<namespace>:load`, in the `minecraft:load` tag. It adds the declared objectives,
+    /// then the declared bossbars, before the commands of the hooks.
+    Load,
+    /// `<namespace>:tick`, in the `minecraft:tick` tag.
+    Tick,
+    /// `<namespace>:uninstall`, for players to run before removing the datapack. It runs
+    /// the commands of the hooks, then removes the declared bossbars, then the declared
+    /// objectives, so the hooks can still use them.
+    Uninstall,
+}
+
+impl Stage {
+    pub fn path(self) -> &'static str {
+        match self {
+            Self::Load => "load",
+            Self::Tick => "tick",
+            Self::Uninstall => "uninstall",
+        }
+    }
+}
+
+/// The lifecycle functions of a namespace: the commands their hooks pushed, and the
+/// objectives and bossbars that contribute to them.
+#[derive(Debug, Clone)]
+pub(crate) struct Lifecycle {
+    builders: BTreeMap<Stage, FunctionBuilder>,
+    objectives: Vec<ScoreboardObjective>,
+    bossbars: BossbarRegistry,
+}
+
+impl Lifecycle {
+    pub(crate) fn new(namespace: &str) -> Self {
+        Self { builders: BTreeMap::new(), objectives: Vec::new(), bossbars: BossbarRegistry::new(namespace) }
+    }
+
+    /// Whether the function of `stage` was added.
+    pub(crate) fn has(&self, stage: Stage) -> bool {
+        self.builders.contains_key(&stage)
+    }
+
+    pub(crate) fn start(&mut self, stage: Stage, location: ResourceLocation) {
+        self.builders.entry(stage).or_insert_with(|| FunctionBuilder::new(location));
+    }
+
+    /// Runs `build` on the function of `stage`, which has to be started, and returns the
+    /// auxiliary functions it made. The objectives of its loops are declared like those of
+    /// [`Lifecycle::declare_objective`], unless they already are.
+    pub(crate) fn build(&mut self, stage: Stage, build: impl FnOnce(&mut FunctionBuilder)) -> Vec<(ResourceLocation, McFunction)> {
+        let builder = self.builders.get_mut(&stage).expect("the stage should have been started");
+        build(builder);
+        let (auxiliary, objectives) = builder.take_auxiliary();
+        for objective in objectives {
+            self.use_objective(objective);
+        }
+        auxiliary
+    }
+
+    /// Declares `objective` like [`Lifecycle::declare_objective`], unless one of the same
+    /// name already is, since loops and raycasts share theirs.
+    pub(crate) fn use_objective(&mut self, objective: ScoreboardObjective) {
+        if !self.objectives.iter().any(|declared| declared.name() == objective.name()) {
+            self.objectives.push(objective);
+        }
+    }
+
+    /// Fails if an objective of the same name was already declared.
+    pub(crate) fn declare_objective(&mut self, objective: ScoreboardObjective) -> Result<(), DatapackError> {
+        if self.objectives.iter().any(|declared| declared.name() == objective.name()) {
+            return Err(DatapackError::Validation(format!("the objective '{}' was already declared", objective.name())));
+        }
+        self.objectives.push(objective);
+        Ok(())
+    }
+
+    pub(crate) fn has_objectives(&self) -> bool {
+        !self.objectives.is_empty()
+    }
+
+    pub(crate) fn bossbars(&mut self) -> &mut BossbarRegistry {
+        &mut self.bossbars
+    }
+
+    /// The function of `stage`, with the contributed commands in the order documented on
+    /// [`Stage`].
+    pub(crate) fn function(&self, stage: Stage) -> McFunction {
+        let hooks = self.builders.get(&stage).map(|builder| builder.function().clone()).unwrap_or_default();
+        match stage {
+            Stage::Load => {
+                let objectives = self.objectives.iter().map(|objective| ScoreboardCommand::add_objective(objective.clone()));
+                objectives.collect::<McFunction>() + self.bossbars.setup() + hooks
+            }
+            Stage::Tick => hooks,
+            Stage::Uninstall => {
+                let objectives = self.objectives.iter().map(|objective| ScoreboardCommand::remove_objective(objective.name()));
+                hooks + self.bossbars.teardown() + objectives.collect()
+            }
+        }
+    }
+}
+
+/// The file of a lifecycle function, rendered when the datapack is written so that it
+/// has everything declared by then.
+pub(crate) struct LifecycleFunction<'l> {
+    pub(crate) lifecycle: &'l Lifecycle,
+    pub(crate) stage: Stage,
+}
+
+impl Display for LifecycleFunction<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.lifecycle.function(self.stage).fmt(f)
+    }
+}
+
+impl File for LifecycleFunction<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::command::bossbar::{BossbarColor, BossbarSetting};
+    use crate::command::schedule::GameTime;
+    use crate::command::scoreboard::{ScoreboardCommand, ScoreboardObjective};
+    use crate::data::function::McFunction;
+    use crate::datapack::pack_meta::PackMeta;
+    use crate::datapack::{Datapack, DuplicatePolicy};
+    use crate::error::DatapackError;
+    use crate::text::TextComponent;
+    use crate::utils::file_system::Entry;
+    use std::collections::BTreeMap;
+
+    fn files(datapack: &Datapack) -> BTreeMap<String, String> {
+        let mut files = BTreeMap::new();
+        datapack.visit(&mut |path, entry| {
+            if let Some(contents) = entry.contents() {
+                files.insert(path.display().to_string(), String::from_utf8(contents).expect("the files are UTF-8"));
+            }
+        });
+        files
+    }
+
+    #[test]
+    fn complete_pack() {
+        let mut datapack = Datapack::new(PackMeta::new(48, "Timer"));
+        let mut namespace = datapack.namespace("timer").expect("should have accepted the namespace");
+        namespace.declare_objective(ScoreboardObjective::dummy("timer").expect("the name is valid")).expect("should have declared the objective");
+        let bar = namespace
+            .declare_bossbar("bar", TextComponent::text("Timer"), [BossbarSetting::Color(BossbarColor::Green)])
+            .expect("should have declared the bossbar");
+        namespace
+            .on_load(|load| {
+                load.push(ScoreboardCommand::set("#elapsed", "timer", 0));
+            })
+            .expect("should have added to the load function");
+        namespace
+            .on_tick(|tick| {
+                tick.push(ScoreboardCommand::add("#elapsed", "timer", 1));
+                tick.push(format!("execute store result bossbar {} value run scoreboard players get #elapsed timer", bar.id()));
+            })
+            .expect("should have added to the tick function");
+        namespace
+            .uninstall(|uninstall| {
+                uninstall.push("tellraw @a \"Timer uninstalled\"");
+            })
+            .expect("should have added to the uninstall function");
+
+        let expected: BTreeMap<String, String> = [
+            ("pack.mcmeta", "{\n  \"pack\": {\n    \"pack_format\": 48,\n    \"description\": \"Timer\"\n  }\n}"),
+            ("data/minecraft/tags/function/load.json", "{\n  \"values\": [\n    \"timer:load\"\n  ]\n}"),
+            ("data/minecraft/tags/function/tick.json", "{\n  \"values\": [\n    \"timer:tick\"\n  ]\n}"),
+            (
+                "data/timer/function/load.mcfunction",
+                "scoreboard objectives add timer dummy\n\
+                 bossbar add timer:bar \"Timer\"\n\
+                 bossbar set timer:bar color green\n\
+                 scoreboard players set #elapsed timer 0\n",
+            ),
+            (
+                "data/timer/function/tick.mcfunction",
+                "scoreboard players add #elapsed timer 1\n\
+                 execute store result bossbar timer:bar value run scoreboard players get #elapsed timer\n",
+            ),
+            (
+                "data/timer/function/uninstall.mcfunction",
+                "tellraw @a \"Timer uninstalled\"\n\
+                 bossbar remove timer:bar\n\
+                 scoreboard objectives remove timer\n",
+            ),
+        ]
+        .into_iter()
+        .map(|(path, contents)| (path.to_string(), contents.to_string()))
+        .collect();
+        assert_eq!(files(&datapack), expected);
+        assert!(datapack.validate().is_empty());
+    }
+
+    #[test]
+    fn hooks_append() {
+        let mut datapack = Datapack::new(PackMeta::new(48, "test"));
+        let mut namespace = datapack.namespace("test").expect("should have accepted the namespace");
+        namespace.on_load(|load| {
+            load.push("say first");
+        }).expect("should have added to the load function");
+        namespace.on_load(|load| {
+            load.push("say second");
+            load.run_later(GameTime::ticks(20).expect("should have accepted the time"), |later| {
+                later.push("say later");
+            }).expect("should have scheduled the function");
+        }).expect("should have added to the load function again");
+        namespace.on_load(|load| {
+            load.run_later(GameTime::ticks(40).expect("should have accepted the time"), |later| {
+                later.push("say even later");
+            }).expect("should have scheduled the function");
+        }).expect("deferred functions of another call shouldn't collide");
+        namespace.declare_objective(ScoreboardObjective::dummy("late").expect("the name is valid")).expect("should have declared the objective");
+        assert!(namespace.declare_objective(ScoreboardObjective::dummy("late").expect("the name is valid")).is_err());
+
+        let files = files(&datapack);
+        assert_eq!(
+            files["data/test/function/load.mcfunction"],
+            "scoreboard objectives add late dummy\n\
+             say first\n\
+             say second\n\
+             schedule function test:load/deferred_0 20t\n\
+             schedule function test:load/deferred_1 40t\n",
+            "declared objectives should come first, even when declared after the hooks",
+        );
+        assert_eq!(files["data/test/function/load/deferred_1.mcfunction"], "say even later\n");
+        assert_eq!(files["data/test/function/uninstall.mcfunction"], "scoreboard objectives remove late\n");
+        assert_eq!(files["data/minecraft/tags/function/load.json"], "{\n  \"values\": [\n    \"test:load\"\n  ]\n}");
+        assert!(!files.contains_key("data/minecraft/tags/function/tick.json"), "there is no tick hook");
+    }
+
+    #[test]
+    fn loop_objectives() {
+        let mut datapack = Datapack::new(PackMeta::new(48, "test"));
+        let mut namespace = datapack.namespace("test").expect("should have accepted the namespace");
+        namespace.on_tick(|tick| {
+            tick.for_count("spin", "loops", 2, |body| {
+                body.push("say spin");
+            }).expect("should have added the loop");
+        }).expect("should have added to the tick function");
+
+        let files = files(&datapack);
+        assert_eq!(files["data/test/function/load.mcfunction"], "scoreboard objectives add loops dummy\n");
+        assert_eq!(files["data/test/function/uninstall.mcfunction"], "scoreboard objectives remove loops\n");
+        assert!(!files.contains_key("data/test/function/tick/setup.mcfunction"), "the load function adds the objective");
+    }
+
+    #[test]
+    fn kept_user_function() {
+        let mut datapack = Datapack::new(PackMeta::new(48, "test")).with_duplicate_policy(DuplicatePolicy::KeepFirst);
+        let mut namespace = datapack.namespace("test").expect("should have accepted the namespace");
+        namespace.add_function("load", McFunction::new().command("say mine")).expect("should have added the function");
+        let objective = ScoreboardObjective::dummy("timer").expect("the name is valid");
+        let result = namespace.declare_objective(objective);
+        assert!(matches!(result, Err(DatapackError::DuplicateEntry(_))), "the objective would never be added");
+
+        let files = files(&datapack);
+        assert_eq!(files["data/test/function/load.mcfunction"], "say mine\n");
+        assert!(!files.contains_key("data/test/function/uninstall.mcfunction"), "nothing should have been added");
+    }
+}
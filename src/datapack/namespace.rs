@@ -0,0 +1,366 @@
+use crate::command::bossbar::{Bossbar, BossbarSetting};
+use crate::command::scoreboard::ScoreboardObjective;
+use crate::data::advancement::Advancement;
+use crate::data::chat_type::ChatType;
+use crate::data::conditions::Predicate;
+use crate::data::damage_type::DamageType;
+use crate::data::dimension::{Dimension, DimensionType};
+use crate::data::enchantment::Enchantment;
+use crate::data::function::{FunctionBuilder, McFunction};
+use crate::data::jukebox_song::JukeboxSong;
+use crate::data::loot_function::ItemModifier;
+use crate::data::loot_table::LootTable;
+use crate::data::painting_variant::PaintingVariant;
+use crate::data::recipe::Recipe;
+use crate::data::tag::{DamageTypeTag, FunctionTag, ItemTag, PaintingVariantTag, Tag, TagKind};
+use crate::data::trim::{TrimMaterial, TrimPattern};
+use crate::data::worldgen::biome::Biome;
+use crate::data::worldgen::carver::ConfiguredCarver;
+use crate::data::worldgen::density_function::DensityFunction;
+use crate::data::wolf_variant::WolfVariant;
+use crate::data::worldgen::feature::{ConfiguredFeature, PlacedFeature};
+use crate::data::worldgen::structure::Structure;
+use crate::data::worldgen::structure_set::StructureSet;
+use crate::data::worldgen::template_pool::TemplatePool;
+use crate::datapack::lifecycle::{Lifecycle, Stage};
+use crate::datapack::{resource_path, Datapack, Slot};
+use crate::datapack::resource::Resource;
+use crate::error::DatapackError;
+use crate::resource_location::ResourceLocation;
+use crate::text::TextComponent;
+
+/// A handle for adding resources to one namespace of a [`Datapack`], returned by
+/// [`Datapack::namespace`].
+pub struct Namespace<'d, 'a> {
+    datapack: &'d mut Datapack<'a>,
+    name: String,
+}
+
+impl<'d, 'a> Namespace<'d, 'a> {
+    pub(super) fn new(datapack: &'d mut Datapack<'a>, name: String) -> Self {
+        Self { datapack, name }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The pack format of the datapack, for commands that depend on it.
+    pub fn pack_format(&self) -> u32 {
+        self.datapack.meta().pack_format
+    }
+
+    /// The location of `path` in this namespace.
+    pub fn location(&self, path: &str) -> Result<ResourceLocation, DatapackError> {
+        ResourceLocation::new(self.name.as_str(), path)
+    }
+
+    /// Adds `resource` at `path` in this namespace and returns its location, for
+    /// referencing it from other resources and commands.
+    pub fn add<R: Resource + 'a>(&mut self, path: &str, resource: R) -> Result<ResourceLocation, DatapackError> {
+        let location = self.location(path)?;
+        self.datapack.add(&location, resource)?;
+        Ok(location)
+    }
+
+    pub fn add_advancement(&mut self, path: &str, advancement: Advancement) -> Result<ResourceLocation, DatapackError> {
+        self.add(path, advancement)
+    }
+
+    pub fn add_biome(&mut self, path: &str, biome: Biome) -> Result<ResourceLocation, DatapackError> {
+        self.add(path, biome)
+    }
+
+    pub fn add_chat_type(&mut self, path: &str, chat_type: ChatType) -> Result<ResourceLocation, DatapackError> {
+        self.add(path, chat_type)
+    }
+
+    pub fn add_configured_carver(&mut self, path: &str, carver: ConfiguredCarver) -> Result<ResourceLocation, DatapackError> {
+        self.add(path, carver)
+    }
+
+    pub fn add_configured_feature(&mut self, path: &str, feature: ConfiguredFeature) -> Result<ResourceLocation, DatapackError> {
+        self.add(path, feature)
+    }
+
+    pub fn add_damage_type(&mut self, path: &str, damage_type: DamageType) -> Result<ResourceLocation, DatapackError> {
+        self.add(path, damage_type)
+    }
+
+    /// Adds `damage_type` and adds it to each of `tags`, such as
+    /// `minecraft:bypasses_armor`, with [`Datapack::add_to_tag`].
+    pub fn add_damage_type_tagged(
+        &mut self,
+        path: &str,
+        damage_type: DamageType,
+        tags: impl IntoIterator<Item = ResourceLocation>,
+    ) -> Result<ResourceLocation, DatapackError> {
+        self.transaction(|namespace| {
+            let location = namespace.add(path, damage_type)?;
+            for tag in tags {
+                namespace.datapack.add_to_tag::<DamageTypeTag>(&tag, location.clone())?;
+            }
+            Ok(location)
+        })
+    }
+
+    pub fn add_density_function(&mut self, path: &str, function: DensityFunction) -> Result<ResourceLocation, DatapackError> {
+        self.add(path, function)
+    }
+
+    pub fn add_dimension(&mut self, path: &str, dimension: Dimension) -> Result<ResourceLocation, DatapackError> {
+        self.add(path, dimension)
+    }
+
+    pub fn add_dimension_type(&mut self, path: &str, dimension_type: DimensionType) -> Result<ResourceLocation, DatapackError> {
+        self.add(path, dimension_type)
+    }
+
+    /// Fails for pack formats before 48, since enchantments were hardcoded before 1.21.
+    pub fn add_enchantment(&mut self, path: &str, enchantment: Enchantment) -> Result<ResourceLocation, DatapackError> {
+        self.add(path, enchantment)
+    }
+
+    pub fn add_function(&mut self, path: &str, function: McFunction) -> Result<ResourceLocation, DatapackError> {
+        self.add(path, function)
+    }
+
+    /// Adds the function `build` makes at `path`, with the auxiliary functions it needs.
+    /// The objectives of its loops are added by `<namespace>:load` and removed by
+    /// `<namespace>:uninstall`, like those of [`Namespace::declare_objective`].
+    pub fn add_function_with(&mut self, path: &str, build: impl FnOnce(&mut FunctionBuilder)) -> Result<ResourceLocation, DatapackError> {
+        let location = self.location(path)?;
+        let mut builder = FunctionBuilder::new(location.clone());
+        build(&mut builder);
+        self.transaction(|namespace| {
+            namespace.use_objectives(builder.objectives().to_vec())?;
+            for (function_location, function) in builder.build() {
+                namespace.add(function_location.path(), function)?;
+            }
+            Ok(location)
+        })
+    }
+
+    pub fn add_item_modifier(&mut self, path: &str, modifier: impl Into<ItemModifier>) -> Result<ResourceLocation, DatapackError> {
+        self.add(path, modifier.into())
+    }
+
+    /// Fails for pack formats before 48.
+    pub fn add_jukebox_song(&mut self, path: &str, song: JukeboxSong) -> Result<ResourceLocation, DatapackError> {
+        self.add(path, song)
+    }
+
+    pub fn add_loot_table(&mut self, path: &str, table: LootTable) -> Result<ResourceLocation, DatapackError> {
+        self.add(path, table)
+    }
+
+    /// Fails for pack formats before 48. The variant can only be picked in the creative
+    /// inventory, unless it is added with [`Namespace::add_painting_variant_placeable`].
+    pub fn add_painting_variant(&mut self, path: &str, variant: PaintingVariant) -> Result<ResourceLocation, DatapackError> {
+        self.add(path, variant)
+    }
+
+    /// Adds `variant` and adds it to the `minecraft:placeable` tag, so placed paintings can
+    /// pick it at random.
+    pub fn add_painting_variant_placeable(&mut self, path: &str, variant: PaintingVariant) -> Result<ResourceLocation, DatapackError> {
+        self.transaction(|namespace| {
+            let location = namespace.add(path, variant)?;
+            let placeable = ResourceLocation::minecraft("placeable").expect("the path is valid");
+            namespace.datapack.add_to_tag::<PaintingVariantTag>(&placeable, location.clone())?;
+            Ok(location)
+        })
+    }
+
+    pub fn add_placed_feature(&mut self, path: &str, feature: PlacedFeature) -> Result<ResourceLocation, DatapackError> {
+        self.add(path, feature)
+    }
+
+    pub fn add_predicate(&mut self, path: &str, predicate: impl Into<Predicate>) -> Result<ResourceLocation, DatapackError> {
+        self.add(path, predicate.into())
+    }
+
+    pub fn add_recipe(&mut self, path: &str, recipe: impl Into<Recipe>) -> Result<ResourceLocation, DatapackError> {
+        self.add(path, recipe.into())
+    }
+
+    pub fn add_structure(&mut self, path: &str, structure: Structure) -> Result<ResourceLocation, DatapackError> {
+        self.add(path, structure)
+    }
+
+    pub fn add_structure_set(&mut self, path: &str, structure_set: StructureSet) -> Result<ResourceLocation, DatapackError> {
+        self.add(path, structure_set)
+    }
+
+    pub fn add_tag<K: TagKind + 'a>(&mut self, path: &str, tag: Tag<K>) -> Result<ResourceLocation, DatapackError> {
+        self.add(path, tag)
+    }
+
+    pub fn add_template_pool(&mut self, path: &str, pool: TemplatePool) -> Result<ResourceLocation, DatapackError> {
+        self.add(path, pool)
+    }
+
+    pub fn add_trim_material(&mut self, path: &str, material: TrimMaterial) -> Result<ResourceLocation, DatapackError> {
+        self.add(path, material)
+    }
+
+    /// Adds `material` and adds its ingredient to the `minecraft:trim_materials` item tag,
+    /// so the smithing table accepts it.
+    pub fn add_trim_material_tagged(&mut self, path: &str, material: TrimMaterial) -> Result<ResourceLocation, DatapackError> {
+        let ingredient = material.ingredient.clone();
+        self.transaction(|namespace| {
+            let location = namespace.add(path, material)?;
+            let tag = ResourceLocation::minecraft("trim_materials").expect("the path is valid");
+            namespace.datapack.add_to_tag::<ItemTag>(&tag, ingredient)?;
+            Ok(location)
+        })
+    }
+
+    pub fn add_trim_pattern(&mut self, path: &str, pattern: TrimPattern) -> Result<ResourceLocation, DatapackError> {
+        self.add(path, pattern)
+    }
+
+    /// Adds `pattern` and adds its template to the `minecraft:trim_templates` item tag, so
+    /// the smithing table accepts it.
+    pub fn add_trim_pattern_tagged(&mut self, path: &str, pattern: TrimPattern) -> Result<ResourceLocation, DatapackError> {
+        let template = pattern.template_item.clone();
+        self.transaction(|namespace| {
+            let location = namespace.add(path, pattern)?;
+            let tag = ResourceLocation::minecraft("trim_templates").expect("the path is valid");
+            namespace.datapack.add_to_tag::<ItemTag>(&tag, template)?;
+            Ok(location)
+        })
+    }
+
+    /// Fails for pack formats before 41.
+    pub fn add_wolf_variant(&mut self, path: &str, variant: WolfVariant) -> Result<ResourceLocation, DatapackError> {
+        self.add(path, variant)
+    }
+
+    /// Adds `function` to the `minecraft:tick` tag with [`Datapack::add_to_tag`], creating
+    /// the tag the first time. Fails if the tag was added whole, with [`Namespace::add_tag`].
+    pub fn add_to_tick(&mut self, function: ResourceLocation) -> Result<(), DatapackError> {
+        let tick = ResourceLocation::minecraft("tick").expect("the path is valid");
+        self.datapack.add_to_tag::<FunctionTag>(&tick, function)
+    }
+
+    /// Adds `function` to the `minecraft:load` tag, like [`Namespace::add_to_tick`].
+    pub fn add_to_load(&mut self, function: ResourceLocation) -> Result<(), DatapackError> {
+        let load = ResourceLocation::minecraft("load").expect("the path is valid");
+        self.datapack.add_to_tag::<FunctionTag>(&load, function)
+    }
+
+    /// Adds the commands `build` pushes to `<namespace>:load`, which is added to the
+    /// `minecraft:load` tag the first time, and returns its location. Each call appends
+    /// to the function, after the commands [`Stage::Load`] contributes.
+    pub fn on_load(&mut self, build: impl FnOnce(&mut FunctionBuilder)) -> Result<ResourceLocation, DatapackError> {
+        self.hook(Stage::Load, build)
+    }
+
+    /// Adds the commands `build` pushes to `<namespace>:tick`, in the `minecraft:tick`
+    /// tag, like [`Namespace::on_load`].
+    pub fn on_tick(&mut self, build: impl FnOnce(&mut FunctionBuilder)) -> Result<ResourceLocation, DatapackError> {
+        self.hook(Stage::Tick, build)
+    }
+
+    /// Adds the commands `build` pushes to `<namespace>:uninstall`, before the commands
+    /// [`Stage::Uninstall`] contributes, like [`Namespace::on_load`]. The function isn't
+    /// in any tag, since players run it themselves.
+    pub fn uninstall(&mut self, build: impl FnOnce(&mut FunctionBuilder)) -> Result<ResourceLocation, DatapackError> {
+        self.hook(Stage::Uninstall, build)
+    }
+
+    /// Declares `objective`, which `<namespace>:load` adds and `<namespace>:uninstall`
+    /// removes. Fails if an objective of the same name was already declared.
+    pub fn declare_objective(&mut self, objective: ScoreboardObjective) -> Result<(), DatapackError> {
+        self.transaction(|namespace| {
+            namespace.lifecycle().declare_objective(objective)?;
+            namespace.start(Stage::Load)?;
+            namespace.start(Stage::Uninstall)?;
+            Ok(())
+        })
+    }
+
+    /// Declares the bossbar `<namespace>:<path>` like [`BossbarRegistry::declare`](crate::command::bossbar::BossbarRegistry::declare),
+    /// which `<namespace>:load` adds and `<namespace>:uninstall` removes.
+    pub fn declare_bossbar(
+        &mut self,
+        path: &str,
+        name: impl Into<TextComponent>,
+        settings: impl IntoIterator<Item = BossbarSetting>,
+    ) -> Result<Bossbar, DatapackError> {
+        self.transaction(|namespace| {
+            let bossbar = namespace.lifecycle().bossbars().declare(path, name, settings)?;
+            namespace.start(Stage::Load)?;
+            namespace.start(Stage::Uninstall)?;
+            Ok(bossbar)
+        })
+    }
+
+    /// Declares the objectives of loops and raycasts, unless they already are, so the
+    /// lifecycle functions add and remove them.
+    pub(crate) fn use_objectives(&mut self, objectives: impl IntoIterator<Item = ScoreboardObjective>) -> Result<(), DatapackError> {
+        for objective in objectives {
+            self.lifecycle().use_objective(objective);
+            self.start(Stage::Load)?;
+            self.start(Stage::Uninstall)?;
+        }
+        Ok(())
+    }
+
+    fn hook(&mut self, stage: Stage, build: impl FnOnce(&mut FunctionBuilder)) -> Result<ResourceLocation, DatapackError> {
+        self.transaction(|namespace| {
+            let location = namespace.start(stage)?;
+            let auxiliary = namespace.lifecycle().build(stage, build);
+            if namespace.lifecycle().has_objectives() {
+                namespace.start(Stage::Load)?;
+                namespace.start(Stage::Uninstall)?;
+            }
+            for (function_location, function) in auxiliary {
+                namespace.add(function_location.path(), function)?;
+            }
+
+            let pack_format = namespace.pack_format();
+            let mut function = namespace.lifecycle().function(stage);
+            function.set_pack_format(pack_format);
+            function.validate()?;
+            let path = resource_path::<McFunction>(&location, pack_format);
+            namespace.datapack.references.insert(path, function.references());
+            Ok(location)
+        })
+    }
+
+    /// Adds the function of `stage` the first time, and returns its location. Fails with
+    /// [`DatapackError::DuplicateEntry`] if another function is at its path, even with
+    /// [`DuplicatePolicy::KeepFirst`](crate::datapack::DuplicatePolicy::KeepFirst).
+    fn start(&mut self, stage: Stage) -> Result<ResourceLocation, DatapackError> {
+        let location = self.location(stage.path())?;
+        if self.lifecycle().has(stage) {
+            return Ok(location);
+        }
+        let path = resource_path::<McFunction>(&location, self.pack_format());
+        self.datapack.insert_slot(&path, Slot::Lifecycle { namespace: self.name.clone(), stage })?;
+        // `DuplicatePolicy::KeepFirst` keeps a function already there, which would drop
+        // what the lifecycle adds and removes.
+        if !matches!(self.datapack.entries.get(&path), Some(Slot::Lifecycle { .. })) {
+            return Err(DatapackError::DuplicateEntry(path));
+        }
+        self.lifecycle().start(stage, location.clone());
+        match stage {
+            Stage::Load => self.add_to_load(location.clone())?,
+            Stage::Tick => self.add_to_tick(location.clone())?,
+            Stage::Uninstall => {}
+        }
+        Ok(location)
+    }
+
+    /// Runs `f` on this namespace like [`Datapack::transaction`], undoing everything it
+    /// added if it fails.
+    pub(crate) fn transaction<T>(&mut self, f: impl FnOnce(&mut Namespace<'_, 'a>) -> Result<T, DatapackError>) -> Result<T, DatapackError> {
+        let name = &self.name;
+        self.datapack.transaction(|datapack| f(&mut Namespace::new(datapack, name.clone())))
+    }
+
+    fn lifecycle(&mut self) -> &mut Lifecycle {
+        self.datapack.lifecycles.entry(self.name.clone()).or_insert_with(|| Lifecycle::new(&self.name))
+    }
+}
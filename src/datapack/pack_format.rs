@@ -0,0 +1,120 @@
+use std::fmt::{self, Display};
+
+/// A Minecraft: Java Edition release, such as 1.20.4.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MinecraftVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl MinecraftVersion {
+    pub const V1_13: Self = Self::new(1, 13, 0);
+    pub const V1_14: Self = Self::new(1, 14, 0);
+    pub const V1_15: Self = Self::new(1, 15, 0);
+    pub const V1_16: Self = Self::new(1, 16, 0);
+    pub const V1_17: Self = Self::new(1, 17, 0);
+    pub const V1_18: Self = Self::new(1, 18, 0);
+    pub const V1_19: Self = Self::new(1, 19, 0);
+    pub const V1_20: Self = Self::new(1, 20, 0);
+    /// The first release with function macros.
+    pub const V1_20_2: Self = Self::new(1, 20, 2);
+    /// The first release with item components instead of item NBT.
+    pub const V1_20_5: Self = Self::new(1, 20, 5);
+    pub const V1_21: Self = Self::new(1, 21, 0);
+
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self { major, minor, patch }
+    }
+
+    /// The data pack format of this release, if it is a known release.
+    pub fn pack_format(self) -> Option<u32> {
+        PACK_FORMAT_TABLE.iter().find(|(version, _)| *version == self).map(|(_, pack_format)| *pack_format)
+    }
+}
+
+impl Display for MinecraftVersion {
+    /// Leaves out a zero patch number, as release names do.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)?;
+        if self.patch != 0 {
+            write!(f, ".{}", self.patch)?;
+        }
+        Ok(())
+    }
+}
+
+const fn v(minor: u32, patch: u32) -> MinecraftVersion {
+    MinecraftVersion::new(1, minor, patch)
+}
+
+/// The data pack format of every release with data packs, oldest first.
+pub const PACK_FORMAT_TABLE: &[(MinecraftVersion, u32)] = &[
+    (v(13, 0), 4), (v(13, 1), 4), (v(13, 2), 4),
+    (v(14, 0), 4), (v(14, 1), 4), (v(14, 2), 4), (v(14, 3), 4), (v(14, 4), 4),
+    (v(15, 0), 5), (v(15, 1), 5), (v(15, 2), 5),
+    (v(16, 0), 5), (v(16, 1), 5), (v(16, 2), 6), (v(16, 3), 6), (v(16, 4), 6), (v(16, 5), 6),
+    (v(17, 0), 7), (v(17, 1), 7),
+    (v(18, 0), 8), (v(18, 1), 8), (v(18, 2), 9),
+    (v(19, 0), 10), (v(19, 1), 10), (v(19, 2), 10), (v(19, 3), 10), (v(19, 4), 12),
+    (v(20, 0), 15), (v(20, 1), 15), (v(20, 2), 18), (v(20, 3), 26), (v(20, 4), 26), (v(20, 5), 41), (v(20, 6), 41),
+    (v(21, 0), 48), (v(21, 1), 48), (v(21, 2), 57), (v(21, 3), 57), (v(21, 4), 61), (v(21, 5), 71),
+    (v(21, 6), 80), (v(21, 7), 81), (v(21, 8), 81),
+];
+
+/// The first pack format whose item stacks have components instead of NBT, 1.20.5's.
+pub const ITEM_COMPONENTS_PACK_FORMAT: u32 = 41;
+
+/// The pack format of 1.21.5, where text components in commands and NBT became SNBT
+/// instead of JSON strings.
+pub const SNBT_TEXT_PACK_FORMAT: u32 = 71;
+
+/// The pack format of 1.20.2, which added function macros.
+pub const MACRO_PACK_FORMAT: u32 = 18;
+
+/// Lookups in [`PACK_FORMAT_TABLE`].
+pub struct PackFormat;
+
+impl PackFormat {
+    /// The pack format of the newest release this crate knows about.
+    pub fn latest() -> u32 {
+        PACK_FORMAT_TABLE.last().map(|(_, pack_format)| *pack_format).expect("the pack format table is not empty")
+    }
+
+    /// The pack format of release `major.minor.patch`, if it is a known release with
+    /// data packs.
+    pub fn from_minecraft_version(major: u32, minor: u32, patch: u32) -> Option<u32> {
+        MinecraftVersion::new(major, minor, patch).pack_format()
+    }
+
+    /// The releases that use `pack_format`, oldest first.
+    pub fn versions(pack_format: u32) -> impl Iterator<Item = MinecraftVersion> {
+        PACK_FORMAT_TABLE.iter().filter(move |(_, format)| *format == pack_format).map(|(version, _)| *version)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_lookup() {
+        assert_eq!(MinecraftVersion::V1_21.pack_format(), Some(48));
+        assert_eq!(PackFormat::from_minecraft_version(1, 20, 4), Some(26));
+        assert_eq!(PackFormat::from_minecraft_version(1, 19, 4), Some(12));
+        assert_eq!(PackFormat::from_minecraft_version(1, 12, 2), None, "1.12.2 has no data packs");
+        assert_eq!(PackFormat::from_minecraft_version(1, 21, 99), None, "1.21.99 is not a release");
+        assert_eq!(PackFormat::latest(), 81);
+        let versions: Vec<String> = PackFormat::versions(41).map(|version| version.to_string()).collect();
+        assert_eq!(versions, ["1.20.5", "1.20.6"]);
+    }
+
+    #[test]
+    fn table_is_ordered() {
+        for pair in PACK_FORMAT_TABLE.windows(2) {
+            let ((previous, previous_format), (version, format)) = (pair[0], pair[1]);
+            assert!(previous < version, "{previous} should come before {version}");
+            assert!(previous_format <= format, "the pack format of {version} should not be older than {previous}'s");
+        }
+    }
+}
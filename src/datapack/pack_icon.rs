@@ -0,0 +1,52 @@
+use std::fs;
+use std::io::{Result, Write};
+use std::path::Path;
+use crate::utils::file_system::{Entry, PackStats};
+#[cfg(feature = "tokio")]
+use crate::utils::file_system::{write_new_async, CreateFuture};
+
+pub const PACK_ICON_FILE_NAME: &str = "pack.png";
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// The `pack.png` shown next to the pack in the data pack selection screen, written
+/// byte for byte.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackIcon {
+    bytes: Vec<u8>,
+}
+
+impl PackIcon {
+    /// Takes the contents of a PNG image. Anything else is still written, with a warning,
+    /// since the game just shows no icon for it.
+    pub fn new(bytes: Vec<u8>) -> Self {
+        if !bytes.starts_with(PNG_SIGNATURE) {
+            log::warn!("the pack icon is not a PNG image");
+        }
+        Self { bytes }
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl Entry for PackIcon {
+    fn create(&self, path: &Path) -> Result<()> {
+        let mut buffer = fs::File::create_new(path)?;
+        buffer.write_all(&self.bytes)
+    }
+
+    fn stats(&self) -> PackStats {
+        PackStats { bytes: self.bytes.len() as u64, files: 1, directories: 0 }
+    }
+
+    fn contents(&self) -> Option<Vec<u8>> {
+        Some(self.bytes.clone())
+    }
+
+    #[cfg(feature = "tokio")]
+    fn create_async<'e>(&'e self, path: &'e Path) -> CreateFuture<'e> {
+        Box::pin(async move { Ok(write_new_async(path, &self.bytes).await?) })
+    }
+}
@@ -0,0 +1,200 @@
+use std::ops::RangeInclusive;
+use serde::ser::{Serialize, SerializeMap, SerializeStruct, Serializer};
+use crate::datapack::pack_format::MinecraftVersion;
+use crate::error::DatapackError;
+use crate::utils::json::json_file;
+
+pub const PACK_META_FILE_NAME: &str = "pack.mcmeta";
+
+/// The first pack format that loads overlays, from 1.20.2.
+pub const OVERLAYS_PACK_FORMAT: u32 = 18;
+
+/// The pack formats from `min_inclusive` to `max_inclusive`. A single pack format is
+/// written as a number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatRange {
+    pub min_inclusive: u32,
+    pub max_inclusive: u32,
+}
+
+impl FormatRange {
+    pub fn new(min_inclusive: u32, max_inclusive: u32) -> Self {
+        Self { min_inclusive, max_inclusive }
+    }
+
+    pub fn contains(&self, pack_format: u32) -> bool {
+        (self.min_inclusive..=self.max_inclusive).contains(&pack_format)
+    }
+}
+
+impl From<u32> for FormatRange {
+    fn from(pack_format: u32) -> Self {
+        Self::new(pack_format, pack_format)
+    }
+}
+
+impl From<(u32, u32)> for FormatRange {
+    fn from((min_inclusive, max_inclusive): (u32, u32)) -> Self {
+        Self::new(min_inclusive, max_inclusive)
+    }
+}
+
+impl From<RangeInclusive<u32>> for FormatRange {
+    fn from(range: RangeInclusive<u32>) -> Self {
+        Self::new(*range.start(), *range.end())
+    }
+}
+
+impl Serialize for FormatRange {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.min_inclusive == self.max_inclusive {
+            return serializer.serialize_u32(self.min_inclusive);
+        }
+        let mut state = serializer.serialize_struct("FormatRange", 2)?;
+        state.serialize_field("min_inclusive", &self.min_inclusive)?;
+        state.serialize_field("max_inclusive", &self.max_inclusive)?;
+        state.end()
+    }
+}
+
+/// A directory next to `data` whose files replace the ones in `data` when the game's
+/// pack format is in `formats`.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct OverlayEntry {
+    pub formats: FormatRange,
+    pub directory: String,
+}
+
+/// Hides the files of lower priority packs, including the vanilla one, whose namespace
+/// and path match these regular expressions. A missing pattern matches anything.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+pub struct PackFilter {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+impl PackFilter {
+    pub fn namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespace = Some(namespace.into());
+        self
+    }
+
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackMeta {
+    pub pack_format: u32,
+    pub description: String,
+    /// Written only when there are any.
+    pub filter: Vec<PackFilter>,
+    /// Written only when there are any, since older versions don't know the section.
+    pub overlays: Vec<OverlayEntry>,
+}
+
+impl PackMeta {
+    pub fn new(pack_format: u32, description: impl Into<String>) -> Self {
+        Self {
+            pack_format,
+            description: description.into(),
+            filter: Vec::new(),
+            overlays: Vec::new(),
+        }
+    }
+
+    /// A pack for `version`, so its pack format doesn't have to be looked up.
+    pub fn for_version(version: MinecraftVersion, description: impl Into<String>) -> Result<Self, DatapackError> {
+        let pack_format = version
+            .pack_format()
+            .ok_or_else(|| DatapackError::Validation(format!("{version} is not a known release with data packs")))?;
+        Ok(Self::new(pack_format, description))
+    }
+}
+
+#[derive(serde::Serialize)]
+struct PackSection<'a> {
+    pack_format: u32,
+    description: &'a str,
+}
+
+#[derive(serde::Serialize)]
+struct FilterSection<'a> {
+    block: &'a [PackFilter],
+}
+
+#[derive(serde::Serialize)]
+struct OverlaysSection<'a> {
+    entries: &'a [OverlayEntry],
+}
+
+impl Serialize for PackMeta {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("pack", &PackSection {
+            pack_format: self.pack_format,
+            description: &self.description,
+        })?;
+        if !self.filter.is_empty() {
+            map.serialize_entry("filter", &FilterSection { block: &self.filter })?;
+        }
+        if !self.overlays.is_empty() {
+            map.serialize_entry("overlays", &OverlaysSection { entries: &self.overlays })?;
+        }
+        map.end()
+    }
+}
+
+json_file!(PackMeta);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pack_meta_display() {
+        let meta = PackMeta::new(48, "A \"quoted\" pack");
+        let expected = "{\n  \"pack\": {\n    \"pack_format\": 48,\n    \"description\": \"A \\\"quoted\\\" pack\"\n  }\n}";
+        assert_eq!(meta.to_string(), expected, "pack.mcmeta was not rendered correctly");
+    }
+
+    #[test]
+    fn filter() {
+        let mut meta = PackMeta::new(48, "test");
+        meta.filter.push(PackFilter::default().namespace("minecraft").path("recipe/.*_from_.*"));
+        meta.filter.push(PackFilter::default().path("advancement/story/.*"));
+        let json: serde_json::Value = serde_json::from_str(&meta.to_string()).expect("pack.mcmeta should be valid JSON");
+        assert_eq!(json["filter"], serde_json::json!({
+            "block": [
+                {"namespace": "minecraft", "path": "recipe/.*_from_.*"},
+                {"path": "advancement/story/.*"},
+            ],
+        }));
+        assert!(!PackMeta::new(48, "test").to_string().contains("filter"), "an empty filter should be left out");
+    }
+
+    #[test]
+    fn overlays() {
+        let mut meta = PackMeta::new(48, "test");
+        meta.overlays.push(OverlayEntry { formats: (18..=41).into(), directory: "legacy".to_string() });
+        meta.overlays.push(OverlayEntry { formats: 48.into(), directory: "v48".to_string() });
+        let json: serde_json::Value = serde_json::from_str(&meta.to_string()).expect("pack.mcmeta should be valid JSON");
+        assert_eq!(json["overlays"], serde_json::json!({
+            "entries": [
+                {"formats": {"min_inclusive": 18, "max_inclusive": 41}, "directory": "legacy"},
+                {"formats": 48, "directory": "v48"},
+            ],
+        }));
+    }
+
+    #[test]
+    fn for_version() {
+        let meta = PackMeta::for_version(MinecraftVersion::V1_21, "test").expect("1.21 should be known");
+        assert_eq!(meta.pack_format, 48);
+        assert!(PackMeta::for_version(MinecraftVersion::new(1, 12, 0), "test").is_err(), "1.12 has no data packs");
+    }
+}
@@ -0,0 +1,65 @@
+use std::path::{Path, PathBuf};
+use crate::error::DatapackError;
+use crate::resource_location::ResourceLocation;
+use crate::utils::file_system::Entry;
+
+/// A file type that lives at `data/<namespace>/<directory>/<path>.<extension>`.
+pub trait Resource: Entry {
+    const DIRECTORY: &'static str;
+    const EXTENSION: &'static str = "json";
+    /// The oldest pack format that can load this type of resource.
+    const MIN_PACK_FORMAT: u32 = 1;
+    /// The oldest pack format that loads this type of resource without an experimental
+    /// feature enabled.
+    const STABLE_PACK_FORMAT: u32 = Self::MIN_PACK_FORMAT;
+
+    /// The directory for `pack_format`, for resource types whose directory was renamed.
+    fn directory(_pack_format: u32) -> &'static str {
+        Self::DIRECTORY
+    }
+
+    /// Lets resources whose format changed between pack formats render for `pack_format`.
+    /// [`Datapack::add`](crate::datapack::Datapack::add) calls it with the datapack's
+    /// pack format before inserting the resource.
+    fn set_pack_format(&mut self, _pack_format: u32) {}
+
+    /// Checks what the type system can't, before [`Datapack::add`](crate::datapack::Datapack::add)
+    /// accepts the resource.
+    fn validate(&self) -> Result<(), DatapackError> {
+        Ok(())
+    }
+
+    /// The resources this one refers to, for
+    /// [`Datapack::validate_references`](crate::datapack::Datapack::validate_references).
+    fn references(&self) -> Vec<Reference> {
+        Vec::new()
+    }
+}
+
+/// A resource that another resource refers to by its location.
+#[derive(Debug, Clone)]
+pub struct Reference {
+    pub location: ResourceLocation,
+    directory: fn(u32) -> &'static str,
+    extension: &'static str,
+}
+
+impl Reference {
+    /// A reference to the resource of type `R` at `location`.
+    pub fn to<R: Resource>(location: ResourceLocation) -> Self {
+        Self { location, directory: R::directory, extension: R::EXTENSION }
+    }
+
+    /// Whether the referenced resource is of type `R`.
+    pub fn is_to<R: Resource>(&self) -> bool {
+        self.extension == R::EXTENSION && (self.directory)(u32::MAX) == R::directory(u32::MAX)
+    }
+
+    /// Where the referenced resource is in a datapack of `pack_format`.
+    pub fn path(&self, pack_format: u32) -> PathBuf {
+        Path::new("data")
+            .join(self.location.namespace())
+            .join((self.directory)(pack_format))
+            .join(format!("{}.{}", self.location.path(), self.extension))
+    }
+}
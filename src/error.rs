@@ -0,0 +1,47 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::io;
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub enum DatapackError {
+    Io(io::Error),
+    DuplicateEntry(PathBuf),
+    InvalidPath(PathBuf),
+    NotADatapack(PathBuf),
+    InvalidResourceLocation(String),
+    UnsupportedPackFormat { directory: &'static str, required: u32, pack_format: u32 },
+    Validation(String),
+}
+
+impl Display for DatapackError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "{error}"),
+            Self::DuplicateEntry(path) => write!(f, "an entry already exists at '{}'", path.display()),
+            Self::InvalidPath(path) => write!(f, "'{}' is not a valid path inside a datapack", path.display()),
+            Self::NotADatapack(path) => write!(f, "'{}' is not a datapack", path.display()),
+            Self::InvalidResourceLocation(location) => write!(f, "'{location}' is not a valid resource location"),
+            Self::UnsupportedPackFormat { directory, required, pack_format } => write!(
+                f,
+                "'{directory}' requires pack format {required} or newer, but the pack format is {pack_format}",
+            ),
+            Self::Validation(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl Error for DatapackError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for DatapackError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
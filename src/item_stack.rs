@@ -0,0 +1,309 @@
+use std::fmt::Write;
+use crate::datapack::pack_format::{ITEM_COMPONENTS_PACK_FORMAT, SNBT_TEXT_PACK_FORMAT};
+use crate::data::tag::TagValue;
+use crate::error::DatapackError;
+use crate::nbt::{NbtCompound, Snbt};
+use crate::resource_location::ResourceLocation;
+use crate::text::TextComponent;
+
+/// The pack format of 1.21.4, where custom model data became lists of values.
+const CUSTOM_MODEL_DATA_LISTS_PACK_FORMAT: u32 = 61;
+
+/// A component of an [`ItemStack`], written as a component on pack formats with item
+/// components, and as the NBT it replaced before them.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ItemComponent {
+    CustomName(Box<TextComponent>),
+    Lore(Vec<TextComponent>),
+    /// Enchantment IDs and their levels.
+    Enchantments(Vec<(ResourceLocation, u32)>),
+    Damage(u32),
+    Unbreakable,
+    CustomModelData(i32),
+    /// Data of the item only datapacks read, written at the top of the NBT before item
+    /// components.
+    CustomData(NbtCompound),
+    /// Any other component, written as `id=value`. Left out before item components, since
+    /// it has no NBT form.
+    Raw { id: ResourceLocation, value: Snbt },
+}
+
+/// `value` as the integer type of its NBT, failing if it doesn't fit.
+fn fit<T: TryFrom<u32>>(value: u32, name: &str) -> Result<T, DatapackError> {
+    T::try_from(value).map_err(|_| DatapackError::Validation(format!("invalid item: the {name} {value} is too large")))
+}
+
+impl ItemComponent {
+    fn id(&self) -> String {
+        let name = match self {
+            Self::CustomName(_) => "custom_name",
+            Self::Lore(_) => "lore",
+            Self::Enchantments(_) => "enchantments",
+            Self::Damage(_) => "damage",
+            Self::Unbreakable => "unbreakable",
+            Self::CustomModelData(_) => "custom_model_data",
+            Self::CustomData(_) => "custom_data",
+            Self::Raw { id, .. } => return id.to_string(),
+        };
+        format!("minecraft:{name}")
+    }
+
+    fn value(&self, pack_format: u32) -> Result<Snbt, DatapackError> {
+        Ok(match self {
+            Self::CustomName(name) => name.to_nbt(pack_format),
+            Self::Lore(lines) => Snbt::List(lines.iter().map(|line| line.to_nbt(pack_format)).collect()),
+            Self::Enchantments(enchantments) => {
+                let levels = enchantments.iter().map(|(id, level)| Ok((id.to_string(), Snbt::Int(fit(*level, "enchantment level")?))));
+                let levels = levels.collect::<Result<NbtCompound, DatapackError>>()?;
+                // Enchantments lost their `levels` wrapper along with text becoming SNBT.
+                match pack_format >= SNBT_TEXT_PACK_FORMAT {
+                    true => levels.into(),
+                    false => NbtCompound::new().with("levels", levels).into(),
+                }
+            }
+            Self::Damage(damage) => Snbt::Int(fit(*damage, "damage")?),
+            Self::Unbreakable => NbtCompound::new().into(),
+            Self::CustomModelData(data) if pack_format >= CUSTOM_MODEL_DATA_LISTS_PACK_FORMAT => {
+                NbtCompound::new().with("floats", vec![Snbt::Float(*data as f32)]).into()
+            }
+            Self::CustomModelData(data) => Snbt::Int(*data),
+            Self::CustomData(data) => data.clone().into(),
+            Self::Raw { value, .. } => value.clone(),
+        })
+    }
+
+    /// Adds the NBT this component replaced to `tag`.
+    fn add_legacy(&self, tag: &mut NbtCompound) -> Result<(), DatapackError> {
+        let mut display = || match tag.remove("display") {
+            Some(Snbt::Compound(display)) => display,
+            _ => NbtCompound::new(),
+        };
+        match self {
+            Self::CustomName(name) => {
+                let display = display().with("Name", name.to_string());
+                tag.insert("display", display);
+            }
+            Self::Lore(lines) => {
+                let display = display().with("Lore", lines.iter().map(|line| Snbt::String(line.to_string())).collect::<Vec<_>>());
+                tag.insert("display", display);
+            }
+            Self::Enchantments(enchantments) => {
+                let enchantments = enchantments
+                    .iter()
+                    .map(|(id, level)| Ok(NbtCompound::new().with("id", id.to_string()).with("lvl", fit::<i16>(*level, "enchantment level")?).into()))
+                    .collect::<Result<Vec<Snbt>, DatapackError>>()?;
+                tag.insert("Enchantments", enchantments);
+            }
+            Self::Damage(damage) => {
+                tag.insert("Damage", fit::<i32>(*damage, "damage")?);
+            }
+            Self::Unbreakable => {
+                tag.insert("Unbreakable", true);
+            }
+            Self::CustomModelData(data) => {
+                tag.insert("CustomModelData", *data);
+            }
+            Self::CustomData(data) => tag.extend(data.iter().map(|(key, value)| (key, value.clone()))),
+            Self::Raw { .. } => {}
+        }
+        Ok(())
+    }
+}
+
+/// Writes `item` with `components` in brackets, or with their NBT before item components.
+/// Fails if a number is too large for its NBT.
+fn render(item: &str, components: &[ItemComponent], pack_format: u32) -> Result<String, DatapackError> {
+    let mut rendered = item.to_string();
+    if pack_format < ITEM_COMPONENTS_PACK_FORMAT {
+        let mut tag = NbtCompound::new();
+        components.iter().try_for_each(|component| component.add_legacy(&mut tag))?;
+        if !tag.is_empty() {
+            write!(rendered, "{tag}").expect("writing to a string doesn't fail");
+        }
+    } else if !components.is_empty() {
+        let components = components
+            .iter()
+            .map(|component| Ok(format!("{}={}", component.id(), component.value(pack_format)?)))
+            .collect::<Result<Vec<String>, DatapackError>>()?;
+        write!(rendered, "[{}]", components.join(",")).expect("writing to a string doesn't fail");
+    }
+    Ok(rendered)
+}
+
+/// An item with its count and components, as given by commands like `give`.
+///
+/// ```
+/// use minecraft_datapack_generator::item_stack::ItemStack;
+///
+/// let location = "minecraft:stick".parse().unwrap();
+/// let stick = ItemStack::new(location).custom_name("Wand").unbreakable();
+/// assert_eq!(stick.render(26).unwrap(), r#"minecraft:stick{display:{Name:'"Wand"'},Unbreakable:1b}"#);
+/// assert_eq!(stick.render(48).unwrap(), r#"minecraft:stick[minecraft:custom_name='"Wand"',minecraft:unbreakable={}]"#);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemStack {
+    pub item: ResourceLocation,
+    pub count: u32,
+    pub components: Vec<ItemComponent>,
+}
+
+impl ItemStack {
+    pub fn new(item: ResourceLocation) -> Self {
+        Self { item, count: 1, components: Vec::new() }
+    }
+
+    pub fn count(mut self, count: u32) -> Self {
+        self.count = count;
+        self
+    }
+
+    pub fn component(mut self, component: ItemComponent) -> Self {
+        self.components.push(component);
+        self
+    }
+
+    pub fn custom_name(self, name: impl Into<TextComponent>) -> Self {
+        self.component(ItemComponent::CustomName(Box::new(name.into())))
+    }
+
+    pub fn lore(self, lines: Vec<TextComponent>) -> Self {
+        self.component(ItemComponent::Lore(lines))
+    }
+
+    /// Adds `enchantment` to the enchantments of the item, adding the component the first
+    /// time.
+    pub fn enchantment(mut self, enchantment: ResourceLocation, level: u32) -> Self {
+        let existing = self.components.iter_mut().find_map(|component| match component {
+            ItemComponent::Enchantments(enchantments) => Some(enchantments),
+            _ => None,
+        });
+        match existing {
+            Some(enchantments) => enchantments.push((enchantment, level)),
+            None => self.components.push(ItemComponent::Enchantments(vec![(enchantment, level)])),
+        }
+        self
+    }
+
+    pub fn damage(self, damage: u32) -> Self {
+        self.component(ItemComponent::Damage(damage))
+    }
+
+    pub fn unbreakable(self) -> Self {
+        self.component(ItemComponent::Unbreakable)
+    }
+
+    pub fn custom_model_data(self, data: i32) -> Self {
+        self.component(ItemComponent::CustomModelData(data))
+    }
+
+    pub fn custom_data(self, data: NbtCompound) -> Self {
+        self.component(ItemComponent::CustomData(data))
+    }
+
+    /// The item and its components as commands read them in a datapack of `pack_format`,
+    /// without the count.
+    pub fn render(&self, pack_format: u32) -> Result<String, DatapackError> {
+        render(&self.item.to_string(), &self.components, pack_format)
+    }
+
+    /// The count as commands read it, failing for an empty stack, which commands reject,
+    /// and for counts that don't fit in their integer argument.
+    pub(crate) fn checked_count(&self) -> Result<u32, DatapackError> {
+        if self.count == 0 {
+            return Err(DatapackError::Validation(format!("invalid item: a stack of '{}' can't be empty", self.item)));
+        }
+        fit::<i32>(self.count, "count")?;
+        Ok(self.count)
+    }
+}
+
+/// Which items a command like `clear` matches: an item or any item of a `#` tag, with
+/// exactly the given components, or at least the given NBT before item components.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemFilter {
+    pub item: TagValue,
+    pub components: Vec<ItemComponent>,
+}
+
+impl ItemFilter {
+    pub fn new(item: impl Into<TagValue>) -> Self {
+        Self { item: item.into(), components: Vec::new() }
+    }
+
+    pub fn component(mut self, component: ItemComponent) -> Self {
+        self.components.push(component);
+        self
+    }
+
+    pub fn render(&self, pack_format: u32) -> Result<String, DatapackError> {
+        render(&self.item.to_string(), &self.components, pack_format)
+    }
+}
+
+impl From<ResourceLocation> for ItemFilter {
+    fn from(item: ResourceLocation) -> Self {
+        Self::new(item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+
+    fn location(location: &str) -> ResourceLocation {
+        location.parse().expect("should have parsed the resource location")
+    }
+
+    fn excalibur() -> ItemStack {
+        ItemStack::new(location("minecraft:diamond_sword"))
+            .custom_name(TextComponent::text("Excalibur").color(Color::Gold))
+            .enchantment(location("minecraft:sharpness"), 5)
+            .enchantment(location("minecraft:unbreaking"), 3)
+    }
+
+    #[test]
+    fn components_by_pack_format() {
+        assert_eq!(
+            excalibur().render(26).expect("should have rendered the item"),
+            r#"minecraft:diamond_sword{display:{Name:'{"text":"Excalibur","color":"gold"}'},Enchantments:[{id:"minecraft:sharpness",lvl:5s},{id:"minecraft:unbreaking",lvl:3s}]}"#,
+        );
+        assert_eq!(
+            excalibur().render(48).expect("should have rendered the item"),
+            r#"minecraft:diamond_sword[minecraft:custom_name='{"text":"Excalibur","color":"gold"}',minecraft:enchantments={levels:{"minecraft:sharpness":5,"minecraft:unbreaking":3}}]"#,
+        );
+        assert_eq!(
+            excalibur().render(71).expect("should have rendered the item"),
+            r#"minecraft:diamond_sword[minecraft:custom_name={color:"gold",text:"Excalibur"},minecraft:enchantments={"minecraft:sharpness":5,"minecraft:unbreaking":3}]"#,
+        );
+        assert_eq!(ItemStack::new(location("minecraft:stone")).count(64).render(48).expect("should have rendered the item"), "minecraft:stone", "the count is not part of the item");
+    }
+
+    #[test]
+    fn legacy_nbt() {
+        let item = ItemStack::new(location("minecraft:carrot_on_a_stick"))
+            .custom_model_data(7)
+            .lore(vec![TextComponent::text("It's \"magic\""), "Right-click".into()])
+            .custom_data(NbtCompound::new().with("wand", true))
+            .component(ItemComponent::Raw { id: location("minecraft:rarity"), value: "epic".into() });
+        assert_eq!(
+            item.render(26).expect("should have rendered the item"),
+            r#"minecraft:carrot_on_a_stick{CustomModelData:7,display:{Lore:['"It\'s \\"magic\\""','"Right-click"']},wand:1b}"#,
+            "components without an NBT form should be left out",
+        );
+        assert_eq!(
+            item.render(61).expect("should have rendered the item"),
+            r#"minecraft:carrot_on_a_stick[minecraft:custom_model_data={floats:[7f]},minecraft:lore=['"It\'s \\"magic\\""','"Right-click"'],minecraft:custom_data={wand:1b},minecraft:rarity="epic"]"#,
+        );
+    }
+
+    #[test]
+    fn numbers_out_of_range() {
+        let sword = ItemStack::new(location("minecraft:diamond_sword")).enchantment(location("minecraft:sharpness"), 40_000);
+        assert!(sword.render(26).is_err(), "enchantment levels are shorts before item components");
+        assert!(sword.render(48).is_ok());
+        let damaged = ItemStack::new(location("minecraft:diamond_sword")).damage(u32::MAX);
+        assert!(matches!(damaged.render(48), Err(DatapackError::Validation(_))), "damage is an int");
+        assert!(ItemStack::new(location("minecraft:stone")).count(0).checked_count().is_err(), "a stack can't be empty");
+    }
+}
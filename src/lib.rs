@@ -1 +1,19 @@
 pub mod utils;
+pub mod error;
+pub mod datapack;
+pub mod resource_location;
+pub mod range;
+pub mod color;
+pub mod text;
+pub mod nbt;
+pub mod block_state;
+pub mod item_stack;
+pub mod command;
+pub mod data;
+
+#[cfg(feature = "macros")]
+pub use minecraft_datapack_generator_macros::mcfunction;
+
+/// Lets the paths `mcfunction!` expands to resolve inside this crate too.
+#[cfg(feature = "macros")]
+extern crate self as minecraft_datapack_generator;
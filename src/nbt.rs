@@ -0,0 +1,570 @@
+use std::fmt::{self, Display};
+use std::iter::Peekable;
+use std::str::{CharIndices, FromStr};
+use serde::{Serialize, Serializer};
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer};
+use crate::error::DatapackError;
+
+/// An NBT value, displayed as SNBT (`{id:"minecraft:stone",Count:1b}`).
+#[derive(Debug, Clone, PartialEq)]
+pub enum Snbt {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    String(String),
+    List(Vec<Snbt>),
+    Compound(NbtCompound),
+    ByteArray(Vec<i8>),
+    IntArray(Vec<i32>),
+    LongArray(Vec<i64>),
+    /// A byte displayed as `1b` or `0b`.
+    Bool(bool),
+}
+
+impl Display for Snbt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Byte(value) => write!(f, "{value}b"),
+            Self::Short(value) => write!(f, "{value}s"),
+            Self::Int(value) => write!(f, "{value}"),
+            Self::Long(value) => write!(f, "{value}L"),
+            Self::Float(value) => write!(f, "{value}f"),
+            Self::Double(value) => write!(f, "{value}d"),
+            Self::String(value) => write!(f, "{}", quote(value)),
+            Self::List(values) => write_array(f, "", values.iter().map(ToString::to_string)),
+            Self::Compound(compound) => write!(f, "{compound}"),
+            Self::ByteArray(values) => write_array(f, "B;", values.iter().map(|value| format!("{value}b"))),
+            Self::IntArray(values) => write_array(f, "I;", values.iter().map(ToString::to_string)),
+            Self::LongArray(values) => write_array(f, "L;", values.iter().map(|value| format!("{value}L"))),
+            Self::Bool(value) => write!(f, "{}b", u8::from(*value)),
+        }
+    }
+}
+
+/// Converts JSON, such as a text component, to the SNBT the game reads it from since 1.21.5.
+/// Whole numbers become ints, or longs if they don't fit, other numbers doubles, and `null`
+/// an empty string.
+impl From<&serde_json::Value> for Snbt {
+    fn from(value: &serde_json::Value) -> Self {
+        use serde_json::Value;
+
+        match value {
+            Value::Null => Self::String(String::new()),
+            Value::Bool(value) => Self::Bool(*value),
+            Value::Number(number) => match number.as_i64() {
+                Some(number) => i32::try_from(number).map_or(Self::Long(number), Self::Int),
+                None => Self::Double(number.as_f64().unwrap_or_default()),
+            },
+            Value::String(value) => Self::String(value.clone()),
+            Value::Array(values) => Self::List(values.iter().map(Self::from).collect()),
+            Value::Object(entries) => Self::Compound(entries.iter().map(|(key, value)| (key.as_str(), Self::from(value))).collect()),
+        }
+    }
+}
+
+/// The name NBT documentation uses for what this crate displays as [`Snbt`].
+pub type NbtValue = Snbt;
+
+fn write_array(f: &mut fmt::Formatter, prefix: &str, values: impl Iterator<Item = String>) -> fmt::Result {
+    write!(f, "[{prefix}{}]", values.collect::<Vec<_>>().join(","))
+}
+
+/// Quotes a string the way the game does: with double quotes, unless the first quote in
+/// the string is a double quote, in which case single quotes are used.
+fn quote(value: &str) -> String {
+    let quote = match value.chars().find(|c| matches!(c, '"' | '\'')) {
+        Some('"') => '\'',
+        _ => '"',
+    };
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push(quote);
+    for c in value.chars() {
+        if c == '\\' || c == quote {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push(quote);
+    quoted
+}
+
+fn is_unquoted_key(key: &str) -> bool {
+    !key.is_empty() && key.chars().all(is_unquoted_char)
+}
+
+macro_rules! impl_from {
+    ($($from:ty => $variant:ident),+ $(,)?) => {
+        $(
+            impl From<$from> for Snbt {
+                fn from(value: $from) -> Self {
+                    Self::$variant(value.into())
+                }
+            }
+        )+
+    };
+}
+
+impl_from!(
+    i8 => Byte,
+    i16 => Short,
+    i32 => Int,
+    i64 => Long,
+    f32 => Float,
+    f64 => Double,
+    bool => Bool,
+    String => String,
+    &str => String,
+    Vec<Snbt> => List,
+    NbtCompound => Compound,
+);
+
+/// An NBT compound that keeps its keys in insertion order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NbtCompound {
+    entries: Vec<(String, Snbt)>,
+}
+
+impl NbtCompound {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts `value` at `key`, replacing any existing value but keeping its position.
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<Snbt>) -> &mut Self {
+        let (key, value) = (key.into(), value.into());
+        match self.entries.iter_mut().find(|(existing, _)| *existing == key) {
+            Some((_, existing)) => *existing = value,
+            None => self.entries.push((key, value)),
+        }
+        self
+    }
+
+    pub fn with(mut self, key: impl Into<String>, value: impl Into<Snbt>) -> Self {
+        self.insert(key, value);
+        self
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Snbt> {
+        self.entries.iter().find(|(existing, _)| existing == key).map(|(_, value)| value)
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<Snbt> {
+        let index = self.entries.iter().position(|(existing, _)| existing == key)?;
+        Some(self.entries.remove(index).1)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Snbt)> {
+        self.entries.iter().map(|(key, value)| (key.as_str(), value))
+    }
+}
+
+impl<K: Into<String>, V: Into<Snbt>> FromIterator<(K, V)> for NbtCompound {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut compound = Self::new();
+        for (key, value) in iter {
+            compound.insert(key, value);
+        }
+        compound
+    }
+}
+
+impl<K: Into<String>, V: Into<Snbt>> Extend<(K, V)> for NbtCompound {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl Display for NbtCompound {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let entries = self.entries.iter().map(|(key, value)| {
+            if is_unquoted_key(key) {
+                format!("{key}:{value}")
+            } else {
+                format!("{}:{value}", quote(key))
+            }
+        });
+        write!(f, "{{{}}}", entries.collect::<Vec<_>>().join(","))
+    }
+}
+
+impl FromStr for NbtCompound {
+    type Err = DatapackError;
+
+    /// Parses an SNBT compound such as `{id:"minecraft:stone",Count:1b}`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.parse()? {
+            Snbt::Compound(compound) => Ok(compound),
+            _ => Err(DatapackError::Validation(format!("invalid SNBT compound '{s}': not a compound"))),
+        }
+    }
+}
+
+impl Serialize for NbtCompound {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for NbtCompound {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(de::Error::custom)
+    }
+}
+
+impl FromStr for Snbt {
+    type Err = DatapackError;
+
+    /// Parses SNBT. A number is read as the type of its suffix, or as an int or double
+    /// without one, so `1b` is a byte and only `true` and `false` are bools. Anything else
+    /// unquoted is a string.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = SnbtParser { source: s, chars: s.char_indices().peekable() };
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        match parser.chars.next() {
+            Some((index, _)) => Err(parser.error(index, "trailing characters")),
+            None => Ok(value),
+        }
+    }
+}
+
+struct SnbtParser<'s> {
+    source: &'s str,
+    chars: Peekable<CharIndices<'s>>,
+}
+
+impl SnbtParser<'_> {
+    fn error(&self, index: usize, reason: &str) -> DatapackError {
+        DatapackError::Validation(format!("invalid SNBT '{}': {reason} at {index}", self.source))
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+    }
+
+    fn position(&mut self) -> usize {
+        self.chars.peek().map_or(self.source.len(), |(index, _)| *index)
+    }
+
+    /// Skips whitespace and `expected`, failing if something else is next.
+    fn expect(&mut self, expected: char) -> Result<(), DatapackError> {
+        self.skip_whitespace();
+        let index = self.position();
+        match self.chars.next_if(|(_, c)| *c == expected) {
+            Some(_) => Ok(()),
+            None => Err(self.error(index, &format!("expected '{expected}'"))),
+        }
+    }
+
+    /// Skips whitespace and `end` or a `,`, and returns whether it was `end`.
+    fn separator(&mut self, end: char) -> Result<bool, DatapackError> {
+        self.skip_whitespace();
+        let index = self.position();
+        match self.chars.next() {
+            Some((_, ',')) => Ok(false),
+            Some((_, c)) if c == end => Ok(true),
+            _ => Err(self.error(index, &format!("expected ',' or '{end}'"))),
+        }
+    }
+
+    fn value(&mut self) -> Result<Snbt, DatapackError> {
+        self.skip_whitespace();
+        let index = self.position();
+        match self.chars.peek().map(|(_, c)| *c) {
+            Some('{') => self.compound().map(Snbt::Compound),
+            Some('[') => self.list(),
+            Some('"' | '\'') => self.quoted().map(Snbt::String),
+            Some(_) => {
+                let token = self.unquoted();
+                match token {
+                    "" => Err(self.error(index, "expected a value")),
+                    "true" => Ok(Snbt::Bool(true)),
+                    "false" => Ok(Snbt::Bool(false)),
+                    _ => Ok(number(token).unwrap_or_else(|| Snbt::String(token.to_string()))),
+                }
+            }
+            None => Err(self.error(index, "expected a value")),
+        }
+    }
+
+    fn compound(&mut self) -> Result<NbtCompound, DatapackError> {
+        self.expect('{')?;
+        let mut compound = NbtCompound::new();
+        self.skip_whitespace();
+        if self.chars.next_if(|(_, c)| *c == '}').is_some() {
+            return Ok(compound);
+        }
+        loop {
+            self.skip_whitespace();
+            let index = self.position();
+            let key = match self.chars.peek() {
+                Some((_, '"' | '\'')) => self.quoted()?,
+                _ => self.unquoted().to_string(),
+            };
+            if key.is_empty() {
+                return Err(self.error(index, "expected a key"));
+            }
+            self.expect(':')?;
+            compound.insert(key, self.value()?);
+            if self.separator('}')? {
+                return Ok(compound);
+            }
+        }
+    }
+
+    fn list(&mut self) -> Result<Snbt, DatapackError> {
+        self.expect('[')?;
+        let start = self.position();
+        let array = match self.source[start..].get(..2) {
+            Some(prefix @ ("B;" | "I;" | "L;")) => prefix.chars().next(),
+            _ => None,
+        };
+        if array.is_some() {
+            self.chars.nth(1);
+        }
+        let mut values = Vec::new();
+        self.skip_whitespace();
+        if self.chars.next_if(|(_, c)| *c == ']').is_none() {
+            loop {
+                let index = self.position();
+                values.push((index, self.value()?));
+                if self.separator(']')? {
+                    break;
+                }
+            }
+        }
+        let element = |(index, value): (usize, Snbt), expected: &str| match value {
+            Snbt::Byte(value) if expected == "byte" => Ok(i64::from(value)),
+            Snbt::Bool(value) if expected == "byte" => Ok(i64::from(value)),
+            Snbt::Int(value) if expected == "int" => Ok(i64::from(value)),
+            Snbt::Long(value) if expected == "long" => Ok(value),
+            _ => Err(self.error(index, &format!("expected a {expected}"))),
+        };
+        Ok(match array {
+            Some('B') => Snbt::ByteArray(values.into_iter().map(|value| element(value, "byte").map(|value| value as i8)).collect::<Result<_, _>>()?),
+            Some('I') => Snbt::IntArray(values.into_iter().map(|value| element(value, "int").map(|value| value as i32)).collect::<Result<_, _>>()?),
+            Some(_) => Snbt::LongArray(values.into_iter().map(|value| element(value, "long")).collect::<Result<_, _>>()?),
+            None => Snbt::List(values.into_iter().map(|(_, value)| value).collect()),
+        })
+    }
+
+    fn quoted(&mut self) -> Result<String, DatapackError> {
+        let (start, quote) = self.chars.next().expect("a quote should be next");
+        let mut value = String::new();
+        while let Some((_, c)) = self.chars.next() {
+            match c {
+                '\\' => match self.chars.next() {
+                    Some((_, escaped)) => value.push(escaped),
+                    None => break,
+                },
+                c if c == quote => return Ok(value),
+                c => value.push(c),
+            }
+        }
+        Err(self.error(start, "unterminated string"))
+    }
+
+    fn unquoted(&mut self) -> &str {
+        let start = self.position();
+        while self.chars.next_if(|(_, c)| is_unquoted_char(*c)).is_some() {}
+        &self.source[start..self.position()]
+    }
+}
+
+/// Reads an unquoted token as a number, or returns `None` if it isn't one.
+fn number(token: &str) -> Option<Snbt> {
+    let (digits, suffix) = match token.char_indices().last()? {
+        (index, suffix) if suffix.is_ascii_alphabetic() => (&token[..index], Some(suffix.to_ascii_lowercase())),
+        _ => (token, None),
+    };
+    match suffix {
+        Some('b') => digits.parse().ok().map(Snbt::Byte),
+        Some('s') => digits.parse().ok().map(Snbt::Short),
+        Some('l') => digits.parse().ok().map(Snbt::Long),
+        Some('f') => digits.parse().ok().filter(|value: &f32| value.is_finite()).map(Snbt::Float),
+        Some('d') => digits.parse().ok().filter(|value: &f64| value.is_finite()).map(Snbt::Double),
+        Some(_) => None,
+        None => digits
+            .parse()
+            .ok()
+            .map(Snbt::Int)
+            .or_else(|| digits.contains(['.', 'e', 'E']).then(|| digits.parse().ok().filter(|value: &f64| value.is_finite())).flatten().map(Snbt::Double)),
+    }
+}
+
+fn is_unquoted_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '+')
+}
+
+/// Builds an [`NbtCompound`] from `key => value` pairs, where each value is anything
+/// that converts into [`Snbt`].
+///
+/// ```
+/// use minecraft_datapack_generator::nbt_compound;
+///
+/// let item = nbt_compound! { "id" => "minecraft:stone", "Count" => 1i8 };
+/// assert_eq!(item.to_string(), r#"{id:"minecraft:stone",Count:1b}"#);
+/// ```
+#[macro_export]
+macro_rules! nbt_compound {
+    ($($key:expr => $value:expr),* $(,)?) => {{
+        #[allow(unused_mut)]
+        let mut compound = $crate::nbt::NbtCompound::new();
+        $(compound.insert($key, $value);)*
+        compound
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::TextComponent;
+
+    #[test]
+    fn number_suffixes() {
+        assert_eq!(Snbt::Byte(1).to_string(), "1b");
+        assert_eq!(Snbt::Short(-2).to_string(), "-2s");
+        assert_eq!(Snbt::Int(3).to_string(), "3");
+        assert_eq!(Snbt::Long(4).to_string(), "4L");
+        assert_eq!(Snbt::Float(3.5).to_string(), "3.5f");
+        assert_eq!(Snbt::Float(1.0).to_string(), "1f");
+        assert_eq!(Snbt::Double(0.1).to_string(), "0.1d");
+        assert_eq!(Snbt::Double(2.0).to_string(), "2d");
+        assert_eq!(Snbt::Bool(true).to_string(), "1b");
+        assert_eq!(Snbt::Bool(false).to_string(), "0b");
+    }
+
+    #[test]
+    fn string_quoting() {
+        assert_eq!(Snbt::from("plain").to_string(), r#""plain""#);
+        assert_eq!(Snbt::from("it's").to_string(), r#""it's""#);
+        assert_eq!(Snbt::from(r#"say "hi""#).to_string(), r#"'say "hi"'"#);
+        assert_eq!(Snbt::from(r#"say "it's""#).to_string(), r#"'say "it\'s"'"#);
+        assert_eq!(Snbt::from(r#"it's "both""#).to_string(), r#""it's \"both\"""#);
+        assert_eq!(Snbt::from(r"back\slash").to_string(), r#""back\\slash""#);
+    }
+
+    #[test]
+    fn text_component_string() {
+        let name = TextComponent::text("Boss");
+        let compound = nbt_compound! { "CustomName" => name.to_string() };
+        assert_eq!(compound.to_string(), r#"{CustomName:'"Boss"'}"#);
+    }
+
+    #[test]
+    fn text_component_snbt() {
+        let name = TextComponent::text("Boss").bold();
+        assert_eq!(Snbt::from(&name.to_json()).to_string(), r#"{bold:1b,text:"Boss"}"#);
+        assert_eq!(Snbt::from(&serde_json::json!([1, 5_000_000_000i64, 0.5])).to_string(), "[1,5000000000L,0.5d]");
+    }
+
+    #[test]
+    fn empty_compound() {
+        assert_eq!(NbtCompound::new().to_string(), "{}");
+        assert_eq!(nbt_compound! {}.to_string(), "{}");
+        assert_eq!(Snbt::List(Vec::new()).to_string(), "[]");
+    }
+
+    #[test]
+    fn nested_lists_of_compounds() {
+        let compound = nbt_compound! {
+            "Items" => vec![
+                Snbt::from(nbt_compound! { "Slot" => 0i8, "id" => "minecraft:diamond", "Count" => 2i8 }),
+                Snbt::from(nbt_compound! { "Slot" => 1i8, "id" => "minecraft:stick", "tag" => nbt_compound! {} }),
+            ],
+            "Tags" => vec![Snbt::from("a"), Snbt::from("b")],
+            "Invisible" => true,
+        };
+        assert_eq!(
+            compound.to_string(),
+            r#"{Items:[{Slot:0b,id:"minecraft:diamond",Count:2b},{Slot:1b,id:"minecraft:stick",tag:{}}],Tags:["a","b"],Invisible:1b}"#,
+        );
+    }
+
+    #[test]
+    fn typed_arrays() {
+        assert_eq!(Snbt::ByteArray(vec![1, -1]).to_string(), "[B;1b,-1b]");
+        assert_eq!(Snbt::IntArray(vec![1, 2, 3]).to_string(), "[I;1,2,3]");
+        assert_eq!(Snbt::LongArray(vec![i64::MIN, 0, i64::MAX]).to_string(), "[L;-9223372036854775808L,0L,9223372036854775807L]");
+        assert_eq!(Snbt::IntArray(Vec::new()).to_string(), "[I;]");
+    }
+
+    #[test]
+    fn compound_keys() {
+        let compound = nbt_compound! { "simple" => 1, "with space" => 2, "minecraft:custom_data" => 3 };
+        assert_eq!(compound.to_string(), r#"{simple:1,"with space":2,"minecraft:custom_data":3}"#);
+    }
+
+    #[test]
+    fn compound_insert_replaces() {
+        let mut compound = nbt_compound! { "a" => 1, "b" => 2 };
+        compound.insert("a", 3);
+        assert_eq!(compound.to_string(), "{a:3,b:2}");
+        assert_eq!(compound.remove("a"), Some(Snbt::Int(3)));
+        assert_eq!(compound.len(), 1);
+
+        compound.extend([("b", 4), ("c", 5)]);
+        assert_eq!(compound.to_string(), "{b:4,c:5}");
+        assert!(compound.contains_key("c"), "extended key should have been inserted");
+    }
+
+    #[test]
+    fn parse_snbt() {
+        let compound = nbt_compound! {
+            "Items" => vec![Snbt::from(nbt_compound! { "Slot" => 0i8, "id" => "minecraft:diamond", "Count" => 2i8 })],
+            "with space" => r#"say "it's""#,
+            "Pos" => vec![Snbt::Double(0.5), Snbt::Float(-1.0), Snbt::Long(4), Snbt::Short(2)],
+            "Arrays" => vec![Snbt::ByteArray(vec![1, -1]), Snbt::IntArray(Vec::new()), Snbt::LongArray(vec![i64::MIN])],
+            "Name" => "plain",
+        };
+        assert_eq!(compound.to_string().parse::<NbtCompound>().expect("should have parsed the compound"), compound);
+        assert_eq!(
+            "{ a : 1 , b : 2.5 , c : true , d : 1e3 , e : [ ] }".parse::<NbtCompound>().expect("should have parsed the compound"),
+            nbt_compound! { "a" => 1, "b" => 2.5, "c" => true, "d" => 1000.0, "e" => Vec::<Snbt>::new() },
+        );
+        assert_eq!("stone".parse::<Snbt>().expect("should have parsed the string"), Snbt::from("stone"));
+        for invalid in ["", "{", "{a:}", "{a:1,}", "{a 1}", "[I;1b]", "'open", "1 2", "[1"] {
+            assert!(invalid.parse::<Snbt>().is_err(), "'{invalid}' should not have parsed");
+        }
+        assert!("[1]".parse::<NbtCompound>().is_err(), "a list should not have parsed as a compound");
+    }
+
+    #[test]
+    fn compound_as_command_argument() {
+        use crate::command::data::{DataCommand, DataTarget};
+        use crate::command::selector::Selector;
+
+        let boss = nbt_compound! {
+            "CustomName" => r#"{"text":"Boss"}"#,
+            "Tags" => vec![NbtValue::from("mytag")],
+        };
+        assert_eq!(boss.to_string(), r#"{CustomName:'{"text":"Boss"}',Tags:["mytag"]}"#);
+        assert_eq!(
+            Selector::all_entities().nbt(&boss).to_string(),
+            r#"@e[nbt={CustomName:'{"text":"Boss"}',Tags:["mytag"]}]"#,
+        );
+        assert_eq!(
+            DataCommand::merge(DataTarget::Entity(Selector::executing_entity()), boss).to_string(),
+            r#"data merge entity @s {CustomName:'{"text":"Boss"}',Tags:["mytag"]}"#,
+        );
+    }
+}
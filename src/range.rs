@@ -0,0 +1,144 @@
+use std::fmt::{self, Display};
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer};
+
+/// A number range as written in commands: `5`, `..5`, `5..` or `3..7`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MinecraftRange<T> {
+    Exact(T),
+    AtMost(T),
+    AtLeast(T),
+    Between(T, T),
+}
+
+/// A range of integers, such as the count of an item predicate.
+pub type IntRange = MinecraftRange<i32>;
+
+impl<T: Copy> MinecraftRange<T> {
+    pub fn min(&self) -> Option<T> {
+        match *self {
+            Self::Exact(value) | Self::AtLeast(value) | Self::Between(value, _) => Some(value),
+            Self::AtMost(_) => None,
+        }
+    }
+
+    pub fn max(&self) -> Option<T> {
+        match *self {
+            Self::Exact(value) | Self::AtMost(value) | Self::Between(_, value) => Some(value),
+            Self::AtLeast(_) => None,
+        }
+    }
+}
+
+impl<T> From<T> for MinecraftRange<T> {
+    fn from(value: T) -> Self {
+        Self::Exact(value)
+    }
+}
+
+impl<T> From<std::ops::RangeInclusive<T>> for MinecraftRange<T> {
+    fn from(range: std::ops::RangeInclusive<T>) -> Self {
+        let (min, max) = range.into_inner();
+        Self::Between(min, max)
+    }
+}
+
+impl<T> From<std::ops::RangeFrom<T>> for MinecraftRange<T> {
+    fn from(range: std::ops::RangeFrom<T>) -> Self {
+        Self::AtLeast(range.start)
+    }
+}
+
+impl<T> From<std::ops::RangeToInclusive<T>> for MinecraftRange<T> {
+    fn from(range: std::ops::RangeToInclusive<T>) -> Self {
+        Self::AtMost(range.end)
+    }
+}
+
+impl<T: Display> Display for MinecraftRange<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Exact(value) => write!(f, "{value}"),
+            Self::AtMost(max) => write!(f, "..{max}"),
+            Self::AtLeast(min) => write!(f, "{min}.."),
+            Self::Between(min, max) => write!(f, "{min}..{max}"),
+        }
+    }
+}
+
+/// Serializes as JSON files write ranges: a bare number, or `{"min": ..., "max": ...}`
+/// with either bound left out.
+impl<T: Copy + Serialize> Serialize for MinecraftRange<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if let Self::Exact(value) = self {
+            return value.serialize(serializer);
+        }
+        let (min, max) = (self.min(), self.max());
+        let mut state = serializer.serialize_struct("MinecraftRange", usize::from(min.is_some()) + usize::from(max.is_some()))?;
+        if let Some(min) = min {
+            state.serialize_field("min", &min)?;
+        }
+        if let Some(max) = max {
+            state.serialize_field("max", &max)?;
+        }
+        state.end()
+    }
+}
+
+/// Reads either form [`Serialize`] writes. An object without bounds is rejected, as the
+/// game does.
+#[cfg(feature = "serde")]
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for MinecraftRange<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr<T> {
+            Exact(T),
+            Bounds { min: Option<T>, max: Option<T> },
+        }
+
+        match Repr::deserialize(deserializer)? {
+            Repr::Exact(value) => Ok(Self::Exact(value)),
+            Repr::Bounds { min: Some(min), max: Some(max) } => Ok(Self::Between(min, max)),
+            Repr::Bounds { min: Some(min), max: None } => Ok(Self::AtLeast(min)),
+            Repr::Bounds { min: None, max: Some(max) } => Ok(Self::AtMost(max)),
+            Repr::Bounds { min: None, max: None } => Err(de::Error::custom("a range needs a min or a max")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn range_display() {
+        assert_eq!(MinecraftRange::Exact(5).to_string(), "5");
+        assert_eq!(MinecraftRange::<i32>::from(..=5).to_string(), "..5");
+        assert_eq!(MinecraftRange::<i32>::from(5..).to_string(), "5..");
+        assert_eq!(MinecraftRange::<i32>::from(3..=7).to_string(), "3..7");
+        assert_eq!(MinecraftRange::<f64>::from(0.5..=2.0).to_string(), "0.5..2");
+    }
+
+    #[test]
+    fn range_json() {
+        let json = |range: MinecraftRange<i32>| serde_json::to_string(&range).expect("should have serialized the range");
+        assert_eq!(json(MinecraftRange::Exact(5)), "5");
+        assert_eq!(json((..=5).into()), r#"{"max":5}"#);
+        assert_eq!(json((5..).into()), r#"{"min":5}"#);
+        assert_eq!(json((3..=7).into()), r#"{"min":3,"max":7}"#);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn range_from_json() {
+        let parse = |json: &str| serde_json::from_str::<MinecraftRange<i32>>(json).expect("should have parsed the range");
+        assert_eq!(parse("5"), MinecraftRange::Exact(5));
+        assert_eq!(parse(r#"{"max":5}"#), MinecraftRange::AtMost(5));
+        assert_eq!(parse(r#"{"min":5}"#), MinecraftRange::AtLeast(5));
+        assert_eq!(parse(r#"{"min":3,"max":7}"#), MinecraftRange::Between(3, 7));
+        assert!(serde_json::from_str::<MinecraftRange<i32>>("{}").is_err(), "a range without bounds should be rejected");
+    }
+}
@@ -0,0 +1,229 @@
+use std::borrow::Cow;
+use std::fmt::{self, Display};
+use std::path::{Component, Path};
+use std::str::FromStr;
+use serde::{Serialize, Serializer};
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer};
+use crate::error::DatapackError;
+
+pub const DEFAULT_NAMESPACE: &str = "minecraft";
+
+/// A namespaced identifier such as `minecraft:stone` or `my_pack:spells/fireball`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ResourceLocation {
+    namespace: Cow<'static, str>,
+    path: Cow<'static, str>,
+}
+
+impl ResourceLocation {
+    /// Fails with [`DatapackError::InvalidResourceLocation`] on characters the game doesn't
+    /// allow, and on empty, `.` and `..` segments of the path, which would put the resource
+    /// outside of its directory.
+    pub fn new(namespace: impl Into<String>, path: impl Into<String>) -> Result<Self, DatapackError> {
+        let (namespace, path) = (namespace.into(), path.into());
+        if !is_valid(namespace.as_bytes(), false) || !is_valid(path.as_bytes(), true) {
+            return Err(DatapackError::InvalidResourceLocation(format!("{namespace}:{path}")));
+        }
+        Ok(Self { namespace: namespace.into(), path: path.into() })
+    }
+
+    /// Parses `s` like [`FromStr`], in a const context, without allocating:
+    ///
+    /// ```
+    /// use minecraft_datapack_generator::resource_location::ResourceLocation;
+    ///
+    /// const STONE: ResourceLocation = ResourceLocation::from_static("minecraft:stone");
+    /// assert_eq!(STONE, "stone".parse().unwrap());
+    /// ```
+    ///
+    /// Panics if `s` isn't a valid resource location, which fails the build when it is
+    /// evaluated at compile time, as for a `const`.
+    pub const fn from_static(s: &'static str) -> Self {
+        let bytes = s.as_bytes();
+        let mut colon = None;
+        let mut i = 0;
+        while i < bytes.len() {
+            if bytes[i] == b':' {
+                assert!(colon.is_none(), "a resource location has at most one ':'");
+                colon = Some(i);
+            }
+            i += 1;
+        }
+        let (namespace, path) = match colon {
+            Some(colon) => {
+                let (namespace, rest) = s.split_at(colon);
+                (namespace, rest.split_at(1).1)
+            }
+            None => (DEFAULT_NAMESPACE, s),
+        };
+        assert!(is_valid(namespace.as_bytes(), false), "invalid namespace");
+        assert!(is_valid(path.as_bytes(), true), "invalid path");
+        Self { namespace: Cow::Borrowed(namespace), path: Cow::Borrowed(path) }
+    }
+
+    pub fn minecraft(path: impl Into<String>) -> Result<Self, DatapackError> {
+        Self::new(DEFAULT_NAMESPACE, path)
+    }
+
+    /// The location of the file at `path` inside the resource directory `base`, such as
+    /// `data/test/function`, whose namespace is the directory after `data`. The file
+    /// `data/test/function/spells/fireball.mcfunction` is then `test:spells/fireball`.
+    ///
+    /// Fails with [`DatapackError::InvalidPath`] if `path` isn't inside `base`, or if
+    /// `base` isn't inside a namespace.
+    pub fn try_from_path(path: &Path, base: &Path) -> Result<Self, DatapackError> {
+        let invalid = || DatapackError::InvalidPath(path.to_path_buf());
+        let components: Vec<&str> = base.components().map(|component| component.as_os_str().to_str()).collect::<Option<_>>().ok_or_else(invalid)?;
+        let data = components.iter().rposition(|component| *component == "data").ok_or_else(invalid)?;
+        let namespace = components.get(data + 1).ok_or_else(invalid)?;
+
+        let relative = path.strip_prefix(base).map_err(|_| invalid())?.with_extension("");
+        let segments = relative
+            .components()
+            .map(|component| match component {
+                Component::Normal(segment) => segment.to_str(),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(invalid)?;
+        Self::new(*namespace, segments.join("/"))
+    }
+
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+}
+
+pub(crate) fn is_valid_namespace(namespace: &str) -> bool {
+    is_valid(namespace.as_bytes(), false)
+}
+
+/// Whether `s` is a valid namespace, or a valid path with `allow_slash`. Neither may have
+/// an empty, `.` or `..` segment, so its files can't end up outside their directory.
+const fn is_valid(s: &[u8], allow_slash: bool) -> bool {
+    let mut i = 0;
+    let mut segment = 0;
+    while i <= s.len() {
+        if i == s.len() || (allow_slash && s[i] == b'/') {
+            let dots = (segment == 1 || segment == 2) && s[i - 1] == b'.' && s[i - segment] == b'.';
+            if segment == 0 || dots {
+                return false;
+            }
+            segment = 0;
+        } else if s[i].is_ascii_lowercase() || s[i].is_ascii_digit() || matches!(s[i], b'_' | b'-' | b'.') {
+            segment += 1;
+        } else {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+impl FromStr for ResourceLocation {
+    type Err = DatapackError;
+
+    /// Parses `namespace:path`, or just `path` in the `minecraft` namespace.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            Some((namespace, path)) => Self::new(namespace, path),
+            None => Self::minecraft(s),
+        }
+    }
+}
+
+impl TryFrom<&str> for ResourceLocation {
+    type Error = DatapackError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl Display for ResourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.namespace, self.path)
+    }
+}
+
+impl Serialize for ResourceLocation {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for ResourceLocation {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resource_location_parse() {
+        let location: ResourceLocation = "test:spells/fireball".parse().expect("should have parsed the resource location");
+        assert_eq!(location.namespace(), "test");
+        assert_eq!(location.path(), "spells/fireball");
+        assert_eq!(location.to_string(), "test:spells/fireball");
+
+        let location: ResourceLocation = "stone".parse().expect("should have parsed the resource location");
+        assert_eq!(location.to_string(), "minecraft:stone", "the namespace should default to 'minecraft'");
+    }
+
+    #[test]
+    fn resource_location_from_path() {
+        let base = Path::new("pack/data/test/function");
+        let location = ResourceLocation::try_from_path(&base.join("spells/fireball.mcfunction"), base)
+            .expect("should have found the resource location");
+        assert_eq!(location.to_string(), "test:spells/fireball");
+
+        let base = Path::new("data/minecraft/tags/function");
+        let location = ResourceLocation::try_from_path(&base.join("load.json"), base).expect("should have found the resource location");
+        assert_eq!(location.to_string(), "minecraft:load");
+
+        let outside = ResourceLocation::try_from_path(Path::new("data/test/loot_table/chest.json"), Path::new("data/test/function"));
+        assert!(matches!(outside, Err(DatapackError::InvalidPath(_))), "the file isn't inside the base");
+        let no_namespace = ResourceLocation::try_from_path(Path::new("function/tick.mcfunction"), Path::new("function"));
+        assert!(matches!(no_namespace, Err(DatapackError::InvalidPath(_))), "the base isn't inside a namespace");
+        let invalid = ResourceLocation::try_from_path(Path::new("data/test/function/Tick.mcfunction"), Path::new("data/test/function"));
+        assert!(matches!(invalid, Err(DatapackError::InvalidResourceLocation(_))), "paths are lowercase");
+    }
+
+    #[test]
+    fn resource_location_from_static() {
+        const FIREBALL: ResourceLocation = ResourceLocation::from_static("test:spells/fireball");
+        assert_eq!(FIREBALL, "test:spells/fireball".parse().expect("should have parsed the resource location"));
+        assert_eq!(ResourceLocation::from_static("stone").to_string(), "minecraft:stone");
+        assert_eq!(ResourceLocation::from_static("test:.hidden/a..b").path(), ".hidden/a..b", "only whole segments of dots are rejected");
+        let mut locations = std::collections::HashSet::new();
+        locations.insert(FIREBALL);
+        assert!(locations.contains(&ResourceLocation::new("test", "spells/fireball").expect("should have accepted the location")));
+    }
+
+    #[test]
+    fn resource_location_from_static_invalid() {
+        for invalid in ["", "test:", ":path", "Test:path", "test:pa th", "a:b:c", "test:../x", "test:a//b"] {
+            let result = std::panic::catch_unwind(|| ResourceLocation::from_static(invalid));
+            assert!(result.is_err(), "'{invalid}' should have been rejected");
+        }
+    }
+
+    #[test]
+    fn resource_location_invalid() {
+        for invalid in ["", "test:", ":path", "Test:path", "test:Path", "test/ns:path", "test:pa th", "a:b:c"] {
+            assert!(invalid.parse::<ResourceLocation>().is_err(), "'{invalid}' should have been rejected");
+        }
+        for invalid in ["test:../x", "test:a/./b", "test:a//b", "test:a/", "test:/a", "..:x", ".:x"] {
+            assert!(invalid.parse::<ResourceLocation>().is_err(), "'{invalid}' should have been rejected");
+        }
+    }
+}
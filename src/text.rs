@@ -0,0 +1,508 @@
+use std::fmt::{self, Display};
+use serde::{Serialize, Serializer};
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer};
+use serde_json::Value;
+use crate::color::Color;
+use crate::datapack::pack_format::SNBT_TEXT_PACK_FORMAT;
+use crate::nbt::Snbt;
+use crate::resource_location::ResourceLocation;
+use crate::utils::file_system::File;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[serde(tag = "action", content = "value", rename_all = "snake_case")]
+pub enum ClickEvent {
+    RunCommand(String),
+    SuggestCommand(String),
+    OpenUrl(String),
+    CopyToClipboard(String),
+    #[serde(serialize_with = "serialize_page")]
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_page"))]
+    ChangePage(u32),
+}
+
+fn serialize_page<S: Serializer>(page: &u32, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(page)
+}
+
+/// Reads the page as a string, as [`serialize_page`] writes it, or as a number.
+#[cfg(feature = "serde")]
+fn deserialize_page<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Page {
+        Number(u32),
+        String(String),
+    }
+
+    match Page::deserialize(deserializer)? {
+        Page::Number(page) => Ok(page),
+        Page::String(page) => page.parse().map_err(de::Error::custom),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[serde(tag = "action", content = "contents", rename_all = "snake_case")]
+pub enum HoverEvent {
+    ShowText(Box<TextComponent>),
+    ShowItem {
+        id: ResourceLocation,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        count: Option<u32>,
+        /// The item's NBT as SNBT.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        tag: Option<String>,
+    },
+    ShowEntity {
+        #[serde(rename = "type")]
+        entity_type: ResourceLocation,
+        /// The entity's UUID.
+        id: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        name: Option<Box<TextComponent>>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[serde(rename_all = "lowercase")]
+pub enum NbtSource {
+    Block(String),
+    Entity(String),
+    Storage(ResourceLocation),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct Score {
+    pub name: String,
+    pub objective: String,
+}
+
+/// What a [`TextComponent`] displays, before styling.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[serde(untagged)]
+pub enum Content {
+    Text {
+        text: String,
+    },
+    Translatable {
+        translate: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        fallback: Option<String>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        with: Vec<TextComponent>,
+    },
+    Score {
+        score: Score,
+    },
+    Selector {
+        selector: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        separator: Option<Box<TextComponent>>,
+    },
+    Keybind {
+        keybind: String,
+    },
+    Nbt {
+        nbt: String,
+        #[serde(flatten)]
+        source: NbtSource,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        interpret: Option<bool>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        separator: Option<Box<TextComponent>>,
+    },
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct Style {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<Color>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub font: Option<ResourceLocation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bold: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub italic: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub underlined: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strikethrough: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub obfuscated: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub insertion: Option<String>,
+    #[serde(rename = "clickEvent", skip_serializing_if = "Option::is_none")]
+    pub click_event: Option<ClickEvent>,
+    #[serde(rename = "hoverEvent", skip_serializing_if = "Option::is_none")]
+    pub hover_event: Option<HoverEvent>,
+}
+
+/// A JSON text component, as used by `tellraw`, `title`, advancements and `pack.mcmeta`.
+///
+/// [`Display`] renders the compact JSON that can be embedded in a command, while the
+/// [`Serialize`] impl embeds it in other JSON files. Unstyled text without children is
+/// rendered as a plain JSON string.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextComponent {
+    pub content: Content,
+    pub style: Style,
+    pub extra: Vec<TextComponent>,
+}
+
+impl TextComponent {
+    pub fn new(content: Content) -> Self {
+        Self {
+            content,
+            style: Style::default(),
+            extra: Vec::new(),
+        }
+    }
+
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::new(Content::Text { text: text.into() })
+    }
+
+    pub fn translate(key: impl Into<String>) -> Self {
+        Self::translate_with(key, Vec::new())
+    }
+
+    pub fn translate_with(key: impl Into<String>, with: Vec<TextComponent>) -> Self {
+        Self::new(Content::Translatable { translate: key.into(), fallback: None, with })
+    }
+
+    pub fn score(name: impl Into<String>, objective: impl Into<String>) -> Self {
+        Self::new(Content::Score { score: Score { name: name.into(), objective: objective.into() } })
+    }
+
+    pub fn selector(selector: impl Display) -> Self {
+        Self::new(Content::Selector { selector: selector.to_string(), separator: None })
+    }
+
+    pub fn keybind(keybind: impl Into<String>) -> Self {
+        Self::new(Content::Keybind { keybind: keybind.into() })
+    }
+
+    pub fn nbt(path: impl Into<String>, source: NbtSource) -> Self {
+        Self::new(Content::Nbt { nbt: path.into(), source, interpret: None, separator: None })
+    }
+
+    /// Sets the fallback of a translatable component.
+    pub fn fallback(mut self, fallback: impl Into<String>) -> Self {
+        if let Content::Translatable { fallback: existing, .. } = &mut self.content {
+            *existing = Some(fallback.into());
+        }
+        self
+    }
+
+    /// Sets the separator of a selector or NBT component.
+    pub fn separator(mut self, separator: TextComponent) -> Self {
+        if let Content::Selector { separator: existing, .. } | Content::Nbt { separator: existing, .. } = &mut self.content {
+            *existing = Some(Box::new(separator));
+        }
+        self
+    }
+
+    /// Sets whether an NBT component parses the value it reads as a text component.
+    pub fn interpret(mut self, interpret: bool) -> Self {
+        if let Content::Nbt { interpret: existing, .. } = &mut self.content {
+            *existing = Some(interpret);
+        }
+        self
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.style.color = Some(color);
+        self
+    }
+
+    pub fn font(mut self, font: ResourceLocation) -> Self {
+        self.style.font = Some(font);
+        self
+    }
+
+    pub fn bold(mut self) -> Self {
+        self.style.bold = Some(true);
+        self
+    }
+
+    pub fn italic(mut self) -> Self {
+        self.style.italic = Some(true);
+        self
+    }
+
+    pub fn underlined(mut self) -> Self {
+        self.style.underlined = Some(true);
+        self
+    }
+
+    pub fn strikethrough(mut self) -> Self {
+        self.style.strikethrough = Some(true);
+        self
+    }
+
+    pub fn obfuscated(mut self) -> Self {
+        self.style.obfuscated = Some(true);
+        self
+    }
+
+    pub fn insertion(mut self, insertion: impl Into<String>) -> Self {
+        self.style.insertion = Some(insertion.into());
+        self
+    }
+
+    pub fn click(mut self, click_event: ClickEvent) -> Self {
+        self.style.click_event = Some(click_event);
+        self
+    }
+
+    pub fn hover(mut self, hover_event: HoverEvent) -> Self {
+        self.style.hover_event = Some(hover_event);
+        self
+    }
+
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn extra(mut self, child: TextComponent) -> Self {
+        self.extra.push(child);
+        self
+    }
+
+    fn is_plain_text(&self) -> bool {
+        matches!(self.content, Content::Text { .. }) && self.style == Style::default() && self.extra.is_empty()
+    }
+
+    /// The component as a JSON value, for use inside other JSON files.
+    pub fn to_json(&self) -> Value {
+        serde_json::to_value(self).expect("text components always serialize")
+    }
+
+    /// The component as a value in NBT, such as an item component or the text of a text
+    /// display: a JSON string, or SNBT since 1.21.5.
+    pub fn to_nbt(&self, pack_format: u32) -> Snbt {
+        match pack_format >= SNBT_TEXT_PACK_FORMAT {
+            true => Snbt::from(&self.to_json()),
+            false => Snbt::String(self.to_string()),
+        }
+    }
+}
+
+impl From<&str> for TextComponent {
+    fn from(text: &str) -> Self {
+        Self::text(text)
+    }
+}
+
+impl From<String> for TextComponent {
+    fn from(text: String) -> Self {
+        Self::text(text)
+    }
+}
+
+#[derive(Serialize)]
+struct TextComponentRepr<'a> {
+    #[serde(flatten)]
+    content: &'a Content,
+    #[serde(flatten)]
+    style: &'a Style,
+    #[serde(skip_serializing_if = "<[TextComponent]>::is_empty")]
+    extra: &'a [TextComponent],
+}
+
+impl Serialize for TextComponent {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if let (true, Content::Text { text }) = (self.is_plain_text(), &self.content) {
+            return serializer.serialize_str(text);
+        }
+        TextComponentRepr {
+            content: &self.content,
+            style: &self.style,
+            extra: &self.extra,
+        }.serialize(serializer)
+    }
+}
+
+/// Reads a plain string, an object, or an array whose first component is the parent of
+/// the rest.
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for TextComponent {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct ComponentRepr {
+            #[serde(flatten)]
+            content: Content,
+            #[serde(flatten)]
+            style: Style,
+            #[serde(default)]
+            extra: Vec<TextComponent>,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Text(String),
+            List(Vec<TextComponent>),
+            Component(Box<ComponentRepr>),
+        }
+
+        match Repr::deserialize(deserializer)? {
+            Repr::Text(text) => Ok(Self::text(text)),
+            Repr::List(components) => {
+                let mut components = components.into_iter();
+                let mut parent = components.next().ok_or_else(|| de::Error::custom("a text component list can't be empty"))?;
+                parent.extra.extend(components);
+                Ok(parent)
+            }
+            Repr::Component(component) => {
+                let ComponentRepr { content, style, extra } = *component;
+                Ok(Self { content, style, extra })
+            }
+        }
+    }
+}
+
+impl Display for TextComponent {
+    /// Renders the component as compact JSON on a single line, ready to be embedded in a command.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let json = serde_json::to_string(self).map_err(|_| fmt::Error)?;
+        f.write_str(&json)
+    }
+}
+
+impl File for TextComponent {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempdir::TempDir;
+    use crate::utils::file_system::Entry;
+
+    #[test]
+    fn plain_text_is_compact() {
+        assert_eq!(TextComponent::text("hi").to_string(), r#""hi""#);
+        assert_eq!(TextComponent::text("hi").to_json(), json!("hi"));
+    }
+
+    #[test]
+    fn styled_text() {
+        let component = TextComponent::text("hi").color(Color::Gold).bold();
+        assert_eq!(component.to_string(), r#"{"text":"hi","color":"gold","bold":true}"#);
+    }
+
+    #[test]
+    fn hex_color() {
+        let component = TextComponent::text("hi").color(Color::Hex(0x9a5cc6));
+        assert_eq!(component.to_string(), r##"{"text":"hi","color":"#9A5CC6"}"##);
+    }
+
+    #[test]
+    fn command_escaping() {
+        let component = TextComponent::text("say \"hi\" C:\\path\nnext line");
+        assert_eq!(component.to_string(), r#""say \"hi\" C:\\path\nnext line""#);
+        assert!(!component.to_string().contains('\n'), "the component should fit on a single command line");
+    }
+
+    #[test]
+    fn nested_extras() {
+        let component = TextComponent::text("Score: ")
+            .color(Color::Gray)
+            .extra(TextComponent::score("@s", "points").color(Color::Yellow).extra(TextComponent::text("!")))
+            .extra(TextComponent::keybind("key.jump"));
+        let expected = json!({
+            "text": "Score: ",
+            "color": "gray",
+            "extra": [
+                {"score": {"name": "@s", "objective": "points"}, "color": "yellow", "extra": ["!"]},
+                {"keybind": "key.jump"},
+            ],
+        });
+        assert_eq!(component.to_json(), expected);
+    }
+
+    #[test]
+    fn translate_and_selector() {
+        let component = TextComponent::translate_with("chat.type.text", vec![
+            TextComponent::selector("@p").separator(TextComponent::text(", ")),
+            TextComponent::text("hello"),
+        ]).fallback("<%s> %s");
+        let expected = json!({
+            "translate": "chat.type.text",
+            "fallback": "<%s> %s",
+            "with": [{"selector": "@p", "separator": ", "}, "hello"],
+        });
+        assert_eq!(component.to_json(), expected);
+    }
+
+    #[test]
+    fn nbt_component() {
+        let storage = ResourceLocation::new("test", "data").expect("should have created the resource location");
+        let component = TextComponent::nbt("message", NbtSource::Storage(storage)).interpret(true);
+        assert_eq!(component.to_json(), json!({"nbt": "message", "storage": "test:data", "interpret": true}));
+    }
+
+    #[test]
+    fn events() {
+        let component = TextComponent::text("[click]")
+            .underlined()
+            .click(ClickEvent::RunCommand("/trigger menu".to_string()))
+            .hover(HoverEvent::ShowText(Box::new(TextComponent::text("Open the menu").italic())));
+        let expected = json!({
+            "text": "[click]",
+            "underlined": true,
+            "clickEvent": {"action": "run_command", "value": "/trigger menu"},
+            "hoverEvent": {"action": "show_text", "contents": {"text": "Open the menu", "italic": true}},
+        });
+        assert_eq!(component.to_json(), expected);
+
+        let page = TextComponent::text("next").click(ClickEvent::ChangePage(2));
+        assert_eq!(page.to_json()["clickEvent"], json!({"action": "change_page", "value": "2"}));
+    }
+
+    #[test]
+    fn hover_item_and_entity() {
+        let item = HoverEvent::ShowItem {
+            id: ResourceLocation::minecraft("diamond").expect("should have created the resource location"),
+            count: Some(3),
+            tag: None,
+        };
+        let entity = HoverEvent::ShowEntity {
+            entity_type: ResourceLocation::minecraft("zombie").expect("should have created the resource location"),
+            id: "00000000-0000-0000-0000-000000000000".to_string(),
+            name: Some(Box::new(TextComponent::text("Bob"))),
+        };
+        assert_eq!(
+            serde_json::to_value(item).expect("should have serialized the hover event"),
+            json!({"action": "show_item", "contents": {"id": "minecraft:diamond", "count": 3}}),
+        );
+        assert_eq!(
+            serde_json::to_value(entity).expect("should have serialized the hover event"),
+            json!({"action": "show_entity", "contents": {"type": "minecraft:zombie", "id": "00000000-0000-0000-0000-000000000000", "name": "Bob"}}),
+        );
+    }
+
+    #[test]
+    fn text_component_file_create() {
+        const ENTRY_NAME: &str = "description.json";
+
+        let temp_dir = TempDir::new(module_path!()).expect("should have created temp dir");
+        let path = temp_dir.path().join(ENTRY_NAME);
+
+        let component = TextComponent::translate("test.description").color(Color::Aqua);
+        component.create(&path).unwrap_or_else(|_| panic!("should have created '{ENTRY_NAME}'"));
+        let read_file_contents = std::fs::read_to_string(&path)
+            .expect("should have been able to read the file");
+        let read_json: Value = serde_json::from_str(&read_file_contents).expect("'{ENTRY_NAME}' should contain valid JSON");
+        assert_eq!(read_json, component.to_json(), "'{ENTRY_NAME}' does not contain the correct contents");
+
+        temp_dir.close().expect("should have closed temp dir");
+    }
+}
@@ -1 +1,3 @@
 pub mod file_system;
+pub mod json;
+pub mod weighted_list;
@@ -1,17 +1,599 @@
+use std::cell::RefCell;
 use std::fs::{self, create_dir};
 use std::fmt::Display;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::ffi::OsString;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::io::prelude::*;
-use std::io::Result;
+use std::io::{ErrorKind, Result};
 use std::boxed::Box;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Deref};
+use std::sync::Arc;
+#[cfg(feature = "tokio")]
+use std::{future::Future, pin::Pin};
+#[cfg(feature = "tokio")]
+use tokio::io::AsyncWriteExt;
+#[cfg(feature = "gzip")]
+use flate2::{write::GzEncoder, Compression};
+use crate::error::DatapackError;
+use crate::utils::json::JsonStyle;
+
+/// What [`Entry::create_async`] returns. It is not `Send`, like most entries, so it can't
+/// be spawned on a multi-threaded runtime, but can be awaited anywhere.
+#[cfg(feature = "tokio")]
+pub type CreateFuture<'e> = Pin<Box<dyn Future<Output = std::result::Result<(), DatapackError>> + 'e>>;
+
+/// Writes `contents` to a new file at `path` without blocking, failing if anything is
+/// already there, like [`fs::File::create_new`].
+#[cfg(feature = "tokio")]
+pub(crate) async fn write_new_async(path: &Path, contents: &[u8]) -> Result<()> {
+    let mut file = tokio::fs::OpenOptions::new().write(true).create_new(true).open(path).await?;
+    file.write_all(contents).await?;
+    file.flush().await
+}
+
+/// Counts and sizes of a tree of entries, computed without touching the filesystem.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PackStats {
+    /// The total size of every file's contents.
+    pub bytes: u64,
+    pub files: usize,
+    /// The number of directories, including the root if it is one.
+    pub directories: usize,
+}
+
+impl Add for PackStats {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            bytes: self.bytes + other.bytes,
+            files: self.files + other.files,
+            directories: self.directories + other.directories,
+        }
+    }
+}
+
+impl AddAssign for PackStats {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl Sum for PackStats {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Add::add)
+    }
+}
+
+/// Something [`Entry::create_with_options`] did, reported to [`WriteOptions::on_progress`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProgressEvent {
+    /// A file was written, or an entry other than a directory created.
+    Created { path: PathBuf, bytes: u64 },
+    DirectoryCreated { path: PathBuf },
+    /// Whatever was at `path` was moved to `backup`.
+    BackedUp { path: PathBuf, backup: PathBuf },
+}
+
+/// How [`Entry::create_with_options`] treats what is already on disk, and what it reports.
+///
+/// The default options fail on anything already at the path of an entry, just like
+/// [`Entry::create`].
+#[derive(Default)]
+pub struct WriteOptions {
+    /// Replaces existing files, and writes into existing directories. Entries already in
+    /// such a directory are kept unless they are replaced.
+    pub allow_overwrite: bool,
+    /// Moves anything replaced to a backup first, like [`Entry::create_with_backup`].
+    /// Writes into existing directories, even without `allow_overwrite`.
+    pub create_backup: bool,
+    /// Writes the files of a directory whose contents are known on several threads.
+    /// Other entries, and every [`ProgressEvent`], stay on the calling thread.
+    pub parallel: bool,
+    pub on_progress: Option<Box<dyn Fn(ProgressEvent)>>,
+}
+
+impl WriteOptions {
+    pub fn allow_overwrite(mut self) -> Self {
+        self.allow_overwrite = true;
+        self
+    }
+
+    pub fn create_backup(mut self) -> Self {
+        self.create_backup = true;
+        self
+    }
+
+    pub fn parallel(mut self) -> Self {
+        self.parallel = true;
+        self
+    }
+
+    pub fn on_progress(mut self, on_progress: impl Fn(ProgressEvent) + 'static) -> Self {
+        self.on_progress = Some(Box::new(on_progress));
+        self
+    }
+
+    pub(crate) fn report(&self, event: ProgressEvent) {
+        if let Some(on_progress) = &self.on_progress {
+            on_progress(event);
+        }
+    }
+
+    /// Makes room for an entry at `path`, backing up or removing what is there if allowed.
+    /// Otherwise leaves it, so creating the entry fails.
+    pub(crate) fn clear(&self, path: &Path) -> Result<()> {
+        if self.create_backup {
+            if let Some(backup) = back_up(path)? {
+                self.report(ProgressEvent::BackedUp { path: path.to_path_buf(), backup });
+            }
+        } else if self.allow_overwrite {
+            match fs::symlink_metadata(path) {
+                Ok(metadata) if metadata.is_dir() => fs::remove_dir_all(path)?,
+                Ok(_) => fs::remove_file(path)?,
+                Err(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Creates the directory at `path`, or keeps the existing one if it may be written into.
+    pub(crate) fn create_dir(&self, path: &Path) -> Result<()> {
+        if path.is_dir() && (self.allow_overwrite || self.create_backup) {
+            return Ok(());
+        }
+        self.clear(path)?;
+        create_dir(path)?;
+        self.report(ProgressEvent::DirectoryCreated { path: path.to_path_buf() });
+        Ok(())
+    }
+
+    /// Creates `entry` at `path`, or with `parallel`, clears its path and queues its
+    /// contents in `pending` for [`WriteOptions::write_pending`] if they are known.
+    pub(crate) fn create_entry(&self, path: &Path, entry: &dyn Entry, pending: &mut Vec<(PathBuf, Vec<u8>)>) -> std::result::Result<(), DatapackError> {
+        match entry.contents().filter(|_| self.parallel) {
+            Some(contents) => {
+                self.clear(path)?;
+                pending.push((path.to_path_buf(), contents));
+                Ok(())
+            }
+            None => entry.create_with_options(path, self),
+        }
+    }
+
+    /// Writes the files queued by [`WriteOptions::create_entry`], split between as many
+    /// threads as are available, then reports them in order.
+    pub(crate) fn write_pending(&self, pending: Vec<(PathBuf, Vec<u8>)>) -> Result<()> {
+        if pending.is_empty() {
+            return Ok(());
+        }
+        let threads = std::thread::available_parallelism().map_or(1, usize::from);
+        let chunk_size = pending.len().div_ceil(threads);
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = pending
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        chunk.iter().try_for_each(|(path, contents)| fs::File::create_new(path)?.write_all(contents))
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .try_for_each(|handle| handle.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic)))
+        })?;
+        for (path, contents) in pending {
+            self.report(ProgressEvent::Created { path, bytes: contents.len() as u64 });
+        }
+        Ok(())
+    }
+}
 
 pub trait Entry {
+    /// Creates the entry at `path`, failing if anything is already there. The same as
+    /// [`Entry::create_with_options`] with the default [`WriteOptions`].
     fn create(&self, path: &Path) -> Result<()>;
+
+    /// By default, the entries of [`Entry::for_each_entry`] as a directory, or a file of
+    /// the size of [`Entry::contents`] if there are none, counted as empty if they aren't
+    /// known.
+    fn stats(&self) -> PackStats {
+        let mut directory: Option<PackStats> = None;
+        self.for_each_entry(&mut |_, entry| {
+            *directory.get_or_insert(PackStats { directories: 1, ..PackStats::default() }) += entry.stats();
+        });
+        directory.unwrap_or_else(|| PackStats {
+            bytes: self.contents().map_or(0, |contents| contents.len() as u64),
+            files: 1,
+            directories: 0,
+        })
+    }
+
+    /// The total size of the contents [`Entry::create`] would write, for checking that
+    /// there is enough space first.
+    fn estimated_size(&self) -> u64 {
+        self.stats().bytes
+    }
+
+    /// Like [`Entry::create`], but first moves anything already at `path` to a backup
+    /// next to it, and returns the backup's path if one was made.
+    ///
+    /// The backup is named after the whole file name, so `a.json` becomes `a.json.bak`
+    /// and can't collide with the backup of `a.mcfunction`. An older backup is replaced.
+    fn create_with_backup(&self, path: &Path) -> std::result::Result<Option<PathBuf>, DatapackError> {
+        let backup = back_up(path)?;
+        self.create(path)?;
+        Ok(backup)
+    }
+
+    /// Like [`Entry::create`], but writes with `tokio::fs`, so an async runtime's thread isn't
+    /// blocked while the tree is written. Entries that don't implement it are created with
+    /// [`Entry::create`], blocking.
+    #[cfg(feature = "tokio")]
+    fn create_async<'e>(&'e self, path: &'e Path) -> CreateFuture<'e> {
+        Box::pin(async move { Ok(self.create(path)?) })
+    }
+
+    /// Like [`Entry::create`], configured by `options`.
+    fn create_with_options(&self, path: &Path, options: &WriteOptions) -> std::result::Result<(), DatapackError> {
+        options.clear(path)?;
+        self.create(path)?;
+        let bytes = fs::metadata(path).map(|metadata| metadata.len()).unwrap_or(0);
+        options.report(ProgressEvent::Created { path: path.to_path_buf(), bytes });
+        Ok(())
+    }
+
+    /// Calls `f` with each entry directly inside this one and its path relative to it,
+    /// which is never for files.
+    fn for_each_entry(&self, _f: &mut dyn FnMut(&Path, &dyn Entry)) {}
+
+    /// Takes the entries out of a [`Directory`], leaving it empty, for [`Merge::merge`].
+    /// Nothing else, not even a [`SharedDirectory`], has entries that can be taken.
+    fn take_entries<'s>(&mut self) -> Option<Directory<'s>>
+    where
+        Self: 's,
+    {
+        None
+    }
+
+    /// What [`Entry::create`] writes, for files that can tell without writing it, such as
+    /// to compare two trees. Never known for directories.
+    fn contents(&self) -> Option<Vec<u8>> {
+        None
+    }
+
+    /// This entry with its JSON files laid out in `style`, which a
+    /// [`Datapack`](crate::datapack::Datapack) writes the entries inserted into it with.
+    /// `None` for entries that are the same in every style, or that keep their own.
+    fn styled(&self, _style: JsonStyle) -> Option<Box<dyn Entry + '_>> {
+        None
+    }
+
+    /// Calls `visitor` with every entry inside this one and its path relative to it, depth
+    /// first, with each directory before its entries. The entries of a directory are
+    /// visited in the order of their names.
+    fn visit(&self, visitor: &mut dyn FnMut(&Path, &dyn Entry)) {
+        self.for_each_entry(&mut |path, entry| {
+            visitor(path, entry);
+            entry.visit(&mut |inner, entry| visitor(&path.join(inner), entry));
+        });
+    }
+
+    /// Checks that what [`Entry::create`] would write at `path` is already there, without
+    /// writing anything, such as to check that a committed datapack is up to date. Files
+    /// whose contents can't be known without writing them, like a [`LazyFile`], only
+    /// have to exist.
+    ///
+    /// Empty directories aren't checked, since version control usually drops them.
+    fn verify(&self, path: &Path) -> std::result::Result<(), Vec<Mismatch>> {
+        let mut expected = BTreeMap::new();
+        let stats = self.stats();
+        if stats.directories == 0 && stats.files == 1 {
+            expected.insert(PathBuf::new(), self.contents());
+        }
+        self.visit(&mut |relative, entry| {
+            let stats = entry.stats();
+            if stats.directories == 0 && stats.files == 1 {
+                expected.insert(relative.to_path_buf(), entry.contents());
+            }
+        });
+
+        let mut mismatches = Vec::new();
+        for (relative, contents) in &expected {
+            let file_path = match relative.as_os_str().is_empty() {
+                true => path.to_path_buf(),
+                false => path.join(relative),
+            };
+            match fs::read(&file_path) {
+                Ok(actual) if contents.as_ref().is_some_and(|contents| *contents != actual) => {
+                    mismatches.push(Mismatch::Modified(relative.clone()));
+                }
+                Ok(_) => {}
+                Err(error) if error.kind() == ErrorKind::NotFound => mismatches.push(Mismatch::Missing(relative.clone())),
+                Err(_) => mismatches.push(Mismatch::Unreadable(relative.clone())),
+            }
+        }
+        if stats.directories > 0 {
+            let mut on_disk = Vec::new();
+            files_on_disk(path, Path::new(""), &mut on_disk);
+            let extra = on_disk.into_iter().filter(|relative| !expected.contains_key(relative));
+            mismatches.extend(extra.map(Mismatch::Extra));
+        }
+        match mismatches.is_empty() {
+            true => Ok(()),
+            false => Err(mismatches),
+        }
+    }
+
+    /// Updates what is at `path` to what [`Entry::create`] would write, writing only the
+    /// files whose contents changed and removing those that aren't in the entry anymore,
+    /// so unchanged files keep their modification time. Directories left empty are
+    /// removed too. Returns what was fixed, as [`Entry::verify`] would have reported it.
+    ///
+    /// Files whose contents can't be known without writing them, like a [`LazyFile`], are
+    /// always written again.
+    fn create_incremental(&self, path: &Path) -> std::result::Result<Vec<Mismatch>, DatapackError> {
+        let stats = self.stats();
+        if stats.directories == 0 && stats.files == 1 {
+            let fixed = update_file(path, Path::new(""), self.contents(), |path| self.create(path))?;
+            return Ok(fixed.into_iter().collect());
+        }
+
+        let mut expected = BTreeMap::new();
+        let mut result = Ok(());
+        self.visit(&mut |relative, entry| {
+            let stats = entry.stats();
+            if result.is_err() || stats.directories != 0 || stats.files != 1 {
+                return;
+            }
+            match update_file(&path.join(relative), relative, entry.contents(), |path| entry.create(path)) {
+                Ok(fixed) => {
+                    expected.insert(relative.to_path_buf(), fixed);
+                }
+                Err(error) => result = Err(error),
+            }
+        });
+        result?;
+
+        let mut fixed: Vec<Mismatch> = expected.values().flatten().cloned().collect();
+        let mut on_disk = Vec::new();
+        files_on_disk(path, Path::new(""), &mut on_disk);
+        on_disk.sort();
+        for relative in on_disk.into_iter().filter(|relative| !expected.contains_key(relative)) {
+            fs::remove_file(path.join(&relative))?;
+            let parents = relative.ancestors().skip(1).take_while(|parent| !parent.as_os_str().is_empty());
+            for parent in parents {
+                if fs::remove_dir(path.join(parent)).is_err() {
+                    break;
+                }
+            }
+            fixed.push(Mismatch::Extra(relative));
+        }
+        Ok(fixed)
+    }
 }
 
-pub trait File: Display {}
+/// Writes the file at `path` for [`Entry::create_incremental`] if `contents` differ from
+/// what is there, or with `create` if they aren't known, and returns the mismatch that was
+/// fixed, if any, for the file at `relative`.
+fn update_file(
+    path: &Path,
+    relative: &Path,
+    contents: Option<Vec<u8>>,
+    create: impl FnOnce(&Path) -> Result<()>,
+) -> Result<Option<Mismatch>> {
+    let existing = match fs::read(path) {
+        Ok(existing) => Some(existing),
+        Err(error) if error.kind() == ErrorKind::NotFound => None,
+        Err(_) if path.is_dir() => {
+            fs::remove_dir_all(path)?;
+            None
+        }
+        Err(error) => return Err(error),
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let relative = relative.to_path_buf();
+    match (contents, existing) {
+        (Some(contents), Some(existing)) if contents == existing => Ok(None),
+        (Some(contents), Some(_)) => {
+            fs::write(path, contents)?;
+            Ok(Some(Mismatch::Modified(relative)))
+        }
+        (Some(contents), None) => {
+            fs::write(path, contents)?;
+            Ok(Some(Mismatch::Missing(relative)))
+        }
+        (None, Some(_)) => {
+            fs::remove_file(path)?;
+            create(path)?;
+            Ok(None)
+        }
+        (None, None) => {
+            create(path)?;
+            Ok(Some(Mismatch::Missing(relative)))
+        }
+    }
+}
+
+/// Adds the path of every file inside `root`, relative to it, to `files`. Anything that
+/// can't be read is left out.
+fn files_on_disk(root: &Path, relative: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(root.join(relative)) else { return };
+    for entry in entries.flatten() {
+        let path = relative.join(entry.file_name());
+        match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => files_on_disk(root, &path, files),
+            Ok(_) => files.push(path),
+            Err(_) => {}
+        }
+    }
+}
+
+/// A difference between an entry and what is on disk, found by [`Entry::verify`]. Paths
+/// are relative to the verified path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mismatch {
+    /// A file of the entry that isn't on disk.
+    Missing(PathBuf),
+    /// A file on disk that isn't in the entry.
+    Extra(PathBuf),
+    /// A file with other contents on disk.
+    Modified(PathBuf),
+    /// A file that is on disk but couldn't be read.
+    Unreadable(PathBuf),
+}
+
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let (path, problem) = match self {
+            Self::Missing(path) => (path, "is missing"),
+            Self::Extra(path) => (path, "shouldn't be there"),
+            Self::Modified(path) => (path, "has other contents"),
+            Self::Unreadable(path) => (path, "couldn't be read"),
+        };
+        write!(f, "'{}' {problem}", path.display())
+    }
+}
+
+/// Moves whatever is at `path` to its backup path, if anything is there.
+pub(crate) fn back_up(path: &Path) -> Result<Option<PathBuf>> {
+    if fs::symlink_metadata(path).is_err() {
+        return Ok(None);
+    }
+    let mut name = path.file_name().unwrap_or(path.as_os_str()).to_os_string();
+    name.push(".bak");
+    let backup = path.with_file_name(name);
+    if backup.is_dir() {
+        fs::remove_dir_all(&backup)?;
+    } else if backup.exists() {
+        fs::remove_file(&backup)?;
+    }
+    fs::rename(path, &backup)?;
+    Ok(Some(backup))
+}
+
+/// An [`Entry`] rendered with [`Display`], such as a function or a JSON resource.
+pub trait File: Display {
+    /// Renders the file with its JSON laid out in `style`. Only JSON files depend on it,
+    /// so by default, it is the same as [`Display`].
+    fn fmt_styled(&self, f: &mut std::fmt::Formatter, _style: JsonStyle) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+/// A [`File`] displayed in `style`, as returned by [`Entry::styled`].
+struct StyledFile<'f> {
+    file: &'f dyn File,
+    style: JsonStyle,
+}
+
+impl Display for StyledFile<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.file.fmt_styled(f, self.style)
+    }
+}
+
+impl File for StyledFile<'_> {
+    fn fmt_styled(&self, f: &mut std::fmt::Formatter, style: JsonStyle) -> std::fmt::Result {
+        self.file.fmt_styled(f, style)
+    }
+}
+
+/// `entry` with its JSON files laid out in `style`, as returned by [`Entry::styled`], or
+/// `entry` itself if it is the same in every style.
+pub(crate) fn with_style<'e>(entry: &'e dyn Entry, style: JsonStyle) -> Box<dyn Entry + 'e> {
+    entry.styled(style).unwrap_or_else(|| Box::new(EntryRef(entry)))
+}
+
+/// A borrowed entry, for [`with_style`] to return an entry that isn't styled.
+struct EntryRef<'e>(&'e dyn Entry);
+
+impl Entry for EntryRef<'_> {
+    fn create(&self, path: &Path) -> Result<()> {
+        self.0.create(path)
+    }
+
+    fn stats(&self) -> PackStats {
+        self.0.stats()
+    }
+
+    fn estimated_size(&self) -> u64 {
+        self.0.estimated_size()
+    }
+
+    fn create_with_backup(&self, path: &Path) -> std::result::Result<Option<PathBuf>, DatapackError> {
+        self.0.create_with_backup(path)
+    }
+
+    #[cfg(feature = "tokio")]
+    fn create_async<'e>(&'e self, path: &'e Path) -> CreateFuture<'e> {
+        self.0.create_async(path)
+    }
+
+    fn create_with_options(&self, path: &Path, options: &WriteOptions) -> std::result::Result<(), DatapackError> {
+        self.0.create_with_options(path, options)
+    }
+
+    fn for_each_entry(&self, f: &mut dyn FnMut(&Path, &dyn Entry)) {
+        self.0.for_each_entry(f);
+    }
+
+    fn contents(&self) -> Option<Vec<u8>> {
+        self.0.contents()
+    }
+
+    fn styled(&self, style: JsonStyle) -> Option<Box<dyn Entry + '_>> {
+        self.0.styled(style)
+    }
+}
+
+/// A file read from disk, written back as is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnedStringFile(pub String);
+
+impl Display for OwnedStringFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl File for OwnedStringFile {}
+
+/// A file read from disk that isn't UTF-8, such as an image or a structure, written back
+/// byte for byte.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnedBytesFile(pub Vec<u8>);
+
+impl Entry for OwnedBytesFile {
+    fn create(&self, path: &Path) -> Result<()> {
+        fs::File::create_new(path)?.write_all(&self.0)
+    }
+
+    fn contents(&self) -> Option<Vec<u8>> {
+        Some(self.0.clone())
+    }
+
+    #[cfg(feature = "tokio")]
+    fn create_async<'e>(&'e self, path: &'e Path) -> CreateFuture<'e> {
+        Box::pin(async move { Ok(write_new_async(path, &self.0).await?) })
+    }
+}
+
+/// Counts the bytes written to it, so a [`File`] can be measured without keeping its
+/// contents.
+#[derive(Default)]
+struct ByteCounter(u64);
+
+impl std::fmt::Write for ByteCounter {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        self.0 += s.len() as u64;
+        Ok(())
+    }
+}
 
 impl<T: File> Entry for T {
     fn create(&self, path: &Path) -> Result<()> {
@@ -19,11 +601,402 @@ impl<T: File> Entry for T {
         write!(buffer, "{}", self)?;
         Ok(())
     }
+
+    fn stats(&self) -> PackStats {
+        PackStats {
+            bytes: self.estimated_size(),
+            files: 1,
+            directories: 0,
+        }
+    }
+
+    #[cfg(feature = "tokio")]
+    fn create_async<'e>(&'e self, path: &'e Path) -> CreateFuture<'e> {
+        let contents = self.to_string().into_bytes();
+        Box::pin(async move { Ok(write_new_async(path, &contents).await?) })
+    }
+
+    fn contents(&self) -> Option<Vec<u8>> {
+        Some(self.to_string().into_bytes())
+    }
+
+    fn styled(&self, style: JsonStyle) -> Option<Box<dyn Entry + '_>> {
+        Some(Box::new(StyledFile { file: self, style }))
+    }
+
+    /// Counts as empty if rendering fails.
+    fn estimated_size(&self) -> u64 {
+        let mut counter = ByteCounter::default();
+        match std::fmt::write(&mut counter, format_args!("{self}")) {
+            Ok(()) => counter.0,
+            Err(_) => 0,
+        }
+    }
+}
+
+/// The file `F` gzipped, for artifacts the game doesn't read, such as a log of what a
+/// generator did, since it can't read gzipped files. The name is left to the caller, and
+/// should usually end in `.gz`.
+///
+/// [`Entry::contents`] and [`Entry::stats`] are about the compressed bytes, since those
+/// are what is on disk.
+#[cfg(feature = "gzip")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gzipped<F>(pub F);
+
+#[cfg(feature = "gzip")]
+impl<F: File> Gzipped<F> {
+    fn compressed(&self) -> Result<Vec<u8>> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        write!(encoder, "{}", self.0)?;
+        encoder.finish()
+    }
+}
+
+#[cfg(feature = "gzip")]
+impl<F: File> Entry for Gzipped<F> {
+    fn create(&self, path: &Path) -> Result<()> {
+        let contents = self.compressed()?;
+        fs::File::create_new(path)?.write_all(&contents)
+    }
+
+    #[cfg(feature = "tokio")]
+    fn create_async<'e>(&'e self, path: &'e Path) -> CreateFuture<'e> {
+        Box::pin(async move { Ok(write_new_async(path, &self.compressed()?).await?) })
+    }
+
+    /// Counts as empty if rendering fails.
+    fn contents(&self) -> Option<Vec<u8>> {
+        Some(self.compressed().unwrap_or_default())
+    }
+
+    fn styled(&self, style: JsonStyle) -> Option<Box<dyn Entry + '_>> {
+        Some(Box::new(Gzipped(StyledFile { file: &self.0, style })))
+    }
+}
+
+/// A file whose contents are only rendered when it is created, and dropped right after,
+/// so a large tree never holds more than one rendered file at a time.
+///
+/// Since rendering happens once, [`Entry::stats`] can't know the size of the contents and
+/// counts them as empty, and creating the file a second time fails.
+pub struct LazyFile<'a> {
+    render: RefCell<Option<Box<dyn FnOnce() -> String + 'a>>>,
+}
+
+impl<'a> LazyFile<'a> {
+    pub fn new(render: impl FnOnce() -> String + 'a) -> Self {
+        Self { render: RefCell::new(Some(Box::new(render))) }
+    }
+}
+
+impl Entry for LazyFile<'_> {
+    fn create(&self, path: &Path) -> Result<()> {
+        let render = self.render.borrow_mut().take().ok_or_else(|| {
+            std::io::Error::other(format!("the lazy file at '{}' was already created", path.display()))
+        })?;
+        let mut buffer = fs::File::create_new(path)?;
+        buffer.write_all(render().as_bytes())
+    }
+
+    #[cfg(feature = "tokio")]
+    fn create_async<'e>(&'e self, path: &'e Path) -> CreateFuture<'e> {
+        let render = self.render.borrow_mut().take();
+        Box::pin(async move {
+            let render = render.ok_or_else(|| {
+                std::io::Error::other(format!("the lazy file at '{}' was already created", path.display()))
+            })?;
+            Ok(write_new_async(path, render().as_bytes()).await?)
+        })
+    }
+
+    fn stats(&self) -> PackStats {
+        PackStats { bytes: 0, files: 1, directories: 0 }
+    }
+}
+
+/// An entry that is only created when `when` is true, so a tree can be built in one
+/// expression and still leave out entries, such as ones for other targets.
+///
+/// When `when` is false, nothing is created at its path and it counts as nothing in
+/// [`Entry::stats`].
+pub struct ConditionalEntry<'a> {
+    when: bool,
+    inner: Box<dyn Entry + 'a>,
+}
+
+impl<'a> ConditionalEntry<'a> {
+    pub fn new(when: bool, inner: impl Entry + 'a) -> Self {
+        Self { when, inner: Box::new(inner) }
+    }
+}
+
+impl Entry for ConditionalEntry<'_> {
+    fn create(&self, path: &Path) -> Result<()> {
+        match self.when {
+            true => self.inner.create(path),
+            false => Ok(()),
+        }
+    }
+
+    fn stats(&self) -> PackStats {
+        match self.when {
+            true => self.inner.stats(),
+            false => PackStats::default(),
+        }
+    }
+
+    fn create_with_backup(&self, path: &Path) -> std::result::Result<Option<PathBuf>, DatapackError> {
+        match self.when {
+            true => self.inner.create_with_backup(path),
+            false => Ok(None),
+        }
+    }
+
+    fn create_with_options(&self, path: &Path, options: &WriteOptions) -> std::result::Result<(), DatapackError> {
+        match self.when {
+            true => self.inner.create_with_options(path, options),
+            false => Ok(()),
+        }
+    }
+
+    #[cfg(feature = "tokio")]
+    fn create_async<'e>(&'e self, path: &'e Path) -> CreateFuture<'e> {
+        match self.when {
+            true => self.inner.create_async(path),
+            false => Box::pin(async { Ok(()) }),
+        }
+    }
+
+    /// The entries of the inner entry, or none when it is excluded. The conditional entry
+    /// itself is still visited.
+    fn for_each_entry(&self, f: &mut dyn FnMut(&Path, &dyn Entry)) {
+        if self.when {
+            self.inner.for_each_entry(f);
+        }
+    }
+
+    fn contents(&self) -> Option<Vec<u8>> {
+        self.inner.contents().filter(|_| self.when)
+    }
+
+    fn styled(&self, style: JsonStyle) -> Option<Box<dyn Entry + '_>> {
+        let inner = self.inner.styled(style)?;
+        Some(Box::new(ConditionalEntry { when: self.when, inner }))
+    }
 }
 
 pub type Directory<'a> = HashMap<OsString, Box<dyn Entry + 'a>>;
 
-impl<'a> Entry for Directory<'a> {
+/// A [`Directory`] whose entries can be shared, so it can be cloned cheaply and its
+/// entries reused in several trees.
+pub type SharedDirectory<'a> = HashMap<OsString, Arc<dyn Entry + 'a>>;
+
+/// How an entry a [`Directory`] can hold is taken out of it, for [`Entry::take_entries`].
+pub trait DirectoryEntry<'a>: Deref<Target = dyn Entry + 'a> + Sized {
+    fn take_all<'s>(directory: &mut HashMap<OsString, Self>) -> Option<Directory<'s>>
+    where
+        Self: 's;
+
+    /// The entry, for as long as it is borrowed.
+    fn as_entry(&self) -> &dyn Entry;
+}
+
+impl<'a> DirectoryEntry<'a> for Box<dyn Entry + 'a> {
+    fn take_all<'s>(directory: &mut HashMap<OsString, Self>) -> Option<Directory<'s>>
+    where
+        Self: 's,
+    {
+        Some(directory.drain().map(|(name, entry)| (name, entry as Box<dyn Entry + 's>)).collect())
+    }
+
+    fn as_entry(&self) -> &dyn Entry {
+        &**self
+    }
+}
+
+/// Shared entries can't be taken, since other trees may hold them too.
+impl<'a> DirectoryEntry<'a> for Arc<dyn Entry + 'a> {
+    fn take_all<'s>(_directory: &mut HashMap<OsString, Self>) -> Option<Directory<'s>>
+    where
+        Self: 's,
+    {
+        None
+    }
+
+    fn as_entry(&self) -> &dyn Entry {
+        &**self
+    }
+}
+
+/// What [`Merge::merge`] does when both directories have a file at the same path, or a
+/// file in one where the other has a directory.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// Keep the entry of the directory merged into.
+    KeepExisting,
+    /// Replace it with the entry of the merged directory.
+    Overwrite,
+    /// Fail with [`DatapackError::DuplicateEntry`], without merging anything.
+    #[default]
+    Error,
+}
+
+pub trait Merge {
+    /// Adds the entries of `other`, merging directories at the same path, such as to add
+    /// generated files to a handwritten pack read with [`FromPath`]. Files at the same
+    /// path are resolved by `policy`.
+    ///
+    /// Fails with [`DatapackError::DuplicateEntry`], without merging anything, if either
+    /// side has a directory that can't be taken apart, like a [`SharedDirectory`], where the
+    /// other has a directory too.
+    fn merge(&mut self, other: Self, policy: MergePolicy) -> std::result::Result<(), DatapackError>;
+}
+
+impl<'a> Merge for Directory<'a> {
+    fn merge(&mut self, mut other: Self, policy: MergePolicy) -> std::result::Result<(), DatapackError> {
+        check_mergeable(self, &mut other, Path::new(""))?;
+        if policy == MergePolicy::Error {
+            let existing = paths(self);
+            let collision = paths(&other).into_iter().find(|(path, is_directory)| match existing.get(path) {
+                Some(existing_is_directory) => !(*is_directory && *existing_is_directory),
+                None => false,
+            });
+            if let Some((path, _)) = collision {
+                return Err(DatapackError::DuplicateEntry(path));
+            }
+        }
+        merge_into(self, other, policy);
+        Ok(())
+    }
+}
+
+/// The path of every entry inside `directory`, and whether it is a directory.
+fn paths(directory: &Directory) -> BTreeMap<PathBuf, bool> {
+    let mut paths = BTreeMap::new();
+    directory.visit(&mut |path, entry| {
+        paths.insert(path.to_path_buf(), entry.stats().directories > 0);
+    });
+    paths
+}
+
+/// Fails if a directory in `directory` or `other` can't be merged with the one at the
+/// same path in the other. Directories are taken apart to look inside them and put back
+/// as they were.
+fn check_mergeable<'a>(directory: &mut Directory<'a>, other: &mut Directory<'a>, path: &Path) -> std::result::Result<(), DatapackError> {
+    for (name, entry) in other.iter_mut() {
+        let Some(existing) = directory.get_mut(name) else {
+            continue;
+        };
+        let path = path.join(name);
+        match (existing.take_entries(), entry.take_entries()) {
+            (Some(mut existing_entries), Some(mut entries)) => {
+                let result = check_mergeable(&mut existing_entries, &mut entries, &path);
+                *existing = Box::new(existing_entries);
+                *entry = Box::new(entries);
+                result?;
+            }
+            (existing_entries, entries) => {
+                // Both are put back before failing, so neither `&` may short-circuit.
+                if is_directory(existing, existing_entries) & is_directory(entry, entries) {
+                    return Err(DatapackError::DuplicateEntry(path));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Puts back the entries `entry` had taken, if any, and returns whether it is a directory.
+fn is_directory<'a>(entry: &mut Box<dyn Entry + 'a>, taken: Option<Directory<'a>>) -> bool {
+    match taken {
+        Some(entries) => {
+            *entry = Box::new(entries);
+            true
+        }
+        None => entry.stats().directories > 0,
+    }
+}
+
+fn merge_into<'a>(directory: &mut Directory<'a>, other: Directory<'a>, policy: MergePolicy) {
+    for (name, mut entry) in other {
+        let Some(existing) = directory.get_mut(&name) else {
+            directory.insert(name, entry);
+            continue;
+        };
+        if let Some(mut entries) = existing.take_entries() {
+            match entry.take_entries() {
+                Some(other_entries) => merge_into(&mut entries, other_entries, policy),
+                None if policy == MergePolicy::Overwrite => {
+                    *existing = entry;
+                    continue;
+                }
+                None => {}
+            }
+            *existing = Box::new(entries);
+        } else if policy == MergePolicy::Overwrite {
+            *existing = entry;
+        }
+    }
+}
+
+pub trait IntoShared<'a> {
+    fn into_shared(self) -> SharedDirectory<'a>;
+}
+
+/// Only the top level is converted, and each subdirectory is shared as a whole.
+impl<'a> IntoShared<'a> for Directory<'a> {
+    fn into_shared(self) -> SharedDirectory<'a> {
+        self.into_iter().map(|(name, entry)| (name, Arc::from(entry))).collect()
+    }
+}
+
+pub trait FromPath: Sized {
+    fn from_path(path: &Path) -> std::result::Result<Self, DatapackError>;
+}
+
+/// Reads the directory at `path`, such as an existing datapack, with each file as an
+/// [`OwnedStringFile`], or an [`OwnedBytesFile`] if it isn't UTF-8, like `pack.png`, and
+/// each subdirectory read the same way. Fails with [`DatapackError::Io`] if anything
+/// can't be read.
+///
+/// Symbolic links are followed, except to a directory the link is already inside, which
+/// would never end. Those are left out with a warning.
+impl FromPath for Directory<'static> {
+    fn from_path(path: &Path) -> std::result::Result<Self, DatapackError> {
+        read_directory(path, &mut vec![fs::canonicalize(path)?])
+    }
+}
+
+/// Reads the directory at `path` for [`FromPath`], where `ancestors` are the resolved paths
+/// of the directories it is in, and its own.
+fn read_directory(path: &Path, ancestors: &mut Vec<PathBuf>) -> std::result::Result<Directory<'static>, DatapackError> {
+    let mut directory = Directory::new();
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        let entry_path = entry.path();
+        let read: Box<dyn Entry> = if entry_path.is_dir() {
+            let resolved = fs::canonicalize(&entry_path)?;
+            if ancestors.contains(&resolved) {
+                log::warn!("'{}' links to a directory it is inside, leaving it out", entry_path.display());
+                continue;
+            }
+            ancestors.push(resolved);
+            let read = read_directory(&entry_path, ancestors);
+            ancestors.pop();
+            Box::new(read?)
+        } else {
+            match String::from_utf8(fs::read(&entry_path)?) {
+                Ok(contents) => Box::new(OwnedStringFile(contents)),
+                Err(error) => Box::new(OwnedBytesFile(error.into_bytes())),
+            }
+        };
+        directory.insert(entry.file_name(), read);
+    }
+    Ok(directory)
+}
+
+impl<'a, E: DirectoryEntry<'a>> Entry for HashMap<OsString, E> {
     fn create(&self, path: &Path) -> Result<()> {
         create_dir(path)?;
         for (name, entry) in self {
@@ -31,6 +1004,69 @@ impl<'a> Entry for Directory<'a> {
         }
         Ok(())
     }
+
+    fn stats(&self) -> PackStats {
+        let root = PackStats { directories: 1, ..PackStats::default() };
+        root + self.values().map(|entry| entry.stats()).sum()
+    }
+
+    /// Keeps an existing directory and backs up its entries one by one instead, so only
+    /// replaced files get a backup. Returns a backup only when something other than a
+    /// directory was at `path`.
+    fn create_with_backup(&self, path: &Path) -> std::result::Result<Option<PathBuf>, DatapackError> {
+        let backup = if path.is_dir() {
+            None
+        } else {
+            let backup = back_up(path)?;
+            create_dir(path)?;
+            backup
+        };
+        for (name, entry) in self {
+            entry.create_with_backup(&path.join(name))?;
+        }
+        Ok(backup)
+    }
+
+    fn create_with_options(&self, path: &Path, options: &WriteOptions) -> std::result::Result<(), DatapackError> {
+        options.create_dir(path)?;
+        let mut pending = Vec::new();
+        for (name, entry) in self {
+            options.create_entry(&path.join(name), entry.as_entry(), &mut pending)?;
+        }
+        Ok(options.write_pending(pending)?)
+    }
+
+    #[cfg(feature = "tokio")]
+    fn create_async<'e>(&'e self, path: &'e Path) -> CreateFuture<'e> {
+        Box::pin(async move {
+            tokio::fs::create_dir(path).await?;
+            for (name, entry) in self {
+                let entry_path = path.join(name);
+                entry.create_async(&entry_path).await?;
+            }
+            Ok(())
+        })
+    }
+
+    fn for_each_entry(&self, f: &mut dyn FnMut(&Path, &dyn Entry)) {
+        let mut names: Vec<&OsString> = self.keys().collect();
+        names.sort();
+        for name in names {
+            f(Path::new(name), &*self[name]);
+        }
+    }
+
+    fn take_entries<'s>(&mut self) -> Option<Directory<'s>>
+    where
+        Self: 's,
+    {
+        E::take_all(self)
+    }
+
+    fn styled(&self, style: JsonStyle) -> Option<Box<dyn Entry + '_>> {
+        let styled: Directory = self.iter().map(|(name, entry)| (name.clone(), with_style(entry.as_entry(), style))).collect();
+        Some(Box::new(styled))
+    }
 }
 
 #[cfg(test)]
@@ -66,6 +1102,92 @@ mod tests {
         temp_dir.close().expect("should have closed temp dir");
     }
 
+    #[test]
+    fn conditional_entry() {
+        let mut directory = Directory::new();
+        directory.insert(OsString::from("included"), Box::new(ConditionalEntry::new(true, StringFile::from("yes"))));
+        directory.insert(OsString::from("excluded"), Box::new(ConditionalEntry::new(false, StringFile::from("no"))));
+        let mut excluded = Directory::new();
+        excluded.insert(OsString::from("file"), Box::new('a'));
+        directory.insert(OsString::from("excluded_directory"), Box::new(ConditionalEntry::new(false, excluded)));
+
+        let temp_dir = TempDir::new(module_path!()).expect("should have created temp dir");
+        let path = temp_dir.path().join("test_directory");
+        directory.create(&path).expect("should have created the directory");
+        let contents = fs::read_to_string(path.join("included")).expect("should have been able to read the file");
+        assert_eq!(contents, "yes");
+        assert!(!path.join("excluded").exists(), "the excluded file should not have been created");
+        assert!(!path.join("excluded_directory").exists(), "the excluded directory should not have been created");
+        assert_eq!(directory.stats(), PackStats { bytes: 3, files: 1, directories: 1 });
+
+        temp_dir.close().expect("should have closed temp dir");
+    }
+
+    #[test]
+    fn estimated_size() {
+        let mut nested = Directory::new();
+        nested.insert(OsString::from("ä"), Box::new('ä'));
+        let mut directory = Directory::new();
+        directory.insert(OsString::from("file"), Box::new(StringFile::from("Hello World!")));
+        directory.insert(OsString::from("nested"), Box::new(nested));
+        assert_eq!(StringFile::from("Hello World!").estimated_size(), 12);
+        assert_eq!(directory.estimated_size(), 14, "the size should be in bytes and include every subdirectory");
+    }
+
+    #[test]
+    fn default_stats() {
+        struct Bytes(&'static [u8]);
+
+        impl Entry for Bytes {
+            fn create(&self, path: &Path) -> Result<()> {
+                fs::write(path, self.0)
+            }
+
+            fn contents(&self) -> Option<Vec<u8>> {
+                Some(self.0.to_vec())
+            }
+        }
+
+        struct Pair;
+
+        impl Entry for Pair {
+            fn create(&self, path: &Path) -> Result<()> {
+                create_dir(path)?;
+                Bytes(b"a").create(&path.join("a"))?;
+                Bytes(b"bc").create(&path.join("b"))
+            }
+
+            fn for_each_entry(&self, f: &mut dyn FnMut(&Path, &dyn Entry)) {
+                f(Path::new("a"), &Bytes(b"a"));
+                f(Path::new("b"), &Bytes(b"bc"));
+            }
+        }
+
+        assert_eq!(Bytes(b"abc").stats(), PackStats { bytes: 3, files: 1, directories: 0 });
+        assert_eq!(Pair.stats(), PackStats { bytes: 3, files: 2, directories: 1 });
+    }
+
+    #[test]
+    fn shared_directory() {
+        let mut directory = Directory::new();
+        directory.insert(OsString::from("a"), Box::new(StringFile::from("shared")));
+        let shared = directory.into_shared();
+        let mut other = shared.clone();
+        other.insert(OsString::from("b"), Arc::new('b'));
+
+        let temp_dir = TempDir::new(module_path!()).expect("should have created temp dir");
+        shared.create(&temp_dir.path().join("shared")).expect("should have created the directory");
+        other.create(&temp_dir.path().join("other")).expect("should have created the directory");
+        for name in ["shared", "other"] {
+            let contents = fs::read_to_string(temp_dir.path().join(name).join("a")).expect("should have been able to read the file");
+            assert_eq!(contents, "shared");
+        }
+        assert!(!temp_dir.path().join("shared/b").exists(), "the original should not have changed");
+        assert_eq!(other.stats(), PackStats { bytes: 7, files: 2, directories: 1 });
+
+        temp_dir.close().expect("should have closed temp dir");
+    }
+
     #[test]
     fn directory_create() {
         const ENTRY_NAME: &str = "test_directory";
@@ -106,6 +1228,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::clone_on_copy)]
     fn sub_files_create() {
         const ENTRY_NAME: &str = "test_directory";
 
@@ -130,4 +1253,408 @@ mod tests {
 
         temp_dir.close().expect("should have closed temp dir");
     }
+
+    #[test]
+    fn nested_stats() {
+        let mut sub_directory = Directory::new();
+        sub_directory.insert(OsString::from("a"), Box::new(StringFile::from("Hello")));
+        sub_directory.insert(OsString::from("b"), Box::new(CharFile::from('!')));
+        sub_directory.insert(OsString::from("empty"), Box::new(Directory::new()));
+
+        let mut entry = Directory::new();
+        entry.insert(OsString::from("sub"), Box::new(sub_directory));
+        entry.insert(OsString::from("c"), Box::new(StringFile::from("World")));
+
+        let expected = PackStats { bytes: 11, files: 3, directories: 3 };
+        assert_eq!(entry.stats(), expected, "stats of the nested tree are incorrect");
+    }
+
+    /// `pack.mcmeta`, `data/shared.txt` and `data/<text>.txt`, each containing `text`.
+    fn merge_tree(text: &str) -> Directory<'static> {
+        let mut data = Directory::new();
+        data.insert(OsString::from(format!("{text}.txt")), Box::new(OwnedStringFile(text.to_string())) as Box<dyn Entry>);
+        data.insert(OsString::from("shared.txt"), Box::new(OwnedStringFile(text.to_string())));
+        let mut root = Directory::new();
+        root.insert(OsString::from("data"), Box::new(data) as Box<dyn Entry>);
+        root.insert(OsString::from("pack.mcmeta"), Box::new(OwnedStringFile(text.to_string())));
+        root
+    }
+
+    fn merged_files(directory: &Directory) -> BTreeMap<PathBuf, String> {
+        let mut files = BTreeMap::new();
+        directory.visit(&mut |path, entry| {
+            if let Some(contents) = entry.contents() {
+                files.insert(path.to_path_buf(), String::from_utf8(contents).expect("the files are UTF-8"));
+            }
+        });
+        files
+    }
+
+    #[test]
+    fn merge_policies() {
+        let file = |path: &str, text: &str| (PathBuf::from(path), text.to_string());
+
+        let mut directory = merge_tree("hand");
+        directory.merge(merge_tree("generated"), MergePolicy::KeepExisting).expect("should have merged the directories");
+        assert_eq!(merged_files(&directory), BTreeMap::from([
+            file("data/generated.txt", "generated"),
+            file("data/hand.txt", "hand"),
+            file("data/shared.txt", "hand"),
+            file("pack.mcmeta", "hand"),
+        ]));
+
+        let mut directory = merge_tree("hand");
+        directory.merge(merge_tree("generated"), MergePolicy::Overwrite).expect("should have merged the directories");
+        assert_eq!(merged_files(&directory), BTreeMap::from([
+            file("data/generated.txt", "generated"),
+            file("data/hand.txt", "hand"),
+            file("data/shared.txt", "generated"),
+            file("pack.mcmeta", "generated"),
+        ]));
+
+        let mut directory = merge_tree("hand");
+        let error = directory.merge(merge_tree("generated"), MergePolicy::Error).expect_err("the files collide");
+        assert!(matches!(&error, DatapackError::DuplicateEntry(path) if path == Path::new("data/shared.txt")), "unexpected error: {error}");
+        assert_eq!(merged_files(&directory), merged_files(&merge_tree("hand")), "nothing should have been merged");
+
+        let mut directory = merge_tree("hand");
+        directory.remove(&OsString::from("pack.mcmeta"));
+        let mut other = merge_tree("generated");
+        other.remove(&OsString::from("pack.mcmeta"));
+        let Some(data) = other.get_mut(&OsString::from("data")).and_then(|data| data.take_entries()) else {
+            panic!("'data' should have been a directory");
+        };
+        let data = data.into_iter().filter(|(name, _)| name != "shared.txt").collect::<Directory>();
+        other.insert(OsString::from("data"), Box::new(data));
+        directory.merge(other, MergePolicy::Error).expect("directories without colliding files should merge");
+        assert_eq!(merged_files(&directory).len(), 3);
+    }
+
+    #[test]
+    fn merge_shared_directories() {
+        let shared = |text: &str| -> Directory<'static> {
+            let mut root = merge_tree(text);
+            let mut data = root.remove(&OsString::from("data")).expect("'data' should have been in the tree");
+            let data: SharedDirectory = data.take_entries().expect("'data' should have been a directory").into_shared();
+            root.insert(OsString::from("data"), Box::new(data));
+            root
+        };
+        for policy in [MergePolicy::KeepExisting, MergePolicy::Overwrite, MergePolicy::Error] {
+            for (mut directory, other) in [(shared("hand"), merge_tree("generated")), (merge_tree("hand"), shared("generated"))] {
+                let before = merged_files(&directory);
+                let error = directory.merge(other, policy).expect_err("a shared directory can't be merged into");
+                assert!(matches!(&error, DatapackError::DuplicateEntry(path) if path == Path::new("data")), "unexpected error: {error}");
+                assert_eq!(merged_files(&directory), before, "nothing should have been merged");
+            }
+        }
+    }
+
+    #[test]
+    fn from_path_round_trip() {
+        let temp_dir = TempDir::new(module_path!()).expect("should have created temp dir");
+        let mut sub_directory = Directory::new();
+        sub_directory.insert(OsString::from("a"), Box::new(StringFile::from("Hello")));
+        sub_directory.insert(OsString::from("empty"), Box::new(Directory::new()));
+        let mut entry = Directory::new();
+        entry.insert(OsString::from("sub"), Box::new(sub_directory));
+        entry.insert(OsString::from("b"), Box::new(StringFile::from("World")));
+        entry.create(&temp_dir.path().join("original")).expect("should have created the directory");
+
+        let read = Directory::from_path(&temp_dir.path().join("original")).expect("should have read the directory");
+        assert_eq!(read.stats(), entry.stats(), "the read tree should have the shape of the written one");
+        read.create(&temp_dir.path().join("copy")).expect("should have written the read directory");
+        let contents = fs::read_to_string(temp_dir.path().join("copy/sub/a")).expect("should have been able to read the file");
+        assert_eq!(contents, "Hello");
+        assert!(temp_dir.path().join("copy/sub/empty").is_dir(), "empty directories should have been kept");
+
+        let missing = Directory::from_path(&temp_dir.path().join("missing"));
+        assert!(matches!(missing, Err(DatapackError::Io(_))), "a missing directory can't be read");
+
+        temp_dir.close().expect("should have closed temp dir");
+    }
+
+    #[test]
+    fn from_path_binary_files() {
+        let temp_dir = TempDir::new(module_path!()).expect("should have created temp dir");
+        let path = temp_dir.path().join("original");
+        fs::create_dir(&path).expect("should have created the directory");
+        let icon = b"\x89PNG\r\n\x1a\n\xff\x00".to_vec();
+        fs::write(path.join("pack.png"), &icon).expect("should have written the icon");
+
+        let read = Directory::from_path(&path).expect("should have read a file that isn't UTF-8");
+        assert_eq!(read[&OsString::from("pack.png")].contents(), Some(icon.clone()));
+        read.create(&temp_dir.path().join("copy")).expect("should have written the read directory");
+        assert_eq!(fs::read(temp_dir.path().join("copy/pack.png")).expect("should have been able to read the file"), icon);
+
+        temp_dir.close().expect("should have closed temp dir");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn from_path_symlink_loop() {
+        let temp_dir = TempDir::new(module_path!()).expect("should have created temp dir");
+        let path = temp_dir.path().join("original");
+        fs::create_dir_all(path.join("sub")).expect("should have created the directory");
+        fs::write(path.join("sub/a"), "Hello").expect("should have written the file");
+        std::os::unix::fs::symlink(&path, path.join("sub/loop")).expect("should have created the link");
+
+        let read = Directory::from_path(&path).expect("should have read the directory");
+        assert_eq!(read.stats(), PackStats { bytes: 5, files: 1, directories: 2 }, "the link back should have been left out");
+
+        temp_dir.close().expect("should have closed temp dir");
+    }
+
+    #[test]
+    fn verify() {
+        let temp_dir = TempDir::new(module_path!()).expect("should have created temp dir");
+        let path = temp_dir.path().join("pack");
+        let mut sub_directory = Directory::new();
+        sub_directory.insert(OsString::from("a"), Box::new(StringFile::from("Hello")));
+        let mut entry = Directory::new();
+        entry.insert(OsString::from("sub"), Box::new(sub_directory));
+        entry.insert(OsString::from("b"), Box::new(StringFile::from("World")));
+        entry.insert(OsString::from("lazy"), Box::new(LazyFile::new(|| "Later".to_string())));
+        entry.create(&path).expect("should have created the directory");
+        assert_eq!(entry.verify(&path), Ok(()), "the tree was just written");
+
+        fs::write(path.join("sub/a"), "Goodbye").expect("should have changed the file");
+        fs::write(path.join("sub/extra"), "!").expect("should have added a file");
+        fs::write(path.join("lazy"), "Changed").expect("should have changed the file");
+        fs::remove_file(path.join("b")).expect("should have removed the file");
+        assert_eq!(
+            entry.verify(&path),
+            Err(vec![
+                Mismatch::Missing(PathBuf::from("b")),
+                Mismatch::Modified(PathBuf::from("sub/a")),
+                Mismatch::Extra(PathBuf::from("sub/extra")),
+            ]),
+            "a lazy file can't be compared",
+        );
+
+        let file = StringFile::from("World");
+        assert_eq!(file.verify(&path.join("sub/extra")), Err(vec![Mismatch::Modified(PathBuf::new())]));
+
+        temp_dir.close().expect("should have closed temp dir");
+    }
+
+    #[test]
+    fn create_incremental() {
+        let temp_dir = TempDir::new(module_path!()).expect("should have created temp dir");
+        let path = temp_dir.path().join("pack");
+        let tree = |a: &str| {
+            let mut sub_directory = Directory::new();
+            sub_directory.insert(OsString::from("a"), Box::new(StringFile::from(a)));
+            let mut entry = Directory::new();
+            entry.insert(OsString::from("sub"), Box::new(sub_directory));
+            entry.insert(OsString::from("b"), Box::new(StringFile::from("World")));
+            entry
+        };
+        let fixed = tree("Hello").create_incremental(&path).expect("should have created the tree");
+        assert_eq!(fixed, [Mismatch::Missing(PathBuf::from("b")), Mismatch::Missing(PathBuf::from("sub/a"))]);
+
+        let long_ago = std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000_000);
+        for file in ["b", "sub/a"] {
+            fs::File::options().write(true).open(path.join(file)).and_then(|file| file.set_modified(long_ago)).expect("should have set the modification time");
+        }
+        fs::create_dir_all(path.join("old/deeper")).expect("should have created a stale directory");
+        fs::write(path.join("old/deeper/stale"), "!").expect("should have added a stale file");
+        let fixed = tree("Goodbye").create_incremental(&path).expect("should have updated the tree");
+        assert_eq!(fixed, [Mismatch::Modified(PathBuf::from("sub/a")), Mismatch::Extra(PathBuf::from("old/deeper/stale"))]);
+
+        let modified = |file: &str| fs::metadata(path.join(file)).and_then(|metadata| metadata.modified()).expect("should have read the modification time");
+        assert_eq!(modified("b"), long_ago, "the unchanged file shouldn't have been written");
+        assert_ne!(modified("sub/a"), long_ago, "the changed file should have been written");
+        assert_eq!(fs::read_to_string(path.join("sub/a")).expect("should have read the file"), "Goodbye");
+        assert!(!path.join("old").exists(), "directories left empty should have been removed");
+        assert_eq!(tree("Goodbye").verify(&path), Ok(()));
+        assert_eq!(tree("Goodbye").create_incremental(&path).expect("should have updated the tree"), []);
+
+        temp_dir.close().expect("should have closed temp dir");
+    }
+
+    #[test]
+    fn visit_leaves() {
+        let mut deeper = Directory::new();
+        deeper.insert(OsString::from("c"), Box::new(StringFile::from("!")));
+        let mut sub_directory = Directory::new();
+        sub_directory.insert(OsString::from("b"), Box::new(StringFile::from("World")));
+        sub_directory.insert(OsString::from("deeper"), Box::new(deeper));
+        let mut entry = Directory::new();
+        entry.insert(OsString::from("sub"), Box::new(sub_directory));
+        entry.insert(OsString::from("a"), Box::new(StringFile::from("Hello")));
+        entry.insert(OsString::from("excluded"), Box::new(ConditionalEntry::new(false, Directory::new())));
+
+        let mut visited = Vec::new();
+        let mut leaves = Vec::new();
+        entry.visit(&mut |path, entry| {
+            visited.push(path.to_path_buf());
+            if entry.stats().directories == 0 {
+                leaves.push(path.to_path_buf());
+            }
+        });
+        assert_eq!(visited, ["a", "excluded", "sub", "sub/b", "sub/deeper", "sub/deeper/c"].map(PathBuf::from), "directories should be visited before their entries");
+        assert_eq!(leaves, ["a", "excluded", "sub/b", "sub/deeper/c"].map(PathBuf::from), "an excluded entry has no entries");
+    }
+
+    #[test]
+    fn create_with_backup() {
+        let temp_dir = TempDir::new(module_path!()).expect("should have created temp dir");
+        let path = temp_dir.path().join("test_directory");
+
+        let first: Directory = [("a", "old")]
+            .into_iter()
+            .map(|(name, contents)| (OsString::from(name), Box::new(StringFile::from(contents)) as Box<dyn Entry>))
+            .collect();
+        let backup = first.create_with_backup(&path).expect("should have created the directory");
+        assert_eq!(backup, None, "nothing should have been backed up on the first run");
+
+        let second: Directory = [("a", "new"), ("b", "added")]
+            .into_iter()
+            .map(|(name, contents)| (OsString::from(name), Box::new(StringFile::from(contents)) as Box<dyn Entry>))
+            .collect();
+        let backup = second.create_with_backup(&path).expect("should have recreated the directory");
+        assert_eq!(backup, None, "an existing directory should be kept, not backed up");
+        let read = |name: &str| fs::read_to_string(path.join(name)).expect("should have been able to read the file");
+        assert_eq!(read("a"), "new", "'a' should have been replaced");
+        assert_eq!(read("a.bak"), "old", "'a' should have been backed up");
+        assert_eq!(read("b"), "added", "'b' should have been created");
+        assert!(!path.join("b.bak").exists(), "'b' was new, so it should not have a backup");
+
+        let backup = StringFile::from("newer").create_with_backup(&path.join("a")).expect("should have replaced the file");
+        assert_eq!(backup, Some(path.join("a.bak")));
+        assert_eq!(read("a.bak"), "new", "the older backup should have been replaced");
+
+        temp_dir.close().expect("should have closed temp dir");
+    }
+
+    #[test]
+    fn create_with_options() {
+        let temp_dir = TempDir::new(module_path!()).expect("should have created temp dir");
+        let path = temp_dir.path().join("test_directory");
+        let directory = |contents: &str| -> Directory {
+            [("a", contents)]
+                .into_iter()
+                .map(|(name, contents)| (OsString::from(name), Box::new(StringFile::from(contents)) as Box<dyn Entry>))
+                .collect()
+        };
+
+        let events = std::rc::Rc::new(RefCell::new(Vec::new()));
+        let recorded = events.clone();
+        let options = WriteOptions::default().on_progress(move |event| recorded.borrow_mut().push(event));
+        directory("old").create_with_options(&path, &options).expect("should have created the directory");
+        assert_eq!(*events.borrow(), [
+            ProgressEvent::DirectoryCreated { path: path.clone() },
+            ProgressEvent::Created { path: path.join("a"), bytes: 3 },
+        ]);
+        assert!(directory("new").create_with_options(&path, &options).is_err(), "the default options should not overwrite");
+
+        directory("new").create_with_options(&path, &WriteOptions::default().allow_overwrite()).expect("should have overwritten the directory");
+        let read = |name: &str| fs::read_to_string(path.join(name)).expect("should have been able to read the file");
+        assert_eq!(read("a"), "new");
+        assert!(!path.join("a.bak").exists(), "overwriting should not make backups");
+
+        directory("newer").create_with_options(&path, &WriteOptions::default().create_backup()).expect("should have backed up the directory");
+        assert_eq!(read("a"), "newer");
+        assert_eq!(read("a.bak"), "new");
+
+        temp_dir.close().expect("should have closed temp dir");
+    }
+
+    #[test]
+    fn create_in_parallel() {
+        let temp_dir = TempDir::new(module_path!()).expect("should have created temp dir");
+        let path = temp_dir.path().join("test_directory");
+        let names: Vec<String> = (0..32).map(|index| format!("file_{index}")).collect();
+        let mut directory: Directory = names
+            .iter()
+            .map(|name| (OsString::from(name), Box::new(StringFile::from(name.as_str())) as Box<dyn Entry>))
+            .collect();
+        directory.insert("nested".into(), Box::new(Directory::from([("a".into(), Box::new(StringFile::from("nested")) as Box<dyn Entry>)])));
+
+        let events = std::rc::Rc::new(RefCell::new(Vec::new()));
+        let recorded = events.clone();
+        let options = WriteOptions::default().parallel().on_progress(move |event| recorded.borrow_mut().push(event));
+        directory.create_with_options(&path, &options).expect("should have created the directory");
+        for name in &names {
+            assert_eq!(fs::read_to_string(path.join(name)).expect("should have been able to read the file"), *name);
+        }
+        assert_eq!(fs::read_to_string(path.join("nested/a")).expect("should have been able to read the file"), "nested");
+        let created = events.borrow().iter().filter(|event| matches!(event, ProgressEvent::Created { .. })).count();
+        assert_eq!(created, names.len() + 1, "every file should have been reported");
+
+        assert!(directory.create_with_options(&path, &options).is_err(), "writing in parallel should not overwrite");
+        directory.create_with_options(&path, &WriteOptions::default().parallel().create_backup()).expect("should have backed up the directory");
+        assert!(path.join("file_0.bak").exists(), "the replaced files should have been backed up");
+
+        temp_dir.close().expect("should have closed temp dir");
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn gzipped_file() {
+        use flate2::read::GzDecoder;
+
+        let temp_dir = TempDir::new(module_path!()).expect("should have created temp dir");
+        let contents = "{\"values\": [\"minecraft:stone\"]}\n".repeat(100);
+        let gzipped_path = temp_dir.path().join("blocks.json.gz");
+        Gzipped(StringFile::from(contents.as_str())).create(&gzipped_path).expect("should have created the file");
+
+        let gzipped = fs::read(&gzipped_path).expect("should have been able to read the file");
+        assert!(gzipped.len() < contents.len(), "the file should have been compressed");
+        let mut inflated = String::new();
+        GzDecoder::new(gzipped.as_slice()).read_to_string(&mut inflated).expect("should have inflated the file");
+        assert_eq!(inflated, contents);
+
+        let plain_path = temp_dir.path().join("plain.json.gz");
+        StringFile::from(contents.as_str()).create(&plain_path).expect("should have created the file");
+        assert_eq!(fs::read_to_string(&plain_path).expect("should have been able to read the file"), contents, "only gzipped entries are compressed");
+
+        temp_dir.close().expect("should have closed temp dir");
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn gzipped_incremental() {
+        let temp_dir = TempDir::new(module_path!()).expect("should have created temp dir");
+        let path = temp_dir.path().join("test_directory");
+        let directory = |contents: &'static str| -> Directory {
+            HashMap::from([(OsString::from("log.txt.gz"), Box::new(Gzipped(StringFile::from(contents))) as Box<dyn Entry>)])
+        };
+
+        directory("first").create(&path).expect("should have created the directory");
+        assert_eq!(directory("first").verify(&path), Ok(()), "the compressed file should match what was written");
+        assert_eq!(directory("first").create_incremental(&path).expect("should have updated the directory"), []);
+
+        assert_eq!(directory("second").verify(&path), Err(vec![Mismatch::Modified(PathBuf::from("log.txt.gz"))]));
+        directory("second").create_incremental(&path).expect("should have updated the directory");
+        assert_eq!(directory("second").verify(&path), Ok(()), "the file should have been written compressed");
+
+        temp_dir.close().expect("should have closed temp dir");
+    }
+
+    #[test]
+    fn lazy_file_renders_once() {
+        let temp_dir = TempDir::new(module_path!()).expect("should have created temp dir");
+        let path = temp_dir.path().join("test_directory");
+
+        let renders = std::cell::Cell::new(0);
+        let mut entry = Directory::new();
+        entry.insert(OsString::from("lazy"), Box::new(LazyFile::new(|| {
+            renders.set(renders.get() + 1);
+            "rendered".to_string()
+        })));
+        assert_eq!(entry.stats(), PackStats { bytes: 0, files: 1, directories: 1 });
+        assert_eq!(renders.get(), 0, "the contents should not have been rendered before 'create'");
+
+        entry.create(&path).expect("should have created the directory");
+        assert_eq!(renders.get(), 1, "the contents should have been rendered exactly once");
+        let contents = fs::read_to_string(path.join("lazy")).expect("should have been able to read the file");
+        assert_eq!(contents, "rendered");
+
+        let second = temp_dir.path().join("second");
+        assert!(entry.create(&second).is_err(), "a lazy file can only be created once");
+        assert_eq!(renders.get(), 1, "the contents should not have been rendered again");
+
+        temp_dir.close().expect("should have closed temp dir");
+    }
 }
@@ -0,0 +1,319 @@
+use std::fmt;
+#[cfg(feature = "serde")]
+use std::fs;
+use std::io;
+#[cfg(feature = "serde")]
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+use serde_json::ser::PrettyFormatter;
+#[cfg(feature = "serde")]
+use serde_json::Value;
+use crate::error::DatapackError;
+use crate::utils::file_system::{with_style, Entry, PackStats, WriteOptions};
+#[cfg(feature = "tokio")]
+use crate::utils::file_system::CreateFuture;
+#[cfg(all(feature = "tokio", feature = "serde"))]
+use crate::utils::file_system::write_new_async;
+
+/// How this crate's JSON files are laid out. Both styles write the same keys in the same
+/// order, so they only differ in whitespace.
+///
+/// JSON inside commands, such as the text of a `tellraw`, is always compact.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsonStyle {
+    /// One key or element per line, indented by `indent` spaces per level.
+    Pretty { indent: usize },
+    /// On a single line, without any whitespace.
+    Compact,
+}
+
+impl JsonStyle {
+    pub fn to_string<T: Serialize + ?Sized>(self, value: &T) -> serde_json::Result<String> {
+        match self {
+            Self::Pretty { indent } => {
+                let indent = " ".repeat(indent);
+                let mut json = Vec::new();
+                let mut serializer = serde_json::Serializer::with_formatter(&mut json, PrettyFormatter::with_indent(indent.as_bytes()));
+                value.serialize(&mut serializer)?;
+                Ok(String::from_utf8(json).expect("serde_json only writes UTF-8"))
+            }
+            Self::Compact => serde_json::to_string(value),
+        }
+    }
+}
+
+/// Two spaces, as the game's own files are indented.
+impl Default for JsonStyle {
+    fn default() -> Self {
+        Self::Pretty { indent: 2 }
+    }
+}
+
+pub(crate) fn fmt_json<T: Serialize + ?Sized>(value: &T, f: &mut fmt::Formatter, style: JsonStyle) -> fmt::Result {
+    let json = style.to_string(value).map_err(|_| fmt::Error)?;
+    f.write_str(&json)
+}
+
+/// An entry whose JSON files are always laid out in `style`, even inside a datapack of
+/// another style.
+pub(crate) struct Styled<'a> {
+    pub entry: Box<dyn Entry + 'a>,
+    pub style: JsonStyle,
+}
+
+impl Styled<'_> {
+    fn styled_entry(&self) -> Box<dyn Entry + '_> {
+        with_style(&*self.entry, self.style)
+    }
+}
+
+impl Entry for Styled<'_> {
+    fn create(&self, path: &Path) -> io::Result<()> {
+        self.styled_entry().create(path)
+    }
+
+    fn stats(&self) -> PackStats {
+        self.styled_entry().stats()
+    }
+
+    fn create_with_backup(&self, path: &Path) -> Result<Option<PathBuf>, DatapackError> {
+        self.styled_entry().create_with_backup(path)
+    }
+
+    fn create_with_options(&self, path: &Path, options: &WriteOptions) -> Result<(), DatapackError> {
+        self.styled_entry().create_with_options(path, options)
+    }
+
+    #[cfg(feature = "tokio")]
+    fn create_async<'e>(&'e self, path: &'e Path) -> CreateFuture<'e> {
+        Box::pin(async move { self.styled_entry().create_async(path).await })
+    }
+
+    fn for_each_entry(&self, f: &mut dyn FnMut(&Path, &dyn Entry)) {
+        self.styled_entry().for_each_entry(f);
+    }
+
+    fn contents(&self) -> Option<Vec<u8>> {
+        self.styled_entry().contents()
+    }
+}
+
+/// Renders `$file` with serde, in the default [`JsonStyle`] when it is displayed, or in
+/// the [`JsonStyle`] of the datapack it is written in.
+macro_rules! json_file {
+    ($($file:ty),+ $(,)?) => {
+        $(
+            impl std::fmt::Display for $file {
+                fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    $crate::utils::json::fmt_json(self, f, $crate::utils::json::JsonStyle::default())
+                }
+            }
+
+            impl $crate::utils::file_system::File for $file {
+                fn fmt_styled(&self, f: &mut std::fmt::Formatter, style: $crate::utils::json::JsonStyle) -> std::fmt::Result {
+                    $crate::utils::json::fmt_json(self, f, style)
+                }
+            }
+        )+
+    };
+}
+
+pub(crate) use json_file;
+
+/// Any [`Serialize`] value as a JSON file, rendered the same way as this crate's own
+/// file types: made with [`JsonFile::new`], it is laid out in the [`JsonStyle`] of the
+/// datapack it is written in.
+///
+/// Unlike a [`File`](crate::utils::file_system::File), a value that can't be serialized
+/// makes [`Entry::create`] fail with [`io::ErrorKind::InvalidData`] instead of panicking,
+/// and nothing is written.
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonFile<T> {
+    value: T,
+    /// The style it is always laid out in, if it doesn't follow the datapack's.
+    style: Option<JsonStyle>,
+}
+
+#[cfg(feature = "serde")]
+impl<T: Serialize> JsonFile<T> {
+    /// In the style of the datapack it is written in, or the default style on its own.
+    pub fn new(value: T) -> Self {
+        Self { value, style: None }
+    }
+
+    /// Always indented, like this crate's own JSON files by default.
+    pub fn pretty(value: T) -> Self {
+        Self { value, style: Some(JsonStyle::default()) }
+    }
+
+    /// Always on a single line.
+    pub fn compact(value: T) -> Self {
+        Self { value, style: Some(JsonStyle::Compact) }
+    }
+
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        self.style.unwrap_or_default().to_string(&self.value)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl JsonFile<Value> {
+    pub fn from_value(value: Value) -> Self {
+        Self::new(value)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: Serialize> JsonFile<T> {
+    fn view(&self, style: JsonStyle) -> JsonView<'_, T> {
+        JsonView {
+            value: &self.value,
+            style,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: Serialize> Entry for JsonFile<T> {
+    fn create(&self, path: &Path) -> io::Result<()> {
+        self.view(self.style.unwrap_or_default()).create(path)
+    }
+
+    #[cfg(feature = "tokio")]
+    fn create_async<'e>(&'e self, path: &'e Path) -> CreateFuture<'e> {
+        Box::pin(async move { self.view(self.style.unwrap_or_default()).create_async(path).await })
+    }
+
+    fn contents(&self) -> Option<Vec<u8>> {
+        self.view(self.style.unwrap_or_default()).contents()
+    }
+
+    /// Lays the file out in `style` unless it was given one of its own.
+    fn styled(&self, style: JsonStyle) -> Option<Box<dyn Entry + '_>> {
+        match self.style {
+            Some(_) => None,
+            None => Some(Box::new(self.view(style))),
+        }
+    }
+
+    fn stats(&self) -> PackStats {
+        self.view(self.style.unwrap_or_default()).stats()
+    }
+}
+
+#[cfg(feature = "serde")]
+/// A [`JsonFile`] value borrowed together with the style it is written in.
+struct JsonView<'v, T> {
+    value: &'v T,
+    style: JsonStyle,
+}
+
+#[cfg(feature = "serde")]
+impl<T: Serialize> JsonView<'_, T> {
+    fn to_json(&self) -> serde_json::Result<String> {
+        self.style.to_string(self.value)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: Serialize> Entry for JsonView<'_, T> {
+    fn create(&self, path: &Path) -> io::Result<()> {
+        let json = self.to_json().map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        let mut buffer = fs::File::create_new(path)?;
+        buffer.write_all(json.as_bytes())
+    }
+
+    #[cfg(feature = "tokio")]
+    fn create_async<'e>(&'e self, path: &'e Path) -> CreateFuture<'e> {
+        Box::pin(async move {
+            let json = self.to_json().map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+            Ok(write_new_async(path, json.as_bytes()).await?)
+        })
+    }
+
+    fn contents(&self) -> Option<Vec<u8>> {
+        self.to_json().ok().map(String::into_bytes)
+    }
+
+    /// Counts a file that can't be serialized as empty.
+    fn stats(&self) -> PackStats {
+        PackStats {
+            bytes: self.to_json().map_or(0, |json| json.len() as u64),
+            files: 1,
+            directories: 0,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+    use std::collections::{BTreeMap, HashMap};
+    use serde::Deserialize;
+    use tempdir::TempDir;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Lang {
+        name: String,
+        entries: BTreeMap<String, BTreeMap<String, String>>,
+    }
+
+    #[test]
+    fn json_file_round_trip() {
+        let lang = Lang {
+            name: "Ελληνικά ✨".to_string(),
+            entries: BTreeMap::from([(
+                "item".to_string(),
+                BTreeMap::from([("ruby".to_string(), "Ρουμπίνι \"💎\"".to_string())]),
+            )]),
+        };
+        let temp_dir = TempDir::new(module_path!()).expect("should have created temp dir");
+        let path = temp_dir.path().join("lang.json");
+        JsonFile::pretty(&lang).create(&path).expect("should have created the file");
+
+        let contents = fs::read_to_string(&path).expect("should have been able to read the file");
+        let read: Lang = serde_json::from_str(&contents).expect("the file should have been valid JSON");
+        assert_eq!(read, lang, "the value did not survive a round trip");
+        assert_eq!(JsonFile::pretty(&lang).stats().bytes, contents.len() as u64);
+        assert!(!JsonFile::compact(&lang).to_json().expect("should have serialized").contains('\n'));
+
+        temp_dir.close().expect("should have closed temp dir");
+    }
+
+    #[test]
+    fn json_file_styles() {
+        use crate::datapack::pack_meta::PackMeta;
+        use crate::datapack::Datapack;
+
+        let mut datapack = Datapack::new(PackMeta::new(48, "test")).with_json_style(JsonStyle::Compact);
+        let value = BTreeMap::from([("a", [1, 2])]);
+        datapack.insert("data/test/follows.json", JsonFile::new(value.clone())).expect("should have inserted the file");
+        datapack.insert("data/test/pretty.json", JsonFile::pretty(value.clone())).expect("should have inserted the file");
+        let temp_dir = TempDir::new(module_path!()).expect("should have created temp dir");
+        datapack.create(&temp_dir.path().join("pack")).expect("should have created the datapack");
+        let read = |file: &str| fs::read_to_string(temp_dir.path().join("pack/data/test").join(file)).expect("should have been able to read the file");
+        assert_eq!(read("follows.json"), r#"{"a":[1,2]}"#, "the file should have followed the datapack's style");
+        assert_eq!(read("pretty.json"), JsonFile::pretty(value).to_json().expect("should have serialized"), "an explicit style should have been kept");
+
+        temp_dir.close().expect("should have closed temp dir");
+    }
+
+    #[test]
+    fn json_file_error() {
+        let value = HashMap::from([((1, 2), "not a string key")]);
+        let temp_dir = TempDir::new(module_path!()).expect("should have created temp dir");
+        let path = temp_dir.path().join("invalid.json");
+        let error = JsonFile::pretty(value).create(&path).expect_err("a map with non-string keys can't be JSON");
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(error.to_string().contains("key must be a string"), "unexpected error: {error}");
+        assert!(!path.exists(), "nothing should have been written");
+
+        temp_dir.close().expect("should have closed temp dir");
+    }
+}
@@ -0,0 +1,171 @@
+use serde::Serialize;
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+
+/// A value a [`WeightedList`] picks by weight, which it writes next to its own fields.
+pub trait Weighted {
+    fn weight(&self) -> u32;
+
+    fn set_weight(&mut self, weight: u32);
+}
+
+/// Values with integer weights, of which the game picks one with a chance in proportion to
+/// its weight, such as the entries of a loot pool.
+///
+/// Written as a list of the values, each an object with its `weight` among its fields.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[serde(transparent)]
+pub struct WeightedList<T> {
+    values: Vec<T>,
+}
+
+impl<T> WeightedList<T> {
+    pub fn new() -> Self {
+        Self { values: Vec::new() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Adds `value` with the weight it already has.
+    pub fn push_value(&mut self, value: T) {
+        self.values.push(value);
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.values.iter()
+    }
+}
+
+impl<T: Weighted> WeightedList<T> {
+    /// Adds `value` with its weight set to `weight`.
+    pub fn push(&mut self, mut value: T, weight: u32) {
+        value.set_weight(weight);
+        self.values.push(value);
+    }
+
+    /// The sum of the weights, which can be more than a single weight can be.
+    pub fn total_weight(&self) -> u64 {
+        self.values.iter().map(|value| u64::from(value.weight())).sum()
+    }
+
+    /// Each value with its weight, in the order they were added.
+    pub fn iter(&self) -> impl Iterator<Item = (&T, u32)> {
+        self.values.iter().map(|value| (value, value.weight()))
+    }
+}
+
+impl<T> Default for WeightedList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Weighted> FromIterator<(T, u32)> for WeightedList<T> {
+    fn from_iter<I: IntoIterator<Item = (T, u32)>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T: Weighted> Extend<(T, u32)> for WeightedList<T> {
+    fn extend<I: IntoIterator<Item = (T, u32)>>(&mut self, iter: I) {
+        for (value, weight) in iter {
+            self.push(value, weight);
+        }
+    }
+}
+
+impl<T> IntoIterator for WeightedList<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.into_iter()
+    }
+}
+
+/// Each value with the weight it already has.
+impl<T> From<Vec<T>> for WeightedList<T> {
+    fn from(values: Vec<T>) -> Self {
+        Self { values }
+    }
+}
+
+impl<T: Weighted> From<Vec<(T, u32)>> for WeightedList<T> {
+    fn from(entries: Vec<(T, u32)>) -> Self {
+        entries.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[derive(Debug, Clone, PartialEq, Serialize)]
+    #[cfg_attr(feature = "serde", derive(Deserialize))]
+    struct Mob {
+        name: String,
+        #[serde(default = "one")]
+        weight: u32,
+    }
+
+    #[cfg(feature = "serde")]
+    fn one() -> u32 {
+        1
+    }
+
+    impl Weighted for Mob {
+        fn weight(&self) -> u32 {
+            self.weight
+        }
+
+        fn set_weight(&mut self, weight: u32) {
+            self.weight = weight;
+        }
+    }
+
+    fn mob(name: &str) -> Mob {
+        Mob { name: name.to_string(), weight: 1 }
+    }
+
+    #[test]
+    fn weighted_list() {
+        let mut mobs: WeightedList<Mob> = [(mob("zombie"), 3), (mob("skeleton"), 2)].into_iter().collect();
+        mobs.push(mob("creeper"), 1);
+        assert_eq!(mobs.total_weight(), 6);
+        assert_eq!(mobs.iter().map(|(mob, weight)| (mob.name.as_str(), weight)).collect::<Vec<_>>(), [
+            ("zombie", 3),
+            ("skeleton", 2),
+            ("creeper", 1),
+        ]);
+        assert_eq!(serde_json::to_value(&mobs).expect("should have serialized the list"), json!([
+            {"name": "zombie", "weight": 3},
+            {"name": "skeleton", "weight": 2},
+            {"name": "creeper", "weight": 1},
+        ]));
+        assert_eq!(WeightedList::from(vec![mob("pig")]).total_weight(), 1);
+    }
+
+    #[test]
+    fn large_total_weight() {
+        let mobs: WeightedList<Mob> = [(mob("zombie"), u32::MAX), (mob("skeleton"), u32::MAX), (mob("creeper"), 2)].into_iter().collect();
+        assert_eq!(mobs.total_weight(), 2 * u64::from(u32::MAX) + 2);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn read_weighted_list() {
+        let read: WeightedList<Mob> = serde_json::from_value(json!([{"name": "zombie", "weight": 3}, {"name": "pig"}]))
+            .expect("should have read the list");
+        assert_eq!(read, WeightedList::from(vec![(mob("zombie"), 3), (mob("pig"), 1)]));
+    }
+}